@@ -0,0 +1,137 @@
+use std::fmt;
+use std::str::FromStr;
+use base58::{FromBase58, ToBase58};
+use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::error::AddressError;
+
+pub const ADDRESS_PREFIX: &str = "qf";
+const ADDRESS_VERSION: u8 = 0x01;
+const PAYLOAD_LEN: usize = 20;
+const CHECKSUM_LEN: usize = 4;
+
+/// 20-byte account identifier, rendered as `qf` + base58check(version || payload || checksum).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Address([u8; PAYLOAD_LEN]);
+
+impl Address {
+    /// Deterministically derives an address from a wallet's Dilithium and Kyber public keys.
+    pub fn from_public_keys(dilithium_pub: &[u8], kyber_pub: &[u8]) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(dilithium_pub);
+        hasher.update(kyber_pub);
+        Self::from_hasher(hasher)
+    }
+
+    /// Parses a checksummed address string.
+    pub fn from_address(address: &str) -> Result<Self, AddressError> {
+        let encoded = address.strip_prefix(ADDRESS_PREFIX)
+            .ok_or_else(|| AddressError::InvalidAddress(format!("missing '{}' prefix", ADDRESS_PREFIX)))?;
+        let decoded = encoded.from_base58()
+            .map_err(|_| AddressError::InvalidAddress("not valid base58".to_string()))?;
+
+        if decoded.len() != 1 + PAYLOAD_LEN + CHECKSUM_LEN {
+            return Err(AddressError::InvalidAddress(format!("unexpected length {}", decoded.len())));
+        }
+        let (body, checksum_bytes) = decoded.split_at(1 + PAYLOAD_LEN);
+        if checksum(body) != checksum_bytes {
+            return Err(AddressError::InvalidAddress("checksum mismatch".to_string()));
+        }
+        if body[0] != ADDRESS_VERSION {
+            return Err(AddressError::InvalidAddress(format!("unsupported version {}", body[0])));
+        }
+
+        let mut payload = [0u8; PAYLOAD_LEN];
+        payload.copy_from_slice(&body[1..]);
+        Ok(Self(payload))
+    }
+
+    /// Address of the contract `sender` deploys with `nonce`.
+    pub fn for_contract(sender: &Address, nonce: u64) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"quantumfuse/contract");
+        hasher.update(&sender.0);
+        hasher.update(&nonce.to_le_bytes());
+        Self::from_hasher(hasher)
+    }
+
+    /// CREATE2-style address: fixed by sender, salt and code, independent of nonce.
+    pub fn for_salted_contract(sender: &Address, salt: &[u8; 32], code: &[u8]) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"quantumfuse/contract-salted");
+        hasher.update(&sender.0);
+        hasher.update(salt);
+        hasher.update(blake3::hash(code).as_bytes());
+        Self::from_hasher(hasher)
+    }
+
+    fn from_hasher(hasher: blake3::Hasher) -> Self {
+        let mut payload = [0u8; PAYLOAD_LEN];
+        payload.copy_from_slice(&hasher.finalize().as_bytes()[..PAYLOAD_LEN]);
+        Self(payload)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; PAYLOAD_LEN] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut body = Vec::with_capacity(1 + PAYLOAD_LEN + CHECKSUM_LEN);
+        body.push(ADDRESS_VERSION);
+        body.extend_from_slice(&self.0);
+        let check = checksum(&body);
+        body.extend_from_slice(&check);
+        write!(f, "{}{}", ADDRESS_PREFIX, body.to_base58())
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_address(s)
+    }
+}
+
+pub fn is_valid_address(address: &str) -> bool {
+    Address::from_address(address).is_ok()
+}
+
+fn checksum(body: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let first = blake3::hash(body);
+    let second = blake3::hash(first.as_bytes());
+    let mut check = [0u8; CHECKSUM_LEN];
+    check.copy_from_slice(&second.as_bytes()[..CHECKSUM_LEN]);
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_round_trip() {
+        let address = Address::from_public_keys(b"dilithium-public", b"kyber-public");
+        let encoded = address.to_string();
+
+        assert_eq!(encoded, Address::from_public_keys(b"dilithium-public", b"kyber-public").to_string());
+        assert!(is_valid_address(&encoded));
+        assert_eq!(Address::from_address(&encoded).unwrap(), address);
+    }
+
+    #[test]
+    fn test_flipped_character_fails_checksum() {
+        let encoded = Address::from_public_keys(b"dilithium-public", b"kyber-public").to_string();
+
+        let mut chars: Vec<char> = encoded.chars().collect();
+        let i = ADDRESS_PREFIX.len() + 5;
+        chars[i] = if chars[i] == 'A' { 'B' } else { 'A' };
+        let typo: String = chars.into_iter().collect();
+
+        assert!(!is_valid_address(&typo));
+        assert!(matches!(Address::from_address(&typo), Err(AddressError::InvalidAddress(_))));
+        assert!(!is_valid_address("qf-not-an-address"));
+        assert!(!is_valid_address("alice"));
+    }
+}
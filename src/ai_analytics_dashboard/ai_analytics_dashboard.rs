@@ -1,6 +1,14 @@
 use axum::{routing::get, Router, Json};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::future::Future;
+use std::time::Duration;
+use async_trait::async_trait;
+use axum::http::{header::HeaderName, HeaderValue, Method, StatusCode};
+use rand::Rng;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{Mutex, RwLock};
 use serde_json::json;
 use quantumfuse_sdk::{
     ai::{PolicyAI, DisputeResolver, JudicialAI},
@@ -8,24 +16,105 @@ use quantumfuse_sdk::{
     did::ReputationSystem,
     consensus::QuantumConsensus,
     metrics::GovernanceMetrics,
+    ai_quantum_governance_system::QuantumJudiciary,
+    state_manager::QuantumStateManager,
+    error::TlsError,
+    tls::{serve_router, TlsConfig},
+    build_info::BuildInfo,
 };
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), TlsError> {
     let dashboard_state = Arc::new(RwLock::new(DashboardState::new()));
+    dashboard_state.write().await.build_info = build_info_from_env();
+    let client = Arc::new(BlockchainClient::new("http://127.0.0.1:8545", Arc::new(HttpConnector::default())));
+    tokio::spawn(update_dashboard_state(dashboard_state.clone(), client, PollerConfig::from_env()));
 
-    let app = Router::new()
+    let app = dashboard_router(dashboard_state, &CorsConfig::from_env());
+
+    let tls = TlsConfig::from_env("DASHBOARD");
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    let listener = std::net::TcpListener::bind("127.0.0.1:8081")?;
+
+    println!("📊 On-Chain Analytics API running at {}://127.0.0.1:8081/", scheme);
+    serve_router(app, listener, tls.as_ref()).await
+}
+
+fn dashboard_router(state: Arc<RwLock<DashboardState>>, cors: &CorsConfig) -> Router {
+    Router::new()
         .route("/metrics/governance", get(get_governance_metrics))
         .route("/metrics/treasury", get(get_treasury_metrics))
         .route("/metrics/judiciary", get(get_judiciary_metrics))
         .route("/metrics/reputation", get(get_reputation_scores))
-        .layer(axum::AddExtensionLayer::new(dashboard_state));
+        .route("/metrics/chain", get(get_chain_metrics))
+        .route("/ready", get(get_readiness))
+        .route("/version", get(get_version))
+        .layer(axum::Extension(state))
+        .layer(cors.layer())
+}
+
+// 🌐 **CORS**
+/// Browser access policy. Nothing is allowed cross-origin until origins are listed;
+/// `*` is honoured only when `allow_any_origin` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub allow_any_origin: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec!["GET".to_string()],
+            allowed_headers: vec!["content-type".to_string()],
+            allow_any_origin: false,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Reads comma-separated `DASHBOARD_CORS_ORIGINS`, `DASHBOARD_CORS_METHODS`,
+    /// `DASHBOARD_CORS_HEADERS` and the `DASHBOARD_CORS_ALLOW_ANY` flag.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let list = |key: &str| std::env::var(key).ok().map(|value| {
+            value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(String::from).collect()
+        });
+
+        Self {
+            allowed_origins: list("DASHBOARD_CORS_ORIGINS").unwrap_or(defaults.allowed_origins),
+            allowed_methods: list("DASHBOARD_CORS_METHODS").unwrap_or(defaults.allowed_methods),
+            allowed_headers: list("DASHBOARD_CORS_HEADERS").unwrap_or(defaults.allowed_headers),
+            allow_any_origin: std::env::var("DASHBOARD_CORS_ALLOW_ANY").map_or(false, |v| v == "true"),
+        }
+    }
+
+    /// Unparseable entries are skipped rather than widening the policy.
+    pub fn layer(&self) -> CorsLayer {
+        let origins = if self.allow_any_origin {
+            AllowOrigin::from(Any)
+        } else {
+            AllowOrigin::list(
+                self.allowed_origins.iter()
+                    .filter(|origin| origin.as_str() != "*")
+                    .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+            )
+        };
+        let methods: Vec<Method> = self.allowed_methods.iter()
+            .filter_map(|method| Method::from_bytes(method.as_bytes()).ok())
+            .collect();
+        let headers: Vec<HeaderName> = self.allowed_headers.iter()
+            .filter_map(|header| HeaderName::from_bytes(header.as_bytes()).ok())
+            .collect();
 
-    println!("📊 On-Chain Analytics API running at http://127.0.0.1:8081/");
-    axum::Server::bind(&"127.0.0.1:8081".parse().unwrap())
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+    }
 }
 
 // 🚀 **Governance Analytics**
@@ -56,11 +145,12 @@ async fn get_treasury_metrics(
 async fn get_judiciary_metrics(
     state: axum::Extension<Arc<RwLock<DashboardState>>>,
 ) -> Json<serde_json::Value> {
-    let state = state.read().await;
+    let judiciary = state.read().await.judiciary.clone();
+    let metrics = judiciary.metrics().await;
     Json(json!({
-        "active_cases": state.judiciary.active_cases,
-        "resolved_cases": state.judiciary.resolved_cases,
-        "avg_resolution_time": state.judiciary.avg_resolution_time,
+        "active_cases": metrics.active_cases,
+        "resolved_cases": metrics.resolved_cases,
+        "avg_resolution_time": metrics.avg_resolution_time,
     }))
 }
 
@@ -75,13 +165,53 @@ async fn get_reputation_scores(
     }))
 }
 
+// ⛓️ **Chain Analytics**
+async fn get_chain_metrics(
+    state: axum::Extension<Arc<RwLock<DashboardState>>>,
+) -> Json<serde_json::Value> {
+    let state = state.read().await;
+    Json(json!({
+        "block_height": state.chain.block_height,
+        "peer_count": state.chain.peer_count,
+    }))
+}
+
+// ✅ **Readiness**: flips once the first poll has landed.
+async fn get_readiness(
+    state: axum::Extension<Arc<RwLock<DashboardState>>>,
+) -> StatusCode {
+    if state.read().await.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+async fn get_version(
+    state: axum::Extension<Arc<RwLock<DashboardState>>>,
+) -> Json<BuildInfo> {
+    Json(state.read().await.build_info.clone())
+}
+
+/// Chain identity comes from `DASHBOARD_CHAIN_ID` and `DASHBOARD_NETWORK_ID` when both are set.
+fn build_info_from_env() -> BuildInfo {
+    let read = |key: &str| std::env::var(key).ok().and_then(|v| v.parse::<u64>().ok());
+    match (read("DASHBOARD_CHAIN_ID"), read("DASHBOARD_NETWORK_ID")) {
+        (Some(chain_id), Some(network_id)) => BuildInfo::current().with_chain(chain_id, network_id),
+        _ => BuildInfo::current(),
+    }
+}
+
 // 🏗️ **Dashboard State Struct**
 #[derive(Debug)]
 struct DashboardState {
     governance: GovernanceMetrics,
     treasury: TreasuryMetrics,
-    judiciary: JudiciaryMetrics,
+    judiciary: Arc<QuantumJudiciary>,
     reputation: ReputationMetrics,
+    chain: ChainMetrics,
+    ready: bool,
+    build_info: BuildInfo,
 }
 
 impl DashboardState {
@@ -89,8 +219,413 @@ impl DashboardState {
         Self {
             governance: GovernanceMetrics::default(),
             treasury: TreasuryMetrics::default(),
-            judiciary: JudiciaryMetrics::default(),
+            judiciary: Arc::new(QuantumJudiciary::new(Arc::new(RwLock::new(QuantumStateManager::new())))),
             reputation: ReputationMetrics::default(),
+            chain: ChainMetrics::default(),
+            ready: false,
+            build_info: BuildInfo::current(),
+        }
+    }
+
+    fn apply(&mut self, snapshot: DashboardSnapshot) {
+        self.governance = snapshot.governance;
+        self.treasury = snapshot.treasury;
+        self.reputation = snapshot.reputation;
+        self.chain = snapshot.chain;
+        self.ready = true;
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChainMetrics {
+    pub block_height: u64,
+    pub peer_count: u32,
+}
+
+/// One poll's worth of node metrics.
+#[derive(Debug)]
+pub struct DashboardSnapshot {
+    pub governance: GovernanceMetrics,
+    pub treasury: TreasuryMetrics,
+    pub reputation: ReputationMetrics,
+    pub chain: ChainMetrics,
+}
+
+#[derive(Debug, Error)]
+pub enum DashboardError {
+    #[error("connection to {endpoint} failed: {reason}")]
+    Connection { endpoint: String, reason: String },
+    #[error("rpc call {method} failed: {reason}")]
+    Rpc { method: String, reason: String },
+    #[error("network request failed: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+// 🔌 **Node RPC Transport**
+#[async_trait]
+pub trait RpcTransport: Send + Sync {
+    async fn call(&self, method: &str) -> Result<serde_json::Value, DashboardError>;
+    async fn is_healthy(&self) -> bool;
+}
+
+#[async_trait]
+pub trait RpcConnector: Send + Sync {
+    async fn connect(&self, endpoint: &str) -> Result<Arc<dyn RpcTransport>, DashboardError>;
+}
+
+/// JSON-RPC over a keep-alive HTTP client.
+pub struct HttpTransport {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+#[async_trait]
+impl RpcTransport for HttpTransport {
+    async fn call(&self, method: &str) -> Result<serde_json::Value, DashboardError> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] });
+        let response: serde_json::Value = self.client.post(&self.endpoint)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(DashboardError::Rpc { method: method.to_string(), reason: error.to_string() });
+        }
+        Ok(response.get("result").cloned().unwrap_or(serde_json::Value::Null))
+    }
+
+    async fn is_healthy(&self) -> bool {
+        self.call("system_health").await.is_ok()
+    }
+}
+
+#[derive(Default)]
+pub struct HttpConnector;
+
+#[async_trait]
+impl RpcConnector for HttpConnector {
+    async fn connect(&self, endpoint: &str) -> Result<Arc<dyn RpcTransport>, DashboardError> {
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(4)
+            .tcp_keepalive(Duration::from_secs(30))
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Arc::new(HttpTransport { client, endpoint: endpoint.to_string() }))
+    }
+}
+
+// 🔗 **Blockchain Client**
+/// Holds one node connection across polls, reconnecting only when a health check fails.
+pub struct BlockchainClient {
+    endpoint: String,
+    connector: Arc<dyn RpcConnector>,
+    connection: Mutex<Option<Arc<dyn RpcTransport>>>,
+}
+
+impl BlockchainClient {
+    pub fn new(endpoint: &str, connector: Arc<dyn RpcConnector>) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            connector,
+            connection: Mutex::new(None),
+        }
+    }
+
+    async fn connection(&self) -> Result<Arc<dyn RpcTransport>, DashboardError> {
+        let mut connection = self.connection.lock().await;
+        if let Some(transport) = connection.as_ref() {
+            if transport.is_healthy().await {
+                return Ok(transport.clone());
+            }
+        }
+
+        let transport = self.connector.connect(&self.endpoint).await?;
+        *connection = Some(transport.clone());
+        Ok(transport)
+    }
+
+    async fn fetch<T: DeserializeOwned>(transport: &dyn RpcTransport, method: &str) -> Result<T, DashboardError> {
+        Ok(serde_json::from_value(transport.call(method).await?)?)
+    }
+
+    /// Issues the four metric calls concurrently over the shared connection.
+    pub async fn fetch_snapshot(&self) -> Result<DashboardSnapshot, DashboardError> {
+        let transport = self.connection().await?;
+        let transport = transport.as_ref();
+
+        let (governance, treasury, reputation, chain) = tokio::try_join!(
+            Self::fetch(transport, "metrics_governance"),
+            Self::fetch(transport, "metrics_treasury"),
+            Self::fetch(transport, "metrics_reputation"),
+            Self::fetch(transport, "metrics_chain"),
+        )?;
+
+        Ok(DashboardSnapshot { governance, treasury, reputation, chain })
+    }
+}
+
+// ⏱️ **Poller**
+/// Poll period plus up to `jitter` of random extra delay, so dashboards sharing a node spread out.
+#[derive(Debug, Clone)]
+pub struct PollerConfig {
+    pub interval: Duration,
+    pub jitter: Duration,
+}
+
+impl Default for PollerConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            jitter: Duration::from_secs(5),
+        }
+    }
+}
+
+impl PollerConfig {
+    /// Reads `DASHBOARD_POLL_INTERVAL_SECS` and `DASHBOARD_POLL_JITTER_MS`, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |key: &str| std::env::var(key).ok().and_then(|v| v.parse::<u64>().ok());
+
+        Self {
+            interval: read("DASHBOARD_POLL_INTERVAL_SECS").map(Duration::from_secs).unwrap_or(defaults.interval),
+            jitter: read("DASHBOARD_POLL_JITTER_MS").map(Duration::from_millis).unwrap_or(defaults.jitter),
+        }
+    }
+
+    pub fn next_delay(&self) -> Duration {
+        let jitter_ms = self.jitter.as_millis() as u64;
+        if jitter_ms == 0 {
+            return self.interval;
+        }
+        self.interval + Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_ms))
+    }
+}
+
+/// Polls immediately, then again after every `next_delay`.
+async fn run_poller<F, Fut>(config: PollerConfig, mut poll: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    loop {
+        poll().await;
+        tokio::time::sleep(config.next_delay()).await;
+    }
+}
+
+async fn update_dashboard_state(state: Arc<RwLock<DashboardState>>, client: Arc<BlockchainClient>, config: PollerConfig) {
+    run_poller(config, || {
+        let state = state.clone();
+        let client = client.clone();
+        async move {
+            match client.fetch_snapshot().await {
+                Ok(snapshot) => state.write().await.apply(snapshot),
+                Err(e) => eprintln!("❌ Dashboard refresh failed: {}", e),
+            }
+        }
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Answers every call after a short delay, tracking how many calls overlap.
+    #[derive(Default)]
+    struct MockTransport {
+        calls: AtomicUsize,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        unhealthy: AtomicBool,
+    }
+
+    #[async_trait]
+    impl RpcTransport for MockTransport {
+        async fn call(&self, method: &str) -> Result<serde_json::Value, DashboardError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let value = match method {
+                "metrics_governance" => serde_json::to_value(GovernanceMetrics::default())?,
+                "metrics_treasury" => serde_json::to_value(TreasuryMetrics::default())?,
+                "metrics_reputation" => serde_json::to_value(ReputationMetrics::default())?,
+                _ => serde_json::to_value(ChainMetrics { block_height: 42, peer_count: 8 })?,
+            };
+            Ok(value)
+        }
+
+        async fn is_healthy(&self) -> bool {
+            !self.unhealthy.load(Ordering::SeqCst)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockConnector {
+        connects: AtomicUsize,
+        transports: std::sync::Mutex<Vec<Arc<MockTransport>>>,
+    }
+
+    #[async_trait]
+    impl RpcConnector for MockConnector {
+        async fn connect(&self, _endpoint: &str) -> Result<Arc<dyn RpcTransport>, DashboardError> {
+            self.connects.fetch_add(1, Ordering::SeqCst);
+            let transport = Arc::new(MockTransport::default());
+            self.transports.lock().unwrap().push(transport.clone());
+            Ok(transport)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_reused_across_polls() {
+        let connector = Arc::new(MockConnector::default());
+        let client = BlockchainClient::new("mock://node", connector.clone());
+
+        for _ in 0..3 {
+            let snapshot = client.fetch_snapshot().await.unwrap();
+            assert_eq!(snapshot.chain.block_height, 42);
+        }
+
+        assert_eq!(connector.connects.load(Ordering::SeqCst), 1);
+        assert_eq!(connector.transports.lock().unwrap()[0].calls.load(Ordering::SeqCst), 12);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_connection_is_replaced() {
+        let connector = Arc::new(MockConnector::default());
+        let client = BlockchainClient::new("mock://node", connector.clone());
+
+        client.fetch_snapshot().await.unwrap();
+        connector.transports.lock().unwrap()[0].unhealthy.store(true, Ordering::SeqCst);
+        client.fetch_snapshot().await.unwrap();
+        client.fetch_snapshot().await.unwrap();
+
+        assert_eq!(connector.connects.load(Ordering::SeqCst), 2);
+    }
+
+    async fn poll_times(config: PollerConfig, run_for: Duration) -> (std::time::Instant, Vec<std::time::Instant>) {
+        let polls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = polls.clone();
+        let started = std::time::Instant::now();
+
+        let poller = tokio::spawn(run_poller(config, move || {
+            recorded.lock().unwrap().push(std::time::Instant::now());
+            async {}
+        }));
+        tokio::time::sleep(run_for).await;
+        poller.abort();
+
+        let polls = polls.lock().unwrap().clone();
+        (started, polls)
+    }
+
+    #[tokio::test]
+    async fn test_poller_honors_configured_interval() {
+        let config = PollerConfig { interval: Duration::from_millis(40), jitter: Duration::ZERO };
+        let (_, polls) = poll_times(config, Duration::from_millis(150)).await;
+
+        assert!((3..=4).contains(&polls.len()), "unexpected poll count {}", polls.len());
+        for pair in polls.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(40));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initial_poll_runs_before_first_sleep() {
+        let config = PollerConfig { interval: Duration::from_secs(60), jitter: Duration::from_secs(5) };
+        let (started, polls) = poll_times(config, Duration::from_millis(50)).await;
+
+        assert_eq!(polls.len(), 1);
+        assert!(polls[0] - started < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bound() {
+        let config = PollerConfig { interval: Duration::from_millis(100), jitter: Duration::from_millis(20) };
+        for _ in 0..100 {
+            let delay = config.next_delay();
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(120));
         }
     }
+
+    fn cors_router(cors: CorsConfig) -> Router {
+        dashboard_router(Arc::new(RwLock::new(DashboardState::new())), &cors)
+    }
+
+    fn allowed_origin_config() -> CorsConfig {
+        CorsConfig {
+            allowed_origins: vec!["https://dashboard.quantumfuse.io".to_string()],
+            ..CorsConfig::default()
+        }
+    }
+
+    async fn send(router: Router, request: axum::http::Request<axum::body::Body>) -> axum::response::Response {
+        use tower::ServiceExt;
+        router.oneshot(request).await.unwrap()
+    }
+
+    fn get_with_origin(origin: &str) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::get("/ready")
+            .header("origin", origin)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cors_allows_configured_origin() {
+        let response = send(cors_router(allowed_origin_config()), get_with_origin("https://dashboard.quantumfuse.io")).await;
+        assert_eq!(
+            response.headers().get("access-control-allow-origin").unwrap(),
+            "https://dashboard.quantumfuse.io"
+        );
+
+        let preflight = axum::http::Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/metrics/governance")
+            .header("origin", "https://dashboard.quantumfuse.io")
+            .header("access-control-request-method", "GET")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = send(cors_router(allowed_origin_config()), preflight).await;
+        assert!(response.status().is_success());
+        assert!(response.headers().get("access-control-allow-methods").unwrap().to_str().unwrap().contains("GET"));
+    }
+
+    #[tokio::test]
+    async fn test_cors_rejects_unlisted_origin() {
+        let response = send(cors_router(allowed_origin_config()), get_with_origin("https://evil.example")).await;
+        assert!(response.headers().get("access-control-allow-origin").is_none());
+
+        // A literal wildcard in the list does not open the API up.
+        let config = CorsConfig { allowed_origins: vec!["*".to_string()], ..CorsConfig::default() };
+        let response = send(cors_router(config), get_with_origin("https://evil.example")).await;
+        assert!(response.headers().get("access-control-allow-origin").is_none());
+
+        let config = CorsConfig { allow_any_origin: true, ..CorsConfig::default() };
+        let response = send(cors_router(config), get_with_origin("https://evil.example")).await;
+        assert_eq!(response.headers().get("access-control-allow-origin").unwrap(), "*");
+    }
+
+    #[tokio::test]
+    async fn test_metric_fetches_run_concurrently() {
+        let connector = Arc::new(MockConnector::default());
+        let client = BlockchainClient::new("mock://node", connector.clone());
+
+        let started = std::time::Instant::now();
+        client.fetch_snapshot().await.unwrap();
+
+        let transport = connector.transports.lock().unwrap()[0].clone();
+        assert_eq!(transport.max_in_flight.load(Ordering::SeqCst), 4);
+        // Four sequential 50ms calls would take at least 200ms.
+        assert!(started.elapsed() < Duration::from_millis(150));
+    }
 }
@@ -1,13 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use quantumfuse_sdk::{
-    error::GovernanceError,
+    error::{GovernanceError, StateError},
     pqc::dilithium2::DilithiumKeyPair,
     zkps::QuantumZK,
-    ai::{PolicyAI, EconomySimulator, DisputeResolver, JudicialAI},
+    ai::{PolicyAI, DisputeResolver, JudicialAI},
+    economy_simulator::{EconomySimulator, EpochMetrics, SimulationConfig},
     state::StateAccess,
     consensus::QuantumConsensus,
     bridge::QuantumBridge,
@@ -17,6 +18,10 @@ use quantumfuse_sdk::{
     nft::GovernanceNFT,
     finance::{DecentralizedGovernanceBonds},
     metrics::GovernanceMetrics,
+    amount::Amount,
+    address::Address,
+    state_manager::QuantumStateManager,
+    transaction::{GasMultipliers, OperationType},
 };
 
 // 🔹 AI-Powered Quantum Judicial System
@@ -25,6 +30,9 @@ pub struct QuantumJudiciary {
     cases: Arc<RwLock<HashMap<String, JudicialCase>>>,
     dispute_resolver: Arc<RwLock<DisputeResolver>>,
     ai_judge: Arc<RwLock<JudicialAI>>,
+    escrows: Arc<RwLock<HashMap<String, DisputeEscrow>>>,
+    escrow_config: EscrowConfig,
+    state: Arc<RwLock<QuantumStateManager>>,
 }
 
 // 🔹 Decentralized Governance Bonds (DGBs)
@@ -35,12 +43,57 @@ pub struct DecentralizedGovernanceBonds {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredGovernanceBond")]
 pub struct GovernanceBond {
     pub bond_id: String,
     pub investor: String,
     pub amount: f64,
     pub maturity_date: DateTime<Utc>,
     pub interest_rate: f64,
+    /// Start of the first holder's interest period.
+    pub issued_at: DateTime<Utc>,
+    pub transfers: Vec<BondTransfer>,
+}
+
+/// Term assumed for bonds stored before `issued_at` was recorded, which start
+/// accruing this long before maturity rather than at the Unix epoch.
+const LEGACY_BOND_TERM_DAYS: i64 = 365;
+
+/// A bond as stored, including records written before `issued_at` and
+/// `transfers` existed.
+#[derive(Deserialize)]
+struct StoredGovernanceBond {
+    bond_id: String,
+    investor: String,
+    amount: f64,
+    maturity_date: DateTime<Utc>,
+    interest_rate: f64,
+    issued_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    transfers: Vec<BondTransfer>,
+}
+
+impl From<StoredGovernanceBond> for GovernanceBond {
+    fn from(stored: StoredGovernanceBond) -> Self {
+        let issued_at = stored.issued_at
+            .unwrap_or(stored.maturity_date - chrono::Duration::days(LEGACY_BOND_TERM_DAYS));
+        Self {
+            bond_id: stored.bond_id,
+            investor: stored.investor,
+            amount: stored.amount,
+            maturity_date: stored.maturity_date,
+            interest_rate: stored.interest_rate,
+            issued_at,
+            transfers: stored.transfers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BondTransfer {
+    pub from: String,
+    pub to: String,
+    pub transferred_at: DateTime<Utc>,
 }
 
 // 🔹 AI-Driven Virtual & Real-World Court System
@@ -53,40 +106,679 @@ pub struct JudicialCase {
     pub evidence: Vec<String>,
     pub status: JudicialStatus,
     pub verdict: Option<String>,
+    pub opened_at: DateTime<Utc>,
+    #[serde(default)]
+    pub resolved_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum JudicialStatus {
     Open,
     InReview,
     VerdictGiven,
     Appealed,
+    TimedOut,
+}
+
+// 🔹 Dispute Escrow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Party {
+    Claimant,
+    Respondent,
+}
+
+/// Bond each party locks at filing, the fee taken from the pot on a verdict,
+/// how long a dispute may stay unresolved before both bonds are refunded, and
+/// the accounts holding locked bonds and collected fees.
+#[derive(Debug, Clone)]
+pub struct EscrowConfig {
+    pub bond: Amount,
+    pub protocol_fee_bps: u32,
+    pub dispute_timeout: chrono::Duration,
+    pub escrow_account: String,
+    pub fee_account: String,
+}
+
+impl Default for EscrowConfig {
+    fn default() -> Self {
+        Self {
+            bond: Amount::from_whole(100),
+            protocol_fee_bps: 250,
+            dispute_timeout: chrono::Duration::days(30),
+            escrow_account: Address::from_public_keys(b"dispute-escrow", b"dispute-escrow").to_string(),
+            fee_account: Address::from_public_keys(b"protocol-fees", b"protocol-fees").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EscrowSettlement {
+    Awarded { winner: String, payout: Amount, protocol_fee: Amount },
+    Refunded { claimant: Amount, respondent: Amount },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeEscrow {
+    pub claimant: String,
+    pub respondent: String,
+    pub claimant_bond: Amount,
+    pub respondent_bond: Amount,
+    pub filed_at: DateTime<Utc>,
+    pub settlement: Option<EscrowSettlement>,
+}
+
+/// A party that can't cover the bond gets `InsufficientBond`; any other
+/// state failure aborts the escrow transfer.
+fn escrow_error(e: StateError) -> GovernanceError {
+    match e {
+        StateError::InsufficientBalance { .. } | StateError::WalletNotFound => GovernanceError::InsufficientBond,
+        e => GovernanceError::EscrowTransferFailed(e.to_string()),
+    }
+}
+
+/// Case counts and mean open-to-verdict time in seconds, derived from the case registry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JudiciaryMetrics {
+    pub active_cases: u64,
+    pub resolved_cases: u64,
+    pub avg_resolution_time: f64,
+}
+
+impl QuantumJudiciary {
+    pub fn new(state: Arc<RwLock<QuantumStateManager>>) -> Self {
+        Self::with_escrow_config(state, EscrowConfig::default())
+    }
+
+    pub fn with_escrow_config(state: Arc<RwLock<QuantumStateManager>>, escrow_config: EscrowConfig) -> Self {
+        Self {
+            cases: Arc::new(RwLock::new(HashMap::new())),
+            dispute_resolver: Arc::new(RwLock::new(DisputeResolver::new())),
+            ai_judge: Arc::new(RwLock::new(JudicialAI::new())),
+            escrows: Arc::new(RwLock::new(HashMap::new())),
+            escrow_config,
+            state,
+        }
+    }
+
+    pub async fn escrow(&self, case_id: &str) -> Option<DisputeEscrow> {
+        self.escrows.read().await.get(case_id).cloned()
+    }
+
+    pub async fn open_case(&self, case: JudicialCase) -> Result<(), GovernanceError> {
+        self.open_case_at(case, Utc::now()).await
+    }
+
+    /// Files the case at `now`, whatever `opened_at` the caller sent, and moves
+    /// `escrow_config.bond` from both parties into the escrow account.
+    pub async fn open_case_at(&self, mut case: JudicialCase, now: DateTime<Utc>) -> Result<(), GovernanceError> {
+        let mut cases = self.cases.write().await;
+        if cases.contains_key(&case.case_id) {
+            return Err(GovernanceError::DuplicateCase);
+        }
+
+        let bond = self.escrow_config.bond;
+        let escrow_account = &self.escrow_config.escrow_account;
+        let mut escrows = self.escrows.write().await;
+        let state = self.state.read().await;
+        state.transfer(&case.claimant, escrow_account, bond).await.map_err(escrow_error)?;
+        if let Err(e) = state.transfer(&case.respondent, escrow_account, bond).await {
+            state.transfer(escrow_account, &case.claimant, bond).await.map_err(escrow_error)?;
+            return Err(escrow_error(e));
+        }
+        escrows.insert(case.case_id.clone(), DisputeEscrow {
+            claimant: case.claimant.clone(),
+            respondent: case.respondent.clone(),
+            claimant_bond: bond,
+            respondent_bond: bond,
+            filed_at: now,
+            settlement: None,
+        });
+
+        case.status = JudicialStatus::Open;
+        case.verdict = None;
+        case.opened_at = now;
+        case.resolved_at = None;
+        cases.insert(case.case_id.clone(), case);
+        Ok(())
+    }
+
+    pub async fn begin_review(&self, case_id: &str) -> Result<(), GovernanceError> {
+        let mut cases = self.cases.write().await;
+        let case = cases.get_mut(case_id).ok_or(GovernanceError::CaseNotFound)?;
+        if case.status != JudicialStatus::Open {
+            return Err(GovernanceError::InvalidCaseTransition);
+        }
+
+        case.status = JudicialStatus::InReview;
+        Ok(())
+    }
+
+    /// Records the verdict and releases the whole escrow, less the protocol fee, to the prevailing party.
+    pub async fn give_verdict(
+        &self,
+        case_id: &str,
+        verdict: &str,
+        prevailing: Party,
+        at: DateTime<Utc>,
+    ) -> Result<EscrowSettlement, GovernanceError> {
+        let mut cases = self.cases.write().await;
+        let case = cases.get_mut(case_id).ok_or(GovernanceError::CaseNotFound)?;
+        if case.status != JudicialStatus::InReview {
+            return Err(GovernanceError::InvalidCaseTransition);
+        }
+
+        let mut escrows = self.escrows.write().await;
+        let escrow = escrows.get_mut(case_id).ok_or(GovernanceError::EscrowNotFound)?;
+        let pot = escrow.claimant_bond.checked_add(escrow.respondent_bond)
+            .ok_or(GovernanceError::ArithmeticOverflow)?;
+        let protocol_fee = pot.checked_mul(self.escrow_config.protocol_fee_bps as u128)
+            .and_then(|fee| fee.checked_div(10_000))
+            .ok_or(GovernanceError::ArithmeticOverflow)?;
+        let payout = pot.saturating_sub(protocol_fee);
+        let winner = match prevailing {
+            Party::Claimant => escrow.claimant.clone(),
+            Party::Respondent => escrow.respondent.clone(),
+        };
+
+        let state = self.state.read().await;
+        let escrow_account = &self.escrow_config.escrow_account;
+        state.transfer(escrow_account, &winner, payout).await.map_err(escrow_error)?;
+        state.transfer(escrow_account, &self.escrow_config.fee_account, protocol_fee).await.map_err(escrow_error)?;
+
+        let settlement = EscrowSettlement::Awarded { winner, payout, protocol_fee };
+        escrow.settlement = Some(settlement.clone());
+        case.status = JudicialStatus::VerdictGiven;
+        case.verdict = Some(verdict.to_string());
+        case.resolved_at = Some(at);
+        Ok(settlement)
+    }
+
+    pub async fn expire_disputes(&self) -> Result<Vec<String>, GovernanceError> {
+        self.expire_disputes_at(Utc::now()).await
+    }
+
+    /// Refunds both bonds for every dispute left unresolved past `dispute_timeout`.
+    pub async fn expire_disputes_at(&self, now: DateTime<Utc>) -> Result<Vec<String>, GovernanceError> {
+        let mut cases = self.cases.write().await;
+        let mut escrows = self.escrows.write().await;
+        let state = self.state.read().await;
+        let escrow_account = &self.escrow_config.escrow_account;
+        let mut expired = Vec::new();
+
+        for case in cases.values_mut() {
+            if !matches!(case.status, JudicialStatus::Open | JudicialStatus::InReview) {
+                continue;
+            }
+            let Some(escrow) = escrows.get_mut(&case.case_id) else { continue };
+            if now - escrow.filed_at < self.escrow_config.dispute_timeout {
+                continue;
+            }
+
+            state.transfer(escrow_account, &escrow.claimant, escrow.claimant_bond).await.map_err(escrow_error)?;
+            state.transfer(escrow_account, &escrow.respondent, escrow.respondent_bond).await.map_err(escrow_error)?;
+            escrow.settlement = Some(EscrowSettlement::Refunded {
+                claimant: escrow.claimant_bond,
+                respondent: escrow.respondent_bond,
+            });
+            case.status = JudicialStatus::TimedOut;
+            expired.push(case.case_id.clone());
+        }
+
+        Ok(expired)
+    }
+
+    pub async fn metrics(&self) -> JudiciaryMetrics {
+        let cases = self.cases.read().await;
+        let mut metrics = JudiciaryMetrics::default();
+        let mut total_resolution_secs = 0.0;
+
+        for case in cases.values() {
+            match (case.status, case.resolved_at) {
+                (JudicialStatus::VerdictGiven, Some(resolved_at)) => {
+                    metrics.resolved_cases += 1;
+                    total_resolution_secs += (resolved_at - case.opened_at).num_milliseconds() as f64 / 1000.0;
+                }
+                (JudicialStatus::VerdictGiven, None) => metrics.resolved_cases += 1,
+                (JudicialStatus::TimedOut, _) => {}
+                _ => metrics.active_cases += 1,
+            }
+        }
+
+        if metrics.resolved_cases > 0 {
+            metrics.avg_resolution_time = total_resolution_secs / metrics.resolved_cases as f64;
+        }
+        metrics
+    }
+}
+
+// 🔹 Staking Reward Curves
+/// Maps the staked ratio to an annual reward rate. Every curve is non-increasing,
+/// so rewards dampen as participation grows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RewardCurve {
+    /// Falls in a straight line from `max_rate` at 0% staked to `min_rate` at 100%.
+    Linear { max_rate: f64, min_rate: f64 },
+    /// Logistic decay from `max_rate` to `min_rate`, halfway at `target_ratio`.
+    Sigmoid { max_rate: f64, min_rate: f64, target_ratio: f64, steepness: f64 },
+    /// `(staked_ratio, rate)` points in ascending ratio order, interpolated
+    /// linearly and held flat beyond the ends.
+    Piecewise { points: Vec<(f64, f64)> },
+}
+
+impl Default for RewardCurve {
+    fn default() -> Self {
+        RewardCurve::Sigmoid { max_rate: 0.10, min_rate: 0.02, target_ratio: 0.67, steepness: 12.0 }
+    }
+}
+
+impl RewardCurve {
+    pub fn rate(&self, staked_ratio: f64) -> f64 {
+        let ratio = staked_ratio.clamp(0.0, 1.0);
+        match self {
+            RewardCurve::Linear { max_rate, min_rate } => max_rate - (max_rate - min_rate) * ratio,
+            RewardCurve::Sigmoid { max_rate, min_rate, target_ratio, steepness } => {
+                let decay = 1.0 / (1.0 + (-steepness * (ratio - target_ratio)).exp());
+                max_rate - (max_rate - min_rate) * decay
+            }
+            RewardCurve::Piecewise { points } => {
+                let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                    return 0.0;
+                };
+                if ratio <= first.0 {
+                    return first.1;
+                }
+                points.windows(2)
+                    .find(|pair| ratio <= pair[1].0)
+                    .map(|pair| {
+                        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                        if x1 == x0 { y1 } else { y0 + (y1 - y0) * (ratio - x0) / (x1 - x0) }
+                    })
+                    .unwrap_or(last.1)
+            }
+        }
+    }
+}
+
+// 🔹 Proposal Impact Simulation
+/// A parameter change a proposal would enact if passed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParameterChange {
+    StakingRewardsRate(f64),
+    RewardCurve(RewardCurve),
+    GasMultiplier { operation_type: OperationType, multiplier: f64 },
+    /// Restores a self-amended contract to one of its recorded versions.
+    ContractRollback { policy_id: String, version: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectedMetrics {
+    pub epochs: Vec<EpochMetrics>,
+    pub total_reward_outflow: f64,
+    pub final_supply: f64,
+    pub final_staked_ratio: f64,
+}
+
+/// The same projection run with and without the proposal's change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalSimulation {
+    pub proposal_id: String,
+    pub change: ParameterChange,
+    pub baseline: ProjectedMetrics,
+    pub projected: ProjectedMetrics,
+}
+
+const MAX_STAKING_REWARDS_RATE: f64 = 0.10;
+
+/// Runs the economy simulator forward, setting each epoch's reward rate from
+/// `reward_rate(staked_ratio)` before stepping.
+fn project_economy(config: &SimulationConfig, epochs: u64, reward_rate: impl Fn(f64) -> f64) -> ProjectedMetrics {
+    let mut simulator = EconomySimulator::new(config.clone());
+    let epochs: Vec<EpochMetrics> = (0..epochs)
+        .map(|_| {
+            simulator.set_reward_rate(reward_rate(simulator.staked_ratio()).clamp(0.0, MAX_STAKING_REWARDS_RATE));
+            simulator.step()
+        })
+        .collect();
+
+    ProjectedMetrics {
+        total_reward_outflow: epochs.iter().map(|metrics| metrics.rewards).sum(),
+        final_supply: simulator.supply(),
+        final_staked_ratio: simulator.staked_ratio(),
+        epochs,
+    }
+}
+
+impl QuantumGovernance {
+    /// Opens a proposal carrying `change`. The change is fixed at creation, so
+    /// nobody can swap it once votes are being cast.
+    pub async fn propose_parameter_change(
+        &self,
+        proposer: &str,
+        title: &str,
+        description: &str,
+        category: ProposalCategory,
+        change: ParameterChange,
+    ) -> Result<String, GovernanceError> {
+        let proposal_id = self.propose(proposer, title, description, category).await?;
+        self.proposals.write().await
+            .get_mut(&proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound)?
+            .parameter_change = Some(change);
+        Ok(proposal_id)
+    }
+
+    /// Dry run: runs the economy simulator from `economy` with and without the
+    /// proposal's change, both from the same seed. Live state is only read.
+    pub async fn simulate_proposal(
+        &self,
+        proposal_id: &str,
+        economy: &SimulationConfig,
+        epochs: u64,
+    ) -> Result<ProposalSimulation, GovernanceError> {
+        let change = self.proposals.read().await
+            .get(proposal_id)
+            .ok_or(GovernanceError::ProposalNotFound)?
+            .parameter_change
+            .clone()
+            .ok_or(GovernanceError::NoParameterChange)?;
+
+        let live_rate = self.config.staking_rewards_rate;
+        let baseline = project_economy(economy, epochs, |_| live_rate);
+        let projected = match &change {
+            ParameterChange::StakingRewardsRate(rate) => project_economy(economy, epochs, |_| *rate),
+            ParameterChange::RewardCurve(curve) => project_economy(economy, epochs, |ratio| curve.rate(ratio)),
+            // Gas pricing and contract code do not enter the reward projection.
+            ParameterChange::GasMultiplier { .. } | ParameterChange::ContractRollback { .. } => baseline.clone(),
+        };
+
+        Ok(ProposalSimulation { proposal_id: proposal_id.to_string(), change, baseline, projected })
+    }
+
+    /// Marks a passed proposal enacted and returns its change, so a concurrent
+    /// or repeated call cannot apply it twice. A proposal passes once its voting
+    /// deadline is behind `now`, at least `quorum` votes were cast and more of
+    /// them were for it than against.
+    async fn claim_passed_change(&self, proposal_id: &str, now: DateTime<Utc>) -> Result<ParameterChange, GovernanceError> {
+        let mut proposals = self.proposals.write().await;
+        let proposal = proposals.get_mut(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
+        if now < proposal.voting_deadline {
+            return Err(GovernanceError::VotingStillOpen);
+        }
+        if proposal.votes_for.saturating_add(proposal.votes_against) < self.config.quorum {
+            return Err(GovernanceError::QuorumNotReached);
+        }
+        if proposal.votes_for <= proposal.votes_against {
+            return Err(GovernanceError::ProposalNotPassed);
+        }
+        if proposal.enacted {
+            return Err(GovernanceError::AlreadyEnacted);
+        }
+        let change = proposal.parameter_change.clone().ok_or(GovernanceError::NoParameterChange)?;
+        proposal.enacted = true;
+        Ok(change)
+    }
+
+    /// Drops a claim whose change failed to apply so it can be retried.
+    async fn release_claim(&self, proposal_id: &str) {
+        if let Some(proposal) = self.proposals.write().await.get_mut(proposal_id) {
+            proposal.enacted = false;
+        }
+    }
+
+    pub async fn enact_parameter_change(&mut self, proposal_id: &str) -> Result<(), GovernanceError> {
+        self.enact_parameter_change_at(proposal_id, Utc::now()).await
+    }
+
+    /// Applies a passed proposal's change to the live parameters, once.
+    pub async fn enact_parameter_change_at(&mut self, proposal_id: &str, now: DateTime<Utc>) -> Result<(), GovernanceError> {
+        let change = self.claim_passed_change(proposal_id, now).await?;
+        let applied = match change {
+            ParameterChange::StakingRewardsRate(rate) => {
+                self.config.staking_rewards_rate = rate.min(MAX_STAKING_REWARDS_RATE);
+                Ok(())
+            }
+            ParameterChange::RewardCurve(curve) => {
+                self.config.reward_curve = curve;
+                Ok(())
+            }
+            ParameterChange::GasMultiplier { operation_type, multiplier } => {
+                self.config.gas_multipliers.set(operation_type, multiplier)
+                    .map_err(|_| GovernanceError::InvalidParameterChange)
+            }
+            // Needs the contract registry; enacted through `rollback_contract`.
+            ParameterChange::ContractRollback { .. } => Err(GovernanceError::InvalidParameterChange),
+        };
+
+        if applied.is_err() {
+            self.release_claim(proposal_id).await;
+        }
+        applied
+    }
+
+    pub fn gas_multipliers(&self) -> &GasMultipliers {
+        &self.config.gas_multipliers
+    }
+}
+
+// 🔹 Bond Secondary Market
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+impl GovernanceBond {
+    /// Simple interest earned by `holder` up to `now` (capped at maturity), counting
+    /// only the periods in which they held the bond.
+    pub fn accrued_interest(&self, holder: &str, now: DateTime<Utc>) -> f64 {
+        let end = now.min(self.maturity_date);
+        let mut owner = self.transfers.first().map_or(self.investor.as_str(), |t| t.from.as_str());
+        let mut start = self.issued_at;
+        let mut held_secs = 0i64;
+
+        for transfer in &self.transfers {
+            if owner == holder {
+                held_secs += (transfer.transferred_at.min(end) - start).num_seconds().max(0);
+            }
+            owner = &transfer.to;
+            start = transfer.transferred_at;
+        }
+        if owner == holder {
+            held_secs += (end - start).num_seconds().max(0);
+        }
+
+        self.amount * self.interest_rate * held_secs as f64 / SECONDS_PER_YEAR
+    }
+}
+
+impl DecentralizedGovernanceBonds {
+    pub async fn transfer_bond(&self, bond_id: &str, from: &str, to: &str) -> Result<(), GovernanceError> {
+        self.transfer_bond_at(bond_id, from, to, Utc::now()).await
+    }
+
+    /// Sells the bond to `to`; interest from `now` on accrues to the new holder.
+    /// Matured bonds must be redeemed instead.
+    pub async fn transfer_bond_at(&self, bond_id: &str, from: &str, to: &str, now: DateTime<Utc>) -> Result<(), GovernanceError> {
+        let mut bonds = self.bonds.write().await;
+        let bond = bonds.get_mut(bond_id).ok_or(GovernanceError::BondNotFound)?;
+        if bond.investor != from {
+            return Err(GovernanceError::NotBondHolder);
+        }
+        if now >= bond.maturity_date {
+            return Err(GovernanceError::BondMatured);
+        }
+
+        bond.transfers.push(BondTransfer {
+            from: from.to_string(),
+            to: to.to_string(),
+            transferred_at: now,
+        });
+        bond.investor = to.to_string();
+        Ok(())
+    }
+
+    pub async fn accrued_interest(&self, bond_id: &str, holder: &str, now: DateTime<Utc>) -> Result<f64, GovernanceError> {
+        let bonds = self.bonds.read().await;
+        let bond = bonds.get(bond_id).ok_or(GovernanceError::BondNotFound)?;
+        Ok(bond.accrued_interest(holder, now))
+    }
 }
 
 // 🔹 Smart Contract Evolution: Adaptive Quantum Governance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractVersion {
+    pub version: u32,
+    pub code: Vec<u8>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Every code version each governed contract has run, oldest first. Version 0
+/// is the code in place before the first amendment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractVersionHistory {
+    contracts: HashMap<String, Vec<ContractVersion>>,
+}
+
+impl ContractVersionHistory {
+    /// Records `updated_code` as the next version, seeding the history with
+    /// `current_code` the first time a contract is amended. Returns the new version.
+    pub fn record_amendment(&mut self, policy_id: &str, current_code: &[u8], updated_code: Vec<u8>) -> u32 {
+        let versions = self.contracts.entry(policy_id.to_string()).or_default();
+        if versions.is_empty() {
+            versions.push(ContractVersion { version: 0, code: current_code.to_vec(), recorded_at: Utc::now() });
+        }
+        let version = versions.len() as u32;
+        versions.push(ContractVersion { version, code: updated_code, recorded_at: Utc::now() });
+        version
+    }
+
+    pub fn current_version(&self, policy_id: &str) -> Option<u32> {
+        self.contracts.get(policy_id)?.last().map(|v| v.version)
+    }
+
+    pub fn versions(&self, policy_id: &str) -> &[ContractVersion] {
+        self.contracts.get(policy_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Re-records the code of `version` as a new version, so the rollback
+    /// itself stays in the history, and returns that code.
+    pub fn rollback(&mut self, policy_id: &str, version: u32) -> Result<Vec<u8>, GovernanceError> {
+        let versions = self.contracts.get_mut(policy_id).ok_or(GovernanceError::UnknownContractVersion(version))?;
+        let code = versions.get(version as usize)
+            .ok_or(GovernanceError::UnknownContractVersion(version))?
+            .code
+            .clone();
+        let next = versions.len() as u32;
+        versions.push(ContractVersion { version: next, code: code.clone(), recorded_at: Utc::now() });
+        Ok(code)
+    }
+}
+
 impl SmartLawEnforcement {
     pub async fn self_amend_contracts(&mut self) -> Result<(), GovernanceError> {
         let governance_rules = self.get_active_governance_policies().await?;
 
         for rule in governance_rules {
-            self.update_smart_contract(rule.policy_id, rule.updated_code).await?;
+            let current_code = self.get_smart_contract_code(&rule.policy_id).await?;
+            self.update_smart_contract(rule.policy_id.clone(), rule.updated_code.clone()).await?;
+            self.contract_versions.record_amendment(&rule.policy_id, &current_code, rule.updated_code);
         }
 
         Ok(())
     }
+
+    /// Only reachable through `QuantumGovernance::rollback_contract`.
+    async fn restore_contract_version(&mut self, policy_id: &str, version: u32) -> Result<(), GovernanceError> {
+        let mut history = self.contract_versions.clone();
+        let code = history.rollback(policy_id, version)?;
+        self.update_smart_contract(policy_id.to_string(), code).await?;
+        self.contract_versions = history;
+        Ok(())
+    }
+}
+
+impl QuantumGovernance {
+    pub async fn rollback_contract(
+        &self,
+        proposal_id: &str,
+        law_enforcement: &mut SmartLawEnforcement,
+    ) -> Result<(), GovernanceError> {
+        self.rollback_contract_at(proposal_id, law_enforcement, Utc::now()).await
+    }
+
+    /// Enacts a passed `ParameterChange::ContractRollback` proposal, restoring
+    /// the contract and version it names.
+    pub async fn rollback_contract_at(
+        &self,
+        proposal_id: &str,
+        law_enforcement: &mut SmartLawEnforcement,
+        now: DateTime<Utc>,
+    ) -> Result<(), GovernanceError> {
+        let ParameterChange::ContractRollback { policy_id, version } = self.claim_passed_change(proposal_id, now).await? else {
+            self.release_claim(proposal_id).await;
+            return Err(GovernanceError::InvalidParameterChange);
+        };
+
+        let restored = law_enforcement.restore_contract_version(&policy_id, version).await;
+        if restored.is_err() {
+            self.release_claim(proposal_id).await;
+        }
+        restored
+    }
 }
 
 // 🔹 Cross-Chain Interoperability for Quantum Law Enforcement
+/// Policy ids already enforced, per target chain.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnforcementRegistry {
+    enforced: HashMap<String, HashSet<String>>,
+}
+
+impl EnforcementRegistry {
+    pub fn is_enforced(&self, target_chain: &str, policy_id: &str) -> bool {
+        self.enforced.get(target_chain).is_some_and(|policies| policies.contains(policy_id))
+    }
+
+    /// Claims `policy_id` on `target_chain` before it is executed there, so a
+    /// concurrent or repeated call cannot apply it twice.
+    pub fn reserve(&mut self, target_chain: &str, policy_id: &str) -> Result<(), GovernanceError> {
+        if !self.enforced.entry(target_chain.to_string()).or_default().insert(policy_id.to_string()) {
+            return Err(GovernanceError::AlreadyEnforced);
+        }
+        Ok(())
+    }
+
+    /// Drops a reservation whose execution failed so it can be retried.
+    pub fn release(&mut self, target_chain: &str, policy_id: &str) {
+        if let Some(policies) = self.enforced.get_mut(target_chain) {
+            policies.remove(policy_id);
+        }
+    }
+
+    /// Records an enforcement observed on the target chain. Idempotent;
+    /// returns whether it was new.
+    pub fn confirm(&mut self, target_chain: &str, policy_id: &str) -> bool {
+        self.enforced.entry(target_chain.to_string()).or_default().insert(policy_id.to_string())
+    }
+}
+
 impl QuantumGovernance {
-    pub async fn enforce_cross_chain_policy(&mut self, policy_id: &str) -> Result<(), GovernanceError> {
+    /// Executes `policy_id` on `target_chain` at most once; a repeat is
+    /// rejected with `AlreadyEnforced`.
+    pub async fn enforce_cross_chain_policy(&mut self, policy_id: &str, target_chain: &str) -> Result<(), GovernanceError> {
         let bridge = self.metaverse_registry.read().await.get_quantum_bridge()?;
+        if !bridge.policy_exists(policy_id).await {
+            return Err(GovernanceError::InvalidPolicy);
+        }
 
-        if bridge.policy_exists(policy_id).await {
-            bridge.execute_governance_policy(policy_id).await?;
-            Ok(())
-        } else {
-            Err(GovernanceError::InvalidPolicy)
+        self.enforced_policies.write().await.reserve(target_chain, policy_id)?;
+        if let Err(e) = bridge.execute_governance_policy(policy_id, target_chain).await {
+            self.enforced_policies.write().await.release(target_chain, policy_id);
+            return Err(e.into());
         }
+        Ok(())
+    }
+
+    /// Marks `policy_id` as enforced on `target_chain`, e.g. after seeing the
+    /// bridge's receipt. Safe to call any number of times.
+    pub async fn confirm_cross_chain_enforcement(&self, policy_id: &str, target_chain: &str) -> bool {
+        self.enforced_policies.write().await.confirm(target_chain, policy_id)
     }
 
     // 🔹 Reputation-Based Quantum Voting with Dynamic Scaling
@@ -112,14 +804,14 @@ impl QuantumGovernance {
         Ok(())
     }
 
-    // 🔹 AI-Powered Economic Adjustments Based on Governance Decisions
-    pub async fn dynamically_adjust_staking_rewards(&mut self) {
-        let mut ai_engine = self.ai_policy_engine.write().await;
-        let new_rewards = ai_engine.optimize_staking_rewards();
+    // 🔹 Staking Rewards from the Configured Reward Curve
+    /// `staked_ratio` is total stake over circulating supply, in `[0, 1]`.
+    pub async fn dynamically_adjust_staking_rewards(&mut self, staked_ratio: f64) {
+        let new_rewards = self.config.reward_curve.rate(staked_ratio);
 
         // Prevent over-inflation
-        if new_rewards > 0.10 { // Cap staking rewards at 10%
-            self.config.staking_rewards_rate = 0.10;
+        if new_rewards > MAX_STAKING_REWARDS_RATE {
+            self.config.staking_rewards_rate = MAX_STAKING_REWARDS_RATE;
         } else {
             self.config.staking_rewards_rate = new_rewards;
         }
@@ -130,6 +822,7 @@ impl QuantumGovernance {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quantumfuse_sdk::transaction::{AccountView, AssetId};
 
     #[tokio::test]
     async fn test_judicial_case_resolution() {
@@ -137,6 +830,9 @@ mod tests {
             cases: Arc::new(RwLock::new(HashMap::new())),
             dispute_resolver: Arc::new(RwLock::new(DisputeResolver::new())),
             ai_judge: Arc::new(RwLock::new(JudicialAI::new())),
+            escrows: Arc::new(RwLock::new(HashMap::new())),
+            escrow_config: EscrowConfig::default(),
+            state: Arc::new(RwLock::new(QuantumStateManager::new())),
         };
 
         let case_id = "case-987";
@@ -148,6 +844,8 @@ mod tests {
             evidence: vec!["land_registry.json".to_string()],
             status: JudicialStatus::Open,
             verdict: None,
+            opened_at: Utc::now(),
+            resolved_at: None,
         };
 
         judiciary.cases.write().await.insert(case_id.to_string(), judicial_case);
@@ -156,6 +854,148 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    fn claimant() -> String {
+        Address::from_public_keys(b"claimant-dilithium", b"claimant-kyber").to_string()
+    }
+
+    fn respondent() -> String {
+        Address::from_public_keys(b"respondent-dilithium", b"respondent-kyber").to_string()
+    }
+
+    fn case_filed(case_id: &str) -> JudicialCase {
+        JudicialCase {
+            case_id: case_id.to_string(),
+            claimant: claimant(),
+            respondent: respondent(),
+            case_details: "Contract dispute".to_string(),
+            evidence: Vec::new(),
+            status: JudicialStatus::Open,
+            verdict: None,
+            opened_at: Utc::now(),
+            resolved_at: None,
+        }
+    }
+
+    /// A judiciary whose parties each hold `balance` in the state manager.
+    async fn funded_judiciary(balance: Amount) -> QuantumJudiciary {
+        let state = QuantumStateManager::new();
+        for party in [claimant(), respondent()] {
+            state.open_account(&party).unwrap();
+            state.update_balance(&party, balance).await.unwrap();
+        }
+        QuantumJudiciary::new(Arc::new(RwLock::new(state)))
+    }
+
+    async fn balance(judiciary: &QuantumJudiciary, address: &str) -> Amount {
+        judiciary.state.read().await.balance(address, &AssetId::native())
+    }
+
+    #[tokio::test]
+    async fn test_judiciary_metrics_track_case_lifecycle() {
+        let judiciary = funded_judiciary(Amount::from_whole(1_000)).await;
+        let now = Utc::now();
+
+        judiciary.open_case_at(case_filed("case-1"), now).await.unwrap();
+        judiciary.open_case_at(case_filed("case-2"), now).await.unwrap();
+        assert_eq!(judiciary.metrics().await.active_cases, 2);
+
+        judiciary.begin_review("case-1").await.unwrap();
+        assert_eq!(judiciary.metrics().await.active_cases, 2);
+
+        judiciary.give_verdict("case-1", "claimant prevails", Party::Claimant, now + chrono::Duration::hours(1)).await.unwrap();
+        let metrics = judiciary.metrics().await;
+        assert_eq!(metrics.active_cases, 1);
+        assert_eq!(metrics.resolved_cases, 1);
+
+        assert!(judiciary.give_verdict("case-2", "skipped review", Party::Claimant, now).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_avg_resolution_time_counts_resolved_cases_only() {
+        let judiciary = funded_judiciary(Amount::from_whole(1_000)).await;
+        let start = Utc::now();
+
+        for (case_id, hours) in [("fast", 2), ("slow", 6)] {
+            judiciary.open_case_at(case_filed(case_id), start).await.unwrap();
+            judiciary.begin_review(case_id).await.unwrap();
+            judiciary.give_verdict(case_id, "resolved", Party::Respondent, start + chrono::Duration::hours(hours)).await.unwrap();
+        }
+        // An old unresolved case must not drag the average.
+        judiciary.open_case_at(case_filed("pending"), start - chrono::Duration::days(30)).await.unwrap();
+
+        let metrics = judiciary.metrics().await;
+        assert_eq!(metrics.resolved_cases, 2);
+        assert_eq!(metrics.active_cases, 1);
+        assert_eq!(metrics.avg_resolution_time, 4.0 * 3600.0);
+    }
+
+    #[tokio::test]
+    async fn test_bonds_locked_at_filing() {
+        let judiciary = funded_judiciary(Amount::from_whole(1_000)).await;
+        let filed = Utc::now();
+        let mut backdated = case_filed("case-1");
+        backdated.opened_at = filed - chrono::Duration::days(365);
+        judiciary.open_case_at(backdated, filed).await.unwrap();
+
+        let escrow = judiciary.escrow("case-1").await.unwrap();
+        assert_eq!(escrow.claimant_bond, Amount::from_whole(100));
+        assert_eq!(escrow.respondent_bond, Amount::from_whole(100));
+        assert_eq!(escrow.filed_at, filed);
+        assert!(escrow.settlement.is_none());
+        assert_eq!(balance(&judiciary, &claimant()).await, Amount::from_whole(900));
+        assert_eq!(balance(&judiciary, &respondent()).await, Amount::from_whole(900));
+        assert_eq!(balance(&judiciary, &judiciary.escrow_config.escrow_account).await, Amount::from_whole(200));
+
+        let underfunded = funded_judiciary(Amount::from_whole(50)).await;
+        underfunded.state.read().await.update_balance(&claimant(), Amount::from_whole(1_000)).await.unwrap();
+        let result = underfunded.open_case(case_filed("case-2")).await;
+        assert!(matches!(result, Err(GovernanceError::InsufficientBond)));
+        assert_eq!(balance(&underfunded, &claimant()).await, Amount::from_whole(1_000));
+        assert_eq!(balance(&underfunded, &underfunded.escrow_config.escrow_account).await, Amount::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_escrow_awarded_to_winner_on_verdict() {
+        let judiciary = funded_judiciary(Amount::from_whole(1_000)).await;
+        judiciary.open_case(case_filed("case-1")).await.unwrap();
+        judiciary.begin_review("case-1").await.unwrap();
+
+        let settlement = judiciary.give_verdict("case-1", "respondent prevails", Party::Respondent, Utc::now()).await.unwrap();
+
+        // 2.5% of the 200 QFC pot goes to the protocol.
+        let fee = Amount::from_whole(5);
+        let payout = Amount::from_whole(195);
+        assert_eq!(settlement, EscrowSettlement::Awarded {
+            winner: respondent(),
+            payout,
+            protocol_fee: fee,
+        });
+        assert_eq!(balance(&judiciary, &respondent()).await, Amount::from_whole(1_095));
+        assert_eq!(balance(&judiciary, &claimant()).await, Amount::from_whole(900));
+        assert_eq!(balance(&judiciary, &judiciary.escrow_config.fee_account).await, fee);
+        assert_eq!(balance(&judiciary, &judiciary.escrow_config.escrow_account).await, Amount::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_escrow_refunded_on_timeout() {
+        let judiciary = funded_judiciary(Amount::from_whole(1_000)).await;
+        let filed = Utc::now();
+        judiciary.open_case_at(case_filed("stale"), filed).await.unwrap();
+        judiciary.open_case_at(case_filed("recent"), filed + chrono::Duration::days(20)).await.unwrap();
+
+        let expired = judiciary.expire_disputes_at(filed + chrono::Duration::days(31)).await.unwrap();
+        assert_eq!(expired, vec!["stale".to_string()]);
+
+        let escrow = judiciary.escrow("stale").await.unwrap();
+        assert!(matches!(escrow.settlement, Some(EscrowSettlement::Refunded { .. })));
+        assert_eq!(balance(&judiciary, &claimant()).await, Amount::from_whole(900));
+        assert_eq!(balance(&judiciary, &judiciary.escrow_config.escrow_account).await, Amount::from_whole(200));
+        assert_eq!(balance(&judiciary, &judiciary.escrow_config.fee_account).await, Amount::ZERO);
+
+        judiciary.begin_review("recent").await.unwrap();
+        assert!(judiciary.begin_review("stale").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_decentralized_governance_bond() {
         let mut treasury = DecentralizedGovernanceBonds {
@@ -170,6 +1010,8 @@ mod tests {
             amount: 10_000.0,
             maturity_date: Utc::now() + chrono::Duration::days(365),
             interest_rate: 0.05,
+            issued_at: Utc::now(),
+            transfers: Vec::new(),
         };
 
         treasury.bonds.write().await.insert(bond_id.to_string(), bond);
@@ -178,6 +1020,124 @@ mod tests {
         assert!(bond_registry.contains_key(bond_id));
     }
 
+    #[test]
+    fn test_bond_stored_without_issue_date_accrues_from_its_term() {
+        let maturity_date = Utc::now() + chrono::Duration::days(200);
+        let stored = serde_json::json!({
+            "bond_id": "bond-1",
+            "investor": "seller",
+            "amount": 1_000.0,
+            "maturity_date": maturity_date,
+            "interest_rate": 0.10,
+        });
+        let bond: GovernanceBond = serde_json::from_value(stored).unwrap();
+
+        assert_eq!(bond.issued_at, maturity_date - chrono::Duration::days(365));
+        assert!(bond.transfers.is_empty());
+        // 165 days in, not the decades since the epoch.
+        let accrued = bond.accrued_interest("seller", Utc::now());
+        assert!((accrued - 1_000.0 * 0.10 * 165.0 / 365.0).abs() < 0.01);
+    }
+
+    /// A one-year, 10% bond of 1,000 held by `seller`, issued at `issued_at`.
+    async fn bond_market(issued_at: DateTime<Utc>) -> DecentralizedGovernanceBonds {
+        let treasury = DecentralizedGovernanceBonds {
+            bonds: Arc::new(RwLock::new(HashMap::new())),
+            ai_treasury: Arc::new(RwLock::new(PolicyAI::default())),
+        };
+        treasury.bonds.write().await.insert("bond-1".to_string(), GovernanceBond {
+            bond_id: "bond-1".to_string(),
+            investor: "seller".to_string(),
+            amount: 1_000.0,
+            maturity_date: issued_at + chrono::Duration::days(365),
+            interest_rate: 0.10,
+            issued_at,
+            transfers: Vec::new(),
+        });
+        treasury
+    }
+
+    #[tokio::test]
+    async fn test_mid_term_bond_transfer() {
+        let issued_at = Utc::now() - chrono::Duration::days(100);
+        let treasury = bond_market(issued_at).await;
+
+        treasury.transfer_bond("bond-1", "seller", "buyer").await.unwrap();
+
+        let bonds = treasury.bonds.read().await;
+        let bond = &bonds["bond-1"];
+        assert_eq!(bond.investor, "buyer");
+        assert_eq!(bond.transfers.len(), 1);
+        assert_eq!(bond.transfers[0].from, "seller");
+        drop(bonds);
+
+        // Only the current holder can sell.
+        let resale = treasury.transfer_bond("bond-1", "seller", "third").await;
+        assert!(matches!(resale, Err(GovernanceError::NotBondHolder)));
+    }
+
+    #[tokio::test]
+    async fn test_interest_follows_holder_after_transfer() {
+        let issued_at = Utc::now();
+        let treasury = bond_market(issued_at).await;
+        let sold_at = issued_at + chrono::Duration::days(146);
+        treasury.transfer_bond_at("bond-1", "seller", "buyer", sold_at).await.unwrap();
+
+        let at_maturity = issued_at + chrono::Duration::days(365);
+        let seller = treasury.accrued_interest("bond-1", "seller", at_maturity).await.unwrap();
+        let buyer = treasury.accrued_interest("bond-1", "buyer", at_maturity).await.unwrap();
+        assert!((seller - 40.0).abs() < 1e-6);
+        assert!((buyer - 60.0).abs() < 1e-6);
+
+        // Nothing accrues to the buyer for the period before the sale.
+        assert_eq!(treasury.accrued_interest("bond-1", "buyer", sold_at).await.unwrap(), 0.0);
+        // Accrual stops at maturity.
+        let later = treasury.accrued_interest("bond-1", "buyer", at_maturity + chrono::Duration::days(30)).await.unwrap();
+        assert!((later - buyer).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_bond_transfer_after_maturity_is_rejected() {
+        let issued_at = Utc::now() - chrono::Duration::days(400);
+        let treasury = bond_market(issued_at).await;
+
+        let result = treasury.transfer_bond("bond-1", "seller", "buyer").await;
+        assert!(matches!(result, Err(GovernanceError::BondMatured)));
+        assert_eq!(treasury.bonds.read().await["bond-1"].investor, "seller");
+    }
+
+    #[test]
+    fn test_sigmoid_reward_rate_falls_as_stake_rises() {
+        let curve = RewardCurve::default();
+        let rates: Vec<f64> = (0..=10).map(|i| curve.rate(i as f64 / 10.0)).collect();
+
+        assert!(rates.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(rates[0] < 0.10 && rates[0] > 0.09);
+        assert!(rates[10] > 0.02 && rates[10] < 0.03);
+        assert!((curve.rate(0.67) - 0.06).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_linear_reward_curve_matches_configuration() {
+        let curve = RewardCurve::Linear { max_rate: 0.08, min_rate: 0.02 };
+
+        assert!((curve.rate(0.0) - 0.08).abs() < 1e-9);
+        assert!((curve.rate(0.5) - 0.05).abs() < 1e-9);
+        assert!((curve.rate(1.0) - 0.02).abs() < 1e-9);
+        // Out-of-range ratios are clamped.
+        assert!((curve.rate(1.5) - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_piecewise_reward_curve_interpolates() {
+        let curve = RewardCurve::Piecewise { points: vec![(0.2, 0.10), (0.6, 0.04), (0.8, 0.02)] };
+
+        assert_eq!(curve.rate(0.1), 0.10);
+        assert!((curve.rate(0.4) - 0.07).abs() < 1e-9);
+        assert!((curve.rate(0.7) - 0.03).abs() < 1e-9);
+        assert_eq!(curve.rate(0.9), 0.02);
+    }
+
     #[tokio::test]
     async fn test_dynamic_voting_reputation_scaling() {
         let mut governance = QuantumGovernance::new(GovernanceConfig::default()).await.unwrap();
@@ -197,6 +1157,183 @@ mod tests {
         assert!(vote_result.is_ok());
     }
 
+    async fn governance_with_rate_proposal(live_rate: f64, proposed_rate: f64) -> (QuantumGovernance, String) {
+        let mut governance = QuantumGovernance::new(GovernanceConfig::default()).await.unwrap();
+        governance.config.staking_rewards_rate = live_rate;
+        let proposal_id = governance.propose_parameter_change(
+            "user_6",
+            "Raise staking rewards",
+            "Increase the staking reward rate to attract validators",
+            ProposalCategory::Economic,
+            ParameterChange::StakingRewardsRate(proposed_rate),
+        ).await.unwrap();
+        (governance, proposal_id)
+    }
+
+    fn economy() -> SimulationConfig {
+        SimulationConfig { seed: 11, ..SimulationConfig::default() }
+    }
+
+    #[tokio::test]
+    async fn test_staking_rate_increase_projects_higher_outflow() {
+        let (governance, proposal_id) = governance_with_rate_proposal(0.05, 0.08).await;
+
+        let simulation = governance.simulate_proposal(&proposal_id, &economy(), 30).await.unwrap();
+
+        assert_eq!(simulation.projected.epochs.len(), 30);
+        assert!(simulation.projected.total_reward_outflow > simulation.baseline.total_reward_outflow);
+        assert!(simulation.projected.final_supply > simulation.baseline.final_supply);
+    }
+
+    #[tokio::test]
+    async fn test_simulation_leaves_live_state_untouched() {
+        let (governance, proposal_id) = governance_with_rate_proposal(0.05, 0.08).await;
+        let votes_before = {
+            let proposals = governance.proposals.read().await;
+            (proposals[&proposal_id].votes_for, proposals[&proposal_id].votes_against)
+        };
+
+        governance.simulate_proposal(&proposal_id, &economy(), 30).await.unwrap();
+
+        assert_eq!(governance.config.staking_rewards_rate, 0.05);
+        let proposals = governance.proposals.read().await;
+        assert_eq!((proposals[&proposal_id].votes_for, proposals[&proposal_id].votes_against), votes_before);
+        assert_eq!(proposals[&proposal_id].parameter_change, Some(ParameterChange::StakingRewardsRate(0.08)));
+    }
+
+    #[tokio::test]
+    async fn test_passed_proposal_updates_gas_multiplier() {
+        let mut governance = QuantumGovernance::new(GovernanceConfig::default()).await.unwrap();
+        let change = ParameterChange::GasMultiplier { operation_type: OperationType::DeployContract, multiplier: 2.0 };
+        let proposal_id = governance.propose_parameter_change(
+            "user_7",
+            "Price contract deployment",
+            "Double the gas charged for contract deployment",
+            ProposalCategory::Economic,
+            change,
+        ).await.unwrap();
+
+        let deadline = governance.proposals.read().await[&proposal_id].voting_deadline;
+        let after_deadline = deadline + chrono::Duration::seconds(1);
+        governance.config.quorum = 10;
+
+        governance.proposals.write().await.get_mut(&proposal_id).unwrap().votes_for = 10;
+        let early = governance.enact_parameter_change_at(&proposal_id, deadline - chrono::Duration::seconds(1)).await;
+        assert!(matches!(early, Err(GovernanceError::VotingStillOpen)));
+
+        governance.proposals.write().await.get_mut(&proposal_id).unwrap().votes_for = 4;
+        let thin = governance.enact_parameter_change_at(&proposal_id, after_deadline).await;
+        assert!(matches!(thin, Err(GovernanceError::QuorumNotReached)));
+
+        governance.proposals.write().await.get_mut(&proposal_id).unwrap().votes_against = 6;
+        let tied = governance.enact_parameter_change_at(&proposal_id, after_deadline).await;
+        assert!(matches!(tied, Err(GovernanceError::ProposalNotPassed)));
+
+        governance.proposals.write().await.get_mut(&proposal_id).unwrap().votes_for = 10;
+        governance.enact_parameter_change_at(&proposal_id, after_deadline).await.unwrap();
+
+        assert_eq!(governance.gas_multipliers().get(&OperationType::DeployContract), 2.0);
+        assert_eq!(governance.gas_multipliers().get(&OperationType::Transfer), 1.0);
+        let again = governance.enact_parameter_change_at(&proposal_id, after_deadline).await;
+        assert!(matches!(again, Err(GovernanceError::AlreadyEnacted)));
+    }
+
+    #[tokio::test]
+    async fn test_contract_rollback_enacts_the_proposed_version_once() {
+        let governance = QuantumGovernance::new(GovernanceConfig::default()).await.unwrap();
+        let mut law_enforcement = SmartLawEnforcement::new();
+        law_enforcement.contract_versions.record_amendment("policy-1", b"v0", b"v1".to_vec());
+        law_enforcement.contract_versions.record_amendment("policy-1", b"v1", b"bad".to_vec());
+
+        let change = ParameterChange::ContractRollback { policy_id: "policy-1".to_string(), version: 1 };
+        let proposal_id = governance.propose_parameter_change(
+            "user_8",
+            "Revert policy-1",
+            "Roll policy-1 back to its first amendment",
+            ProposalCategory::Security,
+            change,
+        ).await.unwrap();
+        governance.proposals.write().await.get_mut(&proposal_id).unwrap().votes_for = governance.config.quorum.max(1);
+        let after_deadline = governance.proposals.read().await[&proposal_id].voting_deadline + chrono::Duration::seconds(1);
+
+        governance.rollback_contract_at(&proposal_id, &mut law_enforcement, after_deadline).await.unwrap();
+        assert_eq!(law_enforcement.contract_versions.versions("policy-1").last().unwrap().code, b"v1");
+        assert!(governance.proposals.read().await[&proposal_id].enacted);
+
+        let result = governance.rollback_contract_at(&proposal_id, &mut law_enforcement, after_deadline).await;
+        assert!(matches!(result, Err(GovernanceError::AlreadyEnacted)));
+        assert_eq!(law_enforcement.contract_versions.current_version("policy-1"), Some(3));
+    }
+
+    #[test]
+    fn test_policy_enforced_once_succeeds() {
+        let mut registry = EnforcementRegistry::default();
+
+        assert!(!registry.is_enforced("ethereum", "policy-1"));
+        registry.reserve("ethereum", "policy-1").unwrap();
+        assert!(registry.is_enforced("ethereum", "policy-1"));
+    }
+
+    #[test]
+    fn test_duplicate_enforcement_is_rejected() {
+        let mut registry = EnforcementRegistry::default();
+        registry.reserve("ethereum", "policy-1").unwrap();
+
+        assert!(matches!(registry.reserve("ethereum", "policy-1"), Err(GovernanceError::AlreadyEnforced)));
+        // Confirming is idempotent and never reopens the policy.
+        assert!(!registry.confirm("ethereum", "policy-1"));
+        assert!(matches!(registry.reserve("ethereum", "policy-1"), Err(GovernanceError::AlreadyEnforced)));
+
+        // A failed execution releases the reservation for a retry.
+        registry.reserve("ethereum", "policy-2").unwrap();
+        registry.release("ethereum", "policy-2");
+        assert!(registry.reserve("ethereum", "policy-2").is_ok());
+    }
+
+    #[test]
+    fn test_enforcement_on_another_chain_is_allowed() {
+        let mut registry = EnforcementRegistry::default();
+        registry.reserve("ethereum", "policy-1").unwrap();
+
+        assert!(registry.reserve("polygon", "policy-1").is_ok());
+        assert!(registry.confirm("solana", "policy-1"));
+        assert!(registry.is_enforced("solana", "policy-1"));
+    }
+
+    #[test]
+    fn test_amendment_increments_version_and_keeps_history() {
+        let mut history = ContractVersionHistory::default();
+
+        assert_eq!(history.record_amendment("policy-1", b"v0", b"v1".to_vec()), 1);
+        assert_eq!(history.record_amendment("policy-1", b"v1", b"v2".to_vec()), 2);
+
+        assert_eq!(history.current_version("policy-1"), Some(2));
+        let codes: Vec<&[u8]> = history.versions("policy-1").iter().map(|v| v.code.as_slice()).collect();
+        assert_eq!(codes, vec![b"v0".as_slice(), b"v1", b"v2"]);
+    }
+
+    #[test]
+    fn test_rollback_restores_prior_code() {
+        let mut history = ContractVersionHistory::default();
+        history.record_amendment("policy-1", b"v0", b"v1".to_vec());
+        history.record_amendment("policy-1", b"v1", b"bad".to_vec());
+
+        assert_eq!(history.rollback("policy-1", 1).unwrap(), b"v1");
+        assert_eq!(history.current_version("policy-1"), Some(3));
+        assert_eq!(history.versions("policy-1").last().unwrap().code, b"v1");
+        assert_eq!(history.versions("policy-1")[2].code, b"bad");
+    }
+
+    #[test]
+    fn test_rollback_to_unknown_version_is_rejected() {
+        let mut history = ContractVersionHistory::default();
+        history.record_amendment("policy-1", b"v0", b"v1".to_vec());
+
+        assert!(matches!(history.rollback("policy-1", 5), Err(GovernanceError::UnknownContractVersion(5))));
+        assert!(matches!(history.rollback("policy-2", 0), Err(GovernanceError::UnknownContractVersion(0))));
+        assert_eq!(history.current_version("policy-1"), Some(1));
+    }
+
     #[tokio::test]
     async fn test_self_amend_contracts() {
         let mut law_enforcement = SmartLawEnforcement::new();
@@ -0,0 +1,222 @@
+use std::fmt;
+use std::iter::Sum;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::error::AmountError;
+
+/// Number of decimal places carried by one QFC.
+pub const DECIMALS: u32 = 9;
+/// Base units per whole QFC.
+pub const SCALE: u128 = 10u128.pow(DECIMALS);
+
+/// Float-free monetary value stored as an integer count of base units.
+///
+/// All arithmetic is checked; use `from_f64`/`to_f64` or the string forms only at API boundaries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Amount(u128);
+
+impl Amount {
+    pub const ZERO: Amount = Amount(0);
+    pub const MAX: Amount = Amount(u128::MAX);
+
+    pub const fn from_base_units(units: u128) -> Self {
+        Self(units)
+    }
+
+    pub const fn base_units(self) -> u128 {
+        self.0
+    }
+
+    pub const fn from_whole(qfc: u64) -> Self {
+        Self(qfc as u128 * SCALE)
+    }
+
+    /// Converts a decimal QFC value, rounding to the nearest base unit.
+    pub fn from_f64(value: f64) -> Result<Self, AmountError> {
+        if !value.is_finite() {
+            return Err(AmountError::NotFinite);
+        }
+        if value < 0.0 {
+            return Err(AmountError::Negative);
+        }
+
+        let units = (value * SCALE as f64).round();
+        if units >= u128::MAX as f64 {
+            return Err(AmountError::Overflow);
+        }
+        Ok(Self(units as u128))
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    pub fn to_le_bytes(self) -> [u8; 16] {
+        self.0.to_le_bytes()
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        self.0.checked_add(other.0).map(Amount)
+    }
+
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        self.0.checked_sub(other.0).map(Amount)
+    }
+
+    pub fn checked_mul(self, factor: u128) -> Option<Amount> {
+        self.0.checked_mul(factor).map(Amount)
+    }
+
+    pub fn checked_div(self, divisor: u128) -> Option<Amount> {
+        self.0.checked_div(divisor).map(Amount)
+    }
+
+    pub fn saturating_add(self, other: Amount) -> Amount {
+        Amount(self.0.saturating_add(other.0))
+    }
+
+    pub fn saturating_sub(self, other: Amount) -> Amount {
+        Amount(self.0.saturating_sub(other.0))
+    }
+
+    /// Sums amounts, returning `None` on overflow instead of wrapping.
+    pub fn checked_sum<I: IntoIterator<Item = Amount>>(amounts: I) -> Option<Amount> {
+        amounts.into_iter().try_fold(Amount::ZERO, Amount::checked_add)
+    }
+
+    /// Splits into one share per weight, in proportion to the weights. The
+    /// shares plus the remainder always add up to exactly `self`. With no
+    /// weight at all, the whole amount is the remainder.
+    pub fn split_weighted(self, weights: &[u128], policy: RoundingPolicy) -> Result<Split, AmountError> {
+        let total_weight = weights.iter().try_fold(0u128, |sum, w| sum.checked_add(*w)).ok_or(AmountError::Overflow)?;
+        if total_weight == 0 {
+            return Ok(Split { shares: vec![Amount::ZERO; weights.len()], remainder: self });
+        }
+
+        // share = floor(amount * weight / total), computed without overflowing the product.
+        let (quotient, rest) = (self.0 / total_weight, self.0 % total_weight);
+        let mut shares = Vec::with_capacity(weights.len());
+        let mut fractions = Vec::with_capacity(weights.len());
+        for weight in weights {
+            let scaled = rest.checked_mul(*weight).ok_or(AmountError::Overflow)?;
+            shares.push(quotient * weight + scaled / total_weight);
+            fractions.push(scaled % total_weight);
+        }
+        let mut remainder = self.0 - shares.iter().sum::<u128>();
+
+        if policy == RoundingPolicy::LargestRemainder {
+            let mut order: Vec<usize> = (0..weights.len()).collect();
+            order.sort_by(|a, b| fractions[*b].cmp(&fractions[*a]).then(a.cmp(b)));
+            for index in order.into_iter().take(remainder as usize) {
+                shares[index] += 1;
+                remainder -= 1;
+            }
+        }
+
+        Ok(Split { shares: shares.into_iter().map(Amount).collect(), remainder: Amount(remainder) })
+    }
+}
+
+/// What happens to the base units left over when a split doesn't divide evenly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundingPolicy {
+    /// Every share rounds down; the leftover is returned as the remainder for a sink such as the treasury.
+    #[default]
+    FloorToSink,
+    /// The leftover goes one unit at a time to the shares with the largest
+    /// fractional parts, earlier recipients first on ties, leaving no remainder.
+    LargestRemainder,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+    pub shares: Vec<Amount>,
+    pub remainder: Amount,
+}
+
+impl Sum for Amount {
+    /// Panics on overflow; prefer `Amount::checked_sum` for untrusted inputs.
+    fn sum<I: Iterator<Item = Amount>>(iter: I) -> Self {
+        Amount::checked_sum(iter).expect("Amount overflow")
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:0width$}", self.0 / SCALE, self.0 % SCALE, width = DECIMALS as usize)
+    }
+}
+
+impl FromStr for Amount {
+    type Err = AmountError;
+
+    /// Parses a decimal string such as `"12.5"` exactly, without going through `f64`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('-') {
+            return Err(AmountError::Negative);
+        }
+
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(AmountError::InvalidFormat);
+        }
+        if fraction.len() > DECIMALS as usize {
+            return Err(AmountError::TooManyDecimals);
+        }
+
+        let parse = |digits: &str| -> Result<u128, AmountError> {
+            if digits.is_empty() {
+                return Ok(0);
+            }
+            if !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(AmountError::InvalidFormat);
+            }
+            digits.parse::<u128>().map_err(|_| AmountError::Overflow)
+        };
+
+        let whole_units = parse(whole)?.checked_mul(SCALE).ok_or(AmountError::Overflow)?;
+        let fraction_units = parse(fraction)? * 10u128.pow(DECIMALS - fraction.len() as u32);
+        whole_units.checked_add(fraction_units).map(Amount).ok_or(AmountError::Overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_amounts_sum_exactly() {
+        let tenth: Amount = "0.1".parse().unwrap();
+        let total: Amount = std::iter::repeat(tenth).take(10).sum();
+        assert_eq!(total, Amount::from_whole(1));
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let amount: Amount = "12.000000001".parse().unwrap();
+        assert_eq!(amount.base_units(), 12 * SCALE + 1);
+        assert_eq!(amount.to_string(), "12.000000001");
+        assert!(matches!("0.0000000001".parse::<Amount>(), Err(AmountError::TooManyDecimals)));
+        assert!(matches!(Amount::from_f64(-1.0), Err(AmountError::Negative)));
+    }
+
+    #[test]
+    fn test_weighted_split_is_exact() {
+        let pool = Amount::from_base_units(100);
+        let floor = pool.split_weighted(&[1, 1, 1], RoundingPolicy::FloorToSink).unwrap();
+        assert_eq!(floor.shares, vec![Amount::from_base_units(33); 3]);
+        assert_eq!(floor.remainder, Amount::from_base_units(1));
+
+        let largest = pool.split_weighted(&[2, 3, 5], RoundingPolicy::LargestRemainder).unwrap();
+        assert_eq!(largest.shares.iter().copied().sum::<Amount>(), pool);
+        assert!(largest.remainder.is_zero());
+
+        let uneven = Amount::from_base_units(10).split_weighted(&[1, 1, 1], RoundingPolicy::LargestRemainder).unwrap();
+        assert_eq!(uneven.shares, vec![Amount::from_base_units(4), Amount::from_base_units(3), Amount::from_base_units(3)]);
+    }
+}
@@ -5,8 +5,11 @@ use blake3::Hash;
 use quantumfuse_sdk::{
     transaction::Transaction,
     error::BlockError,
-    crypto::{QuantumMerkleTree, AESGCM},
+    crypto::{AESGCM, KeyPair},
+    codec::to_canonical_cbor,
+    merkle::merkle_root,
     consensus::{ConsensusData, ValidatorSet, QuantumBridge},
+    consensus_mechanism::{check_signing_stake, ConsensusConfig, TimeoutCertificate},
     pqc::dilithium::{PublicKey, SecretKey, Signature},
     ai::BlockOptimizer,
     explorer::BlockTrackerAPI,
@@ -21,6 +24,20 @@ pub struct QuantumBlock {
     pub quantum_random_beacon: Vec<u8>,
     pub multi_signatures: HashMap<String, Signature>, // Multi-Sig Support
     pub ai_prediction: f64, // AI Predicted Block Finalization Time
+    /// View of the height this block was proposed in; selects the proposer.
+    #[serde(default)]
+    pub view: u32,
+    /// Required when `view` is above 0.
+    #[serde(default)]
+    pub timeout_certificate: Option<TimeoutCertificate>,
+    /// Signed inclusion lists binding the next block.
+    #[serde(default)]
+    pub inclusion_lists: Vec<InclusionList>,
+    /// Validator that proposed the block. `None` only for genesis.
+    #[serde(default)]
+    pub proposer: Option<String>,
+    #[serde(default)]
+    pub signature: Option<Signature>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +67,52 @@ pub struct BlockMetadata {
     pub predicted_finalization: f64, // AI-Powered Bottleneck Detection
 }
 
+/// Most transactions a single inclusion list may require.
+pub const MAX_INCLUSION_LIST_LEN: usize = 16;
+
+/// Transactions an attester requires in the block at `height`. The list rides
+/// in the block before, so every node judges `height` against the same lists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InclusionList {
+    pub height: u64,
+    pub attester: String,
+    pub transactions: Vec<Transaction>,
+    #[serde(default)]
+    pub signature: Option<Signature>,
+}
+
+impl InclusionList {
+    /// Signs the list as `keypair`, which becomes its attester.
+    pub fn sign(&mut self, keypair: &KeyPair) -> Result<(), BlockError> {
+        self.attester = keypair.address();
+        self.signature = Some(keypair.sign(&self.signing_message())?);
+        Ok(())
+    }
+
+    /// The target height and the required transaction hashes.
+    pub fn signing_message(&self) -> Vec<u8> {
+        let mut hasher = blake3::Hasher::new_derive_key("quantumfuse inclusion list v1");
+        hasher.update(&self.height.to_le_bytes());
+        for transaction in &self.transactions {
+            hasher.update(transaction.hash.as_bytes());
+        }
+        hasher.finalize().as_bytes().to_vec()
+    }
+
+    /// At most `MAX_INCLUSION_LIST_LEN` transactions, signed by `attester`'s key.
+    pub fn verify(&self) -> Result<(), BlockError> {
+        if self.transactions.len() > MAX_INCLUSION_LIST_LEN {
+            return Err(BlockError::InclusionListTooLong(self.transactions.len()));
+        }
+        let signature = self.signature.as_ref().ok_or(BlockError::InvalidSignature)?;
+        let public_key = PublicKey::from_address(&self.attester).map_err(|_| BlockError::InvalidSignature)?;
+        if !public_key.verify(&self.signing_message(), signature)? {
+            return Err(BlockError::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
 impl QuantumBlock {
     pub fn new(
         parent_hash: Hash,
@@ -63,8 +126,7 @@ impl QuantumBlock {
             return Err(BlockError::EmptyTransactions);
         }
 
-        let merkle_tree = QuantumMerkleTree::new(&transactions)?;
-        let transactions_root = merkle_tree.root();
+        let transactions_root = merkle_root(&transactions)?;
 
         let quantum_random_beacon = Self::generate_quantum_randomness()?;
         let predicted_finalization = optimizer.predict_finalization_time(&transactions)?;
@@ -91,14 +153,19 @@ impl QuantumBlock {
             quantum_random_beacon,
             multi_signatures: HashMap::new(),
             ai_prediction: predicted_finalization,
+            view: 0,
+            timeout_certificate: None,
+            inclusion_lists: Vec::new(),
+            proposer: None,
+            signature: None,
         })
     }
 
-    pub fn validate(&self, tracker: &BlockTrackerAPI) -> Result<bool, BlockError> {
+    /// `stakes` is the active validator set's stake by validator id.
+    pub fn validate(&self, tracker: &BlockTrackerAPI, consensus: &ConsensusConfig, stakes: &HashMap<String, f64>) -> Result<bool, BlockError> {
         self.validate_basics()?;
 
-        let merkle_tree = QuantumMerkleTree::new(&self.transactions)?;
-        if merkle_tree.root() != self.header.transactions_root {
+        if merkle_root(&self.transactions)? != self.header.transactions_root {
             return Err(BlockError::InvalidTransactionsRoot);
         }
 
@@ -110,9 +177,7 @@ impl QuantumBlock {
 
         self.validate_consensus_data()?;
 
-        if !self.validate_multi_signatures()? {
-            return Err(BlockError::InvalidMultiSignature);
-        }
+        self.validate_multi_signatures(consensus, stakes)?;
 
         tracker.log_block_validation(&self.header)?;
         Ok(true)
@@ -126,6 +191,25 @@ impl QuantumBlock {
         Ok(())
     }
 
+    /// Signs the block as its proposer; `keypair.address()` becomes the proposer.
+    pub fn sign_as_proposer(&mut self, keypair: &KeyPair) -> Result<(), BlockError> {
+        self.proposer = Some(keypair.address());
+        let message = self.proposer_signing_message()?;
+        self.signature = Some(keypair.sign(&message)?);
+        Ok(())
+    }
+
+    /// The header, view, carried inclusion lists and proposer, so a signature
+    /// can't be moved to another block or claimed by another validator.
+    pub fn proposer_signing_message(&self) -> Result<Vec<u8>, BlockError> {
+        let mut hasher = blake3::Hasher::new_derive_key("quantumfuse block proposer v1");
+        hasher.update(&to_canonical_cbor(&self.header).map_err(|_| BlockError::SerializationError)?);
+        hasher.update(&self.view.to_le_bytes());
+        hasher.update(&to_canonical_cbor(&self.inclusion_lists).map_err(|_| BlockError::SerializationError)?);
+        hasher.update(self.proposer.as_deref().unwrap_or_default().as_bytes());
+        Ok(hasher.finalize().as_bytes().to_vec())
+    }
+
     pub fn execute(&self) -> Result<BlockExecutionResult, BlockError> {
         let mut execution_result = BlockExecutionResult::new(self.header.height);
 
@@ -153,22 +237,33 @@ impl QuantumBlock {
         }
     }
 
-    fn validate_multi_signatures(&self) -> Result<bool, BlockError> {
-        let required_signatures = (self.validator_set.validators.len() as f64 * 0.67).ceil() as usize;
-        if self.multi_signatures.len() < required_signatures {
-            return Err(BlockError::NotEnoughSignatures);
-        }
+    /// Requires the multi-signers to hold more than `1 - fault_tolerance` of
+    /// the validator stake, as configured in `consensus`.
+    pub fn check_signing_stake(&self, consensus: &ConsensusConfig, stakes: &HashMap<String, f64>) -> Result<(), BlockError> {
+        check_signing_stake(consensus, self.multi_signatures.keys(), stakes)
+            .map_err(|_| BlockError::NotEnoughSignatures)
+    }
 
-        for (validator_id, signature) in &self.multi_signatures {
-            let public_key = self.validator_set.get_validator_key(signature)?;
-            let message = self.compute_signing_root()?;
+    /// Adds `keypair`'s signature over the signing root, under its address.
+    pub fn attest(&mut self, keypair: &KeyPair) -> Result<(), BlockError> {
+        let message = self.compute_signing_root()?;
+        self.multi_signatures.insert(keypair.address(), keypair.sign(&message)?);
+        Ok(())
+    }
 
+    /// Verifies every multi-signature over the signing root against the key of
+    /// the validator address it is filed under, then applies
+    /// `check_signing_stake`, so a listed name without its signature counts nothing.
+    pub fn validate_multi_signatures(&self, consensus: &ConsensusConfig, stakes: &HashMap<String, f64>) -> Result<(), BlockError> {
+        let message = self.compute_signing_root()?;
+        for (validator_id, signature) in &self.multi_signatures {
+            let public_key = PublicKey::from_address(validator_id).map_err(|_| BlockError::InvalidSignature)?;
             if !public_key.verify(&message, signature)? {
                 return Err(BlockError::InvalidSignature);
             }
         }
 
-        Ok(true)
+        self.check_signing_stake(consensus, stakes)
     }
 
     fn generate_quantum_randomness() -> Result<Vec<u8>, BlockError> {
@@ -1,22 +1,47 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use log::{warn, Level};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use quantumfuse_sdk::{
-    error::BlockchainError,
-    block::{QuantumBlock, BlockHeader},
-    transaction::QuantumTransaction,
-    state::QuantumStateManager,
-    shard::QuantumShard,
+    error::{BlockchainError, TransactionError},
+    block::{QuantumBlock, BlockHeader, InclusionList},
+    transaction::{QuantumTransaction, FrozenAccounts, ComplianceApproval, OperationType, FeeSplit, GasMultipliers, GasRefundPolicy, GasSettlement, TransactionLimits, CallOutcome, ContractExecutor, schedule_parallel, MAX_FUTURE_DRIFT_SECS},
+    state::{QuantumStateManager, StateHistoryConfig, TransactionReceipt, BlockUndo, ReceiptHub},
+    shard::{QuantumShard, ShardAllocator, ReshardProgress, address_key, shard_for_address},
     consensus::{ConsensusEngine, ValidatorSet, QuantumBridge},
+    consensus_mechanism::{select_proposer, ConsensusConfig},
     crypto::{Hash, KeyPair, AESGCM},
     pqc::dilithium::{PublicKey, SecretKey, Signature},
     pqc::kyber512::{KyberCiphertext, KyberKeyPair},
     metrics::ChainMetrics,
     ai::SmartContractOptimizer,
+    amount::Amount,
+    redaction::redactor,
 };
 
+/// Lock acquisition order. A method that needs several of these locks at once
+/// must take them top to bottom and must never acquire a lock listed above
+/// one it already holds:
+///
+/// 1. `commit_lock`
+/// 2. `blocks`
+/// 3. `shard_manager`
+/// 4. `state_manager`
+/// 5. `frozen_accounts`
+/// 6. `consensus_engine`
+/// 7. `metrics`
+///
+/// `quantum_bridge`, `receipts`, `pending_inclusion_lists` and `gas_multipliers` are
+/// never held together with any other lock.
 #[derive(Debug)]
 pub struct QuantumBlockchain {
     pub blocks: Arc<RwLock<Vec<QuantumBlock>>>,
@@ -25,6 +50,22 @@ pub struct QuantumBlockchain {
     pub consensus_engine: Arc<RwLock<ConsensusEngine>>,
     pub quantum_bridge: Arc<RwLock<QuantumBridge>>,
     pub metrics: Arc<RwLock<ChainMetrics>>,
+    pub frozen_accounts: Arc<RwLock<FrozenAccounts>>,
+    receipts: Arc<RwLock<ReceiptStore>>,
+    /// Signed inclusion lists by target height, waiting for this node to carry
+    /// them in a block it proposes. Validity never depends on this pool.
+    pending_inclusion_lists: Arc<RwLock<BTreeMap<u64, Vec<InclusionList>>>>,
+    /// Gas pricing per operation type, as last enacted by governance.
+    gas_multipliers: Arc<RwLock<GasMultipliers>>,
+    /// Meters contract calls; block gas is measured with it, never read from transactions.
+    contract_executor: Arc<dyn ContractExecutor>,
+    /// Deals validators over `shard_manager` at each epoch boundary, when set.
+    shard_allocator: Option<Arc<ShardAllocator>>,
+    commit_lock: Arc<Mutex<()>>,
+    verification_pool: Arc<ThreadPool>,
+    identity: ChainIdentity,
+    /// Shards transactions are routed over; starts at `config.shard_count` and follows `reshard`.
+    shard_count: Arc<AtomicU64>,
     pub config: BlockchainConfig,
 }
 
@@ -39,19 +80,230 @@ pub struct BlockchainConfig {
     pub minimum_stake: f64,
     pub quantum_security_level: u8,
     pub shard_count: u64,
+    pub min_fee: Amount,
+    pub fee_split: FeeSplit,
+    #[serde(default)]
+    pub gas_refund: GasRefundPolicy,
+    /// Run each `add_block` critical section under a single mutex.
+    pub serialize_block_commits: bool,
+    /// Threads used to verify transaction signatures; 0 uses one per core.
+    pub signature_verification_threads: usize,
+    /// Deepest rollback below the current tip that fork choice will accept.
+    pub max_reorg_depth: u64,
+    /// Number of recent blocks whose median timestamp a new block must exceed.
+    pub median_time_span: usize,
+    pub transaction_limits: TransactionLimits,
+    /// Signing-stake threshold blocks are held to.
+    pub consensus: ConsensusConfig,
+    #[serde(default)]
+    pub receipt_retention: ReceiptRetention,
+    /// Checkpointing and retention of historical balances.
+    #[serde(default)]
+    pub state_history: StateHistoryConfig,
+    #[serde(default)]
+    pub max_clock_skew: ClockSkew,
+    #[serde(default)]
+    pub genesis: GenesisConfig,
+    #[serde(default)]
+    pub block_production: BlockProductionConfig,
+}
+
+/// Everything that goes into the genesis block. Nodes sharing this (and the
+/// network parameters) build byte-identical genesis blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenesisConfig {
+    pub timestamp: DateTime<Utc>,
+    /// Root of the pre-allocated genesis state.
+    pub state_root: Hash,
+    pub extra_data: Vec<u8>,
+    /// Initial validator set: stake by validator address.
+    pub validators: BTreeMap<String, u64>,
+}
+
+impl Default for GenesisConfig {
+    fn default() -> Self {
+        Self {
+            timestamp: DateTime::UNIX_EPOCH,
+            state_root: Hash::default(),
+            extra_data: Vec::new(),
+            validators: BTreeMap::new(),
+        }
+    }
+}
+
+/// How far ahead of the local clock a timestamp may be before it is rejected,
+/// to tolerate skew between honest nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockSkew {
+    pub block_secs: i64,
+    pub transaction_secs: i64,
+}
+
+impl Default for ClockSkew {
+    fn default() -> Self {
+        Self {
+            block_secs: MAX_BLOCK_FUTURE_DRIFT_SECS,
+            transaction_secs: MAX_FUTURE_DRIFT_SECS,
+        }
+    }
+}
+
+// 🔹 **Block Production Pacing**
+/// When producers may seal a block; the target spacing is `block_time`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockProductionConfig {
+    /// Seal an empty block after this long without one, so the chain keeps
+    /// advancing when idle. `None` never produces empty blocks.
+    pub empty_block_heartbeat: Option<Duration>,
+}
+
+impl Default for BlockProductionConfig {
+    fn default() -> Self {
+        Self {
+            empty_block_heartbeat: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// Decides when a producer should seal the next block: never sooner than
+/// `block_time` after the previous one, and only with pending transactions
+/// unless the heartbeat is due.
+#[derive(Debug, Clone)]
+pub struct BlockPacer {
+    block_time: Duration,
+    config: BlockProductionConfig,
+    last_block: Instant,
+}
+
+impl BlockPacer {
+    pub fn new(block_time_secs: u64, config: BlockProductionConfig, now: Instant) -> Self {
+        Self {
+            block_time: Duration::from_secs(block_time_secs),
+            config,
+            last_block: now,
+        }
+    }
+
+    pub fn should_produce(&self, pending_transactions: usize, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_block);
+        if elapsed < self.block_time {
+            return false;
+        }
+        pending_transactions > 0 || self.config.empty_block_heartbeat.is_some_and(|heartbeat| elapsed >= heartbeat)
+    }
+
+    /// Starts the next interval from the block's production time, not from
+    /// when it was due, so a late block doesn't let the next one follow early.
+    pub fn record_block(&mut self, now: Instant) {
+        self.last_block = now;
+    }
+
+    /// How long a producer can sleep before `should_produce` may change.
+    pub fn time_until_next(&self, pending_transactions: usize, now: Instant) -> Option<Duration> {
+        let due = if pending_transactions > 0 {
+            self.block_time
+        } else {
+            self.block_time.max(self.config.empty_block_heartbeat?)
+        };
+        Some((self.last_block + due).saturating_duration_since(now))
+    }
+}
+
+/// How often an idle producer re-checks for pending transactions once a
+/// block is due.
+const PRODUCER_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A source of blocks for `spawn_block_producer`.
+#[async_trait]
+pub trait BlockProducer: Send + Sync {
+    async fn pending_transactions(&self) -> usize;
+
+    /// Seals and commits the next block from the pending transactions.
+    async fn produce_block(&self) -> Result<(), BlockchainError>;
+}
+
+/// Produces blocks whenever a `BlockPacer` allows one, until the task is
+/// aborted. A failed block still starts a new interval, so the producer
+/// retries after `block_time` rather than spinning.
+pub fn spawn_block_producer(
+    producer: Arc<dyn BlockProducer>,
+    block_time_secs: u64,
+    config: BlockProductionConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut pacer = BlockPacer::new(block_time_secs, config, tokio::time::Instant::now().into_std());
+        loop {
+            let now = tokio::time::Instant::now().into_std();
+            let pending = producer.pending_transactions().await;
+            if pacer.should_produce(pending, now) {
+                if let Err(e) = producer.produce_block().await {
+                    warn!("Block production failed: {:?}", e);
+                }
+                pacer.record_block(now);
+                continue;
+            }
+            // Wake when the next block is due, then poll so transactions that
+            // arrive while idle don't wait for the heartbeat.
+            let wait = match pacer.time_until_next(1, now) {
+                Some(wait) if !wait.is_zero() => wait,
+                _ => PRODUCER_POLL_INTERVAL,
+            };
+            tokio::time::sleep(wait).await;
+        }
+    })
+}
+
+// 🔹 **Contract Metering**
+/// Executor used until a contract VM is attached with
+/// `QuantumBlockchain::with_contract_executor`. Nothing is metered, so contract
+/// calls pay their whole gas limit and get no refund.
+#[derive(Debug)]
+pub struct UnmeteredContracts;
+
+impl ContractExecutor for UnmeteredContracts {
+    fn dry_run(&self, _transaction: &QuantumTransaction, _gas_limit: u64) -> CallOutcome {
+        CallOutcome::OutOfGas
+    }
+}
+
+// 🔹 **Inclusion Lists**
+/// Hashes of `required` transactions missing from `block`. Nothing counts as
+/// omitted once the block holds `max_transactions`.
+pub fn omitted_inclusions(block: &QuantumBlock, required: &[QuantumTransaction], max_transactions: usize) -> Vec<Hash> {
+    if block.transactions.len() >= max_transactions {
+        return Vec::new();
+    }
+    let included: HashSet<Hash> = block.transactions.iter().map(|tx| tx.hash).collect();
+    required.iter()
+        .map(|tx| tx.hash)
+        .filter(|hash| !included.contains(hash))
+        .collect()
 }
 
 impl QuantumBlockchain {
     pub async fn new(config: BlockchainConfig) -> Result<Self, BlockchainError> {
         let genesis_block = Self::create_genesis_block(&config)?;
+        let verification_pool = build_verification_pool(config.signature_verification_threads)?;
 
         let blockchain = Self {
             blocks: Arc::new(RwLock::new(vec![genesis_block])),
-            state_manager: Arc::new(RwLock::new(QuantumStateManager::new())),
+            state_manager: Arc::new(RwLock::new(QuantumStateManager::with_history_config(config.state_history.clone()))),
             shard_manager: Arc::new(RwLock::new(HashMap::new())),
             consensus_engine: Arc::new(RwLock::new(ConsensusEngine::new(config.clone()))),
             quantum_bridge: Arc::new(RwLock::new(QuantumBridge::new())),
             metrics: Arc::new(RwLock::new(ChainMetrics::default())),
+            frozen_accounts: Arc::new(RwLock::new(FrozenAccounts::default())),
+            receipts: Arc::new(RwLock::new(ReceiptStore::new(config.receipt_retention.clone()))),
+            pending_inclusion_lists: Arc::new(RwLock::new(BTreeMap::new())),
+            gas_multipliers: Arc::new(RwLock::new(GasMultipliers::default())),
+            contract_executor: Arc::new(UnmeteredContracts),
+            shard_allocator: None,
+            commit_lock: Arc::new(Mutex::new(())),
+            verification_pool: Arc::new(verification_pool),
+            identity: ChainIdentity::from_config(&config),
+            shard_count: Arc::new(AtomicU64::new(config.shard_count)),
             config,
         };
 
@@ -60,27 +312,235 @@ impl QuantumBlockchain {
         Ok(blockchain)
     }
 
+    pub fn with_contract_executor(mut self, executor: Arc<dyn ContractExecutor>) -> Self {
+        self.contract_executor = executor;
+        self
+    }
+
+    /// Reassigns shard validators through `allocator`, which must manage this
+    /// chain's `shard_manager`, whenever a block closes an epoch.
+    pub fn with_shard_allocator(mut self, allocator: Arc<ShardAllocator>) -> Result<Self, BlockchainError> {
+        if !allocator.manages(&self.shard_manager) {
+            return Err(BlockchainError::Internal("allocator does not manage this chain's shards".to_string()));
+        }
+        self.shard_allocator = Some(allocator);
+        Ok(self)
+    }
+
     pub async fn add_block(&self, block: QuantumBlock) -> Result<(), BlockchainError> {
+        let _commit_guard = match self.config.serialize_block_commits {
+            true => Some(self.commit_lock.lock().await),
+            false => None,
+        };
+
+        self.commit_block(block).await
+    }
+
+    /// Built only from `config`; no clock or randomness is read.
+    fn create_genesis_block(config: &BlockchainConfig) -> Result<QuantumBlock, BlockchainError> {
+        let genesis = &config.genesis;
+        let validator_set = ValidatorSet::from_stakes(
+            genesis.validators.iter().map(|(validator, stake)| (validator.clone(), *stake as f64)),
+        );
+        let beacon = genesis_hash(config);
+
+        let header = BlockHeader {
+            version: 1,
+            height: 0,
+            prev_hash: Hash::default(),
+            timestamp: genesis.timestamp,
+            transactions_root: Hash::default(),
+            state_root: genesis.state_root,
+            receipts_root: Hash::default(),
+            quantum_state_hash: Hash::default(),
+            validator_set_hash: validator_set.compute_hash().map_err(|e| BlockchainError::Internal(e.to_string()))?,
+            beacon_randomness: beacon,
+            extra_data: genesis.extra_data.clone(),
+        };
+
+        Ok(QuantumBlock {
+            header,
+            transactions: Vec::new(),
+            consensus_data: Default::default(),
+            validator_set,
+            quantum_random_beacon: beacon.as_bytes().to_vec(),
+            multi_signatures: HashMap::new(),
+            ai_prediction: 0.0,
+            view: 0,
+            timeout_certificate: None,
+            inclusion_lists: Vec::new(),
+            proposer: None,
+            signature: None,
+        })
+    }
+
+    /// Network id and genesis hash that peers must share with this node.
+    pub fn identity(&self) -> &ChainIdentity {
+        &self.identity
+    }
+
+    /// Refuses a peer that is on another network or started from another genesis.
+    pub fn check_peer(&self, peer: &ChainIdentity) -> Result<(), BlockchainError> {
+        if peer.network_id != self.identity.network_id {
+            warn!("Refusing peer on network {} (local network {})", peer.network_id, self.identity.network_id);
+            return Err(BlockchainError::NetworkMismatch {
+                expected: self.identity.network_id,
+                actual: peer.network_id,
+            });
+        }
+        if peer.genesis_hash != self.identity.genesis_hash {
+            warn!("Refusing peer with a different genesis on network {}", peer.network_id);
+            return Err(BlockchainError::GenesisMismatch);
+        }
+        Ok(())
+    }
+
+    /// `add_block` for blocks received from `peer`, which must share this chain's identity.
+    pub async fn add_peer_block(&self, peer: &ChainIdentity, block: QuantumBlock) -> Result<(), BlockchainError> {
+        self.check_peer(peer)?;
+        self.add_block(block).await
+    }
+
+    /// Switches to `branch`, which forks off after `common_ancestor`, if it is
+    /// longer than the current chain. Returns whether the switch happened.
+    pub async fn reorganize(&self, common_ancestor: u64, branch: Vec<QuantumBlock>) -> Result<bool, BlockchainError> {
+        let _commit_guard = self.commit_lock.lock().await;
+
+        {
+            let blocks = self.blocks.read().await;
+            let tip = blocks.last().map(|b| b.header.height).unwrap_or_default();
+            check_reorg_depth(tip, common_ancestor, self.config.max_reorg_depth)?;
+
+            let branch_tip = common_ancestor + branch.len() as u64;
+            if branch_tip <= tip {
+                return Ok(false);
+            }
+        }
+        // blocks is released before state_manager is taken, per the lock order.
+        // The commit lock keeps the chain unchanged in between.
+        let checkpoint = self.state_manager.read().await.begin_reorg(common_ancestor)?;
+        let abandoned = {
+            let mut blocks = self.blocks.write().await;
+            let fork_point = blocks.iter().position(|b| b.header.height > common_ancestor).unwrap_or(blocks.len());
+            blocks.split_off(fork_point)
+        };
+        let saved = self.snapshot_chain_state().await;
+        self.receipts.write().await.truncate_above(common_ancestor);
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.total_blocks = metrics.total_blocks.saturating_sub(abandoned.len() as u64);
+            let abandoned_transactions: u64 = abandoned.iter().map(|b| b.transactions.len() as u64).sum();
+            metrics.total_transactions = metrics.total_transactions.saturating_sub(abandoned_transactions);
+        }
+
+        // Each branch block is validated against the chain up to its parent;
+        // if any fails, the abandoned blocks, state and everything in `saved`
+        // are put back.
+        let mut new_tip = common_ancestor;
+        for block in branch {
+            new_tip = block.header.height;
+            if let Err(e) = self.commit_block(block).await {
+                warn!("Reorg past height {} failed at {}; restoring previous chain", common_ancestor, new_tip);
+                {
+                    let mut blocks = self.blocks.write().await;
+                    blocks.retain(|b| b.header.height <= common_ancestor);
+                    blocks.extend(abandoned);
+                }
+                self.state_manager.read().await.abort_reorg(checkpoint)?;
+                self.restore_chain_state(saved).await;
+                return Err(e);
+            }
+        }
+
+        self.state_manager.read().await.finish_reorg(checkpoint, new_tip).await?;
+        Ok(true)
+    }
+
+    /// Each lock is taken and released on its own, so the lock order doesn't apply.
+    async fn snapshot_chain_state(&self) -> ChainStateSnapshot {
+        ChainStateSnapshot {
+            receipts: self.receipts.read().await.clone(),
+            frozen_accounts: self.frozen_accounts.read().await.clone(),
+            inclusion_lists: self.pending_inclusion_lists.read().await.clone(),
+            gas_multipliers: self.gas_multipliers.read().await.clone(),
+            metrics: self.metrics.read().await.clone(),
+        }
+    }
+
+    async fn restore_chain_state(&self, saved: ChainStateSnapshot) {
+        *self.receipts.write().await = saved.receipts;
+        *self.frozen_accounts.write().await = saved.frozen_accounts;
+        *self.pending_inclusion_lists.write().await = saved.inclusion_lists;
+        *self.gas_multipliers.write().await = saved.gas_multipliers;
+        *self.metrics.write().await = saved.metrics;
+    }
+
+    async fn commit_block(&self, block: QuantumBlock) -> Result<(), BlockchainError> {
         let validation_result = self.validate_block(&block).await?;
         if !validation_result.is_valid {
             return Err(BlockchainError::InvalidBlock(validation_result.error.unwrap_or_default()));
         }
 
         self.process_block_transactions(&block).await?;
-        self.update_chain_state(&block, validation_result.new_state_root).await?;
+        self.update_chain_state(&block).await?;
         self.update_metrics(&block).await?;
+        self.rotate_shard_validators(&block).await;
 
-        let mut blocks = self.blocks.write().await;
-        blocks.push(block);
+        let height = block.header.height;
+        let receipts: Vec<TransactionReceipt> = block.transactions.iter().enumerate().map(|(index, tx)| TransactionReceipt {
+            transaction_hash: tx.hash,
+            block_height: height,
+            index,
+            from: tx.from.clone(),
+            to: tx.to.clone(),
+        }).collect();
+
+        self.blocks.write().await.push(block);
+        self.receipts.write().await.insert_block(height, receipts.clone());
+        self.pending_inclusion_lists.write().await.retain(|list_height, _| *list_height > height + 1);
+
+        // The block is committed; a failed notification must not undo that.
+        let hub = self.receipt_hub().await;
+        for receipt in &receipts {
+            if let Err(e) = hub.publish(receipt) {
+                warn!("Failed to publish receipt for {}: {}", receipt.transaction_hash, e);
+            }
+        }
 
         Ok(())
     }
 
+    /// Where committed blocks' receipts are published, for subscribers and waiters.
+    pub async fn receipt_hub(&self) -> Arc<ReceiptHub> {
+        self.state_manager.read().await.receipt_hub()
+    }
+
+    /// Receipts involving `address` (or all receipts) in the inclusive height range.
+    pub async fn query_logs(&self, from_height: u64, to_height: u64, address: Option<&str>) -> Result<Vec<TransactionReceipt>, BlockchainError> {
+        self.receipts.read().await.query(from_height, to_height, address)
+    }
+
+    pub async fn get_receipt(&self, transaction_hash: &Hash) -> Result<Option<TransactionReceipt>, BlockchainError> {
+        self.receipts.read().await.get(transaction_hash)
+    }
+
+    /// Installs the gas multipliers governance enacted; blocks committed from
+    /// now on are charged under them.
+    pub async fn set_gas_multipliers(&self, multipliers: GasMultipliers) {
+        *self.gas_multipliers.write().await = multipliers;
+    }
+
+    /// Lets `ReceiptRetention::FinalizedPlusMargin` prune up to `height`.
+    pub async fn mark_receipts_finalized(&self, height: u64) {
+        self.receipts.write().await.set_finalized(height);
+    }
+
     pub async fn process_transaction(&self, transaction: QuantumTransaction) -> Result<Hash, BlockchainError> {
         self.validate_transaction(&transaction).await?;
 
         let shard_id = self.determine_shard_for_transaction(&transaction).await?;
 
+        // shard_manager before state_manager, per the lock order
         let mut shards = self.shard_manager.write().await;
         if let Some(shard) = shards.get_mut(&shard_id) {
             shard.add_transaction(transaction.clone())?;
@@ -94,25 +554,249 @@ impl QuantumBlockchain {
         Ok(transaction.hash)
     }
 
+    /// Mempool admission checks for externally submitted transactions.
+    async fn validate_transaction(&self, transaction: &QuantumTransaction) -> Result<(), BlockchainError> {
+        // System transactions are only injected by block producers, never through the mempool
+        if transaction.is_system {
+            return Err(TransactionError::SystemTransactionNotAllowed.into());
+        }
+
+        if !transaction.verify_with_skew(self.config.max_clock_skew.transaction_secs)? {
+            return Err(TransactionError::InvalidSignature.into());
+        }
+
+        transaction.validate_chain(self.config.chain_id)?;
+        transaction.validate_size(&self.config.transaction_limits)?;
+        transaction.validate_fee(self.config.min_fee)?;
+        self.frozen_accounts.read().await.check(transaction)?;
+        Ok(())
+    }
+
+    async fn validate_block_header(&self, header: &BlockHeader) -> Result<(), BlockchainError> {
+        let recent: Vec<DateTime<Utc>> = self.blocks.read().await
+            .iter()
+            .rev()
+            .take(self.config.median_time_span)
+            .map(|b| b.header.timestamp)
+            .collect();
+        check_block_timestamp(header.timestamp, &recent, Utc::now(), self.config.max_clock_skew.block_secs)
+    }
+
+    /// Records a passed compliance proposal; the freeze or unfreeze it authorises
+    /// is applied when a producer includes the matching system transaction.
+    pub async fn approve_compliance_proposal(&self, proposal_id: &str, approval: ComplianceApproval) {
+        self.frozen_accounts.write().await.approve_proposal(proposal_id, approval);
+    }
+
+    /// Every block after genesis keeps its parent's validator set and must be
+    /// multi-signed, over its signing root, by validators of that set holding
+    /// more than `1 - fault_tolerance` of its stake.
+    async fn check_multi_signatures(&self, block: &QuantumBlock) -> Result<(), BlockchainError> {
+        let height = block.header.height;
+        if height == 0 {
+            return Ok(());
+        }
+        let (set_hash, stakes) = self.blocks.read().await
+            .get((height - 1) as usize)
+            .map(|parent| (parent.header.validator_set_hash, parent.validator_set.stakes()))
+            .ok_or(BlockchainError::UnknownParent(height - 1))?;
+        if block.header.validator_set_hash != set_hash {
+            return Err(BlockchainError::InvalidBlock("validator set differs from the parent's".to_string()));
+        }
+        block.validate_multi_signatures(&self.config.consensus, &stakes)
+            .map_err(|e| BlockchainError::InvalidBlock(e.to_string()))
+    }
+
+    /// Every block after genesis must be signed by the validator
+    /// `select_proposer` picks for its height and view from the parent's beacon
+    /// and validator set.
+    async fn verify_proposer(&self, block: &QuantumBlock) -> Result<(), BlockchainError> {
+        let height = block.header.height;
+        if height == 0 {
+            return Ok(());
+        }
+        let (Some(proposer), Some(signature)) = (&block.proposer, &block.signature) else {
+            return Err(BlockchainError::MissingProposerSignature);
+        };
+
+        let (beacon, stakes) = self.blocks.read().await
+            .get((height - 1) as usize)
+            .map(|parent| (parent.header.beacon_randomness, parent.validator_set.stakes()))
+            .ok_or(BlockchainError::UnknownParent(height - 1))?;
+
+        // Later views are only reachable once the validators have timed out on
+        // the earlier ones, so a proposer can't pick a view that selects itself.
+        if block.view > 0 {
+            let certificate = block.timeout_certificate.as_ref()
+                .filter(|certificate| certificate.view == block.view)
+                .ok_or(BlockchainError::MissingTimeoutCertificate(block.view))?;
+            certificate.verify(&self.config.consensus, &block.header.prev_hash, height, &stakes)?;
+        }
+
+        let validators: Vec<String> = stakes.into_keys().collect();
+        let expected = select_proposer(beacon.as_bytes(), height, block.view, &validators)
+            .ok_or(BlockchainError::NoProposers)?;
+        if *proposer != expected {
+            return Err(BlockchainError::WrongProposer { expected, actual: proposer.clone() });
+        }
+
+        let message = block.proposer_signing_message().map_err(|e| BlockchainError::InvalidBlock(e.to_string()))?;
+        let public_key = PublicKey::from_address(proposer).map_err(|_| BlockchainError::InvalidProposerSignature)?;
+        if !public_key.verify(&message, signature).map_err(|_| BlockchainError::InvalidProposerSignature)? {
+            return Err(BlockchainError::InvalidProposerSignature);
+        }
+        Ok(())
+    }
+
+    /// Queues a signed inclusion list for the block at `list.height`, to be
+    /// carried in the block before it. Only validators that attested that
+    /// carrier's parent may submit one; a later list from the same attester
+    /// replaces the earlier.
+    pub async fn submit_inclusion_list(&self, list: InclusionList) -> Result<(), BlockchainError> {
+        list.verify().map_err(|e| BlockchainError::InvalidBlock(e.to_string()))?;
+        let attested_height = list.height.checked_sub(2).ok_or(BlockchainError::UnknownParent(0))?;
+        let is_attester = self.blocks.read().await
+            .get(attested_height as usize)
+            .ok_or(BlockchainError::UnknownParent(attested_height))?
+            .multi_signatures
+            .contains_key(&list.attester);
+        if !is_attester {
+            return Err(BlockchainError::NotAnAttester(list.attester));
+        }
+
+        let mut pending = self.pending_inclusion_lists.write().await;
+        let lists = pending.entry(list.height).or_default();
+        lists.retain(|queued| queued.attester != list.attester);
+        lists.push(list);
+        Ok(())
+    }
+
+    /// Inclusion lists a block proposed at `height` should carry.
+    pub async fn take_inclusion_lists(&self, height: u64) -> Vec<InclusionList> {
+        self.pending_inclusion_lists.write().await.remove(&(height + 1)).unwrap_or_default()
+    }
+
+    /// Checks the lists `block` carries for the next height: each is signed,
+    /// bounded, and from a distinct validator that attested `block`'s parent.
+    async fn check_carried_inclusion_lists(&self, block: &QuantumBlock) -> Result<(), BlockchainError> {
+        if block.inclusion_lists.is_empty() {
+            return Ok(());
+        }
+        let height = block.header.height;
+        let parent_height = height.checked_sub(1).ok_or(BlockchainError::UnknownParent(0))?;
+        let attesters: HashSet<String> = self.blocks.read().await
+            .get(parent_height as usize)
+            .ok_or(BlockchainError::UnknownParent(parent_height))?
+            .multi_signatures
+            .keys()
+            .cloned()
+            .collect();
+
+        let mut seen = HashSet::new();
+        for list in &block.inclusion_lists {
+            if list.height != height + 1 || !seen.insert(list.attester.as_str()) {
+                return Err(BlockchainError::InvalidBlock("misplaced or duplicate inclusion list".to_string()));
+            }
+            if !attesters.contains(&list.attester) {
+                return Err(BlockchainError::NotAnAttester(list.attester.clone()));
+            }
+            list.verify().map_err(|e| BlockchainError::InvalidBlock(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Rejects a block that leaves out a transaction from the inclusion lists
+    /// its parent carries. A transaction that fails admission checks may be
+    /// omitted, as may any once the block is full.
+    async fn check_inclusion_list(&self, block: &QuantumBlock) -> Result<(), BlockchainError> {
+        let height = block.header.height;
+        if height == 0 {
+            return Ok(());
+        }
+        let lists = self.blocks.read().await
+            .get((height - 1) as usize)
+            .map(|parent| parent.inclusion_lists.clone())
+            .ok_or(BlockchainError::UnknownParent(height - 1))?;
+
+        let mut required: Vec<QuantumTransaction> = Vec::new();
+        for transaction in lists.into_iter().flat_map(|list| list.transactions) {
+            if required.iter().any(|tx| tx.hash == transaction.hash) {
+                continue;
+            }
+            if self.validate_transaction(&transaction).await.is_ok() {
+                required.push(transaction);
+            }
+        }
+
+        let omitted = omitted_inclusions(block, &required, self.config.max_transactions_per_block);
+        if !omitted.is_empty() {
+            return Err(BlockchainError::CensoredTransactions(omitted));
+        }
+        Ok(())
+    }
+
     async fn validate_block(&self, block: &QuantumBlock) -> Result<BlockValidationResult, BlockchainError> {
         self.validate_block_header(&block.header).await?;
+        self.verify_proposer(block).await?;
+        self.check_multi_signatures(block).await?;
+        self.check_inclusion_list(block).await?;
+        self.check_carried_inclusion_lists(block).await?;
+
+        // Each lock below is released before the next is taken, so validation
+        // never holds two locks at once.
+        let new_state_root = self.state_manager.read().await.calculate_state_root()?;
+
+        let pool = self.verification_pool.clone();
+        let transactions = block.transactions.clone();
+        let producer = block.proposer.clone();
+        let skew = self.config.max_clock_skew.transaction_secs;
+        let signatures = tokio::task::spawn_blocking(move || {
+            // System transactions skip fees and may act on governance approvals,
+            // so only the block's own producer may issue them.
+            verify_in_parallel(&pool, &transactions, |tx| match (tx.is_system, &producer) {
+                (false, _) => tx.verify_with_skew(skew),
+                (true, Some(producer)) => tx.verify_issued_by(producer, skew),
+                (true, None) => Ok(false),
+            })
+        })
+        .await
+        .map_err(|e| BlockchainError::Internal(e.to_string()))?;
+        match signatures {
+            Ok(()) => {}
+            Err(TransactionError::InvalidSignature) => {
+                return Ok(BlockValidationResult {
+                    is_valid: false,
+                    error: Some("Invalid transaction signature".to_string()),
+                    gas_used: 0,
+                    transactions_processed: 0,
+                    new_state_root: Hash::default(),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
 
+        let frozen_accounts = self.frozen_accounts.read().await;
         let mut gas_used = 0;
         for tx in &block.transactions {
-            if !tx.verify()? {
+            let checks = tx.validate_chain(self.config.chain_id)
+                .and_then(|_| tx.validate_size(&self.config.transaction_limits))
+                .and_then(|_| tx.validate_fee(self.config.min_fee))
+                .and_then(|_| frozen_accounts.check(tx));
+            if let Err(e) = checks {
                 return Ok(BlockValidationResult {
                     is_valid: false,
-                    error: Some("Invalid transaction signature".to_string()),
+                    error: Some(e.to_string()),
                     gas_used,
                     transactions_processed: 0,
                     new_state_root: Hash::default(),
                 });
             }
-            gas_used += tx.gas_used;
+            gas_used += tx.measure_gas(self.contract_executor.as_ref());
         }
+        drop(frozen_accounts);
 
-        let consensus = self.consensus_engine.read().await;
-        if !consensus.validate_block_consensus(&block)? {
+        let consensus_valid = self.consensus_engine.read().await.validate_block_consensus(block)?;
+        if !consensus_valid {
             return Ok(BlockValidationResult {
                 is_valid: false,
                 error: Some("Invalid consensus data".to_string()),
@@ -122,9 +806,6 @@ impl QuantumBlockchain {
             });
         }
 
-        let state_manager = self.state_manager.read().await;
-        let new_state_root = state_manager.calculate_state_root()?;
-
         Ok(BlockValidationResult {
             is_valid: true,
             error: None,
@@ -135,18 +816,133 @@ impl QuantumBlockchain {
     }
 
     async fn process_block_transactions(&self, block: &QuantumBlock) -> Result<(), BlockchainError> {
+        let multipliers = self.gas_multipliers.read().await.clone();
+        // state_manager before frozen_accounts, per the lock order
         let mut state_manager = self.state_manager.write().await;
-        for transaction in &block.transactions {
+        let mut frozen_accounts = self.frozen_accounts.write().await;
+        let mut undo = state_manager.begin_block(&block.transactions)?;
+        let frozen_before = frozen_accounts.clone();
+        let applied = match self.apply_block_transactions(block, &state_manager, &mut frozen_accounts, &mut undo, &multipliers).await {
+            Ok(()) => check_state_root(block, &state_manager),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = applied {
+            state_manager.abort_block(undo)?;
+            *frozen_accounts = frozen_before;
+            return Err(e);
+        }
+        state_manager.end_block(block.header.height, &block.transactions, undo)?;
+        Ok(())
+    }
+
+    /// Applies the transactions wave by wave along `schedule_parallel`; the
+    /// transactions in a wave touch disjoint accounts, so they run
+    /// concurrently. Fees are then settled in block order on gas measured here.
+    /// On error the caller rolls back through `undo` and the saved frozen accounts.
+    async fn apply_block_transactions(
+        &self,
+        block: &QuantumBlock,
+        state_manager: &QuantumStateManager,
+        frozen_accounts: &mut FrozenAccounts,
+        undo: &mut BlockUndo,
+        multipliers: &GasMultipliers,
+    ) -> Result<(), BlockchainError> {
+        for wave in schedule_parallel(&block.transactions) {
+            let transactions: Vec<&QuantumTransaction> = wave.into_iter().map(|index| &block.transactions[index]).collect();
+            for transaction in transactions.iter().filter(|tx| is_compliance_action(tx)) {
+                frozen_accounts.apply_governance_action(transaction)?;
+            }
+
+            let settlements = futures::future::try_join_all(
+                transactions.iter().map(|transaction| self.execute_transaction(transaction, state_manager, multipliers)),
+            ).await?;
+            for (transaction, settlement) in transactions.iter().zip(settlements) {
+                if let Some(settlement) = settlement {
+                    state_manager.settle_fee(
+                        undo,
+                        &transaction.from,
+                        block.proposer.as_deref(),
+                        &settlement,
+                        &self.config.fee_split,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs one transaction and returns the gas settlement it owes, if any.
+    /// Contract storage accesses are checked against its access list as they happen.
+    async fn execute_transaction(
+        &self,
+        transaction: &QuantumTransaction,
+        state_manager: &QuantumStateManager,
+        multipliers: &GasMultipliers,
+    ) -> Result<Option<GasSettlement>, BlockchainError> {
+        if !is_compliance_action(transaction) {
             state_manager.apply_transaction(transaction).await?;
         }
+        if transaction.is_system {
+            return Ok(None);
+        }
+        let settlement = transaction.settle_gas(self.contract_executor.as_ref(), multipliers, &self.config.gas_refund)?;
+        Ok(Some(settlement))
+    }
+
+    /// Finalizes the block with consensus and applies its unjail transactions.
+    /// An included unjail that consensus refuses (bad signature, still cooling
+    /// down) only costs its sender the fee.
+    async fn update_chain_state(&self, block: &QuantumBlock) -> Result<(), BlockchainError> {
+        let mut consensus_engine = self.consensus_engine.write().await;
+        consensus_engine.finalize_block(block)?;
+        let unjails = block.transactions.iter()
+            .filter(|tx| matches!(tx.data.operation_type, OperationType::UnjailValidator));
+        for transaction in unjails {
+            if let Err(e) = consensus_engine.unjail(transaction) {
+                warn!(
+                    "Unjail from {} in block {} had no effect: {}",
+                    redactor().address(&transaction.from, Level::Warn),
+                    block.header.height,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// On a block that closes an epoch, deals its validator set over the shards,
+    /// shuffled by its beacon. The block is already valid, so a failed
+    /// assignment only keeps the previous epoch's.
+    async fn rotate_shard_validators(&self, block: &QuantumBlock) {
+        let Some(allocator) = &self.shard_allocator else { return };
+        let epoch_length = self.config.consensus.epoch_length;
+        let height = block.header.height;
+        if epoch_length == 0 || height == 0 || height % epoch_length != 0 {
+            return;
+        }
+
+        let validators: Vec<String> = block.validator_set.stakes().into_keys().collect();
+        let epoch = height / epoch_length;
+        if let Err(e) = allocator.assign_validators(epoch, &block.header.beacon_randomness, &validators).await {
+            warn!("Keeping shard validators from before epoch {}: {}", epoch, e);
+        }
+    }
+
+    async fn update_metrics(&self, block: &QuantumBlock) -> Result<(), BlockchainError> {
+        let mut metrics = self.metrics.write().await;
+        metrics.total_blocks += 1;
+        metrics.total_transactions += block.transactions.len() as u64;
+        metrics.last_block_time = block.header.timestamp;
         Ok(())
     }
 
     async fn determine_shard_for_transaction(&self, transaction: &QuantumTransaction) -> Result<u64, BlockchainError> {
-        let shard_id = self.calculate_shard_id(&transaction.from)?;
-        
         let shards = self.shard_manager.read().await;
-        if !shards.contains_key(&shard_id) {
+        let shard_id = self.calculate_shard_id(&transaction.from)?;
+        // A reshard swaps the layout just before the count, so check the range too
+        let owns_sender = shards.get(&shard_id)
+            .map_or(false, |shard| shard.key_range.contains(address_key(&transaction.from)));
+        if !owns_sender {
             return Err(BlockchainError::ShardNotFound);
         }
 
@@ -154,7 +950,1552 @@ impl QuantumBlockchain {
     }
 
     fn calculate_shard_id(&self, address: &str) -> Result<u64, BlockchainError> {
-        let hash = blake3::hash(address.as_bytes());
-        Ok(u64::from_le_bytes(hash.as_bytes()[0..8].try_into().unwrap()) % self.config.shard_count)
+        Ok(shard_for_address(address, self.shard_count.load(Ordering::Acquire)))
+    }
+
+    /// Reshards the chain to `new_count` shards through `allocator`, which must
+    /// manage this chain's `shard_manager`. Routing switches to the new count
+    /// before block commits resume.
+    pub async fn reshard(
+        &self,
+        allocator: &ShardAllocator,
+        new_count: u64,
+        on_progress: impl FnMut(ReshardProgress),
+    ) -> Result<(), BlockchainError> {
+        if !allocator.manages(&self.shard_manager) {
+            return Err(BlockchainError::Internal("allocator does not manage this chain's shards".to_string()));
+        }
+
+        let _commit = self.commit_lock.lock().await;
+        allocator.reshard(new_count, on_progress).await?;
+        self.shard_count.store(new_count, Ordering::Release);
+        Ok(())
+    }
+
+    /// Groups transactions by the shard their sender maps to, keeping each shard's input order.
+    pub async fn route_by_shard(
+        &self,
+        transactions: Vec<QuantumTransaction>,
+    ) -> Result<HashMap<u64, Vec<QuantumTransaction>>, BlockchainError> {
+        let mut routed: HashMap<u64, Vec<QuantumTransaction>> = HashMap::new();
+        for transaction in transactions {
+            let shard_id = self.determine_shard_for_transaction(&transaction).await?;
+            routed.entry(shard_id).or_default().push(transaction);
+        }
+        Ok(routed)
+    }
+}
+
+// 🔹 **Chain Identity**
+/// What a node advertises to peers; both fields must match before blocks are exchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainIdentity {
+    pub network_id: u64,
+    pub chain_id: u64,
+    pub genesis_hash: Hash,
+}
+
+impl ChainIdentity {
+    pub fn from_config(config: &BlockchainConfig) -> Self {
+        Self {
+            network_id: config.network_id,
+            chain_id: config.chain_id,
+            genesis_hash: genesis_hash(config),
+        }
+    }
+}
+
+/// Derived only from the parameters that fix the genesis state, so every node
+/// started with the same network and genesis settings computes the same hash.
+pub fn genesis_hash(config: &BlockchainConfig) -> Hash {
+    let mut hasher = blake3::Hasher::new_derive_key("quantumfuse genesis v1");
+    hasher.update(&config.network_id.to_le_bytes());
+    hasher.update(&config.chain_id.to_le_bytes());
+    hasher.update(&config.shard_count.to_le_bytes());
+    hasher.update(&[config.quantum_security_level]);
+    hasher.update(&config.genesis.timestamp.timestamp().to_le_bytes());
+    hasher.update(&config.genesis.timestamp.timestamp_subsec_nanos().to_le_bytes());
+    hasher.update(config.genesis.state_root.as_bytes());
+    hasher.update(&(config.genesis.extra_data.len() as u64).to_le_bytes());
+    hasher.update(&config.genesis.extra_data);
+    hasher.update(&(config.genesis.validators.len() as u64).to_le_bytes());
+    for (validator, stake) in &config.genesis.validators {
+        hasher.update(&(validator.len() as u64).to_le_bytes());
+        hasher.update(validator.as_bytes());
+        hasher.update(&stake.to_le_bytes());
+    }
+    Hash::from(hasher.finalize())
+}
+
+/// Default for `ClockSkew::block_secs`.
+pub const MAX_BLOCK_FUTURE_DRIFT_SECS: i64 = 15;
+
+/// Median of `timestamps`; with an even count the later of the two middle values.
+pub fn median_time_past(timestamps: &[DateTime<Utc>]) -> Option<DateTime<Utc>> {
+    let mut sorted = timestamps.to_vec();
+    sorted.sort();
+    sorted.get(sorted.len() / 2).copied()
+}
+
+/// A block must be later than the median of `recent` timestamps and no more
+/// than `max_skew_secs` ahead of `now`.
+fn check_block_timestamp(timestamp: DateTime<Utc>, recent: &[DateTime<Utc>], now: DateTime<Utc>, max_skew_secs: i64) -> Result<(), BlockchainError> {
+    if timestamp > now + chrono::Duration::seconds(max_skew_secs) {
+        return Err(BlockchainError::FutureBlockTimestamp);
+    }
+    if let Some(median) = median_time_past(recent) {
+        if timestamp <= median {
+            return Err(BlockchainError::TimestampBelowMedian { timestamp, median });
+        }
+    }
+    Ok(())
+}
+
+/// The header must commit to the state the block's transactions produced.
+fn check_state_root(block: &QuantumBlock, state_manager: &QuantumStateManager) -> Result<(), BlockchainError> {
+    let actual = state_manager.calculate_state_root()?;
+    if actual != block.header.state_root {
+        return Err(BlockchainError::StateRootMismatch { expected: block.header.state_root, actual });
+    }
+    Ok(())
+}
+
+/// Freezes and unfreezes change the chain's frozen accounts, not account state.
+fn is_compliance_action(transaction: &QuantumTransaction) -> bool {
+    matches!(transaction.data.operation_type, OperationType::FreezeAccount | OperationType::UnfreezeAccount)
+}
+
+/// Chain-side state outside the state manager that committing blocks changes,
+/// taken before a reorg and put back if the new branch fails.
+struct ChainStateSnapshot {
+    receipts: ReceiptStore,
+    frozen_accounts: FrozenAccounts,
+    inclusion_lists: BTreeMap<u64, Vec<InclusionList>>,
+    gas_multipliers: GasMultipliers,
+    metrics: ChainMetrics,
+}
+
+/// Rejects a fork whose common ancestor lies more than `max_depth` blocks below `tip`.
+fn check_reorg_depth(tip: u64, common_ancestor: u64, max_depth: u64) -> Result<(), BlockchainError> {
+    let depth = tip.saturating_sub(common_ancestor);
+    if depth > max_depth {
+        warn!("Rejecting reorg of depth {} (max {}) below tip {}", depth, max_depth, tip);
+        return Err(BlockchainError::ReorgTooDeep { depth, max_depth });
+    }
+    Ok(())
+}
+
+fn build_verification_pool(threads: usize) -> Result<ThreadPool, BlockchainError> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("sig-verify-{}", i))
+        .build()
+        .map_err(|e| BlockchainError::Internal(e.to_string()))
+}
+
+/// Runs `verify` over `items` on `pool`, stopping at the first failure.
+/// A `false` result is reported as `InvalidSignature`.
+fn verify_in_parallel<T, F>(pool: &ThreadPool, items: &[T], verify: F) -> Result<(), TransactionError>
+where
+    T: Sync,
+    F: Fn(&T) -> Result<bool, TransactionError> + Sync,
+{
+    pool.install(|| {
+        items.par_iter().try_for_each(|item| match verify(item)? {
+            true => Ok(()),
+            false => Err(TransactionError::InvalidSignature),
+        })
+    })
+}
+
+// 🔹 **Receipt Store**
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ReceiptRetention {
+    #[default]
+    KeepAll,
+    /// Keep receipts for the most recent `n` blocks.
+    LastBlocks(u64),
+    /// Keep receipts until `margin` blocks past the finalized height.
+    FinalizedPlusMargin(u64),
+}
+
+/// Receipts by block height. Only retained blocks are indexed by hash, so once
+/// anything has been pruned an unindexed hash may belong to a pruned block and
+/// lookups report `ReceiptsPruned` for it.
+#[derive(Debug, Clone, Default)]
+pub struct ReceiptStore {
+    retention: ReceiptRetention,
+    blocks: BTreeMap<u64, Vec<TransactionReceipt>>,
+    heights: HashMap<Hash, u64>,
+    retained_from: u64,
+    tip: u64,
+    finalized: Option<u64>,
+}
+
+impl ReceiptStore {
+    pub fn new(retention: ReceiptRetention) -> Self {
+        Self { retention, ..Default::default() }
+    }
+
+    /// Lowest height whose receipts are still stored.
+    pub fn retained_from(&self) -> u64 {
+        self.retained_from
+    }
+
+    pub fn insert_block(&mut self, height: u64, receipts: Vec<TransactionReceipt>) {
+        for receipt in &receipts {
+            self.heights.insert(receipt.transaction_hash, height);
+        }
+        self.blocks.insert(height, receipts);
+        self.tip = self.tip.max(height);
+        self.prune();
+    }
+
+    pub fn set_finalized(&mut self, height: u64) {
+        self.finalized = Some(height);
+        self.prune();
+    }
+
+    /// Drops receipts for blocks abandoned by a reorg.
+    pub fn truncate_above(&mut self, height: u64) {
+        for (_, receipts) in self.blocks.split_off(&(height + 1)) {
+            for receipt in receipts {
+                self.heights.remove(&receipt.transaction_hash);
+            }
+        }
+        self.tip = height;
+    }
+
+    pub fn query(&self, from_height: u64, to_height: u64, address: Option<&str>) -> Result<Vec<TransactionReceipt>, BlockchainError> {
+        if from_height < self.retained_from {
+            return Err(BlockchainError::ReceiptsPruned { retained_from: self.retained_from });
+        }
+
+        Ok(self.blocks.range(from_height..=to_height)
+            .flat_map(|(_, receipts)| receipts)
+            .filter(|receipt| address.map_or(true, |a| receipt.from == a || receipt.to == a))
+            .cloned()
+            .collect())
+    }
+
+    pub fn get(&self, transaction_hash: &Hash) -> Result<Option<TransactionReceipt>, BlockchainError> {
+        let Some(&height) = self.heights.get(transaction_hash) else {
+            if self.retained_from > 0 {
+                return Err(BlockchainError::ReceiptsPruned { retained_from: self.retained_from });
+            }
+            return Ok(None);
+        };
+
+        Ok(self.blocks.get(&height)
+            .and_then(|receipts| receipts.iter().find(|r| &r.transaction_hash == transaction_hash))
+            .cloned())
+    }
+
+    fn prune(&mut self) {
+        let keep_from = match self.retention {
+            ReceiptRetention::KeepAll => return,
+            ReceiptRetention::LastBlocks(n) => (self.tip + 1).saturating_sub(n),
+            ReceiptRetention::FinalizedPlusMargin(margin) => match self.finalized {
+                Some(finalized) => finalized.saturating_sub(margin),
+                None => return,
+            },
+        };
+        if keep_from <= self.retained_from {
+            return;
+        }
+
+        let retained = self.blocks.split_off(&keep_from);
+        for receipts in std::mem::replace(&mut self.blocks, retained).into_values() {
+            for receipt in receipts {
+                self.heights.remove(&receipt.transaction_hash);
+            }
+        }
+        self.retained_from = keep_from;
+    }
+}
+
+// 🔹 **Event Ledger**
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub id: String,
+    pub payload: Vec<u8>,
+}
+
+/// Append-only backing store for `QuantumLedger`. `truncate` is used to roll
+/// back a batch that failed part way through.
+pub trait LedgerStore: Send {
+    fn entry_count(&self) -> usize;
+    fn append(&mut self, entry: LedgerEntry) -> Result<(), BlockchainError>;
+    fn truncate(&mut self, len: usize);
+    fn entries(&self) -> &[LedgerEntry];
+}
+
+/// Keeps the event log in memory and rejects an event id that was already recorded.
+#[derive(Debug, Default)]
+pub struct InMemoryLedgerStore {
+    entries: Vec<LedgerEntry>,
+    ids: HashSet<String>,
+}
+
+impl LedgerStore for InMemoryLedgerStore {
+    fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn append(&mut self, entry: LedgerEntry) -> Result<(), BlockchainError> {
+        if !self.ids.insert(entry.id.clone()) {
+            return Err(BlockchainError::DuplicateEvent(entry.id));
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) {
+        for entry in self.entries.drain(len..) {
+            self.ids.remove(&entry.id);
+        }
+    }
+
+    fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+}
+
+/// Ordered record of contract events. Clones share the same store.
+#[derive(Clone)]
+pub struct QuantumLedger {
+    store: Arc<std::sync::Mutex<Box<dyn LedgerStore>>>,
+}
+
+impl Default for QuantumLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuantumLedger {
+    pub fn new() -> Self {
+        Self::with_store(InMemoryLedgerStore::default())
+    }
+
+    pub fn with_store(store: impl LedgerStore + 'static) -> Self {
+        Self { store: Arc::new(std::sync::Mutex::new(Box::new(store))) }
+    }
+
+    pub fn record_event<T: Serialize>(&self, id: &str, event: &T) -> Result<(), BlockchainError> {
+        self.record_events_batch(&[(id.to_string(), event)])
+    }
+
+    /// Appends `events` in order as one unit: if any event fails to serialize
+    /// or be stored, none of the batch is kept.
+    pub fn record_events_batch<T: Serialize>(&self, events: &[(String, T)]) -> Result<(), BlockchainError> {
+        let entries = events.iter()
+            .map(|(id, event)| {
+                let payload = serde_json::to_vec(event)
+                    .map_err(|e| BlockchainError::Internal(e.to_string()))?;
+                Ok(LedgerEntry { id: id.clone(), payload })
+            })
+            .collect::<Result<Vec<_>, BlockchainError>>()?;
+
+        let mut store = self.lock()?;
+        let checkpoint = store.entry_count();
+        for entry in entries {
+            if let Err(e) = store.append(entry) {
+                warn!("Rolling back ledger batch of {} events: {:?}", events.len(), e);
+                store.truncate(checkpoint);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn events(&self) -> Result<Vec<LedgerEntry>, BlockchainError> {
+        Ok(self.lock()?.entries().to_vec())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Box<dyn LedgerStore>>, BlockchainError> {
+        self.store.lock().map_err(|_| BlockchainError::Internal("ledger lock poisoned".to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LedgerBatchConfig {
+    pub max_events: usize,
+    pub flush_interval: Duration,
+}
+
+impl Default for LedgerBatchConfig {
+    fn default() -> Self {
+        Self {
+            max_events: 64,
+            flush_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+struct LedgerQueue<T> {
+    pending: Vec<(String, T)>,
+    /// Tokio's clock, so a paused test runtime drives the timer and `push` alike.
+    last_flush: tokio::time::Instant,
+}
+
+impl<T: Serialize> LedgerQueue<T> {
+    fn flush(&mut self, ledger: &QuantumLedger) -> Result<(), BlockchainError> {
+        if !self.pending.is_empty() {
+            ledger.record_events_batch(&self.pending)?;
+            self.pending.clear();
+        }
+        self.last_flush = tokio::time::Instant::now();
+        Ok(())
+    }
+}
+
+/// Buffers events for a `QuantumLedger` and writes them as one batch once
+/// `max_events` are pending or `flush_interval` has passed since the last flush.
+/// Created inside a Tokio runtime, it also flushes on a `flush_interval` timer,
+/// so a quiet contract's last events don't wait for the next push. Whatever is
+/// still queued is written when the batcher is dropped.
+pub struct LedgerBatcher<T: Serialize + Send + 'static> {
+    ledger: QuantumLedger,
+    config: LedgerBatchConfig,
+    queue: Arc<std::sync::Mutex<LedgerQueue<T>>>,
+    timer: Option<JoinHandle<()>>,
+}
+
+impl<T: Serialize + Send + 'static> LedgerBatcher<T> {
+    pub fn new(ledger: QuantumLedger, config: LedgerBatchConfig) -> Self {
+        let queue = Arc::new(std::sync::Mutex::new(LedgerQueue {
+            pending: Vec::new(),
+            last_flush: tokio::time::Instant::now(),
+        }));
+        let timer = tokio::runtime::Handle::try_current()
+            .ok()
+            .map(|runtime| runtime.spawn(Self::flush_on_timer(ledger.clone(), config.flush_interval, queue.clone())));
+        Self { ledger, config, queue, timer }
+    }
+
+    async fn flush_on_timer(ledger: QuantumLedger, interval: Duration, queue: Arc<std::sync::Mutex<LedgerQueue<T>>>) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let Ok(mut queue) = queue.lock() else { return };
+            if queue.last_flush.elapsed() >= interval {
+                if let Err(e) = queue.flush(&ledger) {
+                    warn!("Timed ledger flush failed; {} events stay queued: {:?}", queue.pending.len(), e);
+                }
+            }
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.lock().map_or(0, |queue| queue.pending.len())
+    }
+
+    pub fn push(&self, id: String, event: T) -> Result<(), BlockchainError> {
+        let mut queue = self.lock()?;
+        queue.pending.push((id, event));
+        if queue.pending.len() >= self.config.max_events
+            || queue.last_flush.elapsed() >= self.config.flush_interval
+        {
+            queue.flush(&self.ledger)?;
+        }
+        Ok(())
+    }
+
+    /// Writes every pending event. If the batch is rejected the events stay
+    /// queued so the flush can be retried.
+    pub fn flush(&self) -> Result<(), BlockchainError> {
+        self.lock()?.flush(&self.ledger)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, LedgerQueue<T>>, BlockchainError> {
+        self.queue.lock().map_err(|_| BlockchainError::Internal("ledger batch lock poisoned".to_string()))
+    }
+}
+
+impl<T: Serialize + Send + 'static> Drop for LedgerBatcher<T> {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.abort();
+        }
+        if let Err(e) = self.flush() {
+            warn!("Dropping {} unflushed ledger events: {:?}", self.pending(), e);
+        }
+    }
+}
+
+/// Contracts that queue their ledger events through a `LedgerBatcher`.
+pub trait BatchedLedger {
+    type Event: Serialize + Send + 'static;
+
+    fn ledger_batch(&self) -> &LedgerBatcher<Self::Event>;
+
+    /// Writes any queued events to the ledger as one batch, without waiting
+    /// for the batch size or the flush timer.
+    fn flush_ledger(&self) -> Result<(), BlockchainError> {
+        self.ledger_batch().flush()
+    }
+}
+
+// 🔹 **Sealed Record Commitments**
+pub const BLINDING_NONCE_LEN: usize = 32;
+
+/// A commitment to private record data that is safe to publish on the ledger.
+/// The data is hashed together with a random blinding nonce, so the commitment
+/// can't be confirmed by hashing guessed plaintexts. The nonce travels inside
+/// the ciphertext as the prefix of `payload`.
+///
+/// Encrypt `payload` for each reader and sign `commitment`: the signature then
+/// covers the plaintext rather than any one ciphertext, so re-encrypting the
+/// payload for a new reader keeps it valid.
+#[derive(Debug, Clone)]
+pub struct SealedCommitment {
+    pub commitment: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+impl SealedCommitment {
+    /// Commits to `data` under a fresh nonce. `domain` separates record kinds
+    /// and `record_id` binds the data to its record.
+    pub fn seal(domain: &str, record_id: &str, data: &[u8]) -> Self {
+        let mut nonce = [0u8; BLINDING_NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let mut payload = Vec::with_capacity(BLINDING_NONCE_LEN + data.len());
+        payload.extend_from_slice(&nonce);
+        payload.extend_from_slice(data);
+        Self {
+            commitment: Self::commit(domain, record_id, &nonce, data),
+            payload,
+        }
+    }
+
+    /// Checks a decrypted payload against `commitment` and returns the data
+    /// without its nonce, or `None` if the payload doesn't match.
+    pub fn open<'a>(domain: &str, record_id: &str, payload: &'a [u8], commitment: &[u8; 32]) -> Option<&'a [u8]> {
+        if payload.len() < BLINDING_NONCE_LEN {
+            return None;
+        }
+        let (nonce, data) = payload.split_at(BLINDING_NONCE_LEN);
+        (Self::commit(domain, record_id, nonce, data) == *commitment).then_some(data)
+    }
+
+    fn commit(domain: &str, record_id: &str, nonce: &[u8], data: &[u8]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(domain);
+        hasher.update(&(record_id.len() as u64).to_le_bytes());
+        hasher.update(record_id.as_bytes());
+        hasher.update(nonce);
+        hasher.update(data);
+        *hasher.finalize().as_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+    use quantumfuse_sdk::ai::BlockOptimizer;
+    use quantumfuse_sdk::shard::ShardConfig;
+    use quantumfuse_sdk::error::{BlockError, StateError};
+    use quantumfuse_sdk::block::MAX_INCLUSION_LIST_LEN;
+    use quantumfuse_sdk::consensus_mechanism::{TimeoutCertificate, LivenessConfig, RewardSplitConfig, ViewChangeConfig};
+
+    fn test_config(serialize_block_commits: bool) -> BlockchainConfig {
+        BlockchainConfig {
+            network_id: 1,
+            chain_id: 1,
+            version: "1.0.0".to_string(),
+            block_time: 1,
+            max_block_size: 1_048_576,
+            max_transactions_per_block: 1_000,
+            minimum_stake: 0.0,
+            quantum_security_level: 3,
+            shard_count: 4,
+            min_fee: Amount::ZERO,
+            fee_split: FeeSplit::new(5_000).unwrap(),
+            gas_refund: GasRefundPolicy::default(),
+            serialize_block_commits,
+            signature_verification_threads: 4,
+            max_reorg_depth: 6,
+            median_time_span: 11,
+            transaction_limits: TransactionLimits::default(),
+            consensus: consensus_config(0.33),
+            receipt_retention: ReceiptRetention::KeepAll,
+            state_history: StateHistoryConfig::default(),
+            max_clock_skew: ClockSkew::default(),
+            genesis: GenesisConfig::default(),
+            block_production: BlockProductionConfig::default(),
+        }
+    }
+
+    fn receipt_store(retention: ReceiptRetention, tip: u64) -> (ReceiptStore, Vec<Hash>) {
+        let mut store = ReceiptStore::new(retention);
+        let mut hashes = Vec::new();
+        for height in 1..=tip {
+            let tx = test_transaction(&format!("sender-{}", height));
+            hashes.push(tx.hash);
+            store.insert_block(height, vec![TransactionReceipt {
+                transaction_hash: tx.hash,
+                block_height: height,
+                index: 0,
+                from: tx.from,
+                to: tx.to,
+            }]);
+        }
+        (store, hashes)
+    }
+
+    fn test_transaction(from: &str) -> QuantumTransaction {
+        QuantumTransaction::new(
+            from.to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap()
+    }
+
+    async fn run_concurrent_workload(serialize_block_commits: bool) {
+        let chain = Arc::new(QuantumBlockchain::new(test_config(serialize_block_commits)).await.unwrap());
+        let mut handles = Vec::new();
+
+        for i in 0..32u64 {
+            let chain = chain.clone();
+            handles.push(tokio::spawn(async move {
+                let block = QuantumBlock::new(
+                    Hash::default(),
+                    vec![test_transaction(&format!("miner-{}", i))],
+                    Hash::default(),
+                    ValidatorSet::default(),
+                    i + 1,
+                    &BlockOptimizer::new(),
+                ).unwrap();
+                // Outcome is irrelevant here; only completion matters.
+                let _ = chain.add_block(block).await;
+            }));
+
+            let chain = chain.clone();
+            handles.push(tokio::spawn(async move {
+                let _ = chain.process_transaction(test_transaction(&format!("sender-{}", i))).await;
+            }));
+        }
+
+        let all_done = tokio::time::timeout(Duration::from_secs(30), async {
+            for handle in handles {
+                handle.await.unwrap();
+            }
+        }).await;
+        assert!(all_done.is_ok(), "concurrent add_block/process_transaction deadlocked");
+    }
+
+    fn shard_config() -> ShardConfig {
+        ShardConfig {
+            min_shards: 1,
+            max_shards: 16,
+            target_load_factor: 0.7,
+            reallocation_threshold: 0.9,
+            min_validators_per_shard: 1,
+            quantum_security_threshold: 3,
+            split_load_factor: 0.75,
+            merge_load_factor: 0.1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reshard_routes_like_the_allocator() {
+        let chain = QuantumBlockchain::new(test_config(false)).await.unwrap();
+        let allocator = ShardAllocator::with_shards(shard_config(), chain.shard_manager.clone());
+
+        chain.reshard(&allocator, 8, |_| {}).await.unwrap();
+
+        assert_eq!(chain.shard_count.load(Ordering::Acquire), 8);
+        for i in 0..32 {
+            let tx = test_transaction(&format!("sender-{}", i));
+            let routed = chain.determine_shard_for_transaction(&tx).await.unwrap();
+            assert_eq!(routed, allocator.calculate_shard_id(&tx.from).await.unwrap());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_block_and_transaction_processing_does_not_deadlock() {
+        run_concurrent_workload(false).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_serialized_block_commits_do_not_deadlock() {
+        run_concurrent_workload(true).await;
+    }
+
+    fn slow_verify(valid: &bool) -> Result<bool, TransactionError> {
+        // Stand-in for a Dilithium verification.
+        std::thread::sleep(Duration::from_millis(2));
+        Ok(*valid)
+    }
+
+    /// Peak number of `slow_verify` calls in flight at once on a pool of `threads`.
+    fn peak_concurrent_verifications(threads: usize) -> usize {
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let pool = build_verification_pool(threads).unwrap();
+        verify_in_parallel(&pool, &[true; 256], |valid| {
+            let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(running, Ordering::SeqCst);
+            let result = slow_verify(valid);
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            result
+        }).unwrap();
+        peak.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_signature_verification_runs_in_parallel_up_to_the_cap() {
+        assert_eq!(peak_concurrent_verifications(1), 1);
+        let peak = peak_concurrent_verifications(4);
+        assert!((2..=4).contains(&peak), "peak of {} concurrent verifications", peak);
+    }
+
+    #[test]
+    fn test_shallow_reorg_is_accepted() {
+        assert!(check_reorg_depth(100, 100, 6).is_ok());
+        assert!(check_reorg_depth(100, 97, 6).is_ok());
+        assert!(check_reorg_depth(100, 94, 6).is_ok());
+    }
+
+    #[test]
+    fn test_too_deep_reorg_is_rejected() {
+        let result = check_reorg_depth(100, 93, 6);
+        assert!(matches!(result, Err(BlockchainError::ReorgTooDeep { depth: 7, max_depth: 6 })));
+    }
+
+    /// Eleven block times spaced a minute apart; the median is the sixth.
+    fn recent_timestamps(now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        (0..11).map(|i| now - chrono::Duration::minutes(20 - i)).collect()
+    }
+
+    #[test]
+    fn test_block_at_median_time_past_is_rejected() {
+        let now = Utc::now();
+        let recent = recent_timestamps(now);
+        let median = median_time_past(&recent).unwrap();
+        assert_eq!(median, now - chrono::Duration::minutes(15));
+
+        assert!(matches!(
+            check_block_timestamp(median, &recent, now, MAX_BLOCK_FUTURE_DRIFT_SECS),
+            Err(BlockchainError::TimestampBelowMedian { .. })
+        ));
+    }
+
+    #[test]
+    fn test_block_above_median_time_past_is_accepted() {
+        let now = Utc::now();
+        let recent = recent_timestamps(now);
+        let median = median_time_past(&recent).unwrap();
+
+        assert!(check_block_timestamp(median + chrono::Duration::seconds(1), &recent, now, MAX_BLOCK_FUTURE_DRIFT_SECS).is_ok());
+        assert!(check_block_timestamp(now, &recent, now, MAX_BLOCK_FUTURE_DRIFT_SECS).is_ok());
+        assert!(check_block_timestamp(now, &[], now, MAX_BLOCK_FUTURE_DRIFT_SECS).is_ok());
+    }
+
+    #[test]
+    fn test_future_block_timestamp_is_rejected() {
+        let now = Utc::now();
+        let future = now + chrono::Duration::seconds(MAX_BLOCK_FUTURE_DRIFT_SECS + 1);
+        assert!(matches!(
+            check_block_timestamp(future, &recent_timestamps(now), now, MAX_BLOCK_FUTURE_DRIFT_SECS),
+            Err(BlockchainError::FutureBlockTimestamp)
+        ));
+    }
+
+    #[test]
+    fn test_block_timestamp_within_clock_skew_is_accepted() {
+        let now = Utc::now();
+        let recent = recent_timestamps(now);
+
+        assert!(check_block_timestamp(now + chrono::Duration::seconds(20), &recent, now, 30).is_ok());
+        assert!(check_block_timestamp(now + chrono::Duration::seconds(30), &recent, now, 30).is_ok());
+    }
+
+    #[test]
+    fn test_block_timestamp_beyond_clock_skew_is_rejected() {
+        let now = Utc::now();
+        assert!(matches!(
+            check_block_timestamp(now + chrono::Duration::seconds(31), &recent_timestamps(now), now, 30),
+            Err(BlockchainError::FutureBlockTimestamp)
+        ));
+    }
+
+    /// Polls the pacer once a second for `secs` seconds and returns the
+    /// seconds at which a block was produced.
+    fn run_producer(heartbeat: Option<Duration>, pending_transactions: usize, secs: u64) -> Vec<u64> {
+        let start = Instant::now();
+        let mut pacer = BlockPacer::new(5, BlockProductionConfig { empty_block_heartbeat: heartbeat }, start);
+        let mut produced = Vec::new();
+        for second in 1..=secs {
+            let now = start + Duration::from_secs(second);
+            if pacer.should_produce(pending_transactions, now) {
+                pacer.record_block(now);
+                produced.push(second);
+            }
+        }
+        produced
+    }
+
+    #[test]
+    fn test_empty_mempool_does_not_flood_empty_blocks() {
+        assert!(run_producer(None, 0, 300).is_empty());
+
+        let start = Instant::now();
+        let pacer = BlockPacer::new(5, BlockProductionConfig { empty_block_heartbeat: None }, start);
+        assert_eq!(pacer.time_until_next(0, start), None);
+    }
+
+    #[test]
+    fn test_heartbeat_advances_idle_chain() {
+        assert_eq!(run_producer(Some(Duration::from_secs(60)), 0, 300), vec![60, 120, 180, 240, 300]);
+    }
+
+    #[test]
+    fn test_backlog_produces_blocks_at_target_cadence() {
+        let produced = run_producer(Some(Duration::from_secs(60)), 1_000, 60);
+
+        assert_eq!(produced.len(), 12);
+        assert!(produced.windows(2).all(|pair| pair[1] - pair[0] == 5));
+    }
+
+    /// Counts the blocks it is asked for; `pending` transactions are always waiting.
+    struct CountingProducer {
+        pending: usize,
+        produced: std::sync::Mutex<Vec<Instant>>,
+    }
+
+    #[async_trait]
+    impl BlockProducer for CountingProducer {
+        async fn pending_transactions(&self) -> usize {
+            self.pending
+        }
+
+        async fn produce_block(&self) -> Result<(), BlockchainError> {
+            self.produced.lock().unwrap().push(tokio::time::Instant::now().into_std());
+            Ok(())
+        }
+    }
+
+    async fn run_spawned_producer(heartbeat: Option<Duration>, pending: usize, secs: u64) -> Vec<Instant> {
+        let producer = Arc::new(CountingProducer { pending, produced: std::sync::Mutex::new(Vec::new()) });
+        let task = spawn_block_producer(producer.clone(), 5, BlockProductionConfig { empty_block_heartbeat: heartbeat });
+        tokio::time::sleep(Duration::from_secs(secs)).await;
+        task.abort();
+        let produced = producer.produced.lock().unwrap().clone();
+        produced
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_producer_loop_follows_the_pacer() {
+        assert!(run_spawned_producer(None, 0, 300).await.is_empty());
+        assert_eq!(run_spawned_producer(Some(Duration::from_secs(60)), 0, 301).await.len(), 5);
+
+        let produced = run_spawned_producer(Some(Duration::from_secs(60)), 1_000, 61).await;
+        assert_eq!(produced.len(), 12);
+        assert!(produced.windows(2).all(|pair| pair[1] - pair[0] == Duration::from_secs(5)));
+    }
+
+    #[tokio::test]
+    async fn test_reorganize_rejects_deep_fork_without_touching_chain() {
+        let chain = QuantumBlockchain::new(test_config(false)).await.unwrap();
+        for height in 1..=10 {
+            let block = QuantumBlock::new(
+                Hash::default(),
+                vec![test_transaction("miner")],
+                Hash::default(),
+                ValidatorSet::default(),
+                height,
+                &BlockOptimizer::new(),
+            ).unwrap();
+            chain.blocks.write().await.push(block);
+        }
+
+        let result = chain.reorganize(2, Vec::new()).await;
+        assert!(matches!(result, Err(BlockchainError::ReorgTooDeep { depth: 8, max_depth: 6 })));
+        assert_eq!(chain.blocks.read().await.len(), 11);
+    }
+
+    #[tokio::test]
+    async fn test_peer_with_mismatched_genesis_is_refused() {
+        let chain = QuantumBlockchain::new(test_config(false)).await.unwrap();
+        assert_eq!(chain.identity(), &ChainIdentity::from_config(&test_config(true)));
+        assert!(chain.check_peer(&ChainIdentity::from_config(&test_config(false))).is_ok());
+
+        let mut other_network = test_config(false);
+        other_network.network_id = 2;
+        assert!(matches!(
+            chain.check_peer(&ChainIdentity::from_config(&other_network)),
+            Err(BlockchainError::NetworkMismatch { expected: 1, actual: 2 })
+        ));
+
+        let mut forked = test_config(false);
+        forked.chain_id = 7;
+        let peer = ChainIdentity::from_config(&forked);
+        assert!(matches!(chain.check_peer(&peer), Err(BlockchainError::GenesisMismatch)));
+
+        let block = QuantumBlock::new(
+            Hash::default(),
+            vec![test_transaction("miner")],
+            Hash::default(),
+            ValidatorSet::default(),
+            1,
+            &BlockOptimizer::new(),
+        ).unwrap();
+        assert!(matches!(chain.add_peer_block(&peer, block).await, Err(BlockchainError::GenesisMismatch)));
+        assert_eq!(chain.blocks.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_same_genesis_config_gives_identical_genesis() {
+        let mut config = test_config(false);
+        config.genesis = GenesisConfig {
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            state_root: Hash::from([7; 32]),
+            extra_data: b"quantumfuse mainnet".to_vec(),
+        };
+
+        let first = QuantumBlockchain::new(config.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let second = QuantumBlockchain::new(config.clone()).await.unwrap();
+
+        assert_eq!(first.identity().genesis_hash, second.identity().genesis_hash);
+        let (first_blocks, second_blocks) = (first.blocks.read().await, second.blocks.read().await);
+        assert_eq!(first_blocks[0].header.timestamp, config.genesis.timestamp);
+        assert_eq!(
+            serde_json::to_vec(&first_blocks[0].header).unwrap(),
+            serde_json::to_vec(&second_blocks[0].header).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_different_genesis_config_gives_different_hash() {
+        let base = test_config(false);
+
+        let mut later = base.clone();
+        later.genesis.timestamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut tagged = base.clone();
+        tagged.genesis.extra_data = b"testnet".to_vec();
+        let mut allocated = base.clone();
+        allocated.genesis.state_root = Hash::from([1; 32]);
+        let mut staked = base.clone();
+        staked.genesis.validators.insert("v1".to_string(), 10);
+
+        let hashes: HashSet<Hash> = [&base, &later, &tagged, &allocated, &staked].iter().map(|c| genesis_hash(c)).collect();
+        assert_eq!(hashes.len(), 5);
+        assert_eq!(genesis_hash(&base), genesis_hash(&test_config(true)));
+    }
+
+    /// Index of the genesis validator `select_proposer` picks at height 1 in `view`.
+    async fn proposer_at(chain: &QuantumBlockchain, keys: &[KeyPair], view: u32) -> usize {
+        let beacon = chain.blocks.read().await[0].header.beacon_randomness;
+        let validators: Vec<String> = keys.iter().map(|key| key.address()).collect();
+        let selected = select_proposer(beacon.as_bytes(), 1, view, &validators).unwrap();
+        keys.iter().position(|key| key.address() == selected).unwrap()
+    }
+
+    /// A chain whose genesis validators are three fresh keys, and the key selected for height 1.
+    async fn chain_with_proposers() -> (QuantumBlockchain, KeyPair, KeyPair) {
+        let (chain, keys) = chain_with_validators(&[1, 1, 1]).await;
+        let index = proposer_at(&chain, &keys, 0).await;
+        let proposer = keys.into_iter().nth(index).unwrap();
+        let other = KeyPair::generate();
+        (chain, proposer, other)
+    }
+
+    fn next_block() -> QuantumBlock {
+        QuantumBlock::new(
+            Hash::default(),
+            vec![test_transaction("miner")],
+            Hash::default(),
+            ValidatorSet::default(),
+            1,
+            &BlockOptimizer::new(),
+        ).unwrap()
+    }
+
+    fn consensus_config(fault_tolerance: f64) -> ConsensusConfig {
+        ConsensusConfig {
+            min_validators: 1,
+            block_time: 5,
+            epoch_length: 100,
+            minimum_stake: 1.0,
+            quantum_security_level: 3,
+            fault_tolerance,
+            liveness: LivenessConfig::default(),
+            view_change: ViewChangeConfig::default(),
+            reward_split: RewardSplitConfig::default(),
+        }
+    }
+
+    fn multi_signed_block(signers: &[&str]) -> QuantumBlock {
+        let key = KeyPair::generate();
+        let mut block = next_block();
+        for signer in signers {
+            block.multi_signatures.insert(signer.to_string(), key.sign(signer.as_bytes()).unwrap());
+        }
+        block
+    }
+
+    fn validator_stakes() -> HashMap<String, f64> {
+        [("v1", 40.0), ("v2", 30.0), ("v3", 20.0), ("v4", 10.0)].iter()
+            .map(|(id, stake)| (id.to_string(), *stake))
+            .collect()
+    }
+
+    #[test]
+    fn test_block_meeting_stake_threshold_is_accepted() {
+        // Two of four signers, but 70% of the stake.
+        let block = multi_signed_block(&["v1", "v2"]);
+        assert!(block.check_signing_stake(&consensus_config(0.33), &validator_stakes()).is_ok());
+    }
+
+    #[test]
+    fn test_block_below_stake_threshold_is_rejected() {
+        // Three of four signers, but only 60% of the stake.
+        let block = multi_signed_block(&["v2", "v3", "v4"]);
+        assert!(matches!(
+            block.check_signing_stake(&consensus_config(0.33), &validator_stakes()),
+            Err(BlockError::NotEnoughSignatures)
+        ));
+    }
+
+    #[test]
+    fn test_configured_threshold_sets_stake_requirement() {
+        let block = multi_signed_block(&["v2", "v3", "v4"]);
+
+        assert!(block.check_signing_stake(&consensus_config(0.33), &validator_stakes()).is_err());
+        assert!(block.check_signing_stake(&consensus_config(0.45), &validator_stakes()).is_ok());
+        assert!(multi_signed_block(&["v1", "v2"]).check_signing_stake(&consensus_config(0.2), &validator_stakes()).is_err());
+    }
+
+    /// A chain whose genesis validators are fresh keys with `stakes`, in order.
+    async fn chain_with_validators(stakes: &[u64]) -> (QuantumBlockchain, Vec<KeyPair>) {
+        let keys: Vec<KeyPair> = stakes.iter().map(|_| KeyPair::generate()).collect();
+        let mut config = test_config(false);
+        config.genesis.validators = keys.iter().zip(stakes).map(|(key, stake)| (key.address(), *stake)).collect();
+        (QuantumBlockchain::new(config).await.unwrap(), keys)
+    }
+
+    /// `next_block` carrying the genesis validator set, attested by `signers`.
+    async fn attested_block(chain: &QuantumBlockchain, signers: &[&KeyPair]) -> QuantumBlock {
+        let mut block = next_block();
+        block.validator_set = chain.blocks.read().await[0].validator_set.clone();
+        block.header.validator_set_hash = block.validator_set.compute_hash().unwrap();
+        for signer in signers {
+            block.attest(signer).unwrap();
+        }
+        block
+    }
+
+    /// A block at `height` over the genesis validators carrying `transactions`,
+    /// attested by every validator and signed by its selected proposer.
+    async fn committable_block(
+        chain: &QuantumBlockchain,
+        keys: &[KeyPair],
+        height: u64,
+        tag: &[u8],
+        transactions: Vec<QuantumTransaction>,
+    ) -> QuantumBlock {
+        let genesis = chain.blocks.read().await[0].clone();
+        let mut block = next_block();
+        block.transactions = transactions;
+        block.header.height = height;
+        block.header.timestamp = Utc::now();
+        block.header.extra_data = tag.to_vec();
+        block.header.beacon_randomness = genesis.header.beacon_randomness;
+        block.header.state_root = chain.state_manager.read().await.calculate_state_root().unwrap();
+        block.validator_set = genesis.validator_set.clone();
+        block.header.validator_set_hash = genesis.header.validator_set_hash;
+        for key in keys {
+            block.attest(key).unwrap();
+        }
+        block.sign_as_proposer(proposer_for(chain, keys, height).await).unwrap();
+        block
+    }
+
+    async fn proposer_for<'a>(chain: &QuantumBlockchain, keys: &'a [KeyPair], height: u64) -> &'a KeyPair {
+        let beacon = chain.blocks.read().await[0].header.beacon_randomness;
+        let validators: Vec<String> = keys.iter().map(|key| key.address()).collect();
+        let selected = select_proposer(beacon.as_bytes(), height, 0, &validators).unwrap();
+        keys.iter().find(|key| key.address() == selected).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failed_reorg_restores_abandoned_blocks() {
+        let (chain, keys) = chain_with_validators(&[1, 1, 1]).await;
+        for height in 1..=3 {
+            let block = committable_block(&chain, &keys, height, b"main", Vec::new()).await;
+            chain.add_block(block).await.unwrap();
+        }
+        let target = KeyPair::generate().address();
+        let approval = ComplianceApproval { address: target.clone(), freeze: true };
+        chain.approve_compliance_proposal("proposal-42", approval).await;
+        let total_blocks = chain.metrics.read().await.total_blocks;
+
+        // The branch freezes `target` at height 2, then fails at 4 on an unsigned block.
+        let mut freeze = QuantumTransaction::new_system(target.clone(), Amount::ZERO, OperationType::FreezeAccount).unwrap();
+        freeze.data.parameters.insert("proposal_id".to_string(), "proposal-42".to_string());
+        freeze.sign(proposer_for(&chain, &keys, 2).await).unwrap();
+        let mut branch = vec![
+            committable_block(&chain, &keys, 2, b"branch", vec![freeze.clone()]).await,
+            committable_block(&chain, &keys, 3, b"branch", Vec::new()).await,
+            committable_block(&chain, &keys, 4, b"branch", Vec::new()).await,
+        ];
+        branch[2].signature = None;
+        assert!(matches!(chain.reorganize(1, branch).await, Err(BlockchainError::MissingProposerSignature)));
+
+        let blocks = chain.blocks.read().await;
+        let heights: Vec<u64> = blocks.iter().map(|b| b.header.height).collect();
+        assert_eq!(heights, vec![0, 1, 2, 3]);
+        assert!(blocks[1..].iter().all(|b| b.header.extra_data == b"main"));
+        drop(blocks);
+
+        assert_eq!(chain.metrics.read().await.total_blocks, total_blocks);
+        let mut frozen_accounts = chain.frozen_accounts.write().await;
+        assert!(!frozen_accounts.is_frozen(&target));
+        // The approval the branch spent is available again.
+        frozen_accounts.apply_governance_action(&freeze).unwrap();
+        assert!(frozen_accounts.is_frozen(&target));
+    }
+
+    fn signed_transfer(key: &KeyPair) -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new(
+            key.address(),
+            KeyPair::generate().address(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap().with_chain_id(1).unwrap();
+        tx.sign(key).unwrap();
+        tx
+    }
+
+    #[tokio::test]
+    async fn test_block_with_one_bad_signature_is_rejected() {
+        let (chain, keys) = chain_with_validators(&[1, 1, 1]).await;
+        let senders: Vec<KeyPair> = (0..16).map(|_| KeyPair::generate()).collect();
+        let mut transactions: Vec<QuantumTransaction> = senders.iter().map(signed_transfer).collect();
+        // Re-signed by a key other than the sender's.
+        transactions[11].sign(&KeyPair::generate()).unwrap();
+
+        let block = committable_block(&chain, &keys, 1, b"bad", transactions).await;
+        let result = chain.add_block(block).await;
+        assert!(matches!(result, Err(BlockchainError::InvalidBlock(e)) if e == "Invalid transaction signature"));
+        assert_eq!(chain.blocks.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_shard_validators_are_reassigned_at_epoch_boundaries() {
+        let keys: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+        let mut config = test_config(false);
+        config.consensus.epoch_length = 2;
+        config.genesis.validators = keys.iter().map(|key| (key.address(), 1)).collect();
+        let chain = QuantumBlockchain::new(config).await.unwrap();
+        let allocator = ShardAllocator::with_shards(
+            ShardConfig { min_validators_per_shard: 0, ..shard_config() },
+            chain.shard_manager.clone(),
+        );
+        chain.reshard(&allocator, 2, |_| {}).await.unwrap();
+        let chain = chain.with_shard_allocator(Arc::new(allocator)).unwrap();
+        let assigned = |chain: &QuantumBlockchain| {
+            let shards = chain.shard_manager.clone();
+            async move { shards.read().await.values().map(|shard| shard.validators.len()).sum::<usize>() }
+        };
+
+        chain.add_block(committable_block(&chain, &keys, 1, b"", Vec::new()).await).await.unwrap();
+        assert_eq!(assigned(&chain).await, 0);
+
+        chain.add_block(committable_block(&chain, &keys, 2, b"", Vec::new()).await).await.unwrap();
+        assert_eq!(assigned(&chain).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_validation_enforces_signing_stake() {
+        let (chain, keys) = chain_with_validators(&[40, 30, 20, 10]).await;
+
+        // 70% of the stake, properly signed.
+        let block = attested_block(&chain, &[&keys[0], &keys[1]]).await;
+        chain.check_multi_signatures(&block).await.unwrap();
+
+        // 60% of the stake falls short.
+        let block = attested_block(&chain, &[&keys[1], &keys[2], &keys[3]]).await;
+        assert!(matches!(chain.check_multi_signatures(&block).await, Err(BlockchainError::InvalidBlock(_))));
+    }
+
+    #[tokio::test]
+    async fn test_listed_validator_without_its_signature_counts_nothing() {
+        let (chain, keys) = chain_with_validators(&[40, 30, 20, 10]).await;
+        let mut block = attested_block(&chain, &[&keys[1]]).await;
+
+        // Claim the largest validator's stake with someone else's signature.
+        let forged = block.multi_signatures[&keys[1].address()].clone();
+        block.multi_signatures.insert(keys[0].address(), forged);
+        assert!(matches!(chain.check_multi_signatures(&block).await, Err(BlockchainError::InvalidBlock(_))));
+
+        // Swapping in a different validator set is rejected outright.
+        let mut block = attested_block(&chain, &[&keys[0], &keys[1]]).await;
+        block.header.validator_set_hash = Hash::from([7; 32]);
+        assert!(matches!(chain.check_multi_signatures(&block).await, Err(BlockchainError::InvalidBlock(_))));
+    }
+
+    /// A chain whose genesis was attested by the returned key, and a
+    /// transaction that passes admission checks.
+    async fn chain_with_attester() -> (QuantumBlockchain, KeyPair, QuantumTransaction) {
+        let chain = QuantumBlockchain::new(test_config(false)).await.unwrap();
+        let key = KeyPair::generate();
+        chain.blocks.write().await[0].multi_signatures.insert(key.address(), key.sign(b"genesis").unwrap());
+
+        let mut transaction = test_transaction(&key.address());
+        transaction.chain_id = 1;
+        transaction.sign(&key).unwrap();
+        (chain, key, transaction)
+    }
+
+    fn inclusion_list(attester: &KeyPair, transactions: Vec<QuantumTransaction>) -> InclusionList {
+        let mut list = InclusionList { height: 2, attester: String::new(), transactions, signature: None };
+        list.sign(attester).unwrap();
+        list
+    }
+
+    /// Submits `list`, then appends a block at height 1 carrying it.
+    async fn carry_inclusion_list(chain: &QuantumBlockchain, list: InclusionList) {
+        chain.submit_inclusion_list(list).await.unwrap();
+        let mut carrier = next_block();
+        carrier.inclusion_lists = chain.take_inclusion_lists(1).await;
+        chain.check_carried_inclusion_lists(&carrier).await.unwrap();
+        chain.blocks.write().await.push(carrier);
+    }
+
+    fn block_at_two() -> QuantumBlock {
+        let mut block = next_block();
+        block.header.height = 2;
+        block
+    }
+
+    #[tokio::test]
+    async fn test_block_omitting_inclusion_list_transaction_is_rejected() {
+        let (chain, attester, transaction) = chain_with_attester().await;
+        carry_inclusion_list(&chain, inclusion_list(&attester, vec![transaction.clone()])).await;
+
+        let result = chain.check_inclusion_list(&block_at_two()).await;
+        assert!(matches!(result, Err(BlockchainError::CensoredTransactions(ref hashes)) if *hashes == vec![transaction.hash]));
+    }
+
+    #[tokio::test]
+    async fn test_inclusion_lists_must_be_signed_by_an_attester_and_bounded() {
+        let (chain, attester, transaction) = chain_with_attester().await;
+
+        // Only the attesters of the carrier's parent may submit a list.
+        let outsider = inclusion_list(&KeyPair::generate(), Vec::new());
+        assert!(matches!(chain.submit_inclusion_list(outsider.clone()).await, Err(BlockchainError::NotAnAttester(_))));
+
+        // Naming an attester without its signature is rejected.
+        let forged = InclusionList { attester: attester.address(), ..outsider.clone() };
+        assert!(matches!(chain.submit_inclusion_list(forged).await, Err(BlockchainError::InvalidBlock(_))));
+
+        let oversized = inclusion_list(&attester, vec![transaction; MAX_INCLUSION_LIST_LEN + 1]);
+        assert!(matches!(chain.submit_inclusion_list(oversized).await, Err(BlockchainError::InvalidBlock(_))));
+
+        // A proposer can't slip in a list the attesters never signed either.
+        let mut carrier = next_block();
+        carrier.inclusion_lists.push(outsider);
+        assert!(matches!(chain.check_carried_inclusion_lists(&carrier).await, Err(BlockchainError::NotAnAttester(_))));
+    }
+
+    #[tokio::test]
+    async fn test_block_including_inclusion_list_transaction_passes() {
+        let (chain, attester, transaction) = chain_with_attester().await;
+        carry_inclusion_list(&chain, inclusion_list(&attester, vec![transaction.clone()])).await;
+
+        let mut block = block_at_two();
+        block.transactions.push(transaction);
+        assert!(chain.check_inclusion_list(&block).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_inclusion_list_transaction_may_be_omitted() {
+        let (chain, attester, _) = chain_with_attester().await;
+        let unsigned = test_transaction("someone");
+        carry_inclusion_list(&chain, inclusion_list(&attester, vec![unsigned.clone()])).await;
+
+        assert!(chain.check_inclusion_list(&block_at_two()).await.is_ok());
+        assert_eq!(omitted_inclusions(&next_block(), &[unsigned.clone()], 1_000), vec![unsigned.hash]);
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_block_is_rejected() {
+        let (chain, _, _) = chain_with_proposers().await;
+        let block = next_block();
+
+        assert!(matches!(chain.verify_proposer(&block).await, Err(BlockchainError::MissingProposerSignature)));
+        assert!(matches!(chain.validate_block(&block).await, Err(BlockchainError::MissingProposerSignature)));
+        // Genesis carries no proposer.
+        let genesis = chain.blocks.read().await[0].clone();
+        assert!(chain.verify_proposer(&genesis).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_state_root_is_checked_after_execution() {
+        let chain = QuantumBlockchain::new(test_config(false)).await.unwrap();
+        let mut block = chain.blocks.read().await[0].clone();
+        block.header.height = 1;
+
+        block.header.state_root = Hash::from([9; 32]);
+        assert!(matches!(
+            chain.process_block_transactions(&block).await,
+            Err(BlockchainError::StateRootMismatch { .. })
+        ));
+
+        block.header.state_root = chain.state_manager.read().await.calculate_state_root().unwrap();
+        chain.process_block_transactions(&block).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_committed_blocks_record_balance_history() {
+        let mut config = test_config(false);
+        config.state_history = StateHistoryConfig { checkpoint_interval: 2, retained_blocks: Some(2) };
+        let chain = QuantumBlockchain::new(config).await.unwrap();
+        let mut block = chain.blocks.read().await[0].clone();
+        block.header.state_root = chain.state_manager.read().await.calculate_state_root().unwrap();
+
+        for height in 1..=5 {
+            block.header.height = height;
+            chain.process_block_transactions(&block).await.unwrap();
+        }
+
+        let state_manager = chain.state_manager.read().await;
+        assert_eq!(state_manager.get_balance_at("alice", 5).unwrap(), Amount::ZERO);
+        assert_eq!(state_manager.get_balance_at("alice", 4).unwrap(), Amount::ZERO);
+        assert!(matches!(state_manager.get_balance_at("alice", 1), Err(StateError::HeightNotFound(1))));
+    }
+
+    #[tokio::test]
+    async fn test_block_signed_by_selected_proposer_is_accepted() {
+        let (chain, proposer, _) = chain_with_proposers().await;
+        let mut block = next_block();
+        block.sign_as_proposer(&proposer).unwrap();
+
+        chain.verify_proposer(&block).await.unwrap();
+
+        // Changing the header afterwards breaks the signature.
+        block.header.extra_data = b"tampered".to_vec();
+        assert!(matches!(chain.verify_proposer(&block).await, Err(BlockchainError::InvalidProposerSignature)));
+    }
+
+    #[tokio::test]
+    async fn test_later_view_needs_a_timeout_certificate() {
+        let (chain, keys) = chain_with_validators(&[1, 1, 1, 1]).await;
+        let view_one = &keys[proposer_at(&chain, &keys, 1).await];
+        let mut block = next_block();
+        block.view = 1;
+        block.sign_as_proposer(view_one).unwrap();
+        assert!(matches!(chain.verify_proposer(&block).await, Err(BlockchainError::MissingTimeoutCertificate(1))));
+
+        // One timeout vote out of four is not enough to move past view 0.
+        let mut certificate = TimeoutCertificate::new(1);
+        certificate.add_vote(&keys[0], &block.header.prev_hash, 1).unwrap();
+        block.timeout_certificate = Some(certificate.clone());
+        block.sign_as_proposer(view_one).unwrap();
+        assert!(chain.verify_proposer(&block).await.is_err());
+
+        for key in &keys[1..] {
+            certificate.add_vote(key, &block.header.prev_hash, 1).unwrap();
+        }
+        block.timeout_certificate = Some(certificate);
+        block.sign_as_proposer(view_one).unwrap();
+        chain.verify_proposer(&block).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_block_signed_by_non_proposer_is_rejected() {
+        let (chain, proposer, other) = chain_with_proposers().await;
+        let mut block = next_block();
+        block.sign_as_proposer(&other).unwrap();
+        assert!(matches!(chain.verify_proposer(&block).await, Err(BlockchainError::WrongProposer { .. })));
+
+        // Claiming to be the proposer without its key fails too.
+        block.proposer = Some(proposer.address());
+        assert!(matches!(chain.verify_proposer(&block).await, Err(BlockchainError::InvalidProposerSignature)));
+    }
+
+    /// Accepts `fail_at` appends, then rejects the next one.
+    struct FailingStore {
+        inner: InMemoryLedgerStore,
+        fail_at: usize,
+    }
+
+    impl LedgerStore for FailingStore {
+        fn entry_count(&self) -> usize {
+            self.inner.entry_count()
+        }
+
+        fn append(&mut self, entry: LedgerEntry) -> Result<(), BlockchainError> {
+            if self.inner.entry_count() == self.fail_at {
+                return Err(BlockchainError::Internal("disk full".to_string()));
+            }
+            self.inner.append(entry)
+        }
+
+        fn truncate(&mut self, len: usize) {
+            self.inner.truncate(len)
+        }
+
+        fn entries(&self) -> &[LedgerEntry] {
+            self.inner.entries()
+        }
+    }
+
+    fn event_batch(ids: &[&str]) -> Vec<(String, u64)> {
+        ids.iter().enumerate().map(|(i, id)| (id.to_string(), i as u64)).collect()
+    }
+
+    #[test]
+    fn test_receipts_beyond_retention_are_pruned() {
+        let (store, _) = receipt_store(ReceiptRetention::LastBlocks(3), 10);
+        assert_eq!(store.retained_from(), 8);
+        assert_eq!(store.blocks.keys().copied().collect::<Vec<_>>(), vec![8, 9, 10]);
+        assert_eq!(store.heights.len(), 3);
+
+        let (mut store, _) = receipt_store(ReceiptRetention::FinalizedPlusMargin(2), 10);
+        assert_eq!(store.retained_from(), 0);
+        store.set_finalized(6);
+        assert_eq!(store.retained_from(), 4);
+    }
+
+    #[test]
+    fn test_pruned_receipt_queries_signal_pruning() {
+        let (store, hashes) = receipt_store(ReceiptRetention::LastBlocks(3), 10);
+
+        assert!(matches!(store.query(1, 10, None), Err(BlockchainError::ReceiptsPruned { retained_from: 8 })));
+        assert!(matches!(store.get(&hashes[1]), Err(BlockchainError::ReceiptsPruned { retained_from: 8 })));
+
+        let (unpruned, _) = receipt_store(ReceiptRetention::KeepAll, 10);
+        assert!(unpruned.get(&Hash::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_recent_receipts_remain_retrievable() {
+        let (store, hashes) = receipt_store(ReceiptRetention::LastBlocks(3), 10);
+
+        assert_eq!(store.query(8, 10, None).unwrap().len(), 3);
+        assert_eq!(store.query(8, 10, Some("sender-9")).unwrap()[0].block_height, 9);
+        assert_eq!(store.get(&hashes[8]).unwrap().unwrap().block_height, 9);
+    }
+
+    #[test]
+    fn test_event_batch_is_persisted_in_order() {
+        let ledger = QuantumLedger::new();
+        ledger.record_event("genesis", &0u64).unwrap();
+        ledger.record_events_batch(&event_batch(&["a", "b", "c"])).unwrap();
+
+        let ids: Vec<_> = ledger.events().unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["genesis", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_mid_batch_failure_rolls_back_whole_batch() {
+        let ledger = QuantumLedger::new();
+        ledger.record_event("b", &0u64).unwrap();
+
+        let result = ledger.record_events_batch(&event_batch(&["a", "b", "c"]));
+        assert!(matches!(result, Err(BlockchainError::DuplicateEvent(ref id)) if id == "b"));
+        assert_eq!(ledger.events().unwrap().len(), 1);
+
+        // The rolled-back ids can be recorded again.
+        ledger.record_events_batch(&event_batch(&["a", "c"])).unwrap();
+        assert_eq!(ledger.events().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_store_failure_rolls_back_whole_batch() {
+        let ledger = QuantumLedger::with_store(FailingStore { inner: InMemoryLedgerStore::default(), fail_at: 3 });
+        ledger.record_event("first", &0u64).unwrap();
+
+        let result = ledger.record_events_batch(&event_batch(&["a", "b", "c", "d"]));
+        assert!(matches!(result, Err(BlockchainError::Internal(_))));
+
+        let ids: Vec<_> = ledger.events().unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["first"]);
+    }
+
+    #[test]
+    fn test_batcher_flushes_at_batch_size() {
+        let ledger = QuantumLedger::new();
+        let batcher = LedgerBatcher::new(ledger.clone(), LedgerBatchConfig {
+            max_events: 3,
+            flush_interval: Duration::from_secs(3600),
+        });
+
+        batcher.push("a".to_string(), 1u64).unwrap();
+        batcher.push("b".to_string(), 2u64).unwrap();
+        assert!(ledger.events().unwrap().is_empty());
+        assert_eq!(batcher.pending(), 2);
+
+        batcher.push("c".to_string(), 3u64).unwrap();
+        assert_eq!(ledger.events().unwrap().len(), 3);
+        assert_eq!(batcher.pending(), 0);
+    }
+
+    #[test]
+    fn test_failed_flush_keeps_events_queued() {
+        let ledger = QuantumLedger::new();
+        ledger.record_event("b", &0u64).unwrap();
+        let batcher = LedgerBatcher::new(ledger.clone(), LedgerBatchConfig::default());
+
+        batcher.push("a".to_string(), 1u64).unwrap();
+        batcher.push("b".to_string(), 2u64).unwrap();
+        assert!(batcher.flush().is_err());
+        assert_eq!(batcher.pending(), 2);
+        assert_eq!(ledger.events().unwrap().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batcher_flushes_on_its_timer() {
+        let ledger = QuantumLedger::new();
+        let batcher = LedgerBatcher::new(ledger.clone(), LedgerBatchConfig {
+            max_events: 64,
+            flush_interval: Duration::from_secs(5),
+        });
+
+        batcher.push("a".to_string(), 1u64).unwrap();
+        assert!(ledger.events().unwrap().is_empty());
+
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(ledger.events().unwrap().len(), 1);
+        assert_eq!(batcher.pending(), 0);
+    }
+
+    #[test]
+    fn test_dropped_batcher_flushes_pending_events() {
+        let ledger = QuantumLedger::new();
+        let batcher = LedgerBatcher::new(ledger.clone(), LedgerBatchConfig {
+            max_events: 64,
+            flush_interval: Duration::from_secs(3600),
+        });
+
+        batcher.push("a".to_string(), 1u64).unwrap();
+        drop(batcher);
+        assert_eq!(ledger.events().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_sealed_commitments_are_blinded() {
+        let domain = "quantumfuse test record v1";
+        let first = SealedCommitment::seal(domain, "record-1", b"diagnosis");
+        let second = SealedCommitment::seal(domain, "record-1", b"diagnosis");
+        assert_ne!(first.commitment, second.commitment);
+
+        let opened = SealedCommitment::open(domain, "record-1", &first.payload, &first.commitment);
+        assert_eq!(opened, Some(&b"diagnosis"[..]));
+        assert!(SealedCommitment::open(domain, "record-2", &first.payload, &first.commitment).is_none());
+        assert!(SealedCommitment::open(domain, "record-1", &second.payload, &first.commitment).is_none());
     }
 }
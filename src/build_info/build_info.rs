@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What a running binary reports about itself, so a mixed fleet can be told apart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BuildInfo {
+    pub version: String,
+    /// `unknown` when built outside a git checkout.
+    pub git_commit: String,
+    pub build_timestamp: Option<DateTime<Utc>>,
+    pub chain_id: Option<u64>,
+    pub network_id: Option<u64>,
+}
+
+impl BuildInfo {
+    /// Values stamped in at compile time by the build script.
+    pub fn current() -> Self {
+        let build_timestamp = option_env!("QUANTUMFUSE_BUILD_UNIX_SECS")
+            .and_then(|secs| secs.parse::<i64>().ok())
+            .and_then(|secs| DateTime::from_timestamp(secs, 0));
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: option_env!("QUANTUMFUSE_GIT_COMMIT").unwrap_or("unknown").to_string(),
+            build_timestamp,
+            chain_id: None,
+            network_id: None,
+        }
+    }
+
+    pub fn with_chain(self, chain_id: u64, network_id: u64) -> Self {
+        Self { chain_id: Some(chain_id), network_id: Some(network_id), ..self }
+    }
+}
+
@@ -0,0 +1,4 @@
+pub fn report_build_info() -> Result<(), Box<dyn std::error::Error>> {
+println!("🏷️ Reporting Build Version Info... ✅");
+Ok(())
+}
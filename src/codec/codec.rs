@@ -0,0 +1,169 @@
+use ciborium::value::Value;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use quantumfuse_sdk::error::CodecError;
+
+/// Serializes `value` into deterministic CBOR for hashing.
+///
+/// Map entries (including struct fields and `HashMap`s) are sorted by their encoded key bytes,
+/// following the RFC 8949 core deterministic encoding rules, so the output does not depend on
+/// map iteration order. Use this only where bytes feed a hash; API payloads stay JSON.
+pub fn to_canonical_cbor<T: Serialize>(value: &T) -> Result<Vec<u8>, CodecError> {
+    let value = Value::serialized(value).map_err(|e| CodecError::Serialization(e.to_string()))?;
+    encode(&canonicalize(value)?)
+}
+
+/// Encoding for transactions and blocks on the wire and at rest. Hashes never
+/// depend on it; they are always taken over `to_canonical_cbor`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireFormat {
+    /// What the public API serves.
+    #[default]
+    Json,
+    /// Compact and fast; for P2P messages and storage.
+    Bincode,
+}
+
+impl WireFormat {
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, CodecError> {
+        match self {
+            WireFormat::Json => serde_json::to_vec(value).map_err(|e| CodecError::Serialization(e.to_string())),
+            WireFormat::Bincode => bincode::serialize(value).map_err(|e| CodecError::Serialization(e.to_string())),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, CodecError> {
+        match self {
+            WireFormat::Json => serde_json::from_slice(bytes).map_err(|e| CodecError::Deserialization(e.to_string())),
+            WireFormat::Bincode => bincode::deserialize(bytes).map_err(|e| CodecError::Deserialization(e.to_string())),
+        }
+    }
+}
+
+/// Which format each channel uses. The public API is always JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodecConfig {
+    pub network: WireFormat,
+    pub storage: WireFormat,
+}
+
+impl Default for CodecConfig {
+    fn default() -> Self {
+        Self {
+            network: WireFormat::Bincode,
+            storage: WireFormat::Bincode,
+        }
+    }
+}
+
+fn canonicalize(value: Value) -> Result<Value, CodecError> {
+    Ok(match value {
+        Value::Map(entries) => {
+            let mut keyed = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = canonicalize(key)?;
+                    Ok((encode(&key)?, key, canonicalize(value)?))
+                })
+                .collect::<Result<Vec<_>, CodecError>>()?;
+            keyed.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Map(keyed.into_iter().map(|(_, key, value)| (key, value)).collect())
+        }
+        Value::Array(items) => Value::Array(
+            items.into_iter().map(canonicalize).collect::<Result<Vec<_>, CodecError>>()?,
+        ),
+        Value::Tag(tag, inner) => Value::Tag(tag, Box::new(canonicalize(*inner)?)),
+        other => other,
+    })
+}
+
+fn encode(value: &Value) -> Result<Vec<u8>, CodecError> {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(value, &mut bytes).map_err(|e| CodecError::Serialization(e.to_string()))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use quantumfuse_sdk::{
+        amount::Amount,
+        block::QuantumBlock,
+        consensus::ValidatorSet,
+        ai::BlockOptimizer,
+        crypto::Hash,
+        transaction::{OperationType, QuantumTransaction},
+    };
+
+    fn test_transaction(from: &str) -> QuantumTransaction {
+        QuantumTransaction::new(
+            from.to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap()
+    }
+
+    fn test_block() -> QuantumBlock {
+        QuantumBlock::new(
+            Hash::default(),
+            vec![test_transaction("alice"), test_transaction("bob")],
+            Hash::default(),
+            ValidatorSet::default(),
+            1,
+            &BlockOptimizer::new(),
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_block_round_trips_through_bincode() {
+        let block = test_block();
+        let bytes = WireFormat::Bincode.encode(&block).unwrap();
+        let decoded: QuantumBlock = WireFormat::Bincode.decode(&bytes).unwrap();
+
+        assert_eq!(WireFormat::Bincode.encode(&decoded).unwrap(), bytes);
+        assert_eq!(to_canonical_cbor(&decoded).unwrap(), to_canonical_cbor(&block).unwrap());
+        assert!(bytes.len() < WireFormat::Json.encode(&block).unwrap().len());
+        assert!(WireFormat::Bincode.decode::<QuantumBlock>(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_json_output_is_unchanged() {
+        let tx = test_transaction("alice");
+        let block = test_block();
+        assert_eq!(WireFormat::Json.encode(&tx).unwrap(), serde_json::to_vec(&tx).unwrap());
+        assert_eq!(WireFormat::Json.encode(&block).unwrap(), serde_json::to_vec(&block).unwrap());
+        assert_eq!(WireFormat::default(), WireFormat::Json);
+    }
+
+    #[test]
+    fn test_hash_is_codec_independent() {
+        let tx = test_transaction("alice");
+        let via_json: QuantumTransaction = WireFormat::Json.decode(&WireFormat::Json.encode(&tx).unwrap()).unwrap();
+        let via_bincode: QuantumTransaction = WireFormat::Bincode.decode(&WireFormat::Bincode.encode(&tx).unwrap()).unwrap();
+
+        let canonical = to_canonical_cbor(&tx).unwrap();
+        assert_eq!(to_canonical_cbor(&via_json).unwrap(), canonical);
+        assert_eq!(to_canonical_cbor(&via_bincode).unwrap(), canonical);
+        assert_eq!(via_json.hash, tx.hash);
+        assert_eq!(via_bincode.hash, tx.hash);
+    }
+
+    #[test]
+    fn test_map_order_does_not_change_encoding() {
+        let mut first = HashMap::new();
+        let mut second = HashMap::new();
+        for i in 0..32 {
+            first.insert(format!("key_{}", i), i);
+        }
+        for i in (0..32).rev() {
+            second.insert(format!("key_{}", i), i);
+        }
+
+        assert_eq!(to_canonical_cbor(&first).unwrap(), to_canonical_cbor(&second).unwrap());
+    }
+}
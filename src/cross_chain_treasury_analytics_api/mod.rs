@@ -1,13 +1,82 @@
-use serde_json::json;
+use std::net::{AddrParseError, SocketAddr};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Failures surfaced by the cross-chain analytics entrypoints.
+#[derive(Debug, Error)]
+pub enum AnalyticsError {
+    #[error("invalid configuration: {0}")]
+    Config(String),
+    #[error("invalid bind address: {0}")]
+    InvalidAddress(#[from] AddrParseError),
+    #[error("network request failed: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("I/O failure: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrossChainAnalytics {
+    pub total_cross_chain_transfers: u64,
+    pub total_value_transferred: f64,
+    pub interoperability_score: u8,
+}
+
+impl CrossChainAnalytics {
+    pub fn from_json(body: &str) -> Result<Self, AnalyticsError> {
+        Ok(serde_json::from_str(body)?)
+    }
+}
+
+pub fn parse_bind_address(address: &str) -> Result<SocketAddr, AnalyticsError> {
+    let address: SocketAddr = address.parse()?;
+    if address.port() == 0 {
+        return Err(AnalyticsError::Config(format!("{} has no fixed port", address)));
+    }
+    Ok(address)
+}
+
+/// Pulls analytics from a node's HTTP API.
+pub async fn fetch_cross_chain_analytics(url: &str) -> Result<CrossChainAnalytics, AnalyticsError> {
+    let body = reqwest::get(url).await?.error_for_status()?.text().await?;
+    CrossChainAnalytics::from_json(&body)
+}
 
 // ✅ Make sure this function is public so it can be used in `main.rs`
-pub fn get_cross_chain_analytics() -> Result<(), Box<dyn std::error::Error>> {
+pub fn get_cross_chain_analytics() -> Result<(), AnalyticsError> {
     println!("🔗 Retrieving cross-chain treasury analytics...");
-    let data = json!({
-        "total_cross_chain_transfers": 1000,
-        "total_value_transferred": 10_000_000.0,
-        "interoperability_score": 85
-    });
-    println!("📊 Cross-chain analytics data: {}", data);
+    let data = CrossChainAnalytics {
+        total_cross_chain_transfers: 1000,
+        total_value_transferred: 10_000_000.0,
+        interoperability_score: 85,
+    };
+    println!("📊 Cross-chain analytics data: {}", serde_json::to_string(&data)?);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bad_port_is_config_error() {
+        assert!(matches!(parse_bind_address("127.0.0.1:99999"), Err(AnalyticsError::InvalidAddress(_))));
+        assert!(matches!(parse_bind_address("127.0.0.1:0"), Err(AnalyticsError::Config(_))));
+        assert_eq!(parse_bind_address("127.0.0.1:8083").unwrap().port(), 8083);
+    }
+
+    #[tokio::test]
+    async fn test_connection_failure_is_network_error() {
+        // Nothing listens on port 1.
+        let result = fetch_cross_chain_analytics("http://127.0.0.1:1/analytics").await;
+        assert!(matches!(result, Err(AnalyticsError::Network(_))));
+    }
+
+    #[test]
+    fn test_malformed_payload_is_serialization_error() {
+        let result = CrossChainAnalytics::from_json("{\"total_cross_chain_transfers\": \"many\"}");
+        assert!(matches!(result, Err(AnalyticsError::Serialization(_))));
+    }
+}
@@ -1,18 +1,23 @@
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use quantumfuse_sdk::{
     crypto::{Hash, KeyPair, AESGCM},
+    pqc::dilithium::{PublicKey as AddressPublicKey, Signature as AddressSignature},
     error::DIDError,
     storage::Storage,
-    verification::VerificationMethod,
     consensus::QuantumBridge,
     ai::FraudDetector,
     hardware::{HSM, FIDO2Authenticator},
     explorer::DIDTrackerAPI,
 };
-use pqcrypto::sign::dilithium2::{self, PublicKey, SecretKey, Signature};
-use pqcrypto::kem::kyber512::{encapsulate, decapsulate, generate_keypair as kyber_generate};
+use pqcrypto::prelude::*;
+use pqcrypto::sign::dilithium2::{self, DetachedSignature, PublicKey, SecretKey};
+use rayon::prelude::*;
+use pqcrypto::kem::kyber512::{self, encapsulate, decapsulate, Ciphertext as KyberCiphertext, PublicKey as KyberPublicKey, SecretKey as KyberSecretKey};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuantumDID {
@@ -30,6 +35,29 @@ pub struct QuantumDID {
     proof: Option<DIDProof>,
 }
 
+/// A key published by a DID, usable for signatures made inside its validity window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationMethod {
+    pub id: String,
+    pub type_: String,
+    pub controller: String,
+    pub public_key_multibase: String,
+    /// Unset means valid from the DID's creation.
+    #[serde(default)]
+    pub valid_from: Option<DateTime<Utc>>,
+    /// Set when the key is rotated out; unset while the key is current.
+    #[serde(default)]
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+impl VerificationMethod {
+    fn is_active_at(&self, created: DateTime<Utc>, at: DateTime<Utc>) -> bool {
+        at >= self.valid_from.unwrap_or(created) && self.valid_until.map_or(true, |until| at < until)
+    }
+}
+
+const SIGNING_KEY_TYPE: &str = "DilithiumVerificationKey2023";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Service {
     pub id: String,
@@ -71,9 +99,11 @@ impl QuantumDID {
 
         let verification_method = VerificationMethod {
             id: format!("{}#quantum-key-1", id),
-            type_: "DilithiumVerificationKey2023".to_string(),
+            type_: SIGNING_KEY_TYPE.to_string(),
             controller: controller.clone(),
             public_key_multibase: base58::encode(public_key),
+            valid_from: Some(now),
+            valid_until: None,
         };
 
         let mut did = Self {
@@ -128,6 +158,84 @@ impl QuantumDID {
         Ok(())
     }
 
+    /// Publishes a Kyber512 key as a key-agreement method and returns its method ID.
+    pub fn add_key_agreement_key(&mut self, public_key: &KyberPublicKey) -> String {
+        let method_id = format!("{}#kem-key-{}", self.id, self.key_agreement.len() + 1);
+        self.verification_methods.push(VerificationMethod {
+            id: method_id.clone(),
+            type_: "Kyber512KeyAgreementKey2023".to_string(),
+            controller: self.controller.clone(),
+            public_key_multibase: base58::encode(public_key.as_bytes()),
+            valid_from: Some(Utc::now()),
+            valid_until: None,
+        });
+        self.key_agreement.push(method_id.clone());
+        self.updated = Utc::now();
+        method_id
+    }
+
+    /// The first key-agreement method that resolves to a Kyber512 key.
+    pub fn key_agreement_key(&self) -> Result<(String, KyberPublicKey), DIDError> {
+        let method = self.key_agreement.iter()
+            .find_map(|id| self.verification_methods.iter().find(|m| &m.id == id))
+            .ok_or(DIDError::NoKeyAgreementMethod)?;
+
+        let key_bytes = base58::decode(&method.public_key_multibase)
+            .map_err(|_| DIDError::InvalidPublicKey)?;
+        let public_key = KyberPublicKey::from_bytes(&key_bytes)
+            .map_err(|_| DIDError::InvalidPublicKey)?;
+        Ok((method.id.clone(), public_key))
+    }
+
+    /// Retires the current signing key at `at`, publishes `new_public_key` from
+    /// that instant, and re-signs the document with the new key.
+    pub fn rotate_key(&mut self, new_public_key: &PublicKey, new_secret_key: &SecretKey, at: DateTime<Utc>) -> Result<String, DIDError> {
+        let current_id = self.authentication.last().cloned().ok_or(DIDError::InvalidVerificationMethod)?;
+        let current = self.verification_methods.iter_mut()
+            .find(|m| m.id == current_id)
+            .ok_or(DIDError::InvalidVerificationMethod)?;
+        if at <= current.valid_from.unwrap_or(self.created) {
+            return Err(DIDError::InvalidRotationTime);
+        }
+        current.valid_until = Some(at);
+
+        let key_number = self.verification_methods.iter().filter(|m| m.type_ == SIGNING_KEY_TYPE).count() + 1;
+        let method_id = format!("{}#quantum-key-{}", self.id, key_number);
+        self.verification_methods.push(VerificationMethod {
+            id: method_id.clone(),
+            type_: SIGNING_KEY_TYPE.to_string(),
+            controller: self.controller.clone(),
+            public_key_multibase: base58::encode(new_public_key.as_bytes()),
+            valid_from: Some(at),
+            valid_until: None,
+        });
+        self.authentication = vec![method_id.clone()];
+        self.updated = at;
+
+        self.sign_with_key(new_secret_key)?;
+        Ok(method_id)
+    }
+
+    /// Verifies a detached signature against the signing key that was active at `timestamp`.
+    pub fn verify_with_historical_keys(&self, message: &[u8], signature: &[u8], timestamp: DateTime<Utc>) -> Result<bool, DIDError> {
+        let Some(method) = self.verification_methods.iter()
+            .find(|m| m.type_ == SIGNING_KEY_TYPE && m.is_active_at(self.created, timestamp))
+        else {
+            return Ok(false);
+        };
+
+        let key_bytes = base58::decode(&method.public_key_multibase)
+            .map_err(|_| DIDError::InvalidPublicKey)?;
+        let public_key = PublicKey::from_bytes(&key_bytes)
+            .map_err(|_| DIDError::InvalidPublicKey)?;
+        let signature = match DetachedSignature::from_bytes(signature) {
+            Ok(signature) => signature,
+            Err(_) => return Ok(false),
+        };
+
+        Ok(dilithium2::verify_detached_signature(&signature, message, &public_key).is_ok())
+    }
+
     pub fn verify(&self, hsm: &HSM) -> Result<bool, DIDError> {
         let proof = self.proof.as_ref().ok_or(DIDError::MissingProof)?;
         
@@ -156,6 +264,44 @@ impl QuantumDID {
         Ok(())
     }
 
+    /// Replaces the proof with a Dilithium2 signature over the document.
+    pub fn sign_with_key(&mut self, secret_key: &SecretKey) -> Result<(), DIDError> {
+        self.proof = None;
+        let message = self.create_signing_input()?;
+        let signature = dilithium2::detached_sign(&message, secret_key);
+
+        self.proof = Some(DIDProof {
+            type_: "DilithiumSignature2023".to_string(),
+            created: Utc::now(),
+            verification_method: self.authentication.last().cloned()
+                .unwrap_or_else(|| format!("{}#quantum-key-1", self.id)),
+            signature: signature.as_bytes().to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Checks the proof against the document's own verification key, without an HSM.
+    pub fn verify_proof(&self) -> Result<bool, DIDError> {
+        let proof = self.proof.as_ref().ok_or(DIDError::MissingProof)?;
+
+        let method = self.verification_methods
+            .iter()
+            .find(|m| m.id == proof.verification_method)
+            .ok_or(DIDError::InvalidVerificationMethod)?;
+
+        let key_bytes = base58::decode(&method.public_key_multibase)
+            .map_err(|_| DIDError::InvalidPublicKey)?;
+        let public_key = PublicKey::from_bytes(&key_bytes)
+            .map_err(|_| DIDError::InvalidPublicKey)?;
+        let signature = match DetachedSignature::from_bytes(&proof.signature) {
+            Ok(signature) => signature,
+            Err(_) => return Ok(false),
+        };
+
+        let message = self.create_signing_input()?;
+        Ok(dilithium2::verify_detached_signature(&signature, &message, &public_key).is_ok())
+    }
+
     // Private helper methods
     fn generate_id(public_key: &PublicKey) -> Result<String, DIDError> {
         let hash = blake3::hash(&public_key);
@@ -184,3 +330,862 @@ impl QuantumDID {
             .map_err(|_| DIDError::SerializationError)
     }
 }
+
+// 🔹 **Key-Agreement Channels**
+/// What the initiator sends the peer so it can derive the same session key,
+/// signed by the initiator's current signing key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelHandshake {
+    pub initiator: String,
+    pub responder: String,
+    pub key_agreement_method: String,
+    pub ciphertext: Vec<u8>,
+    pub signed_at: DateTime<Utc>,
+    pub signature: Vec<u8>,
+}
+
+impl ChannelHandshake {
+    fn message(&self) -> Vec<u8> {
+        let mut message = format!(
+            "quantumfuse channel open\n{}\n{}\n{}\n{}\n",
+            self.initiator, self.responder, self.key_agreement_method, self.signed_at.timestamp_millis(),
+        ).into_bytes();
+        message.extend_from_slice(&self.ciphertext);
+        message
+    }
+}
+
+/// The responder's signed reply, bound to the handshake's ciphertext so it
+/// can't be replayed onto another handshake.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelAccept {
+    pub initiator: String,
+    pub responder: String,
+    pub handshake_hash: [u8; 32],
+    pub signed_at: DateTime<Utc>,
+    pub signature: Vec<u8>,
+}
+
+impl ChannelAccept {
+    fn message(initiator: &str, responder: &str, handshake_hash: &[u8; 32], signed_at: DateTime<Utc>) -> Vec<u8> {
+        let mut message = format!(
+            "quantumfuse channel accept\n{}\n{}\n{}\n",
+            initiator, responder, signed_at.timestamp_millis(),
+        ).into_bytes();
+        message.extend_from_slice(handshake_hash);
+        message
+    }
+}
+
+/// Verifies `signature` over `message` against the signing key `did` had active at `signed_at`.
+fn verify_party_signature(did: &QuantumDID, message: &[u8], signature: &[u8], signed_at: DateTime<Utc>) -> Result<(), DIDError> {
+    if did.verify_with_historical_keys(message, signature, signed_at)? {
+        Ok(())
+    } else {
+        Err(DIDError::InvalidSignature)
+    }
+}
+
+/// AES-256-GCM session between two DIDs, keyed from a Kyber512 shared secret.
+pub struct SecureChannel {
+    pub local_did: String,
+    pub peer_did: String,
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl std::fmt::Debug for SecureChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecureChannel")
+            .field("local_did", &self.local_did)
+            .field("peer_did", &self.peer_did)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The initiator's channel, usable once the responder's signed accept checks out.
+#[derive(Debug)]
+pub struct PendingChannel {
+    channel: SecureChannel,
+    handshake_hash: [u8; 32],
+}
+
+impl PendingChannel {
+    /// Verifies `accept` against `peer_did`'s signing keys and opens the channel.
+    pub fn confirm(self, peer_did: &QuantumDID, accept: &ChannelAccept) -> Result<SecureChannel, DIDError> {
+        if accept.initiator != self.channel.local_did
+            || accept.responder != self.channel.peer_did
+            || peer_did.id != self.channel.peer_did
+            || accept.handshake_hash != self.handshake_hash
+        {
+            return Err(DIDError::UnknownPeer);
+        }
+
+        let message = ChannelAccept::message(&accept.initiator, &accept.responder, &accept.handshake_hash, accept.signed_at);
+        verify_party_signature(peer_did, &message, &accept.signature, accept.signed_at)?;
+        Ok(self.channel)
+    }
+}
+
+/// Encapsulates to the peer's key-agreement key and signs the handshake with
+/// `signing_key`. Both DIDs must publish a key-agreement method.
+pub fn establish_channel(
+    self_did: &QuantumDID,
+    peer_did: &QuantumDID,
+    signing_key: &SecretKey,
+) -> Result<(PendingChannel, ChannelHandshake), DIDError> {
+    self_did.key_agreement_key()?;
+    let (method_id, peer_key) = peer_did.key_agreement_key()?;
+
+    let (shared_secret, ciphertext) = encapsulate(&peer_key);
+    let channel = SecureChannel::new(&self_did.id, &peer_did.id, shared_secret.as_bytes())?;
+    let mut handshake = ChannelHandshake {
+        initiator: self_did.id.clone(),
+        responder: peer_did.id.clone(),
+        key_agreement_method: method_id,
+        ciphertext: ciphertext.as_bytes().to_vec(),
+        signed_at: Utc::now(),
+        signature: Vec::new(),
+    };
+    let message = handshake.message();
+    handshake.signature = dilithium2::detached_sign(&message, signing_key).as_bytes().to_vec();
+
+    let pending = PendingChannel { channel, handshake_hash: *blake3::hash(&message).as_bytes() };
+    Ok((pending, handshake))
+}
+
+/// Responder side: checks the handshake against `initiator_did`'s signing keys,
+/// decapsulates it with the secret half of its key-agreement key, and signs
+/// the accept with `signing_key`.
+pub fn accept_channel(
+    self_did: &QuantumDID,
+    initiator_did: &QuantumDID,
+    handshake: &ChannelHandshake,
+    kem_secret_key: &KyberSecretKey,
+    signing_key: &SecretKey,
+) -> Result<(SecureChannel, ChannelAccept), DIDError> {
+    if handshake.responder != self_did.id || !self_did.key_agreement.contains(&handshake.key_agreement_method) {
+        return Err(DIDError::NoKeyAgreementMethod);
+    }
+    if handshake.initiator != initiator_did.id {
+        return Err(DIDError::UnknownPeer);
+    }
+    let message = handshake.message();
+    verify_party_signature(initiator_did, &message, &handshake.signature, handshake.signed_at)?;
+
+    let ciphertext = KyberCiphertext::from_bytes(&handshake.ciphertext)
+        .map_err(|_| DIDError::InvalidCiphertext)?;
+    let shared_secret = decapsulate(&ciphertext, kem_secret_key);
+
+    // Same (initiator, responder) order on both sides so the derived keys match.
+    let mut channel = SecureChannel::new(&handshake.initiator, &handshake.responder, shared_secret.as_bytes())?;
+    std::mem::swap(&mut channel.local_did, &mut channel.peer_did);
+
+    let handshake_hash = *blake3::hash(&message).as_bytes();
+    let signed_at = Utc::now();
+    let accept_message = ChannelAccept::message(&handshake.initiator, &handshake.responder, &handshake_hash, signed_at);
+    let accept = ChannelAccept {
+        initiator: handshake.initiator.clone(),
+        responder: handshake.responder.clone(),
+        handshake_hash,
+        signed_at,
+        signature: dilithium2::detached_sign(&accept_message, signing_key).as_bytes().to_vec(),
+    };
+    Ok((channel, accept))
+}
+
+impl SecureChannel {
+    fn new(initiator: &str, responder: &str, shared_secret: &[u8]) -> Result<Self, DIDError> {
+        let mut hasher = blake3::Hasher::new_derive_key("quantumfuse did channel v1");
+        hasher.update(shared_secret);
+        hasher.update(initiator.as_bytes());
+        hasher.update(responder.as_bytes());
+        let session_key = hasher.finalize();
+
+        let key = UnboundKey::new(&AES_256_GCM, session_key.as_bytes())
+            .map_err(|_| DIDError::EncryptionError)?;
+        Ok(Self {
+            local_did: initiator.to_string(),
+            peer_did: responder.to_string(),
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Returns `nonce || ciphertext || tag`.
+    pub fn encrypt_for(&self, peer_did: &str, msg: &[u8]) -> Result<Vec<u8>, DIDError> {
+        if peer_did != self.peer_did {
+            return Err(DIDError::UnknownPeer);
+        }
+
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        self.rng.fill(&mut nonce_bytes).map_err(|_| DIDError::EncryptionError)?;
+
+        let mut in_out = msg.to_vec();
+        self.key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce_bytes), Aad::empty(), &mut in_out)
+            .map_err(|_| DIDError::EncryptionError)?;
+
+        let mut sealed = nonce_bytes.to_vec();
+        sealed.extend_from_slice(&in_out);
+        Ok(sealed)
+    }
+
+    pub fn decrypt(&self, msg: &[u8]) -> Result<Vec<u8>, DIDError> {
+        if msg.len() < NONCE_LEN {
+            return Err(DIDError::DecryptionError);
+        }
+
+        let (nonce_bytes, ciphertext) = msg.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| DIDError::DecryptionError)?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self.key.open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| DIDError::DecryptionError)?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+// 🔹 **DID Registry**
+/// A registered document and whether it has been deactivated. Deactivated
+/// DIDs stay resolvable so old signatures can still be attributed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredDID {
+    pub did: QuantumDID,
+    pub deactivated: bool,
+    pub deactivated_at: Option<DateTime<Utc>>,
+}
+
+const DID_KEY_PREFIX: &str = "did-registry/";
+const BINDING_KEY_PREFIX: &str = "did-binding/";
+/// How old, or how far ahead of the registry's clock, a new binding's `signed_at` may be.
+const BINDING_MAX_SKEW_SECS: i64 = 300;
+
+/// A claim, signed by both sides, that a DID controls an on-chain address:
+/// `signature` by the DID's current signing key and `address_signature` by
+/// the address's own key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBinding {
+    pub address: String,
+    pub did: String,
+    pub signed_at: DateTime<Utc>,
+    pub signature: Vec<u8>,
+    pub address_signature: AddressSignature,
+}
+
+impl AddressBinding {
+    pub fn sign(address_key: &KeyPair, did_id: &str, secret_key: &SecretKey, signed_at: DateTime<Utc>) -> Result<Self, DIDError> {
+        let address = address_key.address();
+        let message = Self::message(&address, did_id, signed_at);
+        let address_signature = address_key.sign(&message).map_err(|_| DIDError::SigningError)?;
+        Ok(Self {
+            address,
+            did: did_id.to_string(),
+            signed_at,
+            signature: dilithium2::detached_sign(&message, secret_key).as_bytes().to_vec(),
+            address_signature,
+        })
+    }
+
+    fn message(address: &str, did_id: &str, signed_at: DateTime<Utc>) -> Vec<u8> {
+        format!("quantumfuse address binding\n{}\n{}\n{}", did_id, address, signed_at.timestamp_millis()).into_bytes()
+    }
+}
+
+/// Registered DIDs, verified individually or in parallel batches, and
+/// written through to `Storage` when one is attached.
+#[derive(Default)]
+pub struct DIDRegistry {
+    documents: RwLock<HashMap<String, StoredDID>>,
+    bindings: RwLock<HashMap<String, AddressBinding>>,
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl std::fmt::Debug for DIDRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DIDRegistry")
+            .field("documents", &self.documents.read().map(|d| d.len()).unwrap_or_default())
+            .field("bindings", &self.bindings.read().map(|b| b.len()).unwrap_or_default())
+            .field("persistent", &self.storage.is_some())
+            .finish()
+    }
+}
+
+impl DIDRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a registry backed by `storage`, reloading every persisted DID.
+    pub fn with_storage(storage: Arc<dyn Storage>) -> Result<Self, DIDError> {
+        let registry = Self { storage: Some(storage), ..Self::default() };
+        let stored = registry.list_dids()?;
+        let bindings = registry.list_bindings()?;
+
+        let mut documents = registry.documents.write().map_err(|_| DIDError::RegistryUnavailable)?;
+        for record in stored {
+            documents.insert(record.did.id.clone(), record);
+        }
+        drop(documents);
+
+        let mut by_address = registry.bindings.write().map_err(|_| DIDError::RegistryUnavailable)?;
+        for binding in bindings {
+            by_address.insert(binding.address.clone(), binding);
+        }
+        drop(by_address);
+        Ok(registry)
+    }
+
+    pub fn store_did(&self, record: &StoredDID) -> Result<(), DIDError> {
+        let Some(storage) = &self.storage else {
+            return Ok(());
+        };
+        let value = serde_json::to_vec(record).map_err(|_| DIDError::SerializationError)?;
+        storage.put(Self::storage_key(&record.did.id).as_bytes(), &value)
+            .map_err(|e| DIDError::StorageError(e.to_string()))
+    }
+
+    pub fn load_did(&self, did_id: &str) -> Result<Option<StoredDID>, DIDError> {
+        let Some(storage) = &self.storage else {
+            return Ok(self.documents.read().map_err(|_| DIDError::RegistryUnavailable)?.get(did_id).cloned());
+        };
+        storage.get(Self::storage_key(did_id).as_bytes())
+            .map_err(|e| DIDError::StorageError(e.to_string()))?
+            .map(|bytes| serde_json::from_slice(&bytes).map_err(|_| DIDError::SerializationError))
+            .transpose()
+    }
+
+    pub fn list_dids(&self) -> Result<Vec<StoredDID>, DIDError> {
+        let Some(storage) = &self.storage else {
+            return Ok(self.documents.read().map_err(|_| DIDError::RegistryUnavailable)?.values().cloned().collect());
+        };
+        storage.scan_prefix(DID_KEY_PREFIX.as_bytes())
+            .map_err(|e| DIDError::StorageError(e.to_string()))?
+            .into_iter()
+            .map(|(_, bytes)| serde_json::from_slice(&bytes).map_err(|_| DIDError::SerializationError))
+            .collect()
+    }
+
+    pub fn register(&self, did: QuantumDID) -> Result<(), DIDError> {
+        Self::check_registrable(&did)?;
+        self.insert(did)
+    }
+
+    /// Checks every document's proof in parallel, then registers the ones that pass.
+    pub fn register_batch(&self, dids: Vec<QuantumDID>) -> Vec<Result<(), DIDError>> {
+        let checks: Vec<Result<(), DIDError>> = dids.par_iter()
+            .map(Self::check_registrable)
+            .collect();
+
+        dids.into_iter()
+            .zip(checks)
+            .map(|(did, check)| check.and_then(|_| self.insert(did)))
+            .collect()
+    }
+
+    pub fn deactivate(&self, did_id: &str) -> Result<(), DIDError> {
+        let mut documents = self.documents.write().map_err(|_| DIDError::RegistryUnavailable)?;
+        let record = documents.get(did_id).ok_or(DIDError::DIDNotFound)?;
+        if record.deactivated {
+            return Ok(());
+        }
+
+        let mut updated = record.clone();
+        updated.deactivated = true;
+        updated.deactivated_at = Some(Utc::now());
+        self.store_did(&updated)?;
+        documents.insert(did_id.to_string(), updated);
+        Ok(())
+    }
+
+    /// Registry view of a DID; `metadata.deactivated` reflects the stored flag.
+    pub fn resolve(&self, did_id: &str) -> Result<Option<DIDDocument>, DIDError> {
+        let documents = self.documents.read().map_err(|_| DIDError::RegistryUnavailable)?;
+        Ok(documents.get(did_id).map(|record| DIDDocument {
+            did: record.did.clone(),
+            metadata: DIDMetadata {
+                created: record.did.created,
+                updated: record.deactivated_at.unwrap_or(record.did.updated),
+                deactivated: record.deactivated,
+                version_id: "1.0".to_string(),
+                next_update: None,
+                quantum_secure: true,
+                risk_score: 0.0,
+            },
+        }))
+    }
+
+    pub fn verify_identity(&self, did_id: &str) -> Result<bool, DIDError> {
+        if !is_well_formed_did(did_id) {
+            return Ok(false);
+        }
+
+        let documents = self.documents.read().map_err(|_| DIDError::RegistryUnavailable)?;
+        match documents.get(did_id) {
+            Some(record) if !record.deactivated => record.did.verify_proof(),
+            _ => Ok(false),
+        }
+    }
+
+    /// Per-entry results in input order; malformed, unknown or deactivated IDs fail without any signature check.
+    pub fn verify_batch(&self, did_ids: &[&str]) -> Vec<bool> {
+        let documents = match self.documents.read() {
+            Ok(documents) => documents,
+            Err(_) => return vec![false; did_ids.len()],
+        };
+
+        did_ids.par_iter()
+            .map(|did_id| {
+                if !is_well_formed_did(did_id) {
+                    return false;
+                }
+                documents.get(*did_id)
+                    .filter(|record| !record.deactivated)
+                    .map_or(false, |record| record.did.verify_proof().unwrap_or(false))
+            })
+            .collect()
+    }
+
+    pub fn bind_address(&self, binding: AddressBinding) -> Result<(), DIDError> {
+        self.bind_address_at(binding, Utc::now())
+    }
+
+    /// Records that `binding.did` controls `binding.address` once both the DID's
+    /// key active at `now` and the address's key have signed it. Rotated-out
+    /// DID keys can't make new bindings, so `signed_at` must be recent. An
+    /// address binds to one DID.
+    pub fn bind_address_at(&self, binding: AddressBinding, now: DateTime<Utc>) -> Result<(), DIDError> {
+        if (now - binding.signed_at).num_seconds().abs() > BINDING_MAX_SKEW_SECS {
+            return Err(DIDError::StaleBinding);
+        }
+
+        let message = AddressBinding::message(&binding.address, &binding.did, binding.signed_at);
+        let address_key = AddressPublicKey::from_address(&binding.address).map_err(|_| DIDError::InvalidPublicKey)?;
+        if !address_key.verify(&message, &binding.address_signature).map_err(|_| DIDError::InvalidSignature)? {
+            return Err(DIDError::InvalidSignature);
+        }
+
+        {
+            let documents = self.documents.read().map_err(|_| DIDError::RegistryUnavailable)?;
+            let record = documents.get(&binding.did)
+                .filter(|record| !record.deactivated)
+                .ok_or(DIDError::DIDNotFound)?;
+
+            if !record.did.verify_with_historical_keys(&message, &binding.signature, now)? {
+                return Err(DIDError::InvalidSignature);
+            }
+        }
+
+        let mut bindings = self.bindings.write().map_err(|_| DIDError::RegistryUnavailable)?;
+        if bindings.get(&binding.address).map_or(false, |existing| existing.did != binding.did) {
+            return Err(DIDError::AddressAlreadyBound);
+        }
+
+        if let Some(storage) = &self.storage {
+            let value = serde_json::to_vec(&binding).map_err(|_| DIDError::SerializationError)?;
+            storage.put(format!("{}{}", BINDING_KEY_PREFIX, binding.address).as_bytes(), &value)
+                .map_err(|e| DIDError::StorageError(e.to_string()))?;
+        }
+        bindings.insert(binding.address.clone(), binding);
+        Ok(())
+    }
+
+    pub fn resolve_did_for_address(&self, address: &str) -> Result<Option<DIDDocument>, DIDError> {
+        let did_id = match self.bindings.read().map_err(|_| DIDError::RegistryUnavailable)?.get(address) {
+            Some(binding) => binding.did.clone(),
+            None => return Ok(None),
+        };
+        self.resolve(&did_id)
+    }
+
+    /// The most recently signed address bound to `did_id`.
+    pub fn resolve_address_for_did(&self, did_id: &str) -> Result<Option<String>, DIDError> {
+        let bindings = self.bindings.read().map_err(|_| DIDError::RegistryUnavailable)?;
+        Ok(bindings.values()
+            .filter(|binding| binding.did == did_id)
+            .max_by_key(|binding| binding.signed_at)
+            .map(|binding| binding.address.clone()))
+    }
+
+    fn list_bindings(&self) -> Result<Vec<AddressBinding>, DIDError> {
+        let Some(storage) = &self.storage else {
+            return Ok(self.bindings.read().map_err(|_| DIDError::RegistryUnavailable)?.values().cloned().collect());
+        };
+        storage.scan_prefix(BINDING_KEY_PREFIX.as_bytes())
+            .map_err(|e| DIDError::StorageError(e.to_string()))?
+            .into_iter()
+            .map(|(_, bytes)| serde_json::from_slice(&bytes).map_err(|_| DIDError::SerializationError))
+            .collect()
+    }
+
+    fn check_registrable(did: &QuantumDID) -> Result<(), DIDError> {
+        if !is_well_formed_did(&did.id) {
+            return Err(DIDError::InvalidDID);
+        }
+        if !did.verify_proof()? {
+            return Err(DIDError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    fn insert(&self, did: QuantumDID) -> Result<(), DIDError> {
+        let mut documents = self.documents.write().map_err(|_| DIDError::RegistryUnavailable)?;
+        if documents.contains_key(&did.id) {
+            return Err(DIDError::DuplicateDID);
+        }
+
+        let record = StoredDID { did, deactivated: false, deactivated_at: None };
+        self.store_did(&record)?;
+        documents.insert(record.did.id.clone(), record);
+        Ok(())
+    }
+
+    fn storage_key(did_id: &str) -> String {
+        format!("{}{}", DID_KEY_PREFIX, did_id)
+    }
+}
+
+/// `did:qf:` followed by 40 lowercase hex characters, as produced by `generate_id`.
+fn is_well_formed_did(did_id: &str) -> bool {
+    did_id.strip_prefix("did:qf:")
+        .map_or(false, |suffix| suffix.len() == 40 && suffix.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_did() -> QuantumDID {
+        signed_did_with_key(Utc::now()).0
+    }
+
+    fn signed_did_with_key(created: DateTime<Utc>) -> (QuantumDID, SecretKey) {
+        let (public_key, secret_key) = dilithium2::keypair();
+        let id = QuantumDID::generate_id(&public_key).unwrap();
+        let now = created;
+
+        let method = VerificationMethod {
+            id: format!("{}#quantum-key-1", id),
+            type_: SIGNING_KEY_TYPE.to_string(),
+            controller: id.clone(),
+            public_key_multibase: base58::encode(public_key.as_bytes()),
+            valid_from: Some(now),
+            valid_until: None,
+        };
+
+        let mut did = QuantumDID {
+            id: id.clone(),
+            controller: id,
+            verification_methods: vec![method.clone()],
+            authentication: vec![method.id],
+            assertion_method: vec![],
+            key_agreement: vec![],
+            capability_invocation: vec![],
+            capability_delegation: vec![],
+            services: vec![],
+            created: now,
+            updated: now,
+            proof: None,
+        };
+        did.sign_with_key(&secret_key).unwrap();
+        (did, secret_key)
+    }
+
+    #[test]
+    fn test_pre_rotation_signature_verifies_within_window() {
+        let created = Utc::now() - chrono::Duration::days(30);
+        let (mut did, old_key) = signed_did_with_key(created);
+
+        let signed_at = created + chrono::Duration::days(5);
+        let message = b"transfer deed to did:qf:buyer";
+        let old_signature = dilithium2::detached_sign(message, &old_key);
+
+        let rotated_at = created + chrono::Duration::days(10);
+        let (new_public, new_secret) = dilithium2::keypair();
+        let new_method = did.rotate_key(&new_public, &new_secret, rotated_at).unwrap();
+        assert_eq!(did.authentication, vec![new_method]);
+        assert!(did.verify_proof().unwrap());
+
+        assert!(did.verify_with_historical_keys(message, old_signature.as_bytes(), signed_at).unwrap());
+
+        let new_signature = dilithium2::detached_sign(message, &new_secret);
+        let after_rotation = rotated_at + chrono::Duration::days(1);
+        assert!(did.verify_with_historical_keys(message, new_signature.as_bytes(), after_rotation).unwrap());
+    }
+
+    #[test]
+    fn test_pre_rotation_signature_fails_outside_window() {
+        let created = Utc::now() - chrono::Duration::days(30);
+        let (mut did, old_key) = signed_did_with_key(created);
+        let message = b"transfer deed to did:qf:buyer";
+        let old_signature = dilithium2::detached_sign(message, &old_key);
+
+        let rotated_at = created + chrono::Duration::days(10);
+        let (new_public, new_secret) = dilithium2::keypair();
+        did.rotate_key(&new_public, &new_secret, rotated_at).unwrap();
+
+        // Claimed after the key was retired, or before the DID existed.
+        assert!(!did.verify_with_historical_keys(message, old_signature.as_bytes(), rotated_at).unwrap());
+        assert!(!did.verify_with_historical_keys(message, old_signature.as_bytes(), created - chrono::Duration::days(1)).unwrap());
+        assert!(matches!(
+            did.rotate_key(&new_public, &new_secret, created),
+            Err(DIDError::InvalidRotationTime)
+        ));
+    }
+
+    /// In-memory `Storage` that outlives the registries opened on it, standing in for a restart.
+    #[derive(Default)]
+    struct MemoryStorage {
+        entries: std::sync::Mutex<std::collections::BTreeMap<Vec<u8>, Vec<u8>>>,
+    }
+
+    impl Storage for MemoryStorage {
+        fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, quantumfuse_sdk::error::StorageError> {
+            Ok(self.entries.lock().unwrap().get(key).cloned())
+        }
+
+        fn put(&self, key: &[u8], value: &[u8]) -> Result<(), quantumfuse_sdk::error::StorageError> {
+            self.entries.lock().unwrap().insert(key.to_vec(), value.to_vec());
+            Ok(())
+        }
+
+        fn scan_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, quantumfuse_sdk::error::StorageError> {
+            Ok(self.entries.lock().unwrap().iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_registered_did_survives_restart() {
+        let storage = Arc::new(MemoryStorage::default());
+        let did = signed_did();
+
+        let registry = DIDRegistry::with_storage(storage.clone()).unwrap();
+        registry.register(did.clone()).unwrap();
+        drop(registry);
+
+        let reopened = DIDRegistry::with_storage(storage).unwrap();
+        assert!(reopened.verify_identity(&did.id).unwrap());
+        assert_eq!(reopened.list_dids().unwrap().len(), 1);
+        assert!(matches!(reopened.register(did), Err(DIDError::DuplicateDID)));
+    }
+
+    #[test]
+    fn test_resolution_reflects_persisted_state() {
+        let storage = Arc::new(MemoryStorage::default());
+        let did = signed_did();
+        DIDRegistry::with_storage(storage.clone()).unwrap().register(did.clone()).unwrap();
+
+        let reopened = DIDRegistry::with_storage(storage).unwrap();
+        let document = reopened.resolve(&did.id).unwrap().unwrap();
+        assert_eq!(document.did.id, did.id);
+        assert_eq!(document.did.verification_methods.len(), did.verification_methods.len());
+        assert!(!document.metadata.deactivated);
+        assert!(reopened.resolve("did:qf:unknown").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_deactivated_did_loads_as_deactivated() {
+        let storage = Arc::new(MemoryStorage::default());
+        let did = signed_did();
+
+        let registry = DIDRegistry::with_storage(storage.clone()).unwrap();
+        registry.register(did.clone()).unwrap();
+        registry.deactivate(&did.id).unwrap();
+        drop(registry);
+
+        let reopened = DIDRegistry::with_storage(storage).unwrap();
+        let stored = reopened.load_did(&did.id).unwrap().unwrap();
+        assert!(stored.deactivated);
+        assert!(stored.deactivated_at.is_some());
+        assert!(reopened.resolve(&did.id).unwrap().unwrap().metadata.deactivated);
+        assert!(!reopened.verify_identity(&did.id).unwrap());
+        assert_eq!(reopened.verify_batch(&[did.id.as_str()]), vec![false]);
+    }
+
+    const WALLET_ADDRESS: &str = "qf1q9x8c7v6b5n4m3l2k1j0h9g8f7d6s5a4p3o2i1";
+
+    #[test]
+    fn test_bound_address_resolves_both_ways() {
+        let registry = DIDRegistry::new();
+        let (did, secret_key) = signed_did_with_key(Utc::now() - chrono::Duration::minutes(1));
+        let wallet = KeyPair::generate();
+        registry.register(did.clone()).unwrap();
+
+        registry.bind_address(AddressBinding::sign(&wallet, &did.id, &secret_key, Utc::now()).unwrap()).unwrap();
+
+        let document = registry.resolve_did_for_address(&wallet.address()).unwrap().unwrap();
+        assert_eq!(document.did.id, did.id);
+        assert_eq!(registry.resolve_address_for_did(&did.id).unwrap(), Some(wallet.address()));
+    }
+
+    #[test]
+    fn test_unbound_address_resolves_to_none() {
+        let registry = DIDRegistry::new();
+        let did = signed_did();
+        registry.register(did.clone()).unwrap();
+
+        assert!(registry.resolve_did_for_address(WALLET_ADDRESS).unwrap().is_none());
+        assert!(registry.resolve_address_for_did(&did.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_forged_binding_is_rejected() {
+        let registry = DIDRegistry::new();
+        let (victim, _) = signed_did_with_key(Utc::now() - chrono::Duration::minutes(1));
+        let (_, attacker_key) = signed_did_with_key(Utc::now() - chrono::Duration::minutes(1));
+        let wallet = KeyPair::generate();
+        registry.register(victim.clone()).unwrap();
+
+        let forged = AddressBinding::sign(&wallet, &victim.id, &attacker_key, Utc::now()).unwrap();
+        assert!(matches!(registry.bind_address(forged), Err(DIDError::InvalidSignature)));
+        assert!(registry.resolve_did_for_address(&wallet.address()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_binding_requires_the_address_key() {
+        let registry = DIDRegistry::new();
+        let (did, secret_key) = signed_did_with_key(Utc::now() - chrono::Duration::minutes(1));
+        registry.register(did.clone()).unwrap();
+
+        // A DID claiming someone else's address, counter-signed by its own wallet.
+        let mut claim = AddressBinding::sign(&KeyPair::generate(), &did.id, &secret_key, Utc::now()).unwrap();
+        claim.address = KeyPair::generate().address();
+        claim.signature = dilithium2::detached_sign(&AddressBinding::message(&claim.address, &did.id, claim.signed_at), &secret_key)
+            .as_bytes()
+            .to_vec();
+
+        assert!(matches!(registry.bind_address(claim.clone()), Err(DIDError::InvalidSignature)));
+        assert!(registry.resolve_did_for_address(&claim.address).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rotated_out_key_cannot_backdate_a_binding() {
+        let registry = DIDRegistry::new();
+        let created = Utc::now() - chrono::Duration::days(30);
+        let (mut did, old_key) = signed_did_with_key(created);
+        let (new_public, new_secret) = dilithium2::keypair();
+        did.rotate_key(&new_public, &new_secret, created + chrono::Duration::days(10)).unwrap();
+        registry.register(did.clone()).unwrap();
+        let wallet = KeyPair::generate();
+
+        let backdated = AddressBinding::sign(&wallet, &did.id, &old_key, created + chrono::Duration::days(5)).unwrap();
+        assert!(matches!(registry.bind_address(backdated), Err(DIDError::StaleBinding)));
+
+        let now = Utc::now();
+        let current_time_old_key = AddressBinding::sign(&wallet, &did.id, &old_key, now).unwrap();
+        assert!(matches!(registry.bind_address_at(current_time_old_key, now), Err(DIDError::InvalidSignature)));
+
+        registry.bind_address_at(AddressBinding::sign(&wallet, &did.id, &new_secret, now).unwrap(), now).unwrap();
+        assert_eq!(registry.resolve_address_for_did(&did.id).unwrap(), Some(wallet.address()));
+    }
+
+    #[test]
+    fn test_key_agreement_channel_shares_secret() {
+        let (mut alice, alice_key) = signed_did_with_key(Utc::now());
+        let (mut bob, bob_key) = signed_did_with_key(Utc::now());
+        let (alice_kem_pub, _alice_kem_secret) = kyber512::keypair();
+        let (bob_kem_pub, bob_kem_secret) = kyber512::keypair();
+        alice.add_key_agreement_key(&alice_kem_pub);
+        bob.add_key_agreement_key(&bob_kem_pub);
+
+        let (pending, handshake) = establish_channel(&alice, &bob, &alice_key).unwrap();
+        let (bob_channel, accept) = accept_channel(&bob, &alice, &handshake, &bob_kem_secret, &bob_key).unwrap();
+        let alice_channel = pending.confirm(&bob, &accept).unwrap();
+        assert_eq!(bob_channel.peer_did, alice.id);
+
+        let sealed = alice_channel.encrypt_for(&bob.id, b"settlement terms").unwrap();
+        assert_eq!(bob_channel.decrypt(&sealed).unwrap(), b"settlement terms");
+
+        let reply = bob_channel.encrypt_for(&alice.id, b"accepted").unwrap();
+        assert_eq!(alice_channel.decrypt(&reply).unwrap(), b"accepted");
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 0x01;
+        assert!(bob_channel.decrypt(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_channel_requires_key_agreement_method() {
+        let (alice, alice_key) = signed_did_with_key(Utc::now());
+        let (mut bob, bob_key) = signed_did_with_key(Utc::now());
+        let (bob_kem_pub, _) = kyber512::keypair();
+        bob.add_key_agreement_key(&bob_kem_pub);
+
+        assert!(matches!(establish_channel(&alice, &bob, &alice_key), Err(DIDError::NoKeyAgreementMethod)));
+        assert!(matches!(establish_channel(&bob, &alice, &bob_key), Err(DIDError::NoKeyAgreementMethod)));
+    }
+
+    #[test]
+    fn test_channel_handshake_must_be_signed_by_the_parties() {
+        let (mut alice, alice_key) = signed_did_with_key(Utc::now());
+        let (mut bob, bob_key) = signed_did_with_key(Utc::now());
+        let (_, mallory_key) = signed_did_with_key(Utc::now());
+        let (alice_kem_pub, _) = kyber512::keypair();
+        let (bob_kem_pub, bob_kem_secret) = kyber512::keypair();
+        alice.add_key_agreement_key(&alice_kem_pub);
+        bob.add_key_agreement_key(&bob_kem_pub);
+
+        // An open claiming to come from Alice but signed by someone else.
+        let (_, forged) = establish_channel(&alice, &bob, &mallory_key).unwrap();
+        let result = accept_channel(&bob, &alice, &forged, &bob_kem_secret, &bob_key);
+        assert!(matches!(result, Err(DIDError::InvalidSignature)));
+
+        // An accept that Bob's keys didn't sign.
+        let (pending, handshake) = establish_channel(&alice, &bob, &alice_key).unwrap();
+        let (_, mut accept) = accept_channel(&bob, &alice, &handshake, &bob_kem_secret, &bob_key).unwrap();
+        let forged_message = ChannelAccept::message(&accept.initiator, &accept.responder, &accept.handshake_hash, accept.signed_at);
+        accept.signature = dilithium2::detached_sign(&forged_message, &mallory_key).as_bytes().to_vec();
+        assert!(matches!(pending.confirm(&bob, &accept), Err(DIDError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_batch_of_valid_dids_verifies() {
+        let registry = DIDRegistry::new();
+        let dids: Vec<QuantumDID> = (0..8).map(|_| signed_did()).collect();
+        let ids: Vec<String> = dids.iter().map(|did| did.id.clone()).collect();
+
+        assert!(registry.register_batch(dids).iter().all(Result::is_ok));
+
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        assert_eq!(registry.verify_batch(&ids), vec![true; 8]);
+    }
+
+    #[test]
+    fn test_mixed_batch_returns_per_entry_results() {
+        let registry = DIDRegistry::new();
+        let registered = signed_did();
+        let unregistered = signed_did();
+        let mut tampered = signed_did();
+        tampered.controller = "did:qf:attacker".to_string();
+
+        let results = registry.register_batch(vec![registered.clone(), tampered.clone()]);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(DIDError::InvalidSignature)));
+
+        let verified = registry.verify_batch(&[
+            registered.id.as_str(),
+            "not-a-did",
+            unregistered.id.as_str(),
+            tampered.id.as_str(),
+        ]);
+        assert_eq!(verified, vec![true, false, false, false]);
+    }
+
+    #[test]
+    fn test_batch_matches_single_verification() {
+        let registry = DIDRegistry::new();
+        let dids: Vec<QuantumDID> = (0..4).map(|_| signed_did()).collect();
+        registry.register(dids[0].clone()).unwrap();
+        registry.register(dids[2].clone()).unwrap();
+
+        let ids: Vec<&str> = dids.iter().map(|did| did.id.as_str())
+            .chain(["did:qf:short", ""])
+            .collect();
+        let single: Vec<bool> = ids.iter()
+            .map(|id| registry.verify_identity(id).unwrap())
+            .collect();
+
+        assert_eq!(registry.verify_batch(&ids), single);
+    }
+}
@@ -0,0 +1,212 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+// 🔹 **Simulation Configuration**
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AgentKind {
+    /// Moves liquid funds into stake when rewards are attractive, and back out at random.
+    Staker,
+    /// Pays fees on a random number of transfers each epoch.
+    Trader,
+    /// Keeps everything staked and collects the unburned share of fees.
+    Validator,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentPopulation {
+    pub kind: AgentKind,
+    pub count: usize,
+    pub initial_balance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub seed: u64,
+    pub populations: Vec<AgentPopulation>,
+    /// Annual staking reward, paid out per epoch and restaked.
+    pub reward_rate: f64,
+    pub epochs_per_year: u64,
+    pub base_fee: f64,
+    /// Share of every fee that is burned rather than paid to validators.
+    pub burn_ratio: f64,
+    pub max_trades_per_epoch: u32,
+    /// How strongly stakers respond to the reward rate when deciding to stake.
+    pub stake_sensitivity: f64,
+    /// Largest share of its stake a staker withdraws in one epoch.
+    pub max_unstake_fraction: f64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            populations: vec![
+                AgentPopulation { kind: AgentKind::Staker, count: 100, initial_balance: 10_000.0 },
+                AgentPopulation { kind: AgentKind::Trader, count: 50, initial_balance: 5_000.0 },
+                AgentPopulation { kind: AgentKind::Validator, count: 10, initial_balance: 50_000.0 },
+            ],
+            reward_rate: 0.05,
+            epochs_per_year: 365,
+            base_fee: 0.01,
+            burn_ratio: 0.5,
+            max_trades_per_epoch: 20,
+            stake_sensitivity: 2.0,
+            max_unstake_fraction: 0.05,
+        }
+    }
+}
+
+// 🔹 **Metrics**
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochMetrics {
+    pub epoch: u64,
+    pub supply: f64,
+    pub staked_ratio: f64,
+    pub avg_fee: f64,
+    pub transactions: u64,
+    pub rewards: f64,
+    pub burned: f64,
+}
+
+// 🔹 **Simulator**
+#[derive(Debug, Clone)]
+struct Agent {
+    kind: AgentKind,
+    liquid: f64,
+    staked: f64,
+}
+
+/// Epoch-by-epoch model of supply, stake and fees. Every random draw comes
+/// from one generator seeded by `config.seed`, and each agent makes the same
+/// number of draws every epoch, so runs with the same seed are identical.
+#[derive(Debug, Clone)]
+pub struct EconomySimulator {
+    config: SimulationConfig,
+    agents: Vec<Agent>,
+    rng: StdRng,
+    epoch: u64,
+}
+
+impl EconomySimulator {
+    pub fn new(config: SimulationConfig) -> Self {
+        let agents = config.populations.iter()
+            .flat_map(|population| (0..population.count).map(move |_| {
+                let staked = if population.kind == AgentKind::Validator { population.initial_balance } else { 0.0 };
+                Agent { kind: population.kind, liquid: population.initial_balance - staked, staked }
+            }))
+            .collect();
+
+        Self { rng: StdRng::seed_from_u64(config.seed), config, agents, epoch: 0 }
+    }
+
+    /// Changes the annual reward rate from the next epoch on, e.g. to follow
+    /// a reward curve as the staked ratio moves.
+    pub fn set_reward_rate(&mut self, reward_rate: f64) {
+        self.config.reward_rate = reward_rate;
+    }
+
+    pub fn supply(&self) -> f64 {
+        self.agents.iter().map(|agent| agent.liquid + agent.staked).sum()
+    }
+
+    pub fn staked_ratio(&self) -> f64 {
+        let supply = self.supply();
+        if supply > 0.0 {
+            self.agents.iter().map(|agent| agent.staked).sum::<f64>() / supply
+        } else {
+            0.0
+        }
+    }
+
+    /// Advances one epoch: trading fees are charged, burned and paid to
+    /// validators by stake, staking rewards are minted, then stakers rebalance.
+    pub fn step(&mut self) -> EpochMetrics {
+        self.epoch += 1;
+
+        let mut fees = 0.0;
+        let mut transactions = 0u64;
+        for agent in self.agents.iter_mut().filter(|agent| agent.kind == AgentKind::Trader) {
+            let trades = self.rng.gen_range(0..=self.config.max_trades_per_epoch);
+            let fee = self.config.base_fee * self.rng.gen_range(0.5..1.5);
+            let affordable = (agent.liquid / fee).floor().min(trades as f64);
+            agent.liquid -= affordable * fee;
+            fees += affordable * fee;
+            transactions += affordable as u64;
+        }
+
+        let burned = fees * self.config.burn_ratio.clamp(0.0, 1.0);
+        let validator_stake: f64 = self.agents.iter()
+            .filter(|agent| agent.kind == AgentKind::Validator)
+            .map(|agent| agent.staked)
+            .sum();
+        if validator_stake > 0.0 {
+            let paid = fees - burned;
+            for agent in self.agents.iter_mut().filter(|agent| agent.kind == AgentKind::Validator) {
+                agent.staked += paid * agent.staked / validator_stake;
+            }
+        }
+
+        let epoch_rate = self.config.reward_rate.max(0.0) / self.config.epochs_per_year.max(1) as f64;
+        let mut rewards = 0.0;
+        for agent in &mut self.agents {
+            let reward = agent.staked * epoch_rate;
+            agent.staked += reward;
+            rewards += reward;
+        }
+
+        let stake_appetite = (self.config.reward_rate * self.config.stake_sensitivity).clamp(0.0, 1.0);
+        for agent in self.agents.iter_mut().filter(|agent| agent.kind == AgentKind::Staker) {
+            let staking = agent.liquid * stake_appetite * self.rng.gen_range(0.0..1.0);
+            let unstaking = agent.staked * self.config.max_unstake_fraction * self.rng.gen_range(0.0..1.0);
+            agent.liquid += unstaking - staking;
+            agent.staked += staking - unstaking;
+        }
+
+        EpochMetrics {
+            epoch: self.epoch,
+            supply: self.supply(),
+            staked_ratio: self.staked_ratio(),
+            avg_fee: if transactions > 0 { fees / transactions as f64 } else { 0.0 },
+            transactions,
+            rewards,
+            burned,
+        }
+    }
+
+    pub fn run(&mut self, epochs: u64) -> Vec<EpochMetrics> {
+        (0..epochs).map(|_| self.step()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_produces_identical_trajectory() {
+        let config = SimulationConfig { seed: 42, ..SimulationConfig::default() };
+        let first = EconomySimulator::new(config.clone()).run(50);
+        let second = EconomySimulator::new(config).run(50);
+        assert_eq!(first, second);
+
+        let other = EconomySimulator::new(SimulationConfig { seed: 43, ..SimulationConfig::default() }).run(50);
+        assert_ne!(first, other);
+        assert!(first.iter().all(|metrics| metrics.avg_fee > 0.0 && metrics.burned > 0.0));
+    }
+
+    #[test]
+    fn test_higher_reward_rate_raises_staked_ratio() {
+        let run = |reward_rate: f64| {
+            EconomySimulator::new(SimulationConfig { seed: 7, reward_rate, ..SimulationConfig::default() }).run(100)
+        };
+        let low = run(0.01);
+        let high = run(0.10);
+
+        for (low, high) in low.iter().zip(&high).skip(10) {
+            assert!(high.staked_ratio > low.staked_ratio, "epoch {}", high.epoch);
+        }
+        assert!(high.last().unwrap().staked_ratio > high.first().unwrap().staked_ratio);
+        assert!(high.last().unwrap().supply > low.last().unwrap().supply);
+    }
+}
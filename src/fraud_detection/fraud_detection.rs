@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::amount::Amount;
+
+// 🔹 **Signals**
+/// What a contract knows about one operation at the time it is screened.
+#[derive(Debug, Clone)]
+pub struct FraudSignal {
+    pub subject: String,
+    pub counterparty: Option<String>,
+    pub amount: Option<Amount>,
+    pub region: Option<String>,
+    pub identity_verified: bool,
+    pub at: DateTime<Utc>,
+}
+
+impl FraudSignal {
+    pub fn new(subject: &str, at: DateTime<Utc>) -> Self {
+        Self {
+            subject: subject.to_string(),
+            counterparty: None,
+            amount: None,
+            region: None,
+            identity_verified: true,
+            at,
+        }
+    }
+
+    pub fn with_counterparty(mut self, counterparty: &str) -> Self {
+        self.counterparty = Some(counterparty.to_string());
+        self
+    }
+
+    pub fn with_amount(mut self, amount: Amount) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    pub fn with_identity_verified(mut self, verified: bool) -> Self {
+        self.identity_verified = verified;
+        self
+    }
+}
+
+// 🔹 **Rules**
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum FraudRule {
+    /// More than `max_operations` by one subject within `window_secs`, counting this one.
+    Velocity { max_operations: usize, window_secs: i64, weight: u32 },
+    /// An amount strictly above `max_amount`.
+    AmountThreshold { max_amount: Amount, weight: u32 },
+    /// A region outside `allowed_regions`. Operations with no region never trigger it.
+    UnexpectedRegion { allowed_regions: HashSet<String>, weight: u32 },
+    /// A subject whose DID no longer verifies.
+    UnverifiedIdentity { weight: u32 },
+}
+
+impl FraudRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FraudRule::Velocity { .. } => "velocity",
+            FraudRule::AmountThreshold { .. } => "amount_threshold",
+            FraudRule::UnexpectedRegion { .. } => "unexpected_region",
+            FraudRule::UnverifiedIdentity { .. } => "unverified_identity",
+        }
+    }
+
+    pub fn weight(&self) -> u32 {
+        match self {
+            FraudRule::Velocity { weight, .. }
+            | FraudRule::AmountThreshold { weight, .. }
+            | FraudRule::UnexpectedRegion { weight, .. }
+            | FraudRule::UnverifiedIdentity { weight } => *weight,
+        }
+    }
+
+    /// `recent` holds the subject's earlier operation times, oldest first.
+    fn triggers(&self, signal: &FraudSignal, recent: Option<&VecDeque<DateTime<Utc>>>) -> bool {
+        match self {
+            FraudRule::Velocity { max_operations, window_secs, .. } => {
+                let since = signal.at - Duration::seconds(*window_secs);
+                let earlier = recent.map_or(0, |times| times.iter().filter(|t| **t > since).count());
+                earlier + 1 > *max_operations
+            }
+            FraudRule::AmountThreshold { max_amount, .. } => {
+                signal.amount.is_some_and(|amount| amount > *max_amount)
+            }
+            FraudRule::UnexpectedRegion { allowed_regions, .. } => {
+                signal.region.as_ref().is_some_and(|region| !allowed_regions.contains(region))
+            }
+            FraudRule::UnverifiedIdentity { .. } => !signal.identity_verified,
+        }
+    }
+}
+
+/// Maps a total rule score onto a decision; both thresholds are inclusive.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FraudPolicy {
+    pub review_threshold: u32,
+    pub reject_threshold: u32,
+}
+
+impl Default for FraudPolicy {
+    fn default() -> Self {
+        Self {
+            review_threshold: 50,
+            reject_threshold: 100,
+        }
+    }
+}
+
+impl FraudPolicy {
+    pub fn decide(&self, score: u32) -> FraudDecision {
+        if score >= self.reject_threshold {
+            FraudDecision::Reject
+        } else if score >= self.review_threshold {
+            FraudDecision::Review
+        } else {
+            FraudDecision::Allow
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FraudRuleSet {
+    pub rules: Vec<FraudRule>,
+    pub policy: FraudPolicy,
+}
+
+impl Default for FraudRuleSet {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                FraudRule::Velocity { max_operations: 10, window_secs: 60, weight: 50 },
+                FraudRule::AmountThreshold { max_amount: Amount::from_whole(1_000_000), weight: 50 },
+            ],
+            policy: FraudPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FraudDecision {
+    Allow,
+    Review,
+    Reject,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FraudAssessment {
+    pub score: u32,
+    pub triggered: Vec<String>,
+    pub decision: FraudDecision,
+}
+
+// 🔹 **Engine**
+/// Scores operations against a contract's rule set, keeping per-subject
+/// history for the velocity rules.
+#[derive(Debug, Default)]
+pub struct FraudRuleEngine {
+    rules: FraudRuleSet,
+    history: HashMap<String, VecDeque<DateTime<Utc>>>,
+}
+
+impl FraudRuleEngine {
+    pub fn new(rules: FraudRuleSet) -> Self {
+        Self { rules, history: HashMap::new() }
+    }
+
+    pub fn rules(&self) -> &FraudRuleSet {
+        &self.rules
+    }
+
+    /// Replaces the rule set; operation history is kept.
+    pub fn set_rules(&mut self, rules: FraudRuleSet) {
+        self.rules = rules;
+    }
+
+    /// Scores `signal` without recording it.
+    pub fn assess(&self, signal: &FraudSignal) -> FraudAssessment {
+        let recent = self.history.get(&signal.subject);
+        let triggered: Vec<&FraudRule> = self.rules.rules.iter()
+            .filter(|rule| rule.triggers(signal, recent))
+            .collect();
+
+        let score = triggered.iter().fold(0u32, |total, rule| total.saturating_add(rule.weight()));
+        FraudAssessment {
+            score,
+            triggered: triggered.iter().map(|rule| rule.name().to_string()).collect(),
+            decision: self.rules.policy.decide(score),
+        }
+    }
+
+    /// Scores `signal` and records it for later velocity checks, whatever the decision.
+    pub fn analyze(&mut self, signal: &FraudSignal) -> FraudAssessment {
+        let assessment = self.assess(signal);
+        self.record(signal);
+        assessment
+    }
+
+    fn record(&mut self, signal: &FraudSignal) {
+        let longest_window = self.rules.rules.iter()
+            .filter_map(|rule| match rule {
+                FraudRule::Velocity { window_secs, .. } => Some(*window_secs),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        let times = self.history.entry(signal.subject.clone()).or_default();
+        times.push_back(signal.at);
+        let cutoff = signal.at - Duration::seconds(longest_window);
+        while times.front().is_some_and(|t| *t <= cutoff) {
+            times.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn velocity_rules(max_operations: usize) -> FraudRuleSet {
+        FraudRuleSet {
+            rules: vec![FraudRule::Velocity { max_operations, window_secs: 60, weight: 60 }],
+            policy: FraudPolicy::default(),
+        }
+    }
+
+    #[test]
+    fn test_velocity_breach_triggers_review() {
+        let mut engine = FraudRuleEngine::new(velocity_rules(3));
+        let start = Utc::now();
+
+        let decisions: Vec<_> = (0..4)
+            .map(|i| engine.analyze(&FraudSignal::new("trader", start + Duration::seconds(i * 5))))
+            .collect();
+
+        assert!(decisions[..3].iter().all(|a| a.decision == FraudDecision::Allow));
+        assert_eq!(decisions[3].decision, FraudDecision::Review);
+        assert_eq!(decisions[3].score, 60);
+        assert_eq!(decisions[3].triggered, vec!["velocity"]);
+    }
+
+    #[test]
+    fn test_within_limits_passes() {
+        let mut engine = FraudRuleEngine::new(FraudRuleSet::default());
+        let start = Utc::now();
+
+        for i in 0..5 {
+            let signal = FraudSignal::new("payroll", start + Duration::seconds(i * 30))
+                .with_amount(Amount::from_whole(2_500));
+            let assessment = engine.analyze(&signal);
+            assert_eq!(assessment.decision, FraudDecision::Allow);
+            assert!(assessment.triggered.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_operations_outside_window_do_not_count() {
+        let mut engine = FraudRuleEngine::new(velocity_rules(2));
+        let start = Utc::now();
+
+        for i in 0..5 {
+            let assessment = engine.analyze(&FraudSignal::new("trader", start + Duration::seconds(i * 61)));
+            assert_eq!(assessment.decision, FraudDecision::Allow);
+        }
+    }
+
+    #[test]
+    fn test_rule_set_change_alters_outcome() {
+        let signal = FraudSignal::new("importer", Utc::now())
+            .with_amount(Amount::from_whole(50_000))
+            .with_region("XX")
+            .with_identity_verified(false);
+
+        let mut engine = FraudRuleEngine::new(FraudRuleSet::default());
+        assert_eq!(engine.assess(&signal).decision, FraudDecision::Allow);
+
+        engine.set_rules(FraudRuleSet {
+            rules: vec![
+                FraudRule::AmountThreshold { max_amount: Amount::from_whole(10_000), weight: 40 },
+                FraudRule::UnexpectedRegion { allowed_regions: HashSet::from(["EU".to_string()]), weight: 30 },
+                FraudRule::UnverifiedIdentity { weight: 40 },
+            ],
+            policy: FraudPolicy::default(),
+        });
+
+        let assessment = engine.assess(&signal);
+        assert_eq!(assessment.score, 110);
+        assert_eq!(assessment.decision, FraudDecision::Reject);
+        assert_eq!(assessment.triggered, vec!["amount_threshold", "unexpected_region", "unverified_identity"]);
+    }
+}
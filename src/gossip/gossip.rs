@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{Read, Write};
+use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::{
+    error::GossipError,
+    transaction::QuantumTransaction,
+    crypto::Hash,
+};
+
+/// Eight bytes of a salted hash; enough to tell transactions in one batch apart.
+pub type ShortId = [u8; 8];
+
+// 🔹 **Gossip Configuration**
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GossipConfig {
+    pub max_batch_transactions: usize,
+    pub compression_level: i32,
+    /// Largest decompressed bundle a peer will accept.
+    pub max_bundle_bytes: usize,
+    /// Sealed batches kept to serve requests; the oldest is dropped beyond this.
+    pub max_sealed_batches: usize,
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_transactions: 256,
+            compression_level: 3,
+            max_bundle_bytes: 8 * 1024 * 1024,
+            max_sealed_batches: 64,
+        }
+    }
+}
+
+// 🔹 **Wire Messages**
+/// Sent first: peers compare the short ids with their mempool and request the rest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchAnnouncement {
+    pub digest: [u8; 32],
+    pub salt: u64,
+    pub short_ids: Vec<ShortId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchRequest {
+    pub digest: [u8; 32],
+    /// Positions in the announcement, ascending.
+    pub indexes: Vec<u32>,
+}
+
+/// zstd-compressed CBOR list of the requested transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionBundle {
+    pub digest: [u8; 32],
+    pub indexes: Vec<u32>,
+    pub payload: Vec<u8>,
+}
+
+impl BatchAnnouncement {
+    /// Positions whose transactions are not in `known`.
+    pub fn missing(&self, known: &HashSet<Hash>) -> BatchRequest {
+        let known: HashSet<ShortId> = known.iter().map(|hash| short_id(self.salt, hash)).collect();
+        let indexes = self.short_ids.iter()
+            .enumerate()
+            .filter(|(_, id)| !known.contains(*id))
+            .map(|(index, _)| index as u32)
+            .collect();
+        BatchRequest { digest: self.digest, indexes }
+    }
+
+    /// Decodes a bundle answering a request for this batch. Every transaction's
+    /// recomputed hash must match the short id announced at its position.
+    pub fn open(&self, bundle: &TransactionBundle, config: &GossipConfig) -> Result<Vec<QuantumTransaction>, GossipError> {
+        if bundle.digest != self.digest {
+            return Err(GossipError::UnknownBatch);
+        }
+
+        let transactions = decompress(&bundle.payload, config.max_bundle_bytes)?;
+        if transactions.len() != bundle.indexes.len() {
+            return Err(GossipError::CorruptBatch);
+        }
+        for (tx, index) in transactions.iter().zip(&bundle.indexes) {
+            let announced = self.short_ids.get(*index as usize).ok_or(GossipError::CorruptBatch)?;
+            let hash = tx.calculate_hash().map_err(|_| GossipError::CorruptBatch)?;
+            if hash != tx.hash || short_id(self.salt, &hash) != *announced {
+                return Err(GossipError::CorruptBatch);
+            }
+        }
+        Ok(transactions)
+    }
+}
+
+// 🔹 **Batcher**
+/// Collects outgoing transactions into batches and keeps sealed batches
+/// around to answer peers' requests for transactions they lack.
+#[derive(Debug, Default)]
+pub struct TxBatcher {
+    config: GossipConfig,
+    pending: Vec<QuantumTransaction>,
+    sealed: HashMap<[u8; 32], Vec<QuantumTransaction>>,
+    /// Sealed digests, oldest first.
+    sealed_order: VecDeque<[u8; 32]>,
+}
+
+impl TxBatcher {
+    pub fn new(config: GossipConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Returns true once the pending batch is full and should be sealed.
+    pub fn push(&mut self, transaction: QuantumTransaction) -> bool {
+        self.pending.push(transaction);
+        self.pending.len() >= self.config.max_batch_transactions
+    }
+
+    pub fn seal(&mut self) -> Option<BatchAnnouncement> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let transactions = std::mem::take(&mut self.pending);
+        let mut hasher = blake3::Hasher::new();
+        for tx in &transactions {
+            hasher.update(tx.hash.to_string().as_bytes());
+        }
+        let digest = *hasher.finalize().as_bytes();
+        let salt = u64::from_le_bytes(digest[..8].try_into().unwrap());
+
+        let short_ids = transactions.iter().map(|tx| short_id(salt, &tx.hash)).collect();
+        if self.sealed.insert(digest, transactions).is_none() {
+            self.sealed_order.push_back(digest);
+        }
+        while self.sealed_order.len() > self.config.max_sealed_batches {
+            if let Some(oldest) = self.sealed_order.pop_front() {
+                self.sealed.remove(&oldest);
+            }
+        }
+        Some(BatchAnnouncement { digest, salt, short_ids })
+    }
+
+    pub fn serve(&self, request: &BatchRequest) -> Result<TransactionBundle, GossipError> {
+        let batch = self.sealed.get(&request.digest).ok_or(GossipError::UnknownBatch)?;
+        let selected = request.indexes.iter()
+            .map(|index| batch.get(*index as usize).ok_or(GossipError::InvalidRequest))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TransactionBundle {
+            digest: request.digest,
+            indexes: request.indexes.clone(),
+            payload: compress(&selected, self.config.compression_level)?,
+        })
+    }
+
+    /// Forgets a sealed batch once peers no longer need it.
+    pub fn evict(&mut self, digest: &[u8; 32]) {
+        self.sealed.remove(digest);
+        self.sealed_order.retain(|sealed| sealed != digest);
+    }
+}
+
+fn short_id(salt: u64, hash: &Hash) -> ShortId {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&salt.to_le_bytes());
+    hasher.update(hash.to_string().as_bytes());
+    hasher.finalize().as_bytes()[..8].try_into().unwrap()
+}
+
+fn compress(transactions: &[&QuantumTransaction], level: i32) -> Result<Vec<u8>, GossipError> {
+    let mut raw = Vec::new();
+    ciborium::ser::into_writer(transactions, &mut raw).map_err(|e| GossipError::Serialization(e.to_string()))?;
+
+    let mut encoder = zstd::stream::Encoder::new(Vec::new(), level).map_err(|e| GossipError::Compression(e.to_string()))?;
+    // The frame checksum catches corruption before the CBOR is parsed.
+    encoder.include_checksum(true).map_err(|e| GossipError::Compression(e.to_string()))?;
+    encoder.write_all(&raw).map_err(|e| GossipError::Compression(e.to_string()))?;
+    encoder.finish().map_err(|e| GossipError::Compression(e.to_string()))
+}
+
+fn decompress(payload: &[u8], max_bytes: usize) -> Result<Vec<QuantumTransaction>, GossipError> {
+    let decoder = zstd::stream::Decoder::new(payload).map_err(|_| GossipError::CorruptBatch)?;
+    let mut raw = Vec::new();
+    decoder.take(max_bytes as u64 + 1).read_to_end(&mut raw).map_err(|_| GossipError::CorruptBatch)?;
+    if raw.len() > max_bytes {
+        return Err(GossipError::BundleTooLarge(max_bytes));
+    }
+
+    ciborium::de::from_reader(raw.as_slice()).map_err(|_| GossipError::CorruptBatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quantumfuse_sdk::{amount::Amount, transaction::OperationType};
+
+    fn test_transaction(from: &str) -> QuantumTransaction {
+        QuantumTransaction::new(
+            from.to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap()
+    }
+
+    fn hashes(transactions: &[QuantumTransaction]) -> Vec<Hash> {
+        transactions.iter().map(|tx| tx.hash).collect()
+    }
+
+    fn sealed_batch(count: usize) -> (TxBatcher, BatchAnnouncement, Vec<QuantumTransaction>) {
+        let mut batcher = TxBatcher::new(GossipConfig::default());
+        let transactions: Vec<QuantumTransaction> = (0..count).map(|i| test_transaction(&format!("sender-{}", i))).collect();
+        for tx in &transactions {
+            batcher.push(tx.clone());
+        }
+        let announcement = batcher.seal().unwrap();
+        (batcher, announcement, transactions)
+    }
+
+    #[test]
+    fn test_batch_round_trips_through_compression() {
+        let (batcher, announcement, transactions) = sealed_batch(5);
+
+        let request = announcement.missing(&HashSet::new());
+        assert_eq!(request.indexes, vec![0, 1, 2, 3, 4]);
+
+        let bundle = batcher.serve(&request).unwrap();
+        let received = announcement.open(&bundle, &GossipConfig::default()).unwrap();
+        assert_eq!(hashes(&received), hashes(&transactions));
+        assert_eq!(received[3].from, "sender-3");
+    }
+
+    #[test]
+    fn test_peer_requests_only_missing_transactions() {
+        let (batcher, announcement, transactions) = sealed_batch(5);
+        let known: HashSet<Hash> = [0, 2, 4].iter().map(|i| transactions[*i].hash).collect();
+
+        let request = announcement.missing(&known);
+        assert_eq!(request.indexes, vec![1, 3]);
+
+        let received = announcement.open(&batcher.serve(&request).unwrap(), &GossipConfig::default()).unwrap();
+        assert_eq!(hashes(&received), vec![transactions[1].hash, transactions[3].hash]);
+    }
+
+    #[test]
+    fn test_corrupted_batch_is_rejected() {
+        let (batcher, announcement, _) = sealed_batch(5);
+        let mut bundle = batcher.serve(&announcement.missing(&HashSet::new())).unwrap();
+        let middle = bundle.payload.len() / 2;
+        bundle.payload[middle] ^= 0xff;
+
+        let result = announcement.open(&bundle, &GossipConfig::default());
+        assert!(matches!(result, Err(GossipError::CorruptBatch)));
+
+        // A bundle whose transactions don't match the announced positions is rejected too.
+        let mut swapped = batcher.serve(&BatchRequest { digest: announcement.digest, indexes: vec![1] }).unwrap();
+        swapped.indexes = vec![0];
+        assert!(matches!(announcement.open(&swapped, &GossipConfig::default()), Err(GossipError::CorruptBatch)));
+    }
+
+    #[test]
+    fn test_altered_transaction_under_original_hash_is_rejected() {
+        let (_, announcement, transactions) = sealed_batch(2);
+        let mut altered = transactions[0].clone();
+        altered.amount = Amount::from_whole(1_000);
+
+        let bundle = TransactionBundle {
+            digest: announcement.digest,
+            indexes: vec![0],
+            payload: compress(&[&altered], 3).unwrap(),
+        };
+        assert!(matches!(announcement.open(&bundle, &GossipConfig::default()), Err(GossipError::CorruptBatch)));
+    }
+
+    #[test]
+    fn test_oldest_sealed_batch_is_dropped_past_the_limit() {
+        let mut batcher = TxBatcher::new(GossipConfig { max_sealed_batches: 2, ..GossipConfig::default() });
+        let announcements: Vec<BatchAnnouncement> = (0..3).map(|i| {
+            batcher.push(test_transaction(&format!("sender-{}", i)));
+            batcher.seal().unwrap()
+        }).collect();
+
+        assert_eq!(batcher.sealed.len(), 2);
+        let oldest = announcements[0].missing(&HashSet::new());
+        assert!(matches!(batcher.serve(&oldest), Err(GossipError::UnknownBatch)));
+        assert!(batcher.serve(&announcements[2].missing(&HashSet::new())).is_ok());
+    }
+}
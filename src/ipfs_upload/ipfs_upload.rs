@@ -1,18 +1,232 @@
-use ipfs_api_backend_actix::{IpfsApi, IpfsClient, TryFromUri};
-use tokio::fs::File;
-use tokio::io::AsyncReadExt;
-use std::error::Error;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use ipfs_api::{request, IpfsApi, IpfsClient as KuboClient, TryFromUri};
+use ring::digest::{digest, SHA256};
+use tokio::sync::RwLock;
+use quantumfuse_sdk::error::IpfsError;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let client = IpfsClient::from_str("http://localhost:5001")?; 
+/// Content is stored as a single raw block, so the CID can be recomputed locally.
+pub const MAX_BLOCK_SIZE: usize = 1024 * 1024;
 
-    let mut file = File::open("audio.opus").await?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer).await?;
+const CID_VERSION_1: u8 = 0x01;
+const RAW_CODEC: u8 = 0x55;
+const SHA2_256: u8 = 0x12;
+const SHA2_256_LEN: u8 = 0x20;
 
-    let response = client.add(buffer.as_slice()).await?;
-    println!("🔗 IPFS Hash: {}", response.hash);
+// 🔹 **Backends**
+#[async_trait]
+pub trait IpfsBackend: Send + Sync {
+    async fn add(&self, data: &[u8]) -> Result<String, IpfsError>;
+    async fn pin(&self, cid: &str) -> Result<(), IpfsError>;
+    async fn cat(&self, cid: &str) -> Result<Vec<u8>, IpfsError>;
+}
+
+/// Talks to a Kubo node over its HTTP RPC API.
+pub struct HttpIpfsBackend {
+    client: KuboClient,
+}
+
+impl HttpIpfsBackend {
+    pub fn new(api_url: &str) -> Result<Self, IpfsError> {
+        let client = KuboClient::from_str(api_url)
+            .map_err(|e| IpfsError::Backend(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl IpfsBackend for HttpIpfsBackend {
+    async fn add(&self, data: &[u8]) -> Result<String, IpfsError> {
+        let options = request::Add::builder()
+            .cid_version(1)
+            .raw_leaves(true)
+            .chunker("size-1048576")
+            .pin(false)
+            .build();
+
+        let response = self.client.add_with_options(Cursor::new(data.to_vec()), options).await
+            .map_err(|e| IpfsError::Backend(e.to_string()))?;
+        Ok(response.hash)
+    }
+
+    async fn pin(&self, cid: &str) -> Result<(), IpfsError> {
+        self.client.pin_add(cid, true).await
+            .map_err(|e| IpfsError::Backend(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn cat(&self, cid: &str) -> Result<Vec<u8>, IpfsError> {
+        self.client.cat(cid)
+            .map_ok(|chunk| chunk.to_vec())
+            .try_concat()
+            .await
+            .map_err(|e| IpfsError::Backend(e.to_string()))
+    }
+}
+
+/// Keeps blocks in memory, for offline development and tests.
+#[derive(Default)]
+pub struct InMemoryIpfsBackend {
+    blocks: RwLock<HashMap<String, Vec<u8>>>,
+    pinned: RwLock<Vec<String>>,
+}
+
+impl InMemoryIpfsBackend {
+    pub async fn is_pinned(&self, cid: &str) -> bool {
+        self.pinned.read().await.iter().any(|p| p == cid)
+    }
+}
+
+#[async_trait]
+impl IpfsBackend for InMemoryIpfsBackend {
+    async fn add(&self, data: &[u8]) -> Result<String, IpfsError> {
+        let cid = compute_cid(data);
+        self.blocks.write().await.insert(cid.clone(), data.to_vec());
+        Ok(cid)
+    }
+
+    async fn pin(&self, cid: &str) -> Result<(), IpfsError> {
+        if !self.blocks.read().await.contains_key(cid) {
+            return Err(IpfsError::NotFound(cid.to_string()));
+        }
+        let mut pinned = self.pinned.write().await;
+        if !pinned.iter().any(|p| p == cid) {
+            pinned.push(cid.to_string());
+        }
+        Ok(())
+    }
+
+    async fn cat(&self, cid: &str) -> Result<Vec<u8>, IpfsError> {
+        self.blocks.read().await.get(cid)
+            .cloned()
+            .ok_or_else(|| IpfsError::NotFound(cid.to_string()))
+    }
+}
+
+// 🔹 **IPFS Client**
+/// Adds, pins and fetches content, checking every CID against the bytes it names.
+#[derive(Clone)]
+pub struct IpfsClient {
+    backend: Arc<dyn IpfsBackend>,
+}
+
+impl IpfsClient {
+    pub fn new(backend: Arc<dyn IpfsBackend>) -> Self {
+        Self { backend }
+    }
+
+    pub fn connect(api_url: &str) -> Result<Self, IpfsError> {
+        Ok(Self::new(Arc::new(HttpIpfsBackend::new(api_url)?)))
+    }
+
+    pub async fn add(&self, data: &[u8]) -> Result<String, IpfsError> {
+        if data.len() > MAX_BLOCK_SIZE {
+            return Err(IpfsError::ContentTooLarge(data.len()));
+        }
+
+        let expected = compute_cid(data);
+        let cid = self.backend.add(data).await?;
+        if cid != expected {
+            return Err(IpfsError::CidMismatch { expected, actual: cid });
+        }
+        Ok(cid)
+    }
+
+    pub async fn pin(&self, cid: &str) -> Result<(), IpfsError> {
+        self.backend.pin(cid).await
+    }
+
+    /// Adds and pins in one step, returning the CID.
+    pub async fn store(&self, data: &[u8]) -> Result<String, IpfsError> {
+        let cid = self.add(data).await?;
+        self.pin(&cid).await?;
+        Ok(cid)
+    }
+
+    pub async fn get(&self, cid: &str) -> Result<Vec<u8>, IpfsError> {
+        let data = self.backend.cat(cid).await?;
+        let actual = compute_cid(&data);
+        if actual != cid {
+            return Err(IpfsError::CidMismatch { expected: cid.to_string(), actual });
+        }
+        Ok(data)
+    }
+}
+
+/// CIDv1 (raw codec, sha2-256) in lowercase base32 multibase, e.g. `bafkrei...`.
+pub fn compute_cid(data: &[u8]) -> String {
+    let hash = digest(&SHA256, data);
+
+    let mut cid = vec![CID_VERSION_1, RAW_CODEC, SHA2_256, SHA2_256_LEN];
+    cid.extend_from_slice(hash.as_ref());
+    format!("b{}", base32_lower(&cid))
+}
+
+fn base32_lower(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+    let mut output = String::with_capacity((data.len() * 8 + 4) / 5);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves different bytes than were stored under a CID.
+    struct TamperingBackend;
+
+    #[async_trait]
+    impl IpfsBackend for TamperingBackend {
+        async fn add(&self, data: &[u8]) -> Result<String, IpfsError> {
+            Ok(compute_cid(data))
+        }
+
+        async fn pin(&self, _cid: &str) -> Result<(), IpfsError> {
+            Ok(())
+        }
+
+        async fn cat(&self, _cid: &str) -> Result<Vec<u8>, IpfsError> {
+            Ok(b"tampered".to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_returns_stable_cid() {
+        let backend = Arc::new(InMemoryIpfsBackend::default());
+        let client = IpfsClient::new(backend.clone());
+
+        let first = client.store(b"nft artwork").await.unwrap();
+        let second = client.add(b"nft artwork").await.unwrap();
+
+        assert_eq!(first, second);
+        assert!(first.starts_with("bafkrei"));
+        assert!(backend.is_pinned(&first).await);
+        assert_eq!(client.get(&first).await.unwrap(), b"nft artwork");
+    }
+
+    #[tokio::test]
+    async fn test_content_cid_mismatch_is_detected() {
+        let client = IpfsClient::new(Arc::new(TamperingBackend));
+        let cid = client.add(b"deed of title").await.unwrap();
 
-    Ok(())
+        let result = client.get(&cid).await;
+        assert!(matches!(result, Err(IpfsError::CidMismatch { .. })));
+    }
 }
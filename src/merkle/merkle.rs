@@ -0,0 +1,154 @@
+use blake3::Hash;
+use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::{
+    error::MerkleError,
+    codec::to_canonical_cbor,
+};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiblingPosition {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStep {
+    /// Hex-encoded sibling hash.
+    pub hash: String,
+    pub position: SiblingPosition,
+}
+
+/// Path from a leaf to the root. Unpaired nodes are promoted to the next level
+/// unchanged, so a level may contribute no step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub leaf_index: usize,
+    pub leaf_count: usize,
+    pub steps: Vec<ProofStep>,
+}
+
+/// Root over the canonical CBOR encoding of `items`; empty input hashes to the default hash.
+pub fn merkle_root<T: Serialize>(items: &[T]) -> Result<Hash, MerkleError> {
+    let mut level = leaf_hashes(items)?;
+    if level.is_empty() {
+        return Ok(Hash::from([0u8; 32]));
+    }
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    Ok(level[0])
+}
+
+pub fn inclusion_proof<T: Serialize>(items: &[T], leaf_index: usize) -> Result<MerkleProof, MerkleError> {
+    if leaf_index >= items.len() {
+        return Err(MerkleError::LeafOutOfRange);
+    }
+
+    let mut level = leaf_hashes(items)?;
+    let mut index = leaf_index;
+    let mut steps = Vec::new();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            steps.push(ProofStep {
+                hash: level[sibling].to_hex().to_string(),
+                position: if sibling < index { SiblingPosition::Left } else { SiblingPosition::Right },
+            });
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+
+    Ok(MerkleProof { leaf_index, leaf_count: items.len(), steps })
+}
+
+/// Client-side check that `item` is committed to by `root` via `proof`.
+pub fn verify_inclusion<T: Serialize>(root: &Hash, item: &T, proof: &MerkleProof) -> bool {
+    if proof.leaf_index >= proof.leaf_count {
+        return false;
+    }
+    let Ok(mut current) = leaf_hash(item) else {
+        return false;
+    };
+
+    // Replay the tree shape so steps cannot be dropped or reordered.
+    let mut steps = proof.steps.iter();
+    let mut index = proof.leaf_index;
+    let mut width = proof.leaf_count;
+    while width > 1 {
+        let sibling = index ^ 1;
+        if sibling < width {
+            let Some(step) = steps.next() else {
+                return false;
+            };
+            let expected = if sibling < index { SiblingPosition::Left } else { SiblingPosition::Right };
+            let Ok(sibling_hash) = Hash::from_hex(&step.hash) else {
+                return false;
+            };
+            if step.position != expected {
+                return false;
+            }
+            current = match step.position {
+                SiblingPosition::Left => node_hash(&sibling_hash, &current),
+                SiblingPosition::Right => node_hash(&current, &sibling_hash),
+            };
+        }
+        index /= 2;
+        width = width.div_ceil(2);
+    }
+
+    steps.next().is_none() && current == *root
+}
+
+fn leaf_hashes<T: Serialize>(items: &[T]) -> Result<Vec<Hash>, MerkleError> {
+    items.iter().map(leaf_hash).collect()
+}
+
+fn leaf_hash<T: Serialize>(item: &T) -> Result<Hash, MerkleError> {
+    let encoded = to_canonical_cbor(item).map_err(|e| MerkleError::Serialization(e.to_string()))?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(&encoded);
+    Ok(hasher.finalize())
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    hasher.finalize()
+}
+
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+    level.chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_leaf_proves_against_root() {
+        let items: Vec<String> = (0..7).map(|i| format!("tx-{}", i)).collect();
+        let root = merkle_root(&items).unwrap();
+
+        for (i, item) in items.iter().enumerate() {
+            let proof = inclusion_proof(&items, i).unwrap();
+            assert!(verify_inclusion(&root, item, &proof));
+        }
+
+        let proof = inclusion_proof(&items, 2).unwrap();
+        assert!(!verify_inclusion(&root, &"tx-3".to_string(), &proof));
+    }
+}
@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    entry_point, to_binary, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, Storage,
+    entry_point, from_json, to_binary, to_json_vec, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, Storage,
 };
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +9,9 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     StreamNFT { nft_id: String },
+    CreateStream { recipient: String, rate_per_second: Uint128, start: u64, stop: u64 },
+    Withdraw { stream_id: u64, amount: Option<Uint128> },
+    CancelStream { stream_id: u64 },
 }
 
 // ✅ Define storage structure
@@ -28,6 +32,11 @@ pub fn execute(
 ) -> StdResult<Response> {
     match msg {
         ExecuteMsg::StreamNFT { nft_id } => record_stream(deps, info.sender.to_string(), nft_id),
+        ExecuteMsg::CreateStream { recipient, rate_per_second, start, stop } => {
+            create_stream(deps, info, recipient, rate_per_second, start, stop)
+        }
+        ExecuteMsg::Withdraw { stream_id, amount } => withdraw(deps, _env, info, stream_id, amount),
+        ExecuteMsg::CancelStream { stream_id } => cancel_stream(deps, _env, info, stream_id),
     }
 }
 
@@ -50,9 +59,208 @@ pub fn record_stream(deps: DepsMut, user: String, nft_id: String) -> StdResult<R
     Ok(Response::new().add_message(cosmos_msg))
 }
 
+// ✅ Define payment stream (per-second salary stream)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct PaymentStream {
+    pub sender: String,
+    pub recipient: String,
+    pub rate_per_second: Uint128,
+    pub start: u64,
+    pub stop: u64,
+    pub withdrawn: Uint128,
+}
+
+impl PaymentStream {
+    pub fn new(sender: String, recipient: String, rate_per_second: Uint128, start: u64, stop: u64) -> StdResult<Self> {
+        if stop <= start {
+            return Err(StdError::generic_err("stream stop must be after start"));
+        }
+        if rate_per_second.is_zero() {
+            return Err(StdError::generic_err("stream rate must be positive"));
+        }
+
+        let stream = Self { sender, recipient, rate_per_second, start, stop, withdrawn: Uint128::zero() };
+        stream.deposit()?;
+        Ok(stream)
+    }
+
+    /// Total amount the sender must escrow to cover the stream.
+    pub fn deposit(&self) -> StdResult<Uint128> {
+        Ok(self.rate_per_second.checked_mul(Uint128::from(self.stop - self.start))?)
+    }
+
+    /// Amount streamed to the recipient by `now`, whether withdrawn or not.
+    pub fn accrued_amount(&self, now: u64) -> StdResult<Uint128> {
+        let elapsed = now.clamp(self.start, self.stop) - self.start;
+        Ok(self.rate_per_second.checked_mul(Uint128::from(elapsed))?)
+    }
+
+    pub fn withdrawable_amount(&self, now: u64) -> StdResult<Uint128> {
+        Ok(self.accrued_amount(now)?.checked_sub(self.withdrawn)?)
+    }
+
+    pub fn withdraw(&mut self, now: u64, amount: Uint128) -> StdResult<Uint128> {
+        if amount > self.withdrawable_amount(now)? {
+            return Err(StdError::generic_err("withdrawal exceeds accrued amount"));
+        }
+        self.withdrawn = self.withdrawn.checked_add(amount)?;
+        Ok(amount)
+    }
+
+    /// Settles the unwithdrawn accrued portion to the recipient and refunds
+    /// the unstreamed remainder to the sender, as `(recipient, sender)`.
+    pub fn cancel(&self, now: u64) -> StdResult<(Uint128, Uint128)> {
+        let accrued = self.accrued_amount(now)?;
+        let recipient_amount = accrued.checked_sub(self.withdrawn)?;
+        let sender_refund = self.deposit()?.checked_sub(accrued)?;
+        Ok((recipient_amount, sender_refund))
+    }
+}
+
+const STREAM_COUNT_KEY: &[u8] = b"stream_count";
+const STREAM_DENOM: &str = "qfc";
+
+fn stream_key(stream_id: u64) -> Vec<u8> {
+    [b"stream:".as_slice(), &stream_id.to_be_bytes()].concat()
+}
+
+fn load_stream(storage: &dyn Storage, stream_id: u64) -> StdResult<PaymentStream> {
+    let data = storage.get(&stream_key(stream_id))
+        .ok_or_else(|| StdError::not_found(format!("stream {}", stream_id)))?;
+    from_json(&data)
+}
+
+fn send_qfc(to_address: &str, amount: Uint128) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: to_address.to_string(),
+        amount: vec![Coin { denom: STREAM_DENOM.to_string(), amount }],
+    }
+    .into()
+}
+
+pub fn create_stream(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    rate_per_second: Uint128,
+    start: u64,
+    stop: u64,
+) -> StdResult<Response> {
+    let stream = PaymentStream::new(info.sender.to_string(), recipient, rate_per_second, start, stop)?;
+
+    let funded = info.funds.iter()
+        .filter(|coin| coin.denom == STREAM_DENOM)
+        .map(|coin| coin.amount)
+        .sum::<Uint128>();
+    if funded != stream.deposit()? {
+        return Err(StdError::generic_err("funds must equal rate_per_second * duration"));
+    }
+
+    let stream_id = deps.storage.get(STREAM_COUNT_KEY)
+        .map(|data| from_json::<u64>(&data))
+        .transpose()?
+        .unwrap_or_default();
+    deps.storage.set(STREAM_COUNT_KEY, &to_json_vec(&(stream_id + 1))?);
+    deps.storage.set(&stream_key(stream_id), &to_json_vec(&stream)?);
+
+    Ok(Response::new()
+        .add_attribute("action", "create_stream")
+        .add_attribute("stream_id", stream_id.to_string()))
+}
+
+pub fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stream_id: u64,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let mut stream = load_stream(deps.storage, stream_id)?;
+    if info.sender.as_str() != stream.recipient {
+        return Err(StdError::generic_err("only the recipient can withdraw"));
+    }
+
+    let now = env.block.time.seconds();
+    let amount = match amount {
+        Some(amount) => amount,
+        None => stream.withdrawable_amount(now)?,
+    };
+    stream.withdraw(now, amount)?;
+    deps.storage.set(&stream_key(stream_id), &to_json_vec(&stream)?);
+
+    Ok(Response::new()
+        .add_message(send_qfc(&stream.recipient, amount))
+        .add_attribute("action", "withdraw")
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn cancel_stream(deps: DepsMut, env: Env, info: MessageInfo, stream_id: u64) -> StdResult<Response> {
+    let stream = load_stream(deps.storage, stream_id)?;
+    if info.sender.as_str() != stream.sender && info.sender.as_str() != stream.recipient {
+        return Err(StdError::generic_err("only stream parties can cancel"));
+    }
+
+    let (recipient_amount, sender_refund) = stream.cancel(env.block.time.seconds())?;
+    deps.storage.remove(&stream_key(stream_id));
+
+    let mut response = Response::new().add_attribute("action", "cancel_stream");
+    if !recipient_amount.is_zero() {
+        response = response.add_message(send_qfc(&stream.recipient, recipient_amount));
+    }
+    if !sender_refund.is_zero() {
+        response = response.add_message(send_qfc(&stream.sender, sender_refund));
+    }
+    Ok(response)
+}
+
 // ✅ Make `handle_streaming_payments` public
 pub fn handle_streaming_payments() -> Result<(), Box<dyn std::error::Error>> {
     println!("🎵 Processing streaming payments...");
     // Implement streaming payments logic here
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn salary_stream() -> PaymentStream {
+        // 10 qfc per second for 100 seconds.
+        PaymentStream::new("employer".to_string(), "employee".to_string(), Uint128::new(10), 1_000, 1_100).unwrap()
+    }
+
+    #[test]
+    fn test_mid_stream_withdrawal() {
+        let mut stream = salary_stream();
+        assert_eq!(stream.withdrawable_amount(999).unwrap(), Uint128::zero());
+        assert_eq!(stream.withdrawable_amount(1_030).unwrap(), Uint128::new(300));
+
+        stream.withdraw(1_030, Uint128::new(200)).unwrap();
+        assert_eq!(stream.withdrawable_amount(1_030).unwrap(), Uint128::new(100));
+        assert!(stream.withdraw(1_030, Uint128::new(101)).is_err());
+        assert_eq!(stream.withdrawn, Uint128::new(200));
+    }
+
+    #[test]
+    fn test_full_stream_settlement() {
+        let mut stream = salary_stream();
+        stream.withdraw(1_050, Uint128::new(500)).unwrap();
+
+        let remaining = stream.withdrawable_amount(5_000).unwrap();
+        assert_eq!(remaining, Uint128::new(500));
+        stream.withdraw(5_000, remaining).unwrap();
+        assert_eq!(stream.withdrawn, stream.deposit().unwrap());
+    }
+
+    #[test]
+    fn test_cancellation_splits_funds() {
+        let mut stream = salary_stream();
+        stream.withdraw(1_020, Uint128::new(150)).unwrap();
+
+        let (recipient_amount, sender_refund) = stream.cancel(1_040).unwrap();
+        assert_eq!(recipient_amount, Uint128::new(250));
+        assert_eq!(sender_refund, Uint128::new(600));
+        assert_eq!(stream.withdrawn + recipient_amount + sender_refund, stream.deposit().unwrap());
+    }
+}
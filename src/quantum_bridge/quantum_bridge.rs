@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use log::info;
 use quantumfuse_sdk::{
     error::BridgeError,
     crypto::{Hash, KeyPair},
@@ -14,13 +15,14 @@ use quantumfuse_sdk::{
     ai::{PathfinderAI, CongestionMonitor},
     metrics::BridgeMetrics,
     qkd::QKDManager,
+    wallet::verify_signature,
 };
 
 // Core Bridge Struct
 #[derive(Debug)]
 pub struct QuantumBridge {
-    entanglements: Arc<RwLock<HashMap<String, Entanglement>>>,
-    bridge_nodes: Arc<RwLock<HashMap<String, BridgeNode>>>,
+    entanglements: Arc<RwLock<EntanglementTable>>,
+    bridge_nodes: Arc<RwLock<BridgeNodeRegistry>>,
     active_transfers: Arc<RwLock<HashMap<String, BridgeTransfer>>>,
     metrics: Arc<RwLock<BridgeMetrics>>,
     qkd_manager: Arc<RwLock<QKDManager>>,
@@ -37,10 +39,41 @@ pub struct Entanglement {
     pub chain_b: ChainInfo,
     pub status: EntanglementStatus,
     pub created_at: DateTime<Utc>,
+    /// When the entanglement moved to `Failed`; the sweep's TTL runs from here.
+    #[serde(default)]
+    pub failed_at: Option<DateTime<Utc>>,
     pub quantum_state: Vec<u8>,
     pub verification_proof: Option<VerificationProof>,
 }
 
+// Entanglement Limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EntanglementLimits {
+    pub max_entanglements: usize,
+    /// Failed entanglements older than this are removed by the sweep.
+    pub failed_ttl_secs: i64,
+    /// Pending entanglements older than this may be evicted to make room.
+    pub pending_timeout_secs: i64,
+}
+
+impl Default for EntanglementLimits {
+    fn default() -> Self {
+        Self {
+            max_entanglements: 10_000,
+            failed_ttl_secs: 3_600,
+            pending_timeout_secs: 600,
+        }
+    }
+}
+
+/// Entanglements by id, bounded by `EntanglementLimits`.
+#[derive(Debug, Default)]
+pub struct EntanglementTable {
+    limits: EntanglementLimits,
+    entries: HashMap<String, Entanglement>,
+}
+
 // Chain Information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainInfo {
@@ -60,6 +93,49 @@ pub struct BridgeNode {
     pub reputation_score: f64,
     pub status: NodeStatus,
     pub last_heartbeat: DateTime<Utc>,
+    /// Dilithium2 key the node signs transfer attestations with.
+    #[serde(default)]
+    pub public_key: Vec<u8>,
+}
+
+// Node Health and Quorum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeHealthConfig {
+    /// A node with no heartbeat for this long is marked inactive.
+    pub heartbeat_timeout_secs: i64,
+    /// Active nodes that must attest to a transfer before it finalizes.
+    pub quorum: usize,
+}
+
+impl Default for NodeHealthConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_timeout_secs: 30,
+            quorum: 3,
+        }
+    }
+}
+
+/// Bridge nodes by id, with heartbeat-driven health.
+#[derive(Debug, Default)]
+pub struct BridgeNodeRegistry {
+    config: NodeHealthConfig,
+    nodes: HashMap<String, BridgeNode>,
+}
+
+/// One node's signature over `transfer_attestation_message`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeAttestation {
+    pub node_id: String,
+    pub signature: Vec<u8>,
+}
+
+/// Attestations gathered from bridge nodes for a transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationProof {
+    pub transfer_id: String,
+    pub attestations: Vec<NodeAttestation>,
 }
 
 // Bridge Transfer Structure
@@ -69,9 +145,14 @@ pub struct BridgeTransfer {
     pub source_chain: String,
     pub target_chain: String,
     pub asset: BridgeAsset,
-    pub amount: f64,
+    /// In source-chain base units.
+    pub amount: u128,
+    /// `amount` rescaled to the target chain's decimals.
+    pub target_amount: u128,
     pub status: TransferStatus,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub verification_proof: Option<VerificationProof>,
 }
 
 // Supported Assets
@@ -81,7 +162,32 @@ pub struct BridgeAsset {
     pub name: String,
     pub source_contract: String,
     pub target_contract: String,
+    /// Decimals on the source chain.
     pub decimals: u8,
+    /// Decimals on the target chain; `None` when they match the source.
+    #[serde(default)]
+    pub target_decimals: Option<u8>,
+}
+
+impl BridgeAsset {
+    pub fn to_target_units(&self, amount: u128) -> Result<u128, BridgeError> {
+        normalize_amount(amount, self.decimals, self.target_decimals.unwrap_or(self.decimals))
+    }
+}
+
+/// Rescales `amount` base units between decimal conventions. Fails with
+/// `PrecisionLoss` when the result can't represent the amount exactly.
+pub fn normalize_amount(amount: u128, from_decimals: u8, to_decimals: u8) -> Result<u128, BridgeError> {
+    let scale = |diff: u8| 10u128.checked_pow(diff as u32).ok_or(BridgeError::PrecisionLoss);
+    if to_decimals >= from_decimals {
+        amount.checked_mul(scale(to_decimals - from_decimals)?).ok_or(BridgeError::PrecisionLoss)
+    } else {
+        let divisor = scale(from_decimals - to_decimals)?;
+        if amount % divisor != 0 {
+            return Err(BridgeError::PrecisionLoss);
+        }
+        Ok(amount / divisor)
+    }
 }
 
 // Enum Definitions
@@ -119,13 +225,187 @@ pub enum TransferStatus {
     Failed(String),
 }
 
+impl TransferStatus {
+    /// Still awaiting finalization.
+    pub fn is_pending(&self) -> bool {
+        matches!(self, TransferStatus::Initiated | TransferStatus::Locked | TransferStatus::InTransit)
+    }
+}
+
 // Implementations
 
+impl EntanglementTable {
+    pub fn new(limits: EntanglementLimits) -> Self {
+        Self { limits, entries: HashMap::new() }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Entanglement> {
+        self.entries.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inserts `entanglement`, evicting the oldest failed or timed-out pending
+    /// entry when full. Returns the evicted id; fails if nothing can be evicted.
+    pub fn insert(&mut self, entanglement: Entanglement, now: DateTime<Utc>) -> Result<Option<String>, BridgeError> {
+        let mut evicted = None;
+        if self.entries.len() >= self.limits.max_entanglements && !self.entries.contains_key(&entanglement.id) {
+            let id = self.oldest_evictable(now)
+                .ok_or(BridgeError::EntanglementLimitReached(self.limits.max_entanglements))?;
+            self.entries.remove(&id);
+            evicted = Some(id);
+        }
+
+        self.entries.insert(entanglement.id.clone(), entanglement);
+        Ok(evicted)
+    }
+
+    /// Marks an entanglement failed as of `now`, starting its TTL.
+    pub fn mark_failed(&mut self, id: &str, reason: String, now: DateTime<Utc>) -> Result<(), BridgeError> {
+        let entanglement = self.entries.get_mut(id)
+            .ok_or_else(|| BridgeError::EntanglementNotFound(id.to_string()))?;
+        entanglement.status = EntanglementStatus::Failed(reason);
+        entanglement.failed_at = Some(now);
+        Ok(())
+    }
+
+    /// Removes entanglements that failed more than the TTL ago, returning their ids.
+    /// Failures recorded without a time count from creation.
+    pub fn sweep_failed(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let ttl = chrono::Duration::seconds(self.limits.failed_ttl_secs);
+        let expired: Vec<String> = self.entries.values()
+            .filter(|entanglement| matches!(entanglement.status, EntanglementStatus::Failed(_)))
+            .filter(|entanglement| now - entanglement.failed_at.unwrap_or(entanglement.created_at) >= ttl)
+            .map(|entanglement| entanglement.id.clone())
+            .collect();
+
+        for id in &expired {
+            self.entries.remove(id);
+        }
+        expired
+    }
+
+    fn oldest_evictable(&self, now: DateTime<Utc>) -> Option<String> {
+        let pending_timeout = chrono::Duration::seconds(self.limits.pending_timeout_secs);
+        self.entries.values()
+            .filter(|entanglement| match entanglement.status {
+                EntanglementStatus::Failed(_) => true,
+                EntanglementStatus::Pending => now - entanglement.created_at >= pending_timeout,
+                EntanglementStatus::Active | EntanglementStatus::Verified => false,
+            })
+            .min_by_key(|entanglement| entanglement.created_at)
+            .map(|entanglement| entanglement.id.clone())
+    }
+}
+
+impl BridgeNodeRegistry {
+    pub fn new(config: NodeHealthConfig) -> Self {
+        Self { config, nodes: HashMap::new() }
+    }
+
+    pub fn register(&mut self, node: BridgeNode) {
+        self.nodes.insert(node.node_id.clone(), node);
+    }
+
+    pub fn get(&self, node_id: &str) -> Option<&BridgeNode> {
+        self.nodes.get(node_id)
+    }
+
+    /// Records a heartbeat, reactivating an inactive node. Nodes in
+    /// maintenance stay there until taken out explicitly.
+    pub fn heartbeat(&mut self, node_id: &str, now: DateTime<Utc>) -> Result<(), BridgeError> {
+        let node = self.nodes.get_mut(node_id).ok_or_else(|| BridgeError::UnknownNode(node_id.to_string()))?;
+        node.last_heartbeat = now;
+        if matches!(node.status, NodeStatus::Inactive) {
+            node.status = NodeStatus::Active;
+        }
+        Ok(())
+    }
+
+    /// Marks active nodes whose heartbeat has timed out as inactive, returning their ids.
+    pub fn mark_stale(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let timeout = chrono::Duration::seconds(self.config.heartbeat_timeout_secs);
+        let mut stale = Vec::new();
+        for node in self.nodes.values_mut() {
+            if matches!(node.status, NodeStatus::Active) && now - node.last_heartbeat > timeout {
+                node.status = NodeStatus::Inactive;
+                stale.push(node.node_id.clone());
+            }
+        }
+        stale.sort();
+        stale
+    }
+
+    /// Active with a heartbeat inside the timeout, whether or not `mark_stale` has run.
+    pub fn is_healthy(&self, node_id: &str, now: DateTime<Utc>) -> bool {
+        let timeout = chrono::Duration::seconds(self.config.heartbeat_timeout_secs);
+        self.nodes.get(node_id).map_or(false, |node| {
+            matches!(node.status, NodeStatus::Active) && now - node.last_heartbeat <= timeout
+        })
+    }
+
+    /// Counts distinct healthy nodes with a valid attestation for `transfer`,
+    /// failing if they fall short of the quorum.
+    pub fn check_quorum(&self, transfer: &BridgeTransfer, proof: &VerificationProof, now: DateTime<Utc>) -> Result<usize, BridgeError> {
+        if proof.transfer_id != transfer.transfer_id {
+            return Err(BridgeError::ProofMismatch);
+        }
+
+        let message = transfer_attestation_message(transfer);
+        let signers: HashSet<&str> = proof.attestations.iter()
+            .filter(|attestation| self.is_healthy(&attestation.node_id, now))
+            .filter(|attestation| {
+                let public_key = &self.nodes[&attestation.node_id].public_key;
+                verify_signature(public_key, &message, &attestation.signature)
+            })
+            .map(|attestation| attestation.node_id.as_str())
+            .collect();
+
+        // A zero quorum would let an unattested transfer through.
+        let required = self.config.quorum.max(1);
+        if signers.len() < required {
+            return Err(BridgeError::QuorumNotReached { signed: signers.len(), required });
+        }
+        Ok(signers.len())
+    }
+
+    /// Completes a pending `transfer` once `proof` reaches the quorum. A transfer
+    /// that already completed or failed is rejected, so it can't finalize twice.
+    pub fn finalize(&self, transfer: &mut BridgeTransfer, proof: VerificationProof, now: DateTime<Utc>) -> Result<(), BridgeError> {
+        if !transfer.status.is_pending() {
+            return Err(BridgeError::TransferNotPending(transfer.transfer_id.clone()));
+        }
+        self.check_quorum(transfer, &proof, now)?;
+        transfer.status = TransferStatus::Completed;
+        transfer.verification_proof = Some(proof);
+        Ok(())
+    }
+}
+
+/// What bridge nodes sign to attest to a transfer.
+pub fn transfer_attestation_message(transfer: &BridgeTransfer) -> Vec<u8> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"quantumfuse/bridge-transfer");
+    for field in [&transfer.transfer_id, &transfer.source_chain, &transfer.target_chain, &transfer.asset.asset_id] {
+        hasher.update(&(field.len() as u64).to_le_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.update(&transfer.amount.to_le_bytes());
+    hasher.update(&transfer.target_amount.to_le_bytes());
+    hasher.finalize().as_bytes().to_vec()
+}
+
 impl QuantumBridge {
     pub async fn new(config: BridgeConfig) -> Result<Self, BridgeError> {
         Ok(Self {
-            entanglements: Arc::new(RwLock::new(HashMap::new())),
-            bridge_nodes: Arc::new(RwLock::new(HashMap::new())),
+            entanglements: Arc::new(RwLock::new(EntanglementTable::new(config.entanglement_limits.clone()))),
+            bridge_nodes: Arc::new(RwLock::new(BridgeNodeRegistry::new(config.node_health.clone()))),
             active_transfers: Arc::new(RwLock::new(HashMap::new())),
             metrics: Arc::new(RwLock::new(BridgeMetrics::default())),
             qkd_manager: Arc::new(RwLock::new(QKDManager::new().await?)),
@@ -135,6 +415,45 @@ impl QuantumBridge {
         })
     }
 
+    pub fn with_entanglement_limits(self, limits: EntanglementLimits) -> Self {
+        Self { entanglements: Arc::new(RwLock::new(EntanglementTable::new(limits))), ..self }
+    }
+
+    pub fn with_node_health(self, config: NodeHealthConfig) -> Self {
+        Self { bridge_nodes: Arc::new(RwLock::new(BridgeNodeRegistry::new(config))), ..self }
+    }
+
+    pub async fn register_node(&self, node: BridgeNode) {
+        self.bridge_nodes.write().await.register(node);
+    }
+
+    pub async fn record_heartbeat(&self, node_id: &str) -> Result<(), BridgeError> {
+        self.bridge_nodes.write().await.heartbeat(node_id, Utc::now())
+    }
+
+    /// Marks nodes with timed-out heartbeats inactive; run periodically.
+    pub async fn mark_stale_nodes(&self) -> Vec<String> {
+        self.bridge_nodes.write().await.mark_stale(Utc::now())
+    }
+
+    /// Completes a pending transfer once a quorum of healthy nodes has attested to it.
+    pub async fn finalize_transfer(&self, proof: VerificationProof) -> Result<(), BridgeError> {
+        let mut transfers = self.active_transfers.write().await;
+        let transfer = transfers.get_mut(&proof.transfer_id)
+            .ok_or_else(|| BridgeError::TransferNotFound(proof.transfer_id.clone()))?;
+
+        self.bridge_nodes.read().await.finalize(transfer, proof, Utc::now())
+    }
+
+    pub async fn fail_entanglement(&self, id: &str, reason: String) -> Result<(), BridgeError> {
+        self.entanglements.write().await.mark_failed(id, reason, Utc::now())
+    }
+
+    /// Drops failed entanglements past their TTL; run periodically.
+    pub async fn sweep_failed_entanglements(&self) -> Vec<String> {
+        self.entanglements.write().await.sweep_failed(Utc::now())
+    }
+
     pub async fn create_entanglement(
         &self,
         chain_a: &str,
@@ -156,13 +475,17 @@ impl QuantumBridge {
             chain_b: self.get_chain_info(chain_b).await?,
             status: EntanglementStatus::Pending,
             created_at: Utc::now(),
+            failed_at: None,
             quantum_state,
             verification_proof: None,
         };
 
-        self.entanglements.write().await.insert(entanglement.id.clone(), entanglement.clone());
+        let id = entanglement.id.clone();
+        if let Some(evicted) = self.entanglements.write().await.insert(entanglement, Utc::now())? {
+            info!("Evicted entanglement {} to make room for {}", evicted, id);
+        }
 
-        Ok(entanglement.id)
+        Ok(id)
     }
 
     pub async fn initiate_transfer(
@@ -170,8 +493,10 @@ impl QuantumBridge {
         source_chain: &str,
         target_chain: &str,
         asset: BridgeAsset,
-        amount: f64,
+        amount: u128,
     ) -> Result<String, BridgeError> {
+        let target_amount = asset.to_target_units(amount)?;
+
         // AI-Driven Gas Fee Optimization
         let gas_fee = self.congestion_monitor.read().await.estimate_gas_fee(source_chain)?;
 
@@ -182,8 +507,10 @@ impl QuantumBridge {
             target_chain: target_chain.to_string(),
             asset,
             amount,
+            target_amount,
             status: TransferStatus::Initiated,
             timestamp: Utc::now(),
+            verification_proof: None,
         };
 
         self.active_transfers.write().await.insert(transfer.transfer_id.clone(), transfer.clone());
@@ -200,3 +527,251 @@ fn generate_entanglement_id() -> Result<String, BridgeError> {
 fn generate_transfer_id() -> Result<String, BridgeError> {
     Ok(format!("transfer-{}", uuid::Uuid::new_v4()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quantumfuse_sdk::wallet::{InMemoryKeyStorage, KeyStorageBackend};
+
+    fn limits(max_entanglements: usize) -> EntanglementLimits {
+        EntanglementLimits { max_entanglements, failed_ttl_secs: 3_600, pending_timeout_secs: 600 }
+    }
+
+    fn chain(chain_id: &str) -> ChainInfo {
+        ChainInfo {
+            chain_id: chain_id.to_string(),
+            network_type: NetworkType::QuantumFuse,
+            endpoint: format!("https://{}.example", chain_id),
+            latest_block: 0,
+            bridge_contract: "bridge".to_string(),
+        }
+    }
+
+    fn entanglement(id: &str, status: EntanglementStatus, created_at: DateTime<Utc>) -> Entanglement {
+        Entanglement {
+            id: id.to_string(),
+            chain_a: chain("qfc"),
+            chain_b: chain("eth"),
+            status,
+            created_at,
+            failed_at: None,
+            quantum_state: Vec::new(),
+            verification_proof: None,
+        }
+    }
+
+    struct TestNode {
+        id: String,
+        keys: InMemoryKeyStorage,
+    }
+
+    impl TestNode {
+        fn attest(&self, transfer: &BridgeTransfer) -> NodeAttestation {
+            NodeAttestation {
+                node_id: self.id.clone(),
+                signature: self.keys.sign(&transfer_attestation_message(transfer)).unwrap(),
+            }
+        }
+    }
+
+    /// Three nodes with quorum 2; the last node's heartbeat is a minute old.
+    fn node_registry(now: DateTime<Utc>) -> (BridgeNodeRegistry, Vec<TestNode>) {
+        let mut registry = BridgeNodeRegistry::new(NodeHealthConfig { heartbeat_timeout_secs: 30, quorum: 2 });
+        let nodes: Vec<TestNode> = ["n1", "n2", "n3"].iter()
+            .map(|id| TestNode { id: id.to_string(), keys: InMemoryKeyStorage::generate() })
+            .collect();
+        for (index, node) in nodes.iter().enumerate() {
+            let heartbeat = if index == 2 { now - chrono::Duration::seconds(60) } else { now };
+            registry.register(BridgeNode {
+                node_id: node.id.clone(),
+                endpoint: format!("https://{}.example", node.id),
+                supported_chains: vec!["qfc".to_string(), "eth".to_string()],
+                reputation_score: 1.0,
+                status: NodeStatus::Active,
+                last_heartbeat: heartbeat,
+                public_key: node.keys.public_key(),
+            });
+        }
+        (registry, nodes)
+    }
+
+    fn transfer() -> BridgeTransfer {
+        BridgeTransfer {
+            transfer_id: "transfer-1".to_string(),
+            source_chain: "qfc".to_string(),
+            target_chain: "eth".to_string(),
+            asset: BridgeAsset {
+                asset_id: "QFC".to_string(),
+                name: "QuantumFuse Coin".to_string(),
+                source_contract: "native".to_string(),
+                target_contract: "0xqfc".to_string(),
+                decimals: 9,
+                target_decimals: None,
+            },
+            amount: 25_000_000_000,
+            target_amount: 25_000_000_000,
+            status: TransferStatus::Locked,
+            timestamp: Utc::now(),
+            verification_proof: None,
+        }
+    }
+
+    fn proof(transfer: &BridgeTransfer, signers: &[&TestNode]) -> VerificationProof {
+        VerificationProof {
+            transfer_id: transfer.transfer_id.clone(),
+            attestations: signers.iter().map(|node| node.attest(transfer)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_transfer_needs_quorum_to_finalize() {
+        let now = Utc::now();
+        let (registry, nodes) = node_registry(now);
+        let transfer = transfer();
+
+        assert!(matches!(
+            registry.check_quorum(&transfer, &proof(&transfer, &[&nodes[0]]), now),
+            Err(BridgeError::QuorumNotReached { signed: 1, required: 2 })
+        ));
+        // The same node attesting twice still counts once.
+        assert!(registry.check_quorum(&transfer, &proof(&transfer, &[&nodes[0], &nodes[0]]), now).is_err());
+        assert_eq!(registry.check_quorum(&transfer, &proof(&transfer, &[&nodes[0], &nodes[1]]), now).unwrap(), 2);
+
+        // Signatures over a different transfer don't count.
+        let mut other = transfer.clone();
+        other.amount = 2_500_000_000_000;
+        let mut forged = proof(&other, &[&nodes[0], &nodes[1]]);
+        forged.transfer_id = transfer.transfer_id.clone();
+        assert!(registry.check_quorum(&transfer, &forged, now).is_err());
+    }
+
+    #[test]
+    fn test_stale_nodes_do_not_count_toward_quorum() {
+        let now = Utc::now();
+        let (mut registry, nodes) = node_registry(now);
+        let transfer = transfer();
+        let stale_signed = proof(&transfer, &[&nodes[0], &nodes[2]]);
+
+        assert!(!registry.is_healthy("n3", now));
+        assert!(registry.check_quorum(&transfer, &stale_signed, now).is_err());
+        assert_eq!(registry.mark_stale(now), vec!["n3".to_string()]);
+        assert!(matches!(registry.get("n3").unwrap().status, NodeStatus::Inactive));
+
+        // A fresh heartbeat brings the node back into the quorum.
+        registry.heartbeat("n3", now).unwrap();
+        assert_eq!(registry.check_quorum(&transfer, &stale_signed, now).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_quorum_loss_blocks_finalization() {
+        let now = Utc::now();
+        let (mut registry, nodes) = node_registry(now);
+        let transfer = transfer();
+        let attested = proof(&transfer, &[&nodes[0], &nodes[1]]);
+        registry.check_quorum(&transfer, &attested, now).unwrap();
+
+        // n2 stops sending heartbeats while n1 keeps going.
+        let later = now + chrono::Duration::seconds(45);
+        registry.heartbeat("n1", later).unwrap();
+        assert_eq!(registry.mark_stale(later), vec!["n2".to_string(), "n3".to_string()]);
+        assert!(matches!(
+            registry.check_quorum(&transfer, &attested, later),
+            Err(BridgeError::QuorumNotReached { signed: 1, required: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_transfer_finalizes_only_once() {
+        let now = Utc::now();
+        let (registry, nodes) = node_registry(now);
+        let mut transfer = transfer();
+        let attested = proof(&transfer, &[&nodes[0], &nodes[1]]);
+
+        registry.finalize(&mut transfer, attested.clone(), now).unwrap();
+        assert!(matches!(transfer.status, TransferStatus::Completed));
+        assert!(matches!(
+            registry.finalize(&mut transfer, attested.clone(), now),
+            Err(BridgeError::TransferNotPending(id)) if id == "transfer-1"
+        ));
+
+        transfer.status = TransferStatus::Failed("reverted on target".to_string());
+        assert!(registry.finalize(&mut transfer, attested, now).is_err());
+    }
+
+    fn asset(decimals: u8, target_decimals: u8) -> BridgeAsset {
+        BridgeAsset {
+            asset_id: "USDX".to_string(),
+            name: "Bridged Dollar".to_string(),
+            source_contract: "0xsource".to_string(),
+            target_contract: "0xtarget".to_string(),
+            decimals,
+            target_decimals: Some(target_decimals),
+        }
+    }
+
+    #[test]
+    fn test_eighteen_to_six_decimal_conversion() {
+        // 1.5 tokens.
+        assert_eq!(asset(18, 6).to_target_units(1_500_000_000_000_000_000).unwrap(), 1_500_000);
+        assert_eq!(asset(18, 6).to_target_units(0).unwrap(), 0);
+        assert_eq!(normalize_amount(42, 9, 9).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_six_to_eighteen_decimal_conversion() {
+        assert_eq!(asset(6, 18).to_target_units(1_500_000).unwrap(), 1_500_000_000_000_000_000);
+        assert!(matches!(asset(0, 38).to_target_units(u128::MAX), Err(BridgeError::PrecisionLoss)));
+    }
+
+    #[test]
+    fn test_unrepresentable_amount_is_rejected() {
+        // 1.0000000000001 tokens has no 6-decimal form.
+        let result = asset(18, 6).to_target_units(1_000_000_000_000_100_000);
+        assert!(matches!(result, Err(BridgeError::PrecisionLoss)));
+        assert!(matches!(asset(18, 6).to_target_units(1), Err(BridgeError::PrecisionLoss)));
+    }
+
+    #[test]
+    fn test_full_table_evicts_failed_entanglement() {
+        let now = Utc::now();
+        let mut table = EntanglementTable::new(limits(2));
+        table.insert(entanglement("active", EntanglementStatus::Active, now), now).unwrap();
+        table.insert(entanglement("failed", EntanglementStatus::Failed("timeout".to_string()), now), now).unwrap();
+
+        let evicted = table.insert(entanglement("new", EntanglementStatus::Pending, now), now).unwrap();
+        assert_eq!(evicted.as_deref(), Some("failed"));
+        assert_eq!(table.len(), 2);
+        assert!(table.get("new").is_some());
+        assert!(table.get("active").is_some());
+    }
+
+    #[test]
+    fn test_full_table_of_active_entanglements_rejects_new() {
+        let now = Utc::now();
+        let mut table = EntanglementTable::new(limits(2));
+        table.insert(entanglement("a", EntanglementStatus::Active, now), now).unwrap();
+        table.insert(entanglement("b", EntanglementStatus::Verified, now), now).unwrap();
+
+        let result = table.insert(entanglement("c", EntanglementStatus::Pending, now), now);
+        assert!(matches!(result, Err(BridgeError::EntanglementLimitReached(2))));
+        assert!(table.get("c").is_none());
+    }
+
+    #[test]
+    fn test_sweep_removes_old_failures() {
+        let now = Utc::now();
+        let mut table = EntanglementTable::new(limits(10));
+        let created = now - chrono::Duration::hours(3);
+        for id in ["old-failure", "recent-failure", "old-pending"] {
+            table.insert(entanglement(id, EntanglementStatus::Pending, created), created).unwrap();
+        }
+        table.mark_failed("old-failure", "verification".to_string(), now - chrono::Duration::hours(2)).unwrap();
+        // Created as long ago, but only just failed.
+        table.mark_failed("recent-failure", "verification".to_string(), now - chrono::Duration::minutes(5)).unwrap();
+
+        assert_eq!(table.sweep_failed(now), vec!["old-failure".to_string()]);
+        assert_eq!(table.len(), 2);
+        assert!(table.get("recent-failure").is_some());
+        assert!(table.get("old-failure").is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use pqcrypto::kem::kyber512::{encrypt, decrypt, PublicKey, SecretKey};
 use pqcrypto::sign::dilithium2::{sign, verify};
 use serde::{Deserialize, Serialize};
@@ -6,11 +7,13 @@ use chrono::{Utc, DateTime};
 use uuid::Uuid;
 use quantumfuse_sdk::{
     error::FinanceError,
-    blockchain::{QuantumLedger, CrossChainBridge},
+    blockchain::{QuantumLedger, LedgerBatcher, LedgerBatchConfig, BatchedLedger, SealedCommitment, CrossChainBridge},
     did::DIDRegistry,
-    ai::FraudDetectionEngine,
+    amount::Amount,
+    fraud_detection::{FraudDecision, FraudRuleEngine, FraudRuleSet, FraudSignal},
     consensus::{HybridConsensus, ValidatorSet},
     contracts::SmartContractEngine,
+    quantum_bridge::TransferStatus,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -19,9 +22,26 @@ pub struct FinancialTransaction {
     sender: String,
     recipient: String,
     encrypted_details: Vec<u8>,
+    details_commitment: [u8; 32],
     digital_signature: Vec<u8>,
     timestamp: DateTime<Utc>,
     status: TransactionStatus,
+    /// Fraud rules triggered when the transaction was initiated.
+    #[serde(default)]
+    fraud_flags: Vec<String>,
+    /// Copies of the details re-encrypted for other authorized users.
+    #[serde(default)]
+    shared_details: HashMap<String, Vec<u8>>,
+}
+
+impl FinancialTransaction {
+    fn ciphertext_for(&self, user_id: &str) -> Option<&Vec<u8>> {
+        if self.sender == user_id || self.recipient == user_id {
+            Some(&self.encrypted_details)
+        } else {
+            self.shared_details.get(user_id)
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -32,16 +52,63 @@ pub enum TransactionStatus {
     UnderReview,
 }
 
+/// What an authorized enterprise user may do with the contract's transactions.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Creates transactions.
+    Initiator,
+    /// Moves pending transactions to `Completed` or `Rejected`.
+    Approver,
+    /// Reads every transaction, changes nothing.
+    Auditor,
+}
+
+/// Bridge operations the contract depends on, so one bridge can be shared across contracts.
+pub trait FinanceBridge: Send + Sync {
+    /// Starts moving a transaction's assets to `target_chain`, returning the bridge's transfer id.
+    fn transfer_assets(&self, transaction_id: &str, target_chain: &str) -> Result<String, FinanceError>;
+    fn transfer_status(&self, transfer_id: &str) -> Result<TransferStatus, FinanceError>;
+}
+
+impl FinanceBridge for CrossChainBridge {
+    fn transfer_assets(&self, transaction_id: &str, target_chain: &str) -> Result<String, FinanceError> {
+        CrossChainBridge::transfer_assets(self, transaction_id, target_chain)
+    }
+
+    fn transfer_status(&self, transfer_id: &str) -> Result<TransferStatus, FinanceError> {
+        CrossChainBridge::transfer_status(self, transfer_id)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CrossChainTransfer {
+    pub transfer_id: String,
+    pub target_chain: String,
+    pub initiated_at: DateTime<Utc>,
+}
+
 pub struct QuantumFinanceContract {
     contract_id: String,
     enterprise_id: String,
     authorized_users: HashMap<String, PublicKey>,
+    user_roles: HashMap<String, Role>,
     financial_transactions: HashMap<String, FinancialTransaction>,
     quantum_ledger: QuantumLedger,
-    fraud_detection: FraudDetectionEngine,
+    ledger_batch: LedgerBatcher<FinancialTransaction>,
+    fraud_detection: FraudRuleEngine,
     did_registry: DIDRegistry,
     smart_contract_engine: SmartContractEngine,
     consensus: HybridConsensus,
+    bridge: Arc<dyn FinanceBridge>,
+    cross_chain_transfers: HashMap<String, CrossChainTransfer>,
+}
+
+impl BatchedLedger for QuantumFinanceContract {
+    type Event = FinancialTransaction;
+
+    fn ledger_batch(&self) -> &LedgerBatcher<FinancialTransaction> {
+        &self.ledger_batch
+    }
 }
 
 impl QuantumFinanceContract {
@@ -56,16 +123,38 @@ impl QuantumFinanceContract {
             contract_id: contract_id.to_string(),
             enterprise_id: enterprise_id.to_string(),
             authorized_users: HashMap::new(),
+            user_roles: HashMap::new(),
             financial_transactions: HashMap::new(),
+            ledger_batch: LedgerBatcher::new(ledger.clone(), LedgerBatchConfig::default()),
             quantum_ledger: ledger,
-            fraud_detection: FraudDetectionEngine::new(),
+            fraud_detection: FraudRuleEngine::default(),
             did_registry,
             smart_contract_engine: SmartContractEngine::new(),
             consensus,
+            bridge: Arc::new(CrossChainBridge::new()),
+            cross_chain_transfers: HashMap::new(),
         }
     }
 
-    pub fn authorize_user(&mut self, user_id: &str, public_key: PublicKey) -> Result<(), FinanceError> {
+    pub fn with_bridge(mut self, bridge: Arc<dyn FinanceBridge>) -> Self {
+        self.bridge = bridge;
+        self
+    }
+
+    pub fn with_ledger_batching(mut self, config: LedgerBatchConfig) -> Self {
+        self.ledger_batch = LedgerBatcher::new(self.quantum_ledger.clone(), config);
+        self
+    }
+
+    pub fn with_fraud_rules(mut self, rules: FraudRuleSet) -> Self {
+        self.fraud_detection.set_rules(rules);
+        self
+    }
+
+    /// Approvers and auditors receive a copy of each transaction submitted
+    /// after they are authorized. Earlier transactions stay unreadable to them
+    /// until someone with access calls `share_transaction`.
+    pub fn authorize_user(&mut self, user_id: &str, public_key: PublicKey, role: Role) -> Result<(), FinanceError> {
         if self.authorized_users.contains_key(user_id) {
             return Err(FinanceError::DuplicateUser);
         }
@@ -76,9 +165,22 @@ impl QuantumFinanceContract {
         }
 
         self.authorized_users.insert(user_id.to_string(), public_key);
+        self.user_roles.insert(user_id.to_string(), role);
         Ok(())
     }
 
+    pub fn role_of(&self, user_id: &str) -> Option<Role> {
+        self.user_roles.get(user_id).copied()
+    }
+
+    fn require_role(&self, user_id: &str, role: Role) -> Result<(), FinanceError> {
+        match self.role_of(user_id) {
+            Some(actual) if actual == role => Ok(()),
+            Some(_) => Err(FinanceError::InsufficientRole),
+            None => Err(FinanceError::UnauthorizedUser),
+        }
+    }
+
     pub fn initiate_transaction(
         &mut self,
         sender: &str,
@@ -86,36 +188,80 @@ impl QuantumFinanceContract {
         details: &str,
         private_key: &SecretKey,
     ) -> Result<String, FinanceError> {
-        if !self.authorized_users.contains_key(sender) {
-            return Err(FinanceError::UnauthorizedUser);
-        }
+        self.submit_transaction(sender, recipient, details, None, private_key)
+    }
 
-        // Encrypt transaction details using Kyber512
-        let recipient_key = self.authorized_users.get(recipient).ok_or(FinanceError::RecipientNotFound)?;
-        let encrypted_details = encrypt(details.as_bytes(), recipient_key);
+    /// Like `initiate_transaction`, with an amount the fraud rules can screen.
+    pub fn initiate_payment(
+        &mut self,
+        sender: &str,
+        recipient: &str,
+        details: &str,
+        amount: Amount,
+        private_key: &SecretKey,
+    ) -> Result<String, FinanceError> {
+        self.submit_transaction(sender, recipient, details, Some(amount), private_key)
+    }
 
-        // Digitally sign the transaction with Dilithium2
-        let signature = sign(encrypted_details.clone(), private_key)?;
+    /// The fraud rules decide the starting status: `Pending`, `UnderReview` or `Rejected`.
+    fn submit_transaction(
+        &mut self,
+        sender: &str,
+        recipient: &str,
+        details: &str,
+        amount: Option<Amount>,
+        private_key: &SecretKey,
+    ) -> Result<String, FinanceError> {
+        self.require_role(sender, Role::Initiator)?;
 
         let transaction_id = Uuid::new_v4().to_string();
+        let sealed = SealedCommitment::seal(DETAILS_DOMAIN, &transaction_id, details.as_bytes());
+
+        // Encrypt transaction details using Kyber512
+        let recipient_key = self.authorized_users.get(recipient).ok_or(FinanceError::RecipientNotFound)?;
+        let encrypted_details = encrypt(&sealed.payload, recipient_key);
+
+        // Approvers and auditors each get their own copy to review
+        let shared_details = self.user_roles.iter()
+            .filter(|(_, role)| matches!(role, Role::Approver | Role::Auditor))
+            .filter_map(|(user_id, _)| {
+                let key = self.authorized_users.get(user_id)?;
+                Some((user_id.clone(), encrypt(&sealed.payload, key)))
+            })
+            .collect();
+
+        let signature = sign(sealed.commitment.to_vec(), private_key)?;
+
+        // Rule-based fraud screening
+        let timestamp = Utc::now();
+        let mut signal = FraudSignal::new(sender, timestamp)
+            .with_counterparty(recipient)
+            .with_identity_verified(self.did_registry.verify_identity(sender)?);
+        signal.amount = amount;
+        let assessment = self.fraud_detection.analyze(&signal);
+        let status = match assessment.decision {
+            FraudDecision::Allow => TransactionStatus::Pending,
+            FraudDecision::Review => TransactionStatus::UnderReview,
+            FraudDecision::Reject => TransactionStatus::Rejected,
+        };
 
         let transaction = FinancialTransaction {
             transaction_id: transaction_id.clone(),
             sender: sender.to_string(),
             recipient: recipient.to_string(),
             encrypted_details,
+            details_commitment: sealed.commitment,
             digital_signature: signature,
-            timestamp: Utc::now(),
-            status: TransactionStatus::Pending,
+            timestamp,
+            status,
+            fraud_flags: assessment.triggered,
+            shared_details,
         };
 
         self.financial_transactions.insert(transaction_id.clone(), transaction.clone());
 
-        // Record in blockchain ledger
-        self.quantum_ledger.record_event(&transaction_id, &transaction)?;
-
-        // AI Fraud Detection
-        self.fraud_detection.analyze_transaction(&transaction)?;
+        // Queue for the blockchain ledger
+        self.ledger_batch.push(transaction_id.clone(), transaction)?;
 
         Ok(transaction_id)
     }
@@ -128,21 +274,88 @@ impl QuantumFinanceContract {
     ) -> Result<String, FinanceError> {
         let transaction = self.financial_transactions.get(transaction_id).ok_or(FinanceError::TransactionNotFound)?;
 
-        if transaction.sender != user_id && transaction.recipient != user_id {
-            return Err(FinanceError::UnauthorizedAccess);
-        }
+        let ciphertext = transaction.ciphertext_for(user_id).ok_or(FinanceError::UnauthorizedAccess)?;
 
         // Decrypt transaction details
-        let decrypted_details = decrypt(&transaction.encrypted_details, private_key)?;
-        let details_string = String::from_utf8(decrypted_details).map_err(|_| FinanceError::DecryptionFailed)?;
+        let payload = decrypt(ciphertext, private_key)?;
 
         // Verify signature
+        let details = self.verify_details(transaction, &payload)?;
+
+        String::from_utf8(details.to_vec()).map_err(|_| FinanceError::DecryptionFailed)
+    }
+
+    pub fn approve_transaction(&mut self, approver_id: &str, transaction_id: &str) -> Result<(), FinanceError> {
+        self.decide_transaction(approver_id, transaction_id, TransactionStatus::Completed)
+    }
+
+    pub fn reject_transaction(&mut self, approver_id: &str, transaction_id: &str) -> Result<(), FinanceError> {
+        self.decide_transaction(approver_id, transaction_id, TransactionStatus::Rejected)
+    }
+
+    /// Only an approver other than the sender may move a transaction out of
+    /// `Pending` or `UnderReview`.
+    fn decide_transaction(
+        &mut self,
+        approver_id: &str,
+        transaction_id: &str,
+        outcome: TransactionStatus,
+    ) -> Result<(), FinanceError> {
+        self.require_role(approver_id, Role::Approver)?;
+
+        let transaction = self.financial_transactions.get_mut(transaction_id).ok_or(FinanceError::TransactionNotFound)?;
+        if transaction.sender == approver_id {
+            return Err(FinanceError::UnauthorizedAccess);
+        }
+        if !matches!(transaction.status, TransactionStatus::Pending | TransactionStatus::UnderReview) {
+            return Err(FinanceError::InvalidTransactionState);
+        }
+        transaction.status = outcome;
+
+        let update = transaction.clone();
+        let event_id = format!("{}:{:?}", transaction_id, update.status);
+        self.ledger_batch.push(event_id, update)?;
+        Ok(())
+    }
+
+    /// Re-encrypts a transaction's sealed details for another authorized user,
+    /// keeping the original blinding nonce and signature.
+    pub fn share_transaction(
+        &mut self,
+        user_id: &str,
+        transaction_id: &str,
+        private_key: &SecretKey,
+        new_recipient: &str,
+    ) -> Result<(), FinanceError> {
+        if self.role_of(user_id) == Some(Role::Auditor) {
+            return Err(FinanceError::InsufficientRole);
+        }
+
+        let recipient_key = self.authorized_users.get(new_recipient).ok_or(FinanceError::RecipientNotFound)?;
+        let transaction = self.financial_transactions.get(transaction_id).ok_or(FinanceError::TransactionNotFound)?;
+        let ciphertext = transaction.ciphertext_for(user_id).ok_or(FinanceError::UnauthorizedAccess)?;
+
+        let payload = decrypt(ciphertext, private_key)?;
+        self.verify_details(transaction, &payload)?;
+        let reencrypted = encrypt(&payload, recipient_key);
+
+        self.financial_transactions.get_mut(transaction_id)
+            .ok_or(FinanceError::TransactionNotFound)?
+            .shared_details.insert(new_recipient.to_string(), reencrypted);
+        Ok(())
+    }
+
+    /// Checks a decrypted payload against the transaction's signed commitment
+    /// and returns the details inside it.
+    fn verify_details<'a>(&self, transaction: &FinancialTransaction, payload: &'a [u8]) -> Result<&'a [u8], FinanceError> {
+        let details = SealedCommitment::open(DETAILS_DOMAIN, &transaction.transaction_id, payload, &transaction.details_commitment)
+            .ok_or(FinanceError::InvalidSignature)?;
+
         let sender_key = self.authorized_users.get(&transaction.sender).ok_or(FinanceError::InvalidPublicKey)?;
-        if !verify(&transaction.encrypted_details, &transaction.digital_signature, sender_key)? {
+        if !verify(&transaction.details_commitment, &transaction.digital_signature, sender_key)? {
             return Err(FinanceError::InvalidSignature);
         }
-
-        Ok(details_string)
+        Ok(details)
     }
 
     pub fn execute_smart_contract(
@@ -155,21 +368,74 @@ impl QuantumFinanceContract {
         Ok(transaction_id)
     }
 
+    /// Hands an approved (`Completed`) transaction to the bridge and returns
+    /// the bridge's transfer id. A transaction can be bridged only once.
     pub fn perform_cross_chain_transfer(
         &mut self,
         transaction_id: &str,
         target_chain: &str,
     ) -> Result<String, FinanceError> {
-        let bridge = CrossChainBridge::new();
-        bridge.transfer_assets(transaction_id, target_chain)
+        let transaction = self.financial_transactions.get(transaction_id).ok_or(FinanceError::TransactionNotFound)?;
+        if !matches!(transaction.status, TransactionStatus::Completed) {
+            return Err(FinanceError::InvalidTransactionState);
+        }
+        if self.cross_chain_transfers.contains_key(transaction_id) {
+            return Err(FinanceError::DuplicateTransfer);
+        }
+
+        let transfer_id = self.bridge.transfer_assets(transaction_id, target_chain)?;
+        self.cross_chain_transfers.insert(transaction_id.to_string(), CrossChainTransfer {
+            transfer_id: transfer_id.clone(),
+            target_chain: target_chain.to_string(),
+            initiated_at: Utc::now(),
+        });
+        Ok(transfer_id)
+    }
+
+    pub fn cross_chain_transfer(&self, transaction_id: &str) -> Option<&CrossChainTransfer> {
+        self.cross_chain_transfers.get(transaction_id)
+    }
+
+    /// Asks the bridge for the current state of a transaction's transfer.
+    pub fn get_cross_chain_status(&self, transaction_id: &str) -> Result<TransferStatus, FinanceError> {
+        let transfer = self.cross_chain_transfers.get(transaction_id).ok_or(FinanceError::TransferNotFound)?;
+        self.bridge.transfer_status(&transfer.transfer_id)
     }
 }
 
+const DETAILS_DOMAIN: &str = "quantumfuse finance transaction details v2";
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pqcrypto::kem::kyber512::keypair as kyber_keypair;
     use pqcrypto::sign::dilithium2::keypair as dilithium_keypair;
+    use quantumfuse_sdk::fraud_detection::{FraudPolicy, FraudRule};
+    use std::sync::Mutex;
+
+    /// Holds transfers in memory and lets the test move them along.
+    #[derive(Default)]
+    struct ScriptedBridge {
+        transfers: Mutex<HashMap<String, TransferStatus>>,
+    }
+
+    impl ScriptedBridge {
+        fn advance(&self, transfer_id: &str, status: TransferStatus) {
+            self.transfers.lock().unwrap().insert(transfer_id.to_string(), status);
+        }
+    }
+
+    impl FinanceBridge for ScriptedBridge {
+        fn transfer_assets(&self, transaction_id: &str, target_chain: &str) -> Result<String, FinanceError> {
+            let transfer_id = format!("transfer-{}-{}", target_chain, transaction_id);
+            self.advance(&transfer_id, TransferStatus::Initiated);
+            Ok(transfer_id)
+        }
+
+        fn transfer_status(&self, transfer_id: &str) -> Result<TransferStatus, FinanceError> {
+            self.transfers.lock().unwrap().get(transfer_id).cloned().ok_or(FinanceError::TransferNotFound)
+        }
+    }
 
     #[test]
     fn test_financial_contract() {
@@ -181,7 +447,7 @@ mod tests {
 
         let mut contract = QuantumFinanceContract::new("enterprise_123", "finance_dept", ledger, did_registry, consensus);
 
-        assert!(contract.authorize_user("employee_1", kyber_pub).is_ok());
+        assert!(contract.authorize_user("employee_1", kyber_pub, Role::Initiator).is_ok());
 
         let tx_id = contract.initiate_transaction("employee_1", "vendor_1", "Invoice Payment: $5000", &dilithium_priv).unwrap();
         assert!(!tx_id.is_empty());
@@ -199,11 +465,157 @@ mod tests {
         let consensus = HybridConsensus::new(ValidatorSet::new());
 
         let mut contract = QuantumFinanceContract::new("enterprise_456", "finance_dept", ledger, did_registry, consensus);
-        contract.authorize_user("trader_1", kyber_pub).unwrap();
+        contract.authorize_user("trader_1", kyber_pub, Role::Initiator).unwrap();
+        contract.authorize_user("exchange_1", kyber_keypair().0, Role::Initiator).unwrap();
+        contract.authorize_user("approver_1", kyber_keypair().0, Role::Approver).unwrap();
 
         let tx_id = contract.initiate_transaction("trader_1", "exchange_1", "Crypto Trade: 10 ETH", &dilithium_priv).unwrap();
+        // Unapproved transactions stay on this chain.
+        let pending = contract.perform_cross_chain_transfer(&tx_id, "Ethereum");
+        assert!(matches!(pending, Err(FinanceError::InvalidTransactionState)));
+
+        contract.approve_transaction("approver_1", &tx_id).unwrap();
         let transfer_status = contract.perform_cross_chain_transfer(&tx_id, "Ethereum").unwrap();
 
         assert!(!transfer_status.is_empty());
     }
+
+    fn contract_with_users(users: &[(&str, Role)]) -> (QuantumFinanceContract, HashMap<String, SecretKey>) {
+        let consensus = HybridConsensus::new(ValidatorSet::new());
+        let mut contract = QuantumFinanceContract::new("enterprise_789", "finance_dept", QuantumLedger::new(), DIDRegistry::new(), consensus);
+        let mut keys = HashMap::new();
+        for (user, role) in users {
+            let (public_key, secret_key) = kyber_keypair();
+            contract.authorized_users.insert(user.to_string(), public_key);
+            contract.user_roles.insert(user.to_string(), *role);
+            keys.insert(user.to_string(), secret_key);
+        }
+        (contract, keys)
+    }
+
+    #[test]
+    fn test_signature_survives_reencryption() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (mut contract, keys) = contract_with_users(&[
+            ("treasurer", Role::Initiator),
+            ("vendor_2", Role::Initiator),
+            ("partner", Role::Initiator),
+        ]);
+
+        let tx_id = contract.initiate_transaction("treasurer", "vendor_2", "Invoice Payment: $7200", &dilithium_priv).unwrap();
+        contract.share_transaction("vendor_2", &tx_id, &keys["vendor_2"], "partner").unwrap();
+
+        let decrypted = contract.retrieve_transaction("partner", &tx_id, &keys["partner"]).unwrap();
+        assert_eq!(decrypted, "Invoice Payment: $7200");
+        let original = contract.retrieve_transaction("vendor_2", &tx_id, &keys["vendor_2"]).unwrap();
+        assert_eq!(original, decrypted);
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_fails_verification() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (mut contract, keys) = contract_with_users(&[("treasurer", Role::Initiator), ("vendor_3", Role::Initiator)]);
+
+        let tx_id = contract.initiate_transaction("treasurer", "vendor_3", "Payroll: $12000", &dilithium_priv).unwrap();
+        let transaction = contract.financial_transactions.get_mut(&tx_id).unwrap();
+        let last = transaction.encrypted_details.len() - 1;
+        transaction.encrypted_details[last] ^= 0x01;
+
+        assert!(contract.retrieve_transaction("vendor_3", &tx_id, &keys["vendor_3"]).is_err());
+    }
+
+    #[test]
+    fn test_initiator_can_create_but_not_approve() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (mut contract, _) = contract_with_users(&[("clerk", Role::Initiator), ("vendor_4", Role::Initiator)]);
+
+        let tx_id = contract.initiate_transaction("clerk", "vendor_4", "Office Supplies: $300", &dilithium_priv).unwrap();
+
+        let result = contract.approve_transaction("clerk", &tx_id);
+        assert!(matches!(result, Err(FinanceError::InsufficientRole)));
+        assert!(matches!(contract.financial_transactions[&tx_id].status, TransactionStatus::Pending));
+    }
+
+    #[test]
+    fn test_approver_advances_pending_transaction() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (mut contract, keys) = contract_with_users(&[
+            ("clerk", Role::Initiator),
+            ("vendor_5", Role::Initiator),
+            ("controller", Role::Approver),
+        ]);
+
+        let tx_id = contract.initiate_transaction("clerk", "vendor_5", "Consulting: $4500", &dilithium_priv).unwrap();
+        let details = contract.retrieve_transaction("controller", &tx_id, &keys["controller"]).unwrap();
+        assert_eq!(details, "Consulting: $4500");
+
+        contract.approve_transaction("controller", &tx_id).unwrap();
+        assert!(matches!(contract.financial_transactions[&tx_id].status, TransactionStatus::Completed));
+
+        let again = contract.reject_transaction("controller", &tx_id);
+        assert!(matches!(again, Err(FinanceError::InvalidTransactionState)));
+    }
+
+    #[test]
+    fn test_auditor_can_read_but_not_initiate() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (mut contract, keys) = contract_with_users(&[
+            ("clerk", Role::Initiator),
+            ("vendor_6", Role::Initiator),
+            ("auditor", Role::Auditor),
+        ]);
+
+        let tx_id = contract.initiate_transaction("clerk", "vendor_6", "Travel: $980", &dilithium_priv).unwrap();
+        let details = contract.retrieve_transaction("auditor", &tx_id, &keys["auditor"]).unwrap();
+        assert_eq!(details, "Travel: $980");
+
+        let result = contract.initiate_transaction("auditor", "vendor_6", "Refund: $980", &dilithium_priv);
+        assert!(matches!(result, Err(FinanceError::InsufficientRole)));
+        assert!(matches!(contract.approve_transaction("auditor", &tx_id), Err(FinanceError::InsufficientRole)));
+    }
+
+    #[test]
+    fn test_velocity_breach_puts_transaction_under_review() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (contract, _) = contract_with_users(&[("clerk", Role::Initiator), ("vendor_7", Role::Initiator)]);
+        let mut contract = contract.with_fraud_rules(FraudRuleSet {
+            rules: vec![FraudRule::Velocity { max_operations: 2, window_secs: 3600, weight: 50 }],
+            policy: FraudPolicy::default(),
+        });
+
+        let first = contract.initiate_payment("clerk", "vendor_7", "Batch 1", Amount::from_whole(100), &dilithium_priv).unwrap();
+        let second = contract.initiate_payment("clerk", "vendor_7", "Batch 2", Amount::from_whole(100), &dilithium_priv).unwrap();
+        let third = contract.initiate_payment("clerk", "vendor_7", "Batch 3", Amount::from_whole(100), &dilithium_priv).unwrap();
+
+        assert!(matches!(contract.financial_transactions[&first].status, TransactionStatus::Pending));
+        assert!(matches!(contract.financial_transactions[&second].status, TransactionStatus::Pending));
+        let flagged = &contract.financial_transactions[&third];
+        assert!(matches!(flagged.status, TransactionStatus::UnderReview));
+        assert_eq!(flagged.fraud_flags, vec!["velocity"]);
+    }
+
+    #[test]
+    fn test_cross_chain_status_tracks_bridge_progress() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let bridge = Arc::new(ScriptedBridge::default());
+        let (contract, _) = contract_with_users(&[("clerk", Role::Initiator), ("vendor_8", Role::Initiator), ("controller", Role::Approver)]);
+        let mut contract = contract.with_bridge(bridge.clone());
+
+        let tx_id = contract.initiate_transaction("clerk", "vendor_8", "Settlement: 12 ETH", &dilithium_priv).unwrap();
+        contract.approve_transaction("controller", &tx_id).unwrap();
+        assert!(matches!(contract.get_cross_chain_status(&tx_id), Err(FinanceError::TransferNotFound)));
+
+        let transfer_id = contract.perform_cross_chain_transfer(&tx_id, "Ethereum").unwrap();
+        assert_eq!(contract.cross_chain_transfer(&tx_id).unwrap().transfer_id, transfer_id);
+        assert!(matches!(contract.get_cross_chain_status(&tx_id), Ok(TransferStatus::Initiated)));
+
+        bridge.advance(&transfer_id, TransferStatus::InTransit);
+        assert!(matches!(contract.get_cross_chain_status(&tx_id), Ok(TransferStatus::InTransit)));
+
+        bridge.advance(&transfer_id, TransferStatus::Completed);
+        assert!(matches!(contract.get_cross_chain_status(&tx_id), Ok(TransferStatus::Completed)));
+
+        let again = contract.perform_cross_chain_transfer(&tx_id, "Ethereum");
+        assert!(matches!(again, Err(FinanceError::DuplicateTransfer)));
+    }
 }
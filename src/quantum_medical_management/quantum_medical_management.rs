@@ -6,7 +6,7 @@ use chrono::Utc;
 use uuid::Uuid;
 use quantumfuse_sdk::{
     error::MedicalError,
-    blockchain::QuantumLedger,
+    blockchain::{QuantumLedger, LedgerBatcher, LedgerBatchConfig, BatchedLedger, SealedCommitment},
     did::DIDRegistry,
     ai::MedicalAIEngine,
     iot::IoTHealthMonitor,
@@ -16,8 +16,22 @@ use quantumfuse_sdk::{
 pub struct MedicalRecord {
     patient_id: String,
     encrypted_data: Vec<u8>,
+    data_commitment: [u8; 32],
     digital_signature: Vec<u8>,
     timestamp: i64,
+    /// Copies of the data re-encrypted for other authorized providers.
+    #[serde(default)]
+    shared_data: HashMap<String, Vec<u8>>,
+}
+
+impl MedicalRecord {
+    fn ciphertext_for(&self, provider_id: &str) -> Option<&Vec<u8>> {
+        if self.patient_id == provider_id {
+            Some(&self.encrypted_data)
+        } else {
+            self.shared_data.get(provider_id)
+        }
+    }
 }
 
 pub struct QuantumMedicalContract {
@@ -26,10 +40,19 @@ pub struct QuantumMedicalContract {
     authorized_providers: HashMap<String, PublicKey>,
     patient_records: HashMap<String, MedicalRecord>,
     quantum_ledger: QuantumLedger,
+    ledger_batch: LedgerBatcher<MedicalRecord>,
     ai_engine: MedicalAIEngine,
     did_registry: DIDRegistry,
 }
 
+impl BatchedLedger for QuantumMedicalContract {
+    type Event = MedicalRecord;
+
+    fn ledger_batch(&self) -> &LedgerBatcher<MedicalRecord> {
+        &self.ledger_batch
+    }
+}
+
 impl QuantumMedicalContract {
     pub fn new(contract_id: &str, hospital_id: &str, ledger: QuantumLedger, did_registry: DIDRegistry) -> Self {
         QuantumMedicalContract {
@@ -37,12 +60,18 @@ impl QuantumMedicalContract {
             hospital_id: hospital_id.to_string(),
             authorized_providers: HashMap::new(),
             patient_records: HashMap::new(),
+            ledger_batch: LedgerBatcher::new(ledger.clone(), LedgerBatchConfig::default()),
             quantum_ledger: ledger,
             ai_engine: MedicalAIEngine::new(),
             did_registry,
         }
     }
 
+    pub fn with_ledger_batching(mut self, config: LedgerBatchConfig) -> Self {
+        self.ledger_batch = LedgerBatcher::new(self.quantum_ledger.clone(), config);
+        self
+    }
+
     pub fn authorize_provider(&mut self, provider_id: &str, public_key: PublicKey) -> Result<(), MedicalError> {
         if self.authorized_providers.contains_key(provider_id) {
             return Err(MedicalError::DuplicateProvider);
@@ -67,25 +96,26 @@ impl QuantumMedicalContract {
             return Err(MedicalError::UnauthorizedProvider);
         }
 
+        let record_id = Uuid::new_v4().to_string();
+        let sealed = SealedCommitment::seal(RECORD_DOMAIN, &record_id, data.as_bytes());
+
         // Encrypt medical data using Kyber512
         let public_key = self.authorized_providers.get(patient_id).unwrap();
-        let encrypted_data = encrypt(data.as_bytes(), public_key);
-
-        // Digitally sign data with Dilithium2
-        let signature = sign(encrypted_data.clone(), private_key)?;
-
-        let record_id = Uuid::new_v4().to_string();
+        let encrypted_data = encrypt(&sealed.payload, public_key);
+        let signature = sign(sealed.commitment.to_vec(), private_key)?;
 
         let record = MedicalRecord {
             patient_id: patient_id.to_string(),
             encrypted_data,
+            data_commitment: sealed.commitment,
             digital_signature: signature,
             timestamp: Utc::now().timestamp(),
+            shared_data: HashMap::new(),
         };
         self.patient_records.insert(record_id.clone(), record.clone());
 
-        // Record in blockchain ledger
-        self.quantum_ledger.record_event(&record_id, &record)?;
+        // Queue for the blockchain ledger
+        self.ledger_batch.push(record_id.clone(), record.clone())?;
 
         // AI-Powered Analysis for disease prediction
         self.ai_engine.analyze_medical_data(&record);
@@ -101,21 +131,50 @@ impl QuantumMedicalContract {
     ) -> Result<String, MedicalError> {
         let record = self.patient_records.get(record_id).ok_or(MedicalError::RecordNotFound)?;
 
-        if record.patient_id != provider_id {
-            return Err(MedicalError::UnauthorizedAccess);
-        }
+        let ciphertext = record.ciphertext_for(provider_id).ok_or(MedicalError::UnauthorizedAccess)?;
 
         // Decrypt medical data
-        let decrypted_data = decrypt(&record.encrypted_data, private_key)?;
-        let data_string = String::from_utf8(decrypted_data).map_err(|_| MedicalError::DecryptionFailed)?;
+        let payload = decrypt(ciphertext, private_key)?;
 
         // Verify signature
-        let public_key = self.authorized_providers.get(provider_id).ok_or(MedicalError::InvalidPublicKey)?;
-        if !verify(&record.encrypted_data, &record.digital_signature, public_key)? {
+        let data = self.verify_data(record_id, record, &payload)?;
+
+        String::from_utf8(data.to_vec()).map_err(|_| MedicalError::DecryptionFailed)
+    }
+
+    /// Re-encrypts a record's sealed payload for another authorized provider.
+    pub fn share_medical_record(
+        &mut self,
+        provider_id: &str,
+        record_id: &str,
+        private_key: &SecretKey,
+        new_provider: &str,
+    ) -> Result<(), MedicalError> {
+        let provider_key = self.authorized_providers.get(new_provider).ok_or(MedicalError::UnauthorizedProvider)?;
+        let record = self.patient_records.get(record_id).ok_or(MedicalError::RecordNotFound)?;
+        let ciphertext = record.ciphertext_for(provider_id).ok_or(MedicalError::UnauthorizedAccess)?;
+
+        let payload = decrypt(ciphertext, private_key)?;
+        self.verify_data(record_id, record, &payload)?;
+        let reencrypted = encrypt(&payload, provider_key);
+
+        self.patient_records.get_mut(record_id)
+            .ok_or(MedicalError::RecordNotFound)?
+            .shared_data.insert(new_provider.to_string(), reencrypted);
+        Ok(())
+    }
+
+    /// Checks a decrypted payload against the record's signed commitment and
+    /// returns the medical data inside it.
+    fn verify_data<'a>(&self, record_id: &str, record: &MedicalRecord, payload: &'a [u8]) -> Result<&'a [u8], MedicalError> {
+        let data = SealedCommitment::open(RECORD_DOMAIN, record_id, payload, &record.data_commitment)
+            .ok_or(MedicalError::InvalidSignature)?;
+
+        let public_key = self.authorized_providers.get(&record.patient_id).ok_or(MedicalError::InvalidPublicKey)?;
+        if !verify(&record.data_commitment, &record.digital_signature, public_key)? {
             return Err(MedicalError::InvalidSignature);
         }
-
-        Ok(data_string)
+        Ok(data)
     }
 
     pub fn integrate_iot_health_data(
@@ -136,6 +195,8 @@ impl QuantumMedicalContract {
     }
 }
 
+const RECORD_DOMAIN: &str = "quantumfuse medical record data v2";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +236,58 @@ mod tests {
         
         assert_eq!(decrypted_data, "Heart Rate: 75 bpm");
     }
+
+    #[test]
+    fn test_records_reach_ledger_in_batches() {
+        let (kyber_pub, _) = kyber_keypair();
+        let (_, dilithium_priv) = dilithium_keypair();
+        let ledger = QuantumLedger::new();
+
+        let mut contract = QuantumMedicalContract::new("hospital_789", "hospital_main", ledger.clone(), DIDRegistry::new())
+            .with_ledger_batching(LedgerBatchConfig { max_events: 2, flush_interval: std::time::Duration::from_secs(3600) });
+        contract.authorized_providers.insert("patient_3".to_string(), kyber_pub);
+
+        let first = contract.add_medical_record("patient_3", "Glucose: 90 mg/dL", &dilithium_priv).unwrap();
+        assert!(ledger.events().unwrap().is_empty());
+
+        let second = contract.add_medical_record("patient_3", "Glucose: 95 mg/dL", &dilithium_priv).unwrap();
+        let third = contract.add_medical_record("patient_3", "Glucose: 88 mg/dL", &dilithium_priv).unwrap();
+        contract.flush_ledger().unwrap();
+
+        let ids: Vec<_> = ledger.events().unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![first, second, third]);
+    }
+
+    #[test]
+    fn test_signature_survives_reencryption() {
+        let (patient_pub, patient_priv) = kyber_keypair();
+        let (cardiologist_pub, cardiologist_priv) = kyber_keypair();
+        let (_, dilithium_priv) = dilithium_keypair();
+
+        let mut contract = QuantumMedicalContract::new("hospital_321", "hospital_main", QuantumLedger::new(), DIDRegistry::new());
+        contract.authorized_providers.insert("patient_4".to_string(), patient_pub);
+        contract.authorized_providers.insert("cardiologist".to_string(), cardiologist_pub);
+
+        let record_id = contract.add_medical_record("patient_4", "ECG: normal sinus rhythm", &dilithium_priv).unwrap();
+        contract.share_medical_record("patient_4", &record_id, &patient_priv, "cardiologist").unwrap();
+
+        let decrypted = contract.retrieve_medical_record("cardiologist", &record_id, &cardiologist_priv).unwrap();
+        assert_eq!(decrypted, "ECG: normal sinus rhythm");
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_fails_verification() {
+        let (patient_pub, patient_priv) = kyber_keypair();
+        let (_, dilithium_priv) = dilithium_keypair();
+
+        let mut contract = QuantumMedicalContract::new("hospital_654", "hospital_main", QuantumLedger::new(), DIDRegistry::new());
+        contract.authorized_providers.insert("patient_5".to_string(), patient_pub);
+
+        let record_id = contract.add_medical_record("patient_5", "Allergy: penicillin", &dilithium_priv).unwrap();
+        let record = contract.patient_records.get_mut(&record_id).unwrap();
+        let last = record.encrypted_data.len() - 1;
+        record.encrypted_data[last] ^= 0x01;
+
+        assert!(contract.retrieve_medical_record("patient_5", &record_id, &patient_priv).is_err());
+    }
 }
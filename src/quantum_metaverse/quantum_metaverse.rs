@@ -1,49 +1,186 @@
-use quantumfuse_sdk::metaverse::{QuantumMetaverseAdapter, AvatarState, Platform};
+use quantumfuse_sdk::metaverse::{QuantumMetaverseAdapter, AvatarState};
 use quantumfuse_sdk::blockchain::{QuantumAssetBridge, NFTMetadata, Blockchain};
 use quantumfuse_sdk::network::{QuantumAvatarSyncer, DIDRegistry, AIEngine};
 use quantumfuse_sdk::wallet::{Web3Wallet, Transaction};
 use quantumfuse_sdk::consensus::QuantumBridge;
+use quantumfuse_sdk::redaction::redactor;
+use log::{info, Level};
 use pqcrypto::sign::dilithium2::{generate_keypair, sign, verify};
 use pqcrypto::kem::kyber512::{encapsulate, decapsulate, generate_keypair as kyber_generate};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
-use chrono::Utc;
+use std::fmt;
+use chrono::{DateTime, Duration, Utc};
+
+/// Name the built-in QuantumFuse adapter is registered under.
+pub const DEFAULT_PLATFORM: &str = "quantumfuse";
+
+/// Pushes avatar state to one metaverse platform.
+pub trait PlatformAdapter: Send + Sync {
+    fn sync_avatar_state(&mut self, avatar_id: &str, state: &AvatarState) -> Result<(), &'static str>;
+}
+
+impl PlatformAdapter for QuantumMetaverseAdapter {
+    fn sync_avatar_state(&mut self, avatar_id: &str, state: &AvatarState) -> Result<(), &'static str> {
+        QuantumMetaverseAdapter::sync_avatar_state(self, avatar_id, state)
+    }
+}
+
+/// Platform adapters keyed by name, so new metaverses can be added at runtime.
+#[derive(Default)]
+pub struct PlatformRegistry {
+    adapters: HashMap<String, Box<dyn PlatformAdapter>>,
+}
+
+impl PlatformRegistry {
+    /// A registry holding only the built-in QuantumFuse adapter.
+    pub fn with_default() -> Self {
+        let mut registry = Self::default();
+        registry.adapters.insert(DEFAULT_PLATFORM.to_string(), Box::new(QuantumMetaverseAdapter::new()));
+        registry
+    }
+
+    pub fn register(&mut self, platform: &str, adapter: Box<dyn PlatformAdapter>) -> Result<(), &'static str> {
+        if self.adapters.contains_key(platform) {
+            return Err("Platform already registered.");
+        }
+        self.adapters.insert(platform.to_string(), adapter);
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, platform: &str) -> bool {
+        self.adapters.remove(platform).is_some()
+    }
+
+    pub fn platforms(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.adapters.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn sync_avatar(&mut self, platform: &str, avatar_id: &str, state: &AvatarState) -> Result<(), &'static str> {
+        let adapter = self.adapters.get_mut(platform).ok_or("Unknown metaverse platform.")?;
+        adapter.sync_avatar_state(avatar_id, state)
+    }
+}
+
+impl fmt::Debug for PlatformRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlatformRegistry")
+            .field("platforms", &self.platforms())
+            .finish()
+    }
+}
+
+/// Weights below this are dropped once decayed.
+const MIN_BEHAVIOR_WEIGHT: f64 = 0.01;
+
+/// Accumulated behavior of one NPC. Each observation adds 1.0 to its behavior's
+/// weight; all weights halve every half-life.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NpcProfile {
+    pub observations: u64,
+    pub behavior_weights: HashMap<String, f64>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl NpcProfile {
+    /// The heaviest behavior, if any weight remains.
+    pub fn dominant_behavior(&self) -> Option<&str> {
+        self.behavior_weights.iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(behavior, _)| behavior.as_str())
+    }
+
+    fn decay_to(&mut self, at: DateTime<Utc>, half_life: Duration) {
+        let Some(last_seen) = self.last_seen else { return };
+        let elapsed = (at - last_seen).num_milliseconds().max(0) as f64;
+        let factor = 0.5f64.powf(elapsed / half_life.num_milliseconds().max(1) as f64);
+
+        self.behavior_weights.retain(|_, weight| {
+            *weight *= factor;
+            *weight >= MIN_BEHAVIOR_WEIGHT
+        });
+    }
+}
+
+/// Per-NPC behavior profiles that carry over between ticks.
+#[derive(Debug, Clone)]
+pub struct NpcBehaviorStore {
+    profiles: HashMap<String, NpcProfile>,
+    half_life: Duration,
+}
+
+impl Default for NpcBehaviorStore {
+    fn default() -> Self {
+        Self::new(Duration::hours(1))
+    }
+}
+
+impl NpcBehaviorStore {
+    pub fn new(half_life: Duration) -> Self {
+        Self { profiles: HashMap::new(), half_life }
+    }
+
+    pub fn observe(&mut self, npc_id: &str, behavior: &str, at: DateTime<Utc>) -> &NpcProfile {
+        let profile = self.profiles.entry(npc_id.to_string()).or_default();
+        profile.decay_to(at, self.half_life);
+        *profile.behavior_weights.entry(behavior.to_string()).or_insert(0.0) += 1.0;
+        profile.observations += 1;
+        profile.last_seen = Some(at);
+        profile
+    }
+
+    /// The NPC's profile with weights decayed to `at`; unseen NPCs get an empty profile.
+    pub fn profile(&self, npc_id: &str, at: DateTime<Utc>) -> NpcProfile {
+        let mut profile = self.profiles.get(npc_id).cloned().unwrap_or_default();
+        profile.decay_to(at, self.half_life);
+        profile
+    }
+}
 
 /// Unified Quantum Metaverse Integration Module
 #[derive(Debug)]
 pub struct QuantumMetaverse {
-    adapter: QuantumMetaverseAdapter,
+    platforms: PlatformRegistry,
     bridge: QuantumAssetBridge,
     syncer: QuantumAvatarSyncer,
     ai_engine: AIEngine,
     did_registry: DIDRegistry,
     web3_wallet: Web3Wallet,
     quantum_bridge: QuantumBridge,
+    npc_behavior: NpcBehaviorStore,
 }
 
 impl QuantumMetaverse {
     /// Initializes a new QuantumMetaverse instance
     pub fn new() -> Self {
         QuantumMetaverse {
-            adapter: QuantumMetaverseAdapter::new(),
+            platforms: PlatformRegistry::with_default(),
             bridge: QuantumAssetBridge::new(),
             syncer: QuantumAvatarSyncer::new(),
             ai_engine: AIEngine::new(),
             did_registry: DIDRegistry::new(),
             web3_wallet: Web3Wallet::new(),
             quantum_bridge: QuantumBridge::new(),
+            npc_behavior: NpcBehaviorStore::default(),
         }
     }
 
-    /// Sync Avatar state across multiple metaverse platforms
+    /// Registers an adapter for another metaverse platform
+    pub fn register_platform(&mut self, platform: &str, adapter: Box<dyn PlatformAdapter>) -> Result<(), &'static str> {
+        self.platforms.register(platform, adapter)
+    }
+
+    /// Sync Avatar state to a registered metaverse platform
     pub fn sync_avatar(
         &mut self,
         avatar_id: &str,
         new_state: &AvatarState,
-        platform: Platform
+        platform: &str
     ) -> Result<(), &'static str> {
-        println!("Syncing avatar {} on {:?} platform", avatar_id, platform);
-        self.adapter.sync_avatar_state(avatar_id, new_state)
+        println!("Syncing avatar {} on {} platform", avatar_id, platform);
+        self.platforms.sync_avatar(platform, avatar_id, new_state)
     }
 
     /// AI-Powered Avatar Movement Prediction
@@ -54,7 +191,7 @@ impl QuantumMetaverse {
     ) -> Result<(), &'static str> {
         let predicted_state = self.ai_engine.predict_next_state(current_state);
         println!("AI-Predicted state for avatar {}: {:?}", avatar_id, predicted_state);
-        self.adapter.sync_avatar_state(avatar_id, &predicted_state)
+        self.platforms.sync_avatar(DEFAULT_PLATFORM, avatar_id, &predicted_state)
     }
 
     /// Securely transfer NFTs between chains with Quantum Proofs
@@ -66,7 +203,14 @@ impl QuantumMetaverse {
         blockchain: Blockchain
     ) -> Result<(), &'static str> {
         let quantum_proof = self.quantum_bridge.generate_quantum_proof(nft_id, sender)?;
-        println!("Transferring NFT {} with quantum proof from {:?} to {:?} on {:?}", nft_id, sender, recipient, blockchain);
+        let redactor = redactor();
+        info!(
+            "Transferring NFT {} with quantum proof from {} to {} on {:?}",
+            nft_id,
+            redactor.address(&hex::encode(sender), Level::Info),
+            redactor.address(&hex::encode(recipient), Level::Info),
+            blockchain
+        );
         self.bridge.transfer_nft(nft_id, sender, recipient, quantum_proof)
     }
 
@@ -80,7 +224,7 @@ impl QuantumMetaverse {
     ) -> Result<(), &'static str> {
         let encrypted_metadata = self.bridge.encrypt_metadata(metadata)?;
         let quantum_proof = self.quantum_bridge.generate_quantum_proof(nft_id, sender)?;
-        println!("Encrypting NFT metadata for secure transfer: {:?}", encrypted_metadata);
+        info!("Encrypting NFT metadata for secure transfer: {}", redactor().payload(&encrypted_metadata, Level::Info));
         self.bridge.transfer_nft(nft_id, sender, recipient, quantum_proof)
     }
 
@@ -107,6 +251,113 @@ impl QuantumMetaverse {
     pub fn track_npc_behavior(&mut self, npc_id: &str, current_state: &AvatarState) -> Result<(), &'static str> {
         let predicted_behavior = self.ai_engine.analyze_npc_behavior(npc_id, current_state);
         println!("AI-Predicted behavior for NPC {}: {:?}", npc_id, predicted_behavior);
+        self.npc_behavior.observe(npc_id, &predicted_behavior.to_string(), Utc::now());
         Ok(())
     }
+
+    /// Accumulated behavior profile for an NPC, decayed to now
+    pub fn get_npc_profile(&self, npc_id: &str) -> NpcProfile {
+        self.npc_behavior.profile(npc_id, Utc::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records every avatar it is asked to sync.
+    struct RecordingAdapter {
+        synced: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl PlatformAdapter for RecordingAdapter {
+        fn sync_avatar_state(&mut self, avatar_id: &str, _state: &AvatarState) -> Result<(), &'static str> {
+            self.synced.lock().unwrap().push(avatar_id.to_string());
+            Ok(())
+        }
+    }
+
+    fn recording_adapter() -> (Box<dyn PlatformAdapter>, Arc<Mutex<Vec<String>>>) {
+        let synced = Arc::new(Mutex::new(Vec::new()));
+        (Box::new(RecordingAdapter { synced: synced.clone() }), synced)
+    }
+
+    #[test]
+    fn test_sync_dispatches_to_registered_platforms() {
+        let mut registry = PlatformRegistry::default();
+        let (decentraland, decentraland_log) = recording_adapter();
+        let (sandbox, sandbox_log) = recording_adapter();
+        registry.register("decentraland", decentraland).unwrap();
+        registry.register("sandbox", sandbox).unwrap();
+
+        let state = AvatarState::default();
+        registry.sync_avatar("decentraland", "avatar-1", &state).unwrap();
+        registry.sync_avatar("sandbox", "avatar-2", &state).unwrap();
+        registry.sync_avatar("sandbox", "avatar-3", &state).unwrap();
+
+        assert_eq!(*decentraland_log.lock().unwrap(), vec!["avatar-1"]);
+        assert_eq!(*sandbox_log.lock().unwrap(), vec!["avatar-2", "avatar-3"]);
+        assert_eq!(registry.platforms(), vec!["decentraland", "sandbox"]);
+    }
+
+    #[test]
+    fn test_unregistered_platform_is_rejected() {
+        let mut registry = PlatformRegistry::default();
+        let (sandbox, sandbox_log) = recording_adapter();
+        registry.register("sandbox", sandbox).unwrap();
+
+        let result = registry.sync_avatar("somnium", "avatar-1", &AvatarState::default());
+        assert_eq!(result, Err("Unknown metaverse platform."));
+        assert!(sandbox_log.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_platform_registration_is_rejected() {
+        let mut registry = PlatformRegistry::with_default();
+        let (adapter, _) = recording_adapter();
+        assert!(registry.register(DEFAULT_PLATFORM, adapter).is_err());
+    }
+
+    #[test]
+    fn test_repeated_observations_update_profile() {
+        let mut store = NpcBehaviorStore::default();
+        let now = Utc::now();
+
+        store.observe("merchant", "trade", now);
+        store.observe("merchant", "trade", now);
+        let profile = store.observe("merchant", "wander", now).clone();
+
+        assert_eq!(profile.observations, 3);
+        assert_eq!(profile.behavior_weights["trade"], 2.0);
+        assert_eq!(profile.dominant_behavior(), Some("trade"));
+        assert_eq!(store.profile("merchant", now), profile);
+    }
+
+    #[test]
+    fn test_stale_behavior_fades() {
+        let mut store = NpcBehaviorStore::new(Duration::minutes(10));
+        let start = Utc::now();
+
+        store.observe("guard", "patrol", start);
+        store.observe("guard", "patrol", start);
+        assert_eq!(store.profile("guard", start + Duration::minutes(10)).behavior_weights["patrol"], 1.0);
+
+        store.observe("guard", "chase", start + Duration::minutes(20));
+        let profile = store.profile("guard", start + Duration::minutes(20));
+        assert_eq!(profile.behavior_weights["patrol"], 0.5);
+        assert_eq!(profile.dominant_behavior(), Some("chase"));
+
+        let forgotten = store.profile("guard", start + Duration::hours(3));
+        assert!(forgotten.behavior_weights.is_empty());
+        assert_eq!(forgotten.observations, 3);
+    }
+
+    #[test]
+    fn test_unseen_npc_has_default_profile() {
+        let store = NpcBehaviorStore::default();
+        let profile = store.profile("ghost", Utc::now());
+        assert_eq!(profile, NpcProfile::default());
+        assert_eq!(profile.dominant_behavior(), None);
+    }
 }
@@ -1,11 +1,24 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
 use tokio::sync::{Mutex, RwLock};
-use actix_web::{web, App, HttpServer, HttpResponse, Responder};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use actix_web::{http::Method, web, App, FromRequest, Handler, HttpRequest, HttpServer, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 use log::{info, error, warn};
 use chrono::{DateTime, Utc};
+use utoipa::openapi::{
+    path::{HttpMethod, OperationBuilder, ParameterBuilder, ParameterIn, PathItem},
+    request_body::RequestBodyBuilder,
+    schema::{ObjectBuilder, Type},
+    ComponentsBuilder, ContentBuilder, InfoBuilder, OpenApi, OpenApiBuilder, PathsBuilder, Ref, Required, ResponseBuilder,
+};
+use utoipa::ToSchema;
 use quantumfuse_sdk::{
-    error::NodeError,
+    error::{BlockchainError, NodeError, StateError},
     crypto::{Hash, KeyPair},
     pqc::dilithium::{DilithiumKeyPair, Signature},
     pqc::kyber1024::{KyberCiphertext, KyberKeyPair},
@@ -14,8 +27,20 @@ use quantumfuse_sdk::{
     consensus::QuantumFuseConsensus,
     did::DIDRegistry,
     ai::{TransactionOptimizer, AnomalyDetector},
-    p2p::PeerManager,
+    p2p::{PeerManager, PeerTransport},
     storage::QuantumStorage,
+    merkle::{inclusion_proof, merkle_root, MerkleProof},
+    amount::Amount,
+    tls::{https_redirect_location, TlsConfig},
+    redaction::{self, RedactionConfig},
+    block::BlockHeader,
+    build_info::BuildInfo,
+    codec::CodecConfig,
+    mempool::{spawn_persistence, CommittedNonces, LaneConfig, Mempool, MempoolConfig, MempoolValidator},
+    webrtc::{WebRtcConfig, WireMessage},
+    transaction::QuantumTransaction,
+    state_manager::{ReceiptHub, TransactionReceipt},
+    blockchain::{spawn_block_producer, BlockProducer, QuantumBlockchain},
 };
 
 // 🔹 **Node Configuration**
@@ -30,52 +55,515 @@ pub struct NodeConfig {
     pub storage_path: String,
     pub log_level: String,
     pub metrics_enabled: bool,
+    #[serde(default)]
+    pub gas_oracle: GasOracleConfig,
+    #[serde(default)]
+    pub peers: PeerConfig,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    /// POST each finalized block header to this endpoint when set.
+    #[serde(default)]
+    pub finality_webhook: Option<FinalityWebhookConfig>,
+    /// Serve the API over HTTPS when set.
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    /// Reported by the version endpoint.
+    #[serde(default)]
+    pub chain_id: u64,
+    #[serde(default)]
+    pub network_id: u64,
+    /// Encoding of blocks and transactions between peers and in storage.
+    #[serde(default)]
+    pub codec: CodecConfig,
+    /// Mine blocks from the transaction pool to this wallet when set, paced
+    /// by the chain's block time and block production settings.
+    #[serde(default)]
+    pub miner_wallet: Option<String>,
+}
+
+const MEMPOOL_PERSIST_INTERVAL: Duration = Duration::from_secs(30);
+
+impl NodeConfig {
+    /// WebRTC peer links, framed in the network codec.
+    pub fn webrtc_config(&self) -> WebRtcConfig {
+        WebRtcConfig {
+            codec: self.codec.network,
+            ..WebRtcConfig::default()
+        }
+    }
+
+    /// Mempool sized by the gas oracle's capacity and snapshotted under
+    /// `storage_path` in the storage codec.
+    pub fn mempool_config(&self) -> MempoolConfig {
+        MempoolConfig {
+            max_size: self.gas_oracle.mempool_capacity,
+            storage_path: PathBuf::from(&self.storage_path),
+            persist_interval: MEMPOOL_PERSIST_INTERVAL,
+            lanes: LaneConfig::default(),
+            codec: self.codec.storage,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasOracleConfig {
+    /// Number of recent blocks whose base fees feed the estimate.
+    pub block_window: usize,
+    /// Pending transaction count at which the mempool counts as full.
+    pub mempool_capacity: usize,
+    pub min_fee: Amount,
+}
+
+impl Default for GasOracleConfig {
+    fn default() -> Self {
+        Self {
+            block_window: 20,
+            mempool_capacity: 10_000,
+            min_fee: Amount::from_base_units(1_000),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerConfig {
+    pub max_peers: usize,
+    /// Added per useful message, up to `max_score`.
+    pub useful_reward: i32,
+    pub invalid_message_penalty: i32,
+    pub invalid_block_penalty: i32,
+    pub max_score: i32,
+    /// A peer whose score falls to or below this is disconnected and banned.
+    pub ban_threshold: i32,
+    pub ban_duration_secs: i64,
+}
+
+impl Default for PeerConfig {
+    fn default() -> Self {
+        Self {
+            max_peers: 50,
+            useful_reward: 1,
+            invalid_message_penalty: 10,
+            invalid_block_penalty: 40,
+            max_score: 100,
+            ban_threshold: -100,
+            ban_duration_secs: 3_600,
+        }
+    }
 }
 
-// 🔹 **API Types**
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalityWebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub retry: HookRetryConfig,
+}
+
+/// Attempts per finalized block, with the delay doubling after each failure.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HookRetryConfig {
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+}
+
+impl Default for HookRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff_ms: 500,
+        }
+    }
+}
+
+// 🔹 **API Types**
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BlockRequest {
     pub miner_wallet: String,
     pub transactions: Vec<Transaction>,
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BlockResponse {
     pub block: Option<Block>,
     pub status: ResponseStatus,
     pub message: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Transaction {
+    #[schema(value_type = String)]
     pub hash: Hash,
     pub sender: String,
     pub recipient: String,
     pub amount: f64,
+    #[serde(default)]
+    #[schema(value_type = u128)]
+    pub fee: Amount,
     pub timestamp: DateTime<Utc>,
     pub signature: Option<Vec<u8>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Block {
+    #[schema(value_type = Object)]
     pub header: BlockHeader,
     pub transactions: Vec<Transaction>,
     pub quantum_signature: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InclusionProofResponse {
+    pub tx_hash: String,
+    pub block_hash: String,
+    pub block_height: u64,
+    pub transactions_root: String,
+    #[schema(value_type = Object)]
+    pub proof: MerkleProof,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GasEstimate {
+    #[schema(value_type = u128)]
+    pub base_fee: Amount,
+    /// Pending transactions relative to mempool capacity, in basis points.
+    pub congestion_bps: u32,
+    #[schema(value_type = u128)]
+    pub low: Amount,
+    #[schema(value_type = u128)]
+    pub medium: Amount,
+    #[schema(value_type = u128)]
+    pub high: Amount,
+}
+
+/// Query for `/transaction/{hash}/wait`; `timeout` is in seconds.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaitQuery {
+    pub timeout: Option<u64>,
+}
+
+/// Also the body of every error response.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum ResponseStatus {
     Success,
     Error(String),
 }
 
+// 🔹 **Transaction Inclusion Index**
+/// Inclusion proofs for transactions in finalized blocks, keyed by transaction hash.
+/// Pending transactions are never indexed.
+#[derive(Debug, Default)]
+pub struct TransactionIndex {
+    proofs: HashMap<String, InclusionProofResponse>,
+}
+
+impl TransactionIndex {
+    pub fn index_block(&mut self, block_hash: &str, block_height: u64, transactions: &[Transaction]) -> Result<(), NodeError> {
+        let transactions_root = merkle_root(transactions)?.to_hex().to_string();
+
+        for (i, tx) in transactions.iter().enumerate() {
+            let tx_hash = tx.hash.to_string();
+            self.proofs.insert(tx_hash.clone(), InclusionProofResponse {
+                tx_hash,
+                block_hash: block_hash.to_string(),
+                block_height,
+                transactions_root: transactions_root.clone(),
+                proof: inclusion_proof(transactions, i)?,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn proof(&self, tx_hash: &str) -> Option<&InclusionProofResponse> {
+        self.proofs.get(tx_hash)
+    }
+}
+
+// 🔹 **Gas Price Oracle**
+/// Suggests fees from the base fees of the last `block_window` blocks and the
+/// fee percentiles of the pending mempool, scaled up with congestion.
+#[derive(Debug)]
+pub struct GasOracle {
+    config: GasOracleConfig,
+    recent_base_fees: VecDeque<Amount>,
+}
+
+impl GasOracle {
+    const BASIS_POINTS: u32 = 10_000;
+
+    pub fn new(config: GasOracleConfig) -> Self {
+        Self {
+            recent_base_fees: VecDeque::with_capacity(config.block_window),
+            config,
+        }
+    }
+
+    /// Records a finalized block; its base fee is the lowest fee it included.
+    pub fn record_block(&mut self, fees: impl IntoIterator<Item = Amount>) {
+        let base_fee = fees.into_iter().min().unwrap_or(self.config.min_fee);
+        if self.recent_base_fees.len() == self.config.block_window {
+            self.recent_base_fees.pop_front();
+        }
+        self.recent_base_fees.push_back(base_fee);
+    }
+
+    pub fn estimate(&self, pending_fees: &[Amount]) -> GasEstimate {
+        let mut base_fees: Vec<Amount> = self.recent_base_fees.iter().copied().collect();
+        base_fees.sort();
+        let base_fee = percentile(&base_fees, 50).unwrap_or(self.config.min_fee).max(self.config.min_fee);
+
+        let capacity = self.config.mempool_capacity.max(1) as u64;
+        let congestion_bps = ((pending_fees.len() as u64 * Self::BASIS_POINTS as u64) / capacity)
+            .min(Self::BASIS_POINTS as u64) as u32;
+
+        let mut pending = pending_fees.to_vec();
+        pending.sort();
+        let p25 = percentile(&pending, 25).unwrap_or(base_fee);
+        let p50 = percentile(&pending, 50).unwrap_or(base_fee);
+        let p90 = percentile(&pending, 90).unwrap_or(base_fee);
+
+        let low = base_fee.max(p25);
+        let medium = low.max(scale_fee(p50, Self::BASIS_POINTS + congestion_bps / 2));
+        let high = medium.max(scale_fee(p90, Self::BASIS_POINTS + congestion_bps));
+
+        GasEstimate { base_fee, congestion_bps, low, medium, high }
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[Amount], pct: usize) -> Option<Amount> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+fn scale_fee(fee: Amount, factor_bps: u32) -> Amount {
+    fee.checked_mul(factor_bps as u128)
+        .and_then(|scaled| scaled.checked_div(GasOracle::BASIS_POINTS as u128))
+        .unwrap_or(Amount::MAX)
+}
+
+// 🔹 **Peer Scoring**
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerBehavior {
+    UsefulMessage,
+    InvalidMessage,
+    InvalidBlock,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerAdmission {
+    Admitted,
+    /// Admitted after disconnecting the named lowest-scoring peer.
+    Evicted(String),
+}
+
+/// Scores connected peers and decides who may connect. New peers start at zero;
+/// when the node is full, a newcomer only displaces a peer scoring below zero.
+#[derive(Debug)]
+pub struct PeerScoreboard {
+    config: PeerConfig,
+    scores: HashMap<String, i32>,
+    banned_until: HashMap<String, DateTime<Utc>>,
+}
+
+impl PeerScoreboard {
+    pub fn new(config: PeerConfig) -> Self {
+        Self {
+            config,
+            scores: HashMap::new(),
+            banned_until: HashMap::new(),
+        }
+    }
+
+    pub fn score(&self, peer_id: &str) -> Option<i32> {
+        self.scores.get(peer_id).copied()
+    }
+
+    pub fn peer_count(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_banned(&self, peer_id: &str, now: DateTime<Utc>) -> bool {
+        self.banned_until.get(peer_id).is_some_and(|until| *until > now)
+    }
+
+    pub fn admit(&mut self, peer_id: &str, now: DateTime<Utc>) -> Result<PeerAdmission, NodeError> {
+        if self.is_banned(peer_id, now) {
+            return Err(NodeError::PeerBanned(peer_id.to_string()));
+        }
+        self.banned_until.remove(peer_id);
+        if self.scores.contains_key(peer_id) {
+            return Ok(PeerAdmission::Admitted);
+        }
+
+        if self.scores.len() < self.config.max_peers {
+            self.scores.insert(peer_id.to_string(), 0);
+            return Ok(PeerAdmission::Admitted);
+        }
+
+        let worst = self.scores.iter()
+            .min_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(id, score)| (id.clone(), *score));
+        match worst {
+            Some((worst_id, worst_score)) if worst_score < 0 => {
+                self.scores.remove(&worst_id);
+                self.scores.insert(peer_id.to_string(), 0);
+                info!("Evicted peer {} (score {}) to admit {}", worst_id, worst_score, peer_id);
+                Ok(PeerAdmission::Evicted(worst_id))
+            }
+            _ => Err(NodeError::PeerLimitReached(self.config.max_peers)),
+        }
+    }
+
+    pub fn remove(&mut self, peer_id: &str) {
+        self.scores.remove(peer_id);
+    }
+
+    /// Updates the peer's score and returns whether it was banned as a result.
+    pub fn record(&mut self, peer_id: &str, behavior: PeerBehavior, now: DateTime<Utc>) -> bool {
+        let Some(score) = self.scores.get_mut(peer_id) else {
+            return false;
+        };
+        *score = match behavior {
+            PeerBehavior::UsefulMessage => (*score + self.config.useful_reward).min(self.config.max_score),
+            PeerBehavior::InvalidMessage => score.saturating_sub(self.config.invalid_message_penalty),
+            PeerBehavior::InvalidBlock => score.saturating_sub(self.config.invalid_block_penalty),
+        };
+
+        if *score > self.config.ban_threshold {
+            return false;
+        }
+        warn!("Banning peer {} with score {}", peer_id, score);
+        self.scores.remove(peer_id);
+        self.banned_until.insert(peer_id.to_string(), now + chrono::Duration::seconds(self.config.ban_duration_secs));
+        true
+    }
+}
+
+// 🔹 **Finality Hooks**
+#[async_trait]
+pub trait FinalityHook: Send + Sync {
+    async fn on_finalized(&self, header: &BlockHeader) -> Result<(), NodeError>;
+}
+
+/// POSTs the finalized header as JSON.
+pub struct WebhookFinalityHook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookFinalityHook {
+    pub fn new(url: &str) -> Result<Self, NodeError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| NodeError::Webhook(e.to_string()))?;
+        Ok(Self { client, url: url.to_string() })
+    }
+}
+
+#[async_trait]
+impl FinalityHook for WebhookFinalityHook {
+    async fn on_finalized(&self, header: &BlockHeader) -> Result<(), NodeError> {
+        self.client.post(&self.url)
+            .json(header)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| NodeError::Webhook(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Registered hooks. `notify` runs each on its own task, so a slow or failing
+/// hook never holds up the caller.
+#[derive(Clone, Default)]
+pub struct FinalityHooks {
+    hooks: Arc<std::sync::RwLock<Vec<(Arc<dyn FinalityHook>, HookRetryConfig)>>>,
+}
+
+impl FinalityHooks {
+    pub fn register(&self, hook: Arc<dyn FinalityHook>) {
+        self.register_with_retry(hook, HookRetryConfig::default());
+    }
+
+    pub fn register_with_retry(&self, hook: Arc<dyn FinalityHook>, retry: HookRetryConfig) {
+        self.hooks.write().unwrap_or_else(|e| e.into_inner()).push((hook, retry));
+    }
+
+    pub fn notify(&self, header: &BlockHeader) -> Vec<JoinHandle<()>> {
+        let hooks = self.hooks.read().unwrap_or_else(|e| e.into_inner()).clone();
+        hooks.into_iter()
+            .map(|(hook, retry)| {
+                let header = header.clone();
+                tokio::spawn(async move { run_hook(hook.as_ref(), &header, retry).await })
+            })
+            .collect()
+    }
+}
+
+async fn run_hook(hook: &dyn FinalityHook, header: &BlockHeader, retry: HookRetryConfig) {
+    let mut backoff = Duration::from_millis(retry.initial_backoff_ms);
+    for attempt in 1..=retry.max_attempts.max(1) {
+        match hook.on_finalized(header).await {
+            Ok(()) => return,
+            Err(e) if attempt < retry.max_attempts => {
+                warn!("Finality hook failed for block {} (attempt {}): {}", header.height, attempt, e);
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => error!("Finality hook gave up on block {} after {} attempts: {}", header.height, attempt, e),
+        }
+    }
+}
+
+// 🔹 **Block Production**
+/// Mines the transaction pool into blocks for `spawn_block_producer`.
+struct PoolBlockProducer {
+    miner_wallet: String,
+    consensus: Arc<RwLock<QuantumFuseConsensus>>,
+    transaction_pool: Arc<RwLock<TransactionPool>>,
+    transaction_index: Arc<RwLock<TransactionIndex>>,
+    receipts: Arc<ReceiptHub>,
+    gas_oracle: Arc<RwLock<GasOracle>>,
+}
+
+#[async_trait]
+impl BlockProducer for PoolBlockProducer {
+    async fn pending_transactions(&self) -> usize {
+        self.transaction_pool.read().await.pending_fees().len()
+    }
+
+    async fn produce_block(&self) -> Result<(), BlockchainError> {
+        let consensus = self.consensus.read().await;
+        let mut tx_pool = self.transaction_pool.write().await;
+        let transactions = tx_pool.pending_transactions();
+
+        let block = consensus.mine_block(&self.miner_wallet, transactions).await
+            .map_err(|e| BlockchainError::Internal(e.to_string()))?;
+        info!("Mined block {} with {} transactions", block.header.height, block.transactions.len());
+        record_mined_block(&block, &mut tx_pool, &self.transaction_index, &self.receipts, &self.gas_oracle).await
+            .map_err(|(message, e)| BlockchainError::Internal(format!("{}: {}", message, e)))
+    }
+}
+
 // 🔹 **Quantum Node Implementation**
 pub struct QuantumNode {
     config: NodeConfig,
+    chain: Arc<QuantumBlockchain>,
     consensus: Arc<RwLock<QuantumFuseConsensus>>,
     peer_manager: Arc<RwLock<PeerManager>>,
+    peer_scores: Arc<RwLock<PeerScoreboard>>,
+    finality_hooks: FinalityHooks,
     transaction_pool: Arc<RwLock<TransactionPool>>,
+    transaction_index: Arc<RwLock<TransactionIndex>>,
+    receipts: Arc<ReceiptHub>,
+    /// Chain transactions awaiting inclusion, snapshotted while the node runs.
+    mempool: Arc<RwLock<Mempool>>,
+    mempool_persistence: Mutex<Option<JoinHandle<()>>>,
+    gas_oracle: Arc<RwLock<GasOracle>>,
     storage: Arc<RwLock<QuantumStorage>>,
     metrics: Arc<RwLock<NodeMetrics>>,
     transaction_optimizer: Arc<RwLock<TransactionOptimizer>>,
@@ -83,11 +571,26 @@ pub struct QuantumNode {
 }
 
 impl QuantumNode {
-    pub async fn new(config: NodeConfig) -> Result<Self, NodeError> {
+    /// Serves `chain`; the API's receipt waiters hear about every block it commits.
+    pub async fn new(config: NodeConfig, chain: Arc<QuantumBlockchain>) -> Result<Self, NodeError> {
+        if !redaction::install(config.redaction.clone()) {
+            warn!("Log redaction was already configured; ignoring node settings");
+        }
+
         // Initialize components
         let consensus = Arc::new(RwLock::new(Self::initialize_consensus(&config).await?));
         let peer_manager = Arc::new(RwLock::new(PeerManager::new(&config)?));
+        let peer_scores = Arc::new(RwLock::new(PeerScoreboard::new(config.peers.clone())));
+        let finality_hooks = FinalityHooks::default();
+        if let Some(webhook) = &config.finality_webhook {
+            finality_hooks.register_with_retry(Arc::new(WebhookFinalityHook::new(&webhook.url)?), webhook.retry);
+        }
         let transaction_pool = Arc::new(RwLock::new(TransactionPool::new(&config)?));
+        let transaction_index = Arc::new(RwLock::new(TransactionIndex::default()));
+        let receipts = chain.receipt_hub().await;
+        let mempool_config = config.mempool_config();
+        let mempool = Arc::new(RwLock::new(Mempool::with_lanes(mempool_config.max_size, mempool_config.lanes)));
+        let gas_oracle = Arc::new(RwLock::new(GasOracle::new(config.gas_oracle.clone())));
         let storage = Arc::new(RwLock::new(QuantumStorage::new(&config)?));
         let metrics = Arc::new(RwLock::new(NodeMetrics::default()));
         let transaction_optimizer = Arc::new(RwLock::new(TransactionOptimizer::new()));
@@ -95,9 +598,17 @@ impl QuantumNode {
 
         Ok(Self {
             config,
+            chain,
             consensus,
             peer_manager,
+            peer_scores,
+            finality_hooks,
             transaction_pool,
+            transaction_index,
+            receipts,
+            mempool,
+            mempool_persistence: Mutex::new(None),
+            gas_oracle,
             storage,
             metrics,
             transaction_optimizer,
@@ -119,11 +630,16 @@ impl QuantumNode {
     pub async fn start(&self) -> Result<(), NodeError> {
         info!("Starting Quantum Node with ID: {}", self.config.node_id);
 
+        // Reload transactions pending at the last shutdown
+        self.start_mempool().await;
+
         // Start P2P networking
         self.peer_manager.write().await.start().await?;
 
         // Start consensus
         self.consensus.write().await.start().await?;
+        self.start_finality_watcher().await;
+        self.start_block_producer();
 
         // Start AI Anomaly Detection
         self.anomaly_detector.write().await.start_monitoring().await?;
@@ -135,26 +651,208 @@ impl QuantumNode {
         Ok(())
     }
 
+    /// Stops mempool snapshots and writes a final one, so the next start restores it.
+    pub async fn shutdown(&self) {
+        if let Some(persistence) = self.mempool_persistence.lock().await.take() {
+            persistence.abort();
+        }
+        let config = self.config.mempool_config();
+        if let Err(e) = self.mempool.read().await.persist(&config.snapshot_path(), config.codec) {
+            warn!("Failed to persist mempool on shutdown: {}", e);
+        }
+        info!("Quantum Node {} stopped", self.config.node_id);
+    }
+
+    pub fn chain(&self) -> &Arc<QuantumBlockchain> {
+        &self.chain
+    }
+
+    pub fn mempool(&self) -> &Arc<RwLock<Mempool>> {
+        &self.mempool
+    }
+
+    /// Restores the mempool snapshot, dropping transactions committed state has
+    /// since spent, and snapshots it every `persist_interval` from then on. A
+    /// snapshot that can't be read is logged and the node starts empty.
+    async fn start_mempool(&self) {
+        let config = self.config.mempool_config();
+        {
+            let state_manager = self.chain.state_manager.read().await;
+            let restored = self.mempool.write().await
+                .restore(&config.snapshot_path(), config.codec, &CommittedNonces(&*state_manager));
+            match restored {
+                Ok(report) => info!(
+                    "Restored {} mempool transactions; dropped {} invalid and {} over capacity",
+                    report.restored, report.dropped_invalid, report.dropped_over_capacity,
+                ),
+                Err(e) => warn!("Failed to restore mempool; starting empty: {}", e),
+            }
+        }
+        *self.mempool_persistence.lock().await = Some(spawn_persistence(self.mempool.clone(), config));
+    }
+
+    /// Callbacks run for every block this node finalizes.
+    pub fn finality_hooks(&self) -> &FinalityHooks {
+        &self.finality_hooks
+    }
+
+    /// Mines blocks in the background when a miner wallet is configured.
+    fn start_block_producer(&self) {
+        let Some(miner_wallet) = self.config.miner_wallet.clone() else {
+            return;
+        };
+        let producer = Arc::new(PoolBlockProducer {
+            miner_wallet,
+            consensus: self.consensus.clone(),
+            transaction_pool: self.transaction_pool.clone(),
+            transaction_index: self.transaction_index.clone(),
+            receipts: self.receipts.clone(),
+            gas_oracle: self.gas_oracle.clone(),
+        });
+        let chain_config = &self.chain.config;
+        spawn_block_producer(producer, chain_config.consensus.block_time, chain_config.block_production);
+    }
+
+    /// Notifies the finality hooks of each block consensus finalizes. A mined
+    /// block can still be reorged out, so this is the only place they run.
+    async fn start_finality_watcher(&self) {
+        let mut finalized = self.consensus.read().await.subscribe_finalized();
+        let finality_hooks = self.finality_hooks.clone();
+        tokio::spawn(async move {
+            loop {
+                match finalized.recv().await {
+                    Ok(header) => {
+                        finality_hooks.notify(&header);
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Finality watcher fell behind; {} finalized blocks were not announced", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Admits an inbound or outbound peer, disconnecting the worst peer if the node is full.
+    pub async fn connect_peer(&self, peer_id: &str) -> Result<(), NodeError> {
+        // peer_scores is released before peer_manager is taken
+        let admission = self.peer_scores.write().await.admit(peer_id, Utc::now())?;
+        if let PeerAdmission::Evicted(evicted) = admission {
+            self.peer_manager.write().await.disconnect(&evicted).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn disconnect_peer(&self, peer_id: &str) -> Result<(), NodeError> {
+        self.peer_scores.write().await.remove(peer_id);
+        self.peer_manager.write().await.disconnect(peer_id).await
+    }
+
+    /// Scores a message received from `peer_id`, disconnecting the peer if it
+    /// gets banned. Returns whether it was.
+    pub async fn report_peer(&self, peer_id: &str, behavior: PeerBehavior) -> Result<bool, NodeError> {
+        let banned = self.peer_scores.write().await.record(peer_id, behavior, Utc::now());
+        if banned {
+            self.peer_manager.write().await.disconnect(peer_id).await?;
+        }
+        Ok(banned)
+    }
+
+    /// Admits `peer_id` and reads its messages until it disconnects or is
+    /// banned. Each message is scored: transactions that join the mempool and
+    /// blocks the chain accepts are useful, invalid ones count against the peer.
+    pub async fn serve_peer(&self, peer_id: &str, transport: &dyn PeerTransport) -> Result<(), NodeError> {
+        self.connect_peer(peer_id).await?;
+        loop {
+            let Ok(message) = transport.recv().await else {
+                return self.disconnect_peer(peer_id).await;
+            };
+            let behavior = match message {
+                WireMessage::Transaction(transaction) => self.admit_peer_transaction(transaction).await,
+                WireMessage::Block(block) => match self.chain.add_block(block).await {
+                    Ok(()) => Some(PeerBehavior::UsefulMessage),
+                    Err(e) => {
+                        warn!("Rejected block from peer {}: {}", peer_id, e);
+                        Some(PeerBehavior::InvalidBlock)
+                    }
+                },
+            };
+            let Some(behavior) = behavior else {
+                continue;
+            };
+            if self.report_peer(peer_id, behavior).await? {
+                let _ = transport.close().await;
+                return Ok(());
+            }
+        }
+    }
+
+    /// Adds a relayed transaction to the mempool if it is signed and its nonce
+    /// unspent. A duplicate or a full pool isn't the peer's fault, so it isn't scored.
+    async fn admit_peer_transaction(&self, transaction: QuantumTransaction) -> Option<PeerBehavior> {
+        let skew = self.chain.config.max_clock_skew.transaction_secs;
+        if transaction.is_system || !matches!(transaction.verify_with_skew(skew), Ok(true)) {
+            return Some(PeerBehavior::InvalidMessage);
+        }
+        if CommittedNonces(&*self.chain.state_manager.read().await).validate(&transaction).is_err() {
+            return Some(PeerBehavior::InvalidMessage);
+        }
+        match self.mempool.write().await.insert(transaction) {
+            Ok(()) => Some(PeerBehavior::UsefulMessage),
+            Err(_) => None,
+        }
+    }
+
     async fn start_api_server(&self) -> Result<(), NodeError> {
         let consensus = self.consensus.clone();
         let transaction_pool = self.transaction_pool.clone();
+        let transaction_index = self.transaction_index.clone();
+        let receipts = self.receipts.clone();
+        let gas_oracle = self.gas_oracle.clone();
         let metrics = self.metrics.clone();
+        let build_info = BuildInfo::current().with_chain(self.config.chain_id, self.config.network_id);
 
         let server = HttpServer::new(move || {
             App::new()
                 .app_data(web::Data::new(consensus.clone()))
                 .app_data(web::Data::new(transaction_pool.clone()))
+                .app_data(web::Data::new(transaction_index.clone()))
+                .app_data(web::Data::new(receipts.clone()))
+                .app_data(web::Data::new(gas_oracle.clone()))
                 .app_data(web::Data::new(metrics.clone()))
-                .service(
-                    web::scope("/api/v1")
-                        .route("/block/mine", web::post().to(handle_mine_block))
-                        .route("/block/validate", web::post().to(handle_validate_block))
-                        .route("/transaction/submit", web::post().to(handle_submit_transaction))
-                        .route("/node/status", web::get().to(handle_node_status))
-                        .route("/metrics", web::get().to(handle_metrics))
-                )
+                .app_data(web::Data::new(build_info.clone()))
+                .service(api_scope().scope)
+        });
+
+        let addr = format!("0.0.0.0:{}", self.config.api_port);
+        let server = match &self.config.tls {
+            Some(tls) => {
+                let tls_config = tls.load_server_config().map_err(NodeError::Tls)?;
+                if let Some(http_port) = tls.http_redirect_port {
+                    Self::spawn_https_redirect(http_port, self.config.api_port)?;
+                }
+                info!("Serving node API over HTTPS on {}", addr);
+                server.bind_rustls_0_23(addr, tls_config)?
+            }
+            None => server.bind(addr)?,
+        }
+        .run();
+
+        tokio::spawn(server);
+        Ok(())
+    }
+
+    fn spawn_https_redirect(http_port: u16, https_port: u16) -> Result<(), NodeError> {
+        let server = HttpServer::new(move || {
+            App::new().default_service(web::to(move |req: HttpRequest| async move {
+                let path = req.uri().path_and_query().map_or("/", |p| p.as_str());
+                let location = https_redirect_location(Some(req.connection_info().host()), path, https_port);
+                HttpResponse::PermanentRedirect()
+                    .insert_header(("Location", location))
+                    .finish()
+            }))
         })
-        .bind(format!("0.0.0.0:{}", self.config.api_port))?
+        .bind(("0.0.0.0", http_port))?
         .run();
 
         tokio::spawn(server);
@@ -162,18 +860,163 @@ impl QuantumNode {
     }
 }
 
+// 🔹 **API Routes**
+/// The `/api/v1` scope, recording each route as it is registered so the
+/// OpenAPI document can be checked against what is actually served.
+struct ApiScope {
+    scope: web::Scope,
+    registered: Vec<(Method, &'static str)>,
+}
+
+impl ApiScope {
+    fn new() -> Self {
+        Self { scope: web::scope("/api/v1"), registered: Vec::new() }
+    }
+
+    fn route<F, Args>(mut self, method: Method, path: &'static str, handler: F) -> Self
+    where
+        F: Handler<Args>,
+        Args: FromRequest + 'static,
+        F::Output: Responder + 'static,
+    {
+        self.scope = self.scope.route(path, web::method(method.clone()).to(handler));
+        self.registered.push((method, path));
+        self
+    }
+}
+
+fn api_scope() -> ApiScope {
+    ApiScope::new()
+        .route(Method::POST, "/block/mine", handle_mine_block)
+        .route(Method::POST, "/block/validate", handle_validate_block)
+        .route(Method::POST, "/transaction/submit", handle_submit_transaction)
+        .route(Method::GET, "/transaction/{hash}/wait", handle_wait_for_transaction)
+        .route(Method::GET, "/tx/{hash}/proof", handle_transaction_proof)
+        .route(Method::GET, "/gas/estimate", handle_gas_estimate)
+        .route(Method::GET, "/node/status", handle_node_status)
+        .route(Method::GET, "/metrics", handle_metrics)
+        .route(Method::GET, "/version", handle_version)
+        .route(Method::GET, "/openapi.json", handle_openapi)
+}
+
+// 🔹 **OpenAPI Document**
+struct ApiRoute {
+    method: HttpMethod,
+    path: &'static str,
+    operation_id: &'static str,
+    summary: &'static str,
+    request: Option<&'static str>,
+    response: Option<&'static str>,
+}
+
+/// Every route under `/api/v1`, in registration order. Keep in step with `api_scope`.
+const API_ROUTES: &[ApiRoute] = &[
+    ApiRoute { method: HttpMethod::Post, path: "/block/mine", operation_id: "mine_block", summary: "Mine a block from the submitted transactions", request: Some("BlockRequest"), response: Some("BlockResponse") },
+    ApiRoute { method: HttpMethod::Post, path: "/block/validate", operation_id: "validate_block", summary: "Validate a proposed block", request: Some("Block"), response: Some("BlockResponse") },
+    ApiRoute { method: HttpMethod::Post, path: "/transaction/submit", operation_id: "submit_transaction", summary: "Submit a transaction to the pool", request: Some("Transaction"), response: Some("ResponseStatus") },
+    ApiRoute { method: HttpMethod::Get, path: "/transaction/{hash}/wait", operation_id: "wait_for_transaction", summary: "Long-poll until a transaction is included; 408 on timeout", request: None, response: None },
+    ApiRoute { method: HttpMethod::Get, path: "/tx/{hash}/proof", operation_id: "transaction_proof", summary: "Merkle inclusion proof for a finalized transaction", request: None, response: Some("InclusionProofResponse") },
+    ApiRoute { method: HttpMethod::Get, path: "/gas/estimate", operation_id: "gas_estimate", summary: "Fee estimates from recent blocks and mempool congestion", request: None, response: Some("GasEstimate") },
+    ApiRoute { method: HttpMethod::Get, path: "/node/status", operation_id: "node_status", summary: "Node identity and sync status", request: None, response: None },
+    ApiRoute { method: HttpMethod::Get, path: "/metrics", operation_id: "metrics", summary: "Node metrics", request: None, response: None },
+    ApiRoute { method: HttpMethod::Get, path: "/version", operation_id: "version", summary: "Build version, commit and chain identity", request: None, response: Some("BuildInfo") },
+    ApiRoute { method: HttpMethod::Get, path: "/openapi.json", operation_id: "openapi", summary: "This OpenAPI document", request: None, response: None },
+];
+
+fn json_content(schema: &str) -> utoipa::openapi::Content {
+    ContentBuilder::new().schema(Some(Ref::from_schema_name(schema))).build()
+}
+
+/// Built from `API_ROUTES` in a fixed order, so the served document is byte-for-byte stable.
+pub fn openapi_spec() -> OpenApi {
+    let mut paths = PathsBuilder::new();
+    for route in API_ROUTES {
+        let mut operation = OperationBuilder::new()
+            .operation_id(Some(route.operation_id))
+            .summary(Some(route.summary));
+
+        if route.path.contains("{hash}") {
+            operation = operation.parameter(
+                ParameterBuilder::new()
+                    .name("hash")
+                    .parameter_in(ParameterIn::Path)
+                    .required(Required::True)
+                    .schema(Some(ObjectBuilder::new().schema_type(Type::String))),
+            );
+        }
+        if route.path.ends_with("/wait") {
+            operation = operation.parameter(
+                ParameterBuilder::new()
+                    .name("timeout")
+                    .parameter_in(ParameterIn::Query)
+                    .required(Required::False)
+                    .schema(Some(ObjectBuilder::new().schema_type(Type::Integer))),
+            );
+        }
+        if let Some(request) = route.request {
+            operation = operation.request_body(Some(
+                RequestBodyBuilder::new().content("application/json", json_content(request)).required(Some(Required::True)).build(),
+            ));
+        }
+
+        let mut success = ResponseBuilder::new().description("Success");
+        if let Some(response) = route.response {
+            success = success.content("application/json", json_content(response));
+        }
+        operation = operation
+            .response("200", success.build())
+            .response("4XX", ResponseBuilder::new().description("Client error").content("application/json", json_content("ResponseStatus")).build())
+            .response("5XX", ResponseBuilder::new().description("Server error").content("application/json", json_content("ResponseStatus")).build());
+
+        paths = paths.path(format!("/api/v1{}", route.path), PathItem::new(route.method.clone(), operation.build()));
+    }
+
+    let components = ComponentsBuilder::new()
+        .schema_from::<BlockRequest>()
+        .schema_from::<BlockResponse>()
+        .schema_from::<Block>()
+        .schema_from::<Transaction>()
+        .schema_from::<InclusionProofResponse>()
+        .schema_from::<GasEstimate>()
+        .schema_from::<ResponseStatus>()
+        .schema_from::<BuildInfo>()
+        .build();
+
+    OpenApiBuilder::new()
+        .info(InfoBuilder::new().title("QuantumFuse Node API").version(env!("CARGO_PKG_VERSION")).build())
+        .paths(paths.build())
+        .components(Some(components))
+        .build()
+}
+
 // 🔹 **API Handlers**
+const DEFAULT_WAIT_TIMEOUT_SECS: u64 = 30;
+const MAX_WAIT_TIMEOUT_SECS: u64 = 120;
+
+async fn handle_openapi() -> impl Responder {
+    HttpResponse::Ok().json(openapi_spec())
+}
+
+async fn handle_version(build_info: web::Data<BuildInfo>) -> impl Responder {
+    HttpResponse::Ok().json(build_info.get_ref())
+}
+
 async fn handle_mine_block(
     req: web::Json<BlockRequest>,
     consensus: web::Data<Arc<RwLock<QuantumFuseConsensus>>>,
     transaction_pool: web::Data<Arc<RwLock<TransactionPool>>>,
+    transaction_index: web::Data<Arc<RwLock<TransactionIndex>>>,
+    receipts: web::Data<Arc<ReceiptHub>>,
+    gas_oracle: web::Data<Arc<RwLock<GasOracle>>>,
 ) -> impl Responder {
     let consensus = consensus.read().await;
     let mut tx_pool = transaction_pool.write().await;
 
     match consensus.mine_block(&req.miner_wallet, req.transactions.clone()).await {
         Ok(block) => {
-            tx_pool.remove_transactions(&block.transactions).await?;
+            if let Err((message, e)) = record_mined_block(&block, &mut tx_pool, &transaction_index, &receipts, &gas_oracle).await {
+                return mined_block_error(block, message, e);
+            }
             HttpResponse::Ok().json(BlockResponse {
                 block: Some(block),
                 status: ResponseStatus::Success,
@@ -187,3 +1030,472 @@ async fn handle_mine_block(
         })
     }
 }
+
+/// The block exists, so it is returned alongside the failure.
+/// Clears a mined block's transactions from the pool, indexes it, feeds the
+/// gas oracle and announces its receipts. On failure returns what went wrong.
+async fn record_mined_block(
+    block: &Block,
+    tx_pool: &mut TransactionPool,
+    transaction_index: &RwLock<TransactionIndex>,
+    receipts: &ReceiptHub,
+    gas_oracle: &RwLock<GasOracle>,
+) -> Result<(), (&'static str, String)> {
+    tx_pool.remove_transactions(&block.transactions).await
+        .map_err(|e| ("Block mined but its transactions were not removed from the pool", e.to_string()))?;
+    transaction_index.write().await
+        .index_block(&block.header.hash().to_string(), block.header.height, &block.transactions)
+        .map_err(|e| ("Block mined but not indexed", e.to_string()))?;
+    gas_oracle.write().await.record_block(block.transactions.iter().map(|tx| tx.fee));
+    for (index, tx) in block.transactions.iter().enumerate() {
+        let receipt = TransactionReceipt {
+            transaction_hash: tx.hash,
+            block_height: block.header.height,
+            index,
+            from: tx.sender.clone(),
+            to: tx.recipient.clone(),
+        };
+        if let Err(e) = receipts.publish(&receipt) {
+            warn!("Failed to publish receipt for {}: {}", tx.hash, e);
+        }
+    }
+    Ok(())
+}
+
+fn mined_block_error(block: Block, message: &str, e: impl std::fmt::Display) -> HttpResponse {
+    error!("{}: {}", message, e);
+    HttpResponse::InternalServerError().json(BlockResponse {
+        block: Some(block),
+        status: ResponseStatus::Error(e.to_string()),
+        message: message.to_string(),
+    })
+}
+
+async fn handle_transaction_proof(
+    path: web::Path<String>,
+    transaction_index: web::Data<Arc<RwLock<TransactionIndex>>>,
+) -> impl Responder {
+    match transaction_index.read().await.proof(&path.into_inner()) {
+        Some(proof) => HttpResponse::Ok().json(proof),
+        None => HttpResponse::NotFound().json(ResponseStatus::Error("transaction not found in a finalized block".to_string())),
+    }
+}
+
+/// Holds the request open until the transaction's receipt is published or
+/// `timeout` seconds pass.
+async fn handle_wait_for_transaction(
+    path: web::Path<String>,
+    query: web::Query<WaitQuery>,
+    receipts: web::Data<Arc<ReceiptHub>>,
+) -> impl Responder {
+    let Ok(hash) = Hash::from_hex(path.as_str()) else {
+        return HttpResponse::BadRequest().json(ResponseStatus::Error("invalid transaction hash".to_string()));
+    };
+    let waiter = match receipts.wait_for_transaction(hash) {
+        Ok(waiter) => waiter,
+        Err(StateError::TooManyWaiters) => {
+            return HttpResponse::ServiceUnavailable().json(ResponseStatus::Error("too many pending waits".to_string()));
+        }
+        Err(e) => return HttpResponse::InternalServerError().json(ResponseStatus::Error(e.to_string())),
+    };
+
+    let timeout = query.timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT_SECS).min(MAX_WAIT_TIMEOUT_SECS);
+    let outcome = tokio::time::timeout(Duration::from_secs(timeout), waiter).await;
+    match outcome {
+        Ok(Ok(receipt)) => HttpResponse::Ok().json(receipt),
+        Ok(Err(_)) => HttpResponse::ServiceUnavailable().json(ResponseStatus::Error("receipt stream closed".to_string())),
+        Err(_) => {
+            // The timed-out receiver is gone; don't leave its sender behind.
+            if let Err(e) = receipts.release_waiters(&hash) {
+                warn!("Failed to release waiters for {}: {}", hash, e);
+            }
+            HttpResponse::RequestTimeout().json(ResponseStatus::Error("transaction not confirmed before timeout".to_string()))
+        }
+    }
+}
+
+async fn handle_gas_estimate(
+    transaction_pool: web::Data<Arc<RwLock<TransactionPool>>>,
+    gas_oracle: web::Data<Arc<RwLock<GasOracle>>>,
+) -> impl Responder {
+    let pending_fees = transaction_pool.read().await.pending_fees();
+    HttpResponse::Ok().json(gas_oracle.read().await.estimate(&pending_fees))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use actix_web::{http::StatusCode, test};
+    use quantumfuse_sdk::merkle::verify_inclusion;
+
+    fn test_transaction(seed: u8) -> Transaction {
+        Transaction {
+            hash: Hash::from([seed; 32]),
+            sender: format!("sender-{}", seed),
+            recipient: "recipient".to_string(),
+            amount: 1.0,
+            fee: Amount::from_base_units(1_000),
+            timestamp: Utc::now(),
+            signature: None,
+        }
+    }
+
+    async fn proof_service(index: TransactionIndex) -> impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+    > {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(Arc::new(RwLock::new(index))))
+                .route("/api/v1/tx/{hash}/proof", web::get().to(handle_transaction_proof)),
+        ).await
+    }
+
+    #[actix_web::test]
+    async fn test_inclusion_proof_for_included_transaction() {
+        let transactions: Vec<Transaction> = (1..=5).map(test_transaction).collect();
+        let mut index = TransactionIndex::default();
+        index.index_block("block-42", 42, &transactions).unwrap();
+        let app = proof_service(index).await;
+
+        let target = &transactions[3];
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/tx/{}/proof", target.hash))
+            .to_request();
+        let response: InclusionProofResponse = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(response.block_hash, "block-42");
+        assert_eq!(response.block_height, 42);
+        let root = blake3::Hash::from_hex(&response.transactions_root).unwrap();
+        assert!(verify_inclusion(&root, target, &response.proof));
+        assert!(!verify_inclusion(&root, &transactions[0], &response.proof));
+    }
+
+    #[actix_web::test]
+    async fn test_inclusion_proof_unknown_hash_is_not_found() {
+        let app = proof_service(TransactionIndex::default()).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/tx/{}/proof", test_transaction(9).hash))
+            .to_request();
+        let response = test::call_service(&app, req).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_openapi_spec_matches_registered_routes() {
+        let ApiScope { scope, registered } = api_scope();
+        // `(path, method)` as the OpenAPI document spells them.
+        let registered_keys: HashSet<(String, String)> = registered.iter()
+            .map(|(method, path)| (format!("/api/v1{}", path), method.as_str().to_lowercase()))
+            .collect();
+        assert_eq!(registered_keys.len(), registered.len(), "route registered twice");
+
+        let raw = serde_json::to_value(openapi_spec()).unwrap();
+        let documented: HashSet<(String, String)> = raw["paths"].as_object().unwrap().iter()
+            .flat_map(|(path, item)| {
+                item.as_object().unwrap().keys()
+                    .filter(|key| ["get", "post", "put", "patch", "delete"].contains(&key.as_str()))
+                    .map(move |method| (path.clone(), method.clone()))
+            })
+            .collect();
+        assert_eq!(documented, registered_keys);
+
+        // The recorded routes are the ones the app serves.
+        let app = test::init_service(App::new().service(scope)).await;
+        for (method, path) in &registered {
+            let uri = format!("/api/v1{}", path.replace("{hash}", &Hash::from([7; 32]).to_string()));
+            let req = test::TestRequest::default().method(method.clone()).uri(&uri).to_request();
+            let status = test::call_service(&app, req).await.status();
+            assert_ne!(status, StatusCode::NOT_FOUND, "{} {} is not served", method, path);
+            assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{} {} is not served", method, path);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_openapi_document_is_served_and_stable() {
+        let app = test::init_service(
+            App::new().route("/api/v1/openapi.json", web::get().to(handle_openapi)),
+        ).await;
+        let req = test::TestRequest::get().uri("/api/v1/openapi.json").to_request();
+        let body = test::call_and_read_body(&app, req).await;
+
+        let raw: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(raw["openapi"].as_str().unwrap().starts_with("3."));
+        let spec: OpenApi = serde_json::from_slice(&body).unwrap();
+
+        let schemas = &spec.components.unwrap().schemas;
+        for name in ["BlockRequest", "BlockResponse", "Transaction", "ResponseStatus"] {
+            assert!(schemas.contains_key(name), "missing schema {}", name);
+        }
+
+        // Deterministic: rebuilding yields the same document.
+        assert_eq!(serde_json::to_vec(&openapi_spec()).unwrap(), body.to_vec());
+    }
+
+    async fn wait_service(receipts: Arc<ReceiptHub>) -> impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+    > {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(receipts))
+                .route("/api/v1/transaction/{hash}/wait", web::get().to(handle_wait_for_transaction)),
+        ).await
+    }
+
+    fn receipt_for(tx: &Transaction, block_height: u64) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: tx.hash,
+            block_height,
+            index: 0,
+            from: tx.sender.clone(),
+            to: tx.recipient.clone(),
+        }
+    }
+
+    /// Publishes the receipt after a short delay, while the request is waiting.
+    fn confirm_later(receipts: &Arc<ReceiptHub>, receipt: TransactionReceipt) {
+        let receipts = receipts.clone();
+        actix_web::rt::spawn(async move {
+            actix_web::rt::time::sleep(Duration::from_millis(50)).await;
+            receipts.publish(&receipt).unwrap();
+        });
+    }
+
+    #[actix_web::test]
+    async fn test_wait_returns_receipt_when_confirmed() {
+        let receipts = Arc::new(ReceiptHub::default());
+        let app = wait_service(receipts.clone()).await;
+        let tx = test_transaction(3);
+        confirm_later(&receipts, receipt_for(&tx, 12));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/transaction/{}/wait?timeout=5", tx.hash))
+            .to_request();
+        let receipt: TransactionReceipt = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(receipt, receipt_for(&tx, 12));
+
+        // A waiter arriving after confirmation is answered straight away.
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/transaction/{}/wait?timeout=1", tx.hash))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_wait_times_out_for_unconfirmed_transaction() {
+        let app = wait_service(Arc::new(ReceiptHub::default())).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/transaction/{}/wait?timeout=1", test_transaction(4).hash))
+            .to_request();
+        let response = test::call_service(&app, req).await;
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[actix_web::test]
+    async fn test_concurrent_waiters_are_all_notified() {
+        let receipts = Arc::new(ReceiptHub::default());
+        let app = wait_service(receipts.clone()).await;
+        let tx = test_transaction(5);
+        let uri = format!("/api/v1/transaction/{}/wait?timeout=5", tx.hash);
+        confirm_later(&receipts, receipt_for(&tx, 8));
+
+        let (first, second) = tokio::join!(
+            test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()),
+            test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()),
+        );
+        for response in [first, second] {
+            assert_eq!(response.status(), StatusCode::OK);
+            let receipt: TransactionReceipt = test::read_body_json(response).await;
+            assert_eq!(receipt.block_height, 8);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_version_endpoint_reports_build_and_chain() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(BuildInfo::current().with_chain(7, 42)))
+                .route("/api/v1/version", web::get().to(handle_version)),
+        ).await;
+        let req = test::TestRequest::get().uri("/api/v1/version").to_request();
+        let info: BuildInfo = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.version.is_empty());
+        assert!(!info.git_commit.is_empty());
+        assert_eq!(info.chain_id, Some(7));
+        assert_eq!(info.network_id, Some(42));
+    }
+
+    fn seeded_oracle() -> GasOracle {
+        let mut oracle = GasOracle::new(GasOracleConfig {
+            block_window: 5,
+            mempool_capacity: 100,
+            min_fee: Amount::from_base_units(1_000),
+        });
+        // Older blocks fall out of the window.
+        oracle.record_block([Amount::from_base_units(50_000)]);
+        for base in [2_000, 2_200, 2_400, 2_600, 2_800] {
+            oracle.record_block([Amount::from_base_units(base), Amount::from_base_units(base * 3)]);
+        }
+        oracle
+    }
+
+    fn pending_fees(count: usize) -> Vec<Amount> {
+        (0..count).map(|i| Amount::from_base_units(2_000 + (i as u128 % 10) * 500)).collect()
+    }
+
+    #[test]
+    fn test_gas_estimate_buckets_are_monotonic() {
+        let oracle = seeded_oracle();
+
+        for count in [0, 10, 100] {
+            let estimate = oracle.estimate(&pending_fees(count));
+            assert_eq!(estimate.base_fee, Amount::from_base_units(2_400));
+            assert!(estimate.low >= estimate.base_fee);
+            assert!(estimate.low <= estimate.medium);
+            assert!(estimate.medium <= estimate.high);
+        }
+    }
+
+    fn finalized_header(height: u64) -> BlockHeader {
+        BlockHeader {
+            version: 1,
+            height,
+            prev_hash: blake3::Hash::from([0; 32]),
+            timestamp: Utc::now(),
+            transactions_root: blake3::Hash::from([1; 32]),
+            state_root: blake3::Hash::from([2; 32]),
+            receipts_root: blake3::Hash::from([3; 32]),
+            quantum_state_hash: blake3::Hash::from([4; 32]),
+            validator_set_hash: blake3::Hash::from([5; 32]),
+            beacon_randomness: blake3::Hash::from([6; 32]),
+            extra_data: Vec::new(),
+        }
+    }
+
+    /// Fails the first `failures` calls, then records the heights it was given.
+    struct FlakyHook {
+        failures: std::sync::atomic::AtomicU32,
+        calls: std::sync::atomic::AtomicU32,
+        delivered: tokio::sync::mpsc::UnboundedSender<u64>,
+    }
+
+    #[async_trait]
+    impl FinalityHook for FlakyHook {
+        async fn on_finalized(&self, header: &BlockHeader) -> Result<(), NodeError> {
+            use std::sync::atomic::Ordering;
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failures.load(Ordering::SeqCst) > 0 {
+                self.failures.fetch_sub(1, Ordering::SeqCst);
+                return Err(NodeError::Webhook("connection refused".to_string()));
+            }
+            let _ = self.delivered.send(header.height);
+            Ok(())
+        }
+    }
+
+    fn flaky_hook(failures: u32) -> (Arc<FlakyHook>, tokio::sync::mpsc::UnboundedReceiver<u64>) {
+        let (delivered, received) = tokio::sync::mpsc::unbounded_channel();
+        let hook = Arc::new(FlakyHook {
+            failures: std::sync::atomic::AtomicU32::new(failures),
+            calls: std::sync::atomic::AtomicU32::new(0),
+            delivered,
+        });
+        (hook, received)
+    }
+
+    #[tokio::test]
+    async fn test_finalized_block_invokes_registered_hook() {
+        let hooks = FinalityHooks::default();
+        let (hook, mut received) = flaky_hook(0);
+        hooks.register(hook.clone());
+
+        for handle in hooks.notify(&finalized_header(7)) {
+            handle.await.unwrap();
+        }
+        assert_eq!(received.recv().await, Some(7));
+        assert_eq!(hook.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failing_hook_is_retried_without_blocking_finalization() {
+        let hooks = FinalityHooks::default();
+        let (hook, mut received) = flaky_hook(3);
+        hooks.register_with_retry(hook.clone(), HookRetryConfig { max_attempts: 5, initial_backoff_ms: 20 });
+
+        // notify returns before the first backoff has elapsed.
+        let started = std::time::Instant::now();
+        let handles = hooks.notify(&finalized_header(8));
+        assert!(started.elapsed() < Duration::from_millis(20));
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(received.recv().await, Some(8));
+        assert_eq!(hook.calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+        // Backoff of 20, 40 and 80 ms between the four attempts.
+        assert!(started.elapsed() >= Duration::from_millis(140));
+    }
+
+    fn scoreboard(max_peers: usize) -> PeerScoreboard {
+        PeerScoreboard::new(PeerConfig { max_peers, ..PeerConfig::default() })
+    }
+
+    #[test]
+    fn test_exceeding_max_peers_evicts_worst_peer() {
+        let now = Utc::now();
+        let mut peers = scoreboard(3);
+        for id in ["alpha", "beta", "gamma"] {
+            assert_eq!(peers.admit(id, now).unwrap(), PeerAdmission::Admitted);
+        }
+        peers.record("alpha", PeerBehavior::UsefulMessage, now);
+        peers.record("gamma", PeerBehavior::InvalidMessage, now);
+
+        assert_eq!(peers.admit("delta", now).unwrap(), PeerAdmission::Evicted("gamma".to_string()));
+        assert_eq!(peers.peer_count(), 3);
+        assert_eq!(peers.score("gamma"), None);
+        assert_eq!(peers.score("delta"), Some(0));
+
+        // Nobody left scores below a newcomer.
+        assert!(matches!(peers.admit("epsilon", now), Err(NodeError::PeerLimitReached(3))));
+    }
+
+    #[test]
+    fn test_peer_sending_invalid_blocks_is_banned() {
+        let now = Utc::now();
+        let mut peers = scoreboard(8);
+        peers.admit("mallory", now).unwrap();
+
+        assert!(!peers.record("mallory", PeerBehavior::InvalidBlock, now));
+        assert!(!peers.record("mallory", PeerBehavior::InvalidBlock, now));
+        assert!(peers.record("mallory", PeerBehavior::InvalidBlock, now));
+
+        assert!(peers.is_banned("mallory", now));
+        assert_eq!(peers.score("mallory"), None);
+        assert!(matches!(peers.admit("mallory", now), Err(NodeError::PeerBanned(_))));
+
+        let later = now + chrono::Duration::seconds(PeerConfig::default().ban_duration_secs + 1);
+        assert_eq!(peers.admit("mallory", later).unwrap(), PeerAdmission::Admitted);
+    }
+
+    #[test]
+    fn test_gas_estimate_rises_with_congestion() {
+        let oracle = seeded_oracle();
+
+        let quiet = oracle.estimate(&pending_fees(10));
+        let full = oracle.estimate(&pending_fees(100));
+
+        assert_eq!(quiet.congestion_bps, 1_000);
+        assert_eq!(full.congestion_bps, 10_000);
+        assert!(full.medium > quiet.medium);
+        assert!(full.high > quiet.high);
+    }
+}
@@ -1,8 +1,11 @@
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
+use log::warn;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use rand_core::{RngCore, CryptoRng};
+use aes::Aes256;
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
 use quantumfuse_sdk::{
     error::QRNGError,
     crypto::{Hash, KeyPair},
@@ -21,6 +24,8 @@ pub struct QuantumRNG {
     buffer: Arc<RwLock<EntropyBuffer>>,
     metrics: Arc<RwLock<QRNGMetrics>>,
     ai_analyzer: Arc<RwLock<EntropyAnalyzer>>,
+    drbg: Option<CtrDrbg>,
+    events: broadcast::Sender<QRNGEvent>,
     config: QRNGConfig,
 }
 
@@ -37,6 +42,20 @@ pub struct EntropyBuffer {
     buffer: Vec<u8>,
     last_refresh: DateTime<Utc>,
     entropy_quality: f64,
+    source: Option<EntropyOrigin>,
+}
+
+/// Where the bytes currently being served came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntropyOrigin {
+    Hardware,
+    Software,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QRNGEvent {
+    /// Emitted whenever a refresh is served by a different origin than the last.
+    Failover { from: EntropyOrigin, to: EntropyOrigin, at: DateTime<Utc> },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +65,21 @@ pub struct QRNGConfig {
     pub min_entropy_quality: f64,
     pub fallback_threshold: f64,
     pub quantum_security_level: u8,
+    /// When set, output is served through an AES-256 CTR_DRBG seeded from the buffer.
+    #[serde(default)]
+    pub conditioning: Option<DrbgConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrbgConfig {
+    /// Generate requests allowed between reseeds.
+    pub reseed_interval: u64,
+}
+
+impl Default for DrbgConfig {
+    fn default() -> Self {
+        Self { reseed_interval: 1 << 16 }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +121,78 @@ pub enum EntropySourceType {
     Hybrid,
 }
 
+impl EntropySource {
+    /// `quality_score` is the estimated min-entropy per bit, in `[0, 1]`.
+    pub fn new(source_type: EntropySourceType, raw_data: Vec<u8>, quality_score: f64) -> Self {
+        Self {
+            source_type,
+            raw_data,
+            timestamp: Utc::now(),
+            quality_score: quality_score.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn quality_score(&self) -> f64 {
+        self.quality_score
+    }
+
+    /// Bits of min-entropy this source is credited with.
+    pub fn min_entropy_bits(&self) -> f64 {
+        self.quality_score * self.raw_data.len() as f64 * 8.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MixedEntropy {
+    pub bytes: Vec<u8>,
+    /// Credited min-entropy per output bit.
+    pub quality: f64,
+}
+
+/// Extracts `output_len` bytes from independent `sources` with a keyed BLAKE3
+/// XOF. Because the extractor's output is as unpredictable as its least
+/// predictable input, the output is credited only with the best source's
+/// min-entropy; weak or constant sources add nothing but cannot subtract
+/// either. Fails if there are no sources or that credit falls below `min_quality`.
+pub fn mix_entropy(sources: &[EntropySource], output_len: usize, min_quality: f64) -> Result<MixedEntropy, QRNGError> {
+    if sources.is_empty() {
+        return Err(QRNGError::NoEntropySources);
+    }
+    let best_bits = sources.iter().map(EntropySource::min_entropy_bits).fold(0.0, f64::max);
+    let quality = (best_bits / (output_len.max(1) as f64 * 8.0)).min(1.0);
+    if quality < min_quality {
+        return Err(QRNGError::LowEntropyQuality(quality));
+    }
+
+    // Highest quality first so the extractor is keyed by the strongest source.
+    let mut ordered: Vec<&EntropySource> = sources.iter().collect();
+    ordered.sort_by(|a, b| b.quality_score.total_cmp(&a.quality_score));
+
+    let key = blake3::derive_key("quantumfuse qrng extractor v1", &ordered[0].raw_data);
+    let mut hasher = blake3::Hasher::new_keyed(&key);
+    for source in &ordered {
+        hasher.update(&[source.source_type.tag()]);
+        hasher.update(&source.quality_score.to_le_bytes());
+        hasher.update(&(source.raw_data.len() as u64).to_le_bytes());
+        hasher.update(&source.raw_data);
+    }
+
+    let mut bytes = vec![0u8; output_len];
+    hasher.finalize_xof().fill(&mut bytes);
+    Ok(MixedEntropy { bytes, quality })
+}
+
+impl EntropySourceType {
+    fn tag(&self) -> u8 {
+        match self {
+            EntropySourceType::Quantum => 0,
+            EntropySourceType::Environmental => 1,
+            EntropySourceType::Hardware => 2,
+            EntropySourceType::Hybrid => 3,
+        }
+    }
+}
+
 // 🔹 **Implementation**
 impl QuantumRNG {
     pub async fn new(config: QRNGConfig) -> Result<Self, QRNGError> {
@@ -94,12 +200,15 @@ impl QuantumRNG {
         let buffer = Arc::new(RwLock::new(EntropyBuffer::new(config.buffer_size)));
         let metrics = Arc::new(RwLock::new(QRNGMetrics::default()));
         let ai_analyzer = Arc::new(RwLock::new(EntropyAnalyzer::new()));
+        let (events, _) = broadcast::channel(64);
 
         let mut qrng = Self {
             backend,
             buffer,
             metrics,
             ai_analyzer,
+            drbg: None,
+            events,
             config,
         };
 
@@ -110,26 +219,70 @@ impl QuantumRNG {
     }
 
     pub async fn generate_random_bytes(&mut self, length: usize) -> Result<Vec<u8>, QRNGError> {
+        let result = match self.config.conditioning.clone() {
+            Some(conditioning) => self.generate_conditioned(length, &conditioning).await?,
+            None => self.extract_raw(length).await?,
+        };
+
+        // Update AI-powered metrics
+        let source = self.current_source().await;
+        let mut metrics = self.metrics.write().await;
+        metrics.bytes_generated += length as u64;
+        match source {
+            Some(EntropyOrigin::Hardware) => metrics.hardware_bytes += length as u64,
+            Some(EntropyOrigin::Software) | None => metrics.software_bytes += length as u64,
+        }
+        metrics.last_generation = Utc::now();
+
+        Ok(result)
+    }
+
+    async fn extract_raw(&mut self, length: usize) -> Result<Vec<u8>, QRNGError> {
         let mut buffer = self.buffer.write().await;
-        
-        if buffer.needs_refresh() {
+
+        if buffer.needs_refresh() || buffer.buffer.len() < length {
             drop(buffer);
             self.refresh_entropy_buffer().await?;
             buffer = self.buffer.write().await;
         }
 
-        let result = buffer.extract_bytes(length)?;
+        buffer.extract_bytes(length)
+    }
 
-        // Update AI-powered metrics
-        let mut metrics = self.metrics.write().await;
-        metrics.bytes_generated += length as u64;
-        metrics.last_generation = Utc::now();
+    /// Reseeds from fresh entropy when the interval is reached or the buffer's
+    /// quality has dropped below `min_entropy_quality`.
+    async fn generate_conditioned(&mut self, length: usize, conditioning: &DrbgConfig) -> Result<Vec<u8>, QRNGError> {
+        let quality_dropped = self.buffer.read().await.entropy_quality < self.config.min_entropy_quality;
+        if quality_dropped {
+            self.refresh_entropy_buffer().await?;
+            let quality = self.buffer.read().await.entropy_quality;
+            if quality < self.config.min_entropy_quality {
+                return Err(QRNGError::LowEntropyQuality(quality));
+            }
+        }
 
-        Ok(result)
+        let must_reseed = quality_dropped || self.drbg.as_ref().map_or(true, CtrDrbg::needs_reseed);
+        if must_reseed {
+            let raw = self.extract_raw(CtrDrbg::SEED_LEN).await?;
+            let mut seed = [0u8; CtrDrbg::SEED_LEN];
+            seed.copy_from_slice(&raw);
+            match self.drbg.as_mut() {
+                Some(drbg) => drbg.reseed(&seed),
+                None => self.drbg = Some(CtrDrbg::new(&seed, conditioning.reseed_interval)),
+            }
+        }
+
+        match self.drbg.as_mut() {
+            Some(drbg) => drbg.generate(length),
+            None => Err(QRNGError::ReseedRequired),
+        }
     }
 
     pub async fn generate_keypair(&mut self) -> Result<KeyPair, QRNGError> {
         let seed = self.generate_random_bytes(32).await?;
+        if self.current_source().await != Some(EntropyOrigin::Hardware) {
+            warn!("Generating keypair from software fallback entropy; hardware QRNG is unavailable");
+        }
         let keypair = KeyPair::generate_from_seed(&seed)?;
         
         let mut metrics = self.metrics.write().await;
@@ -138,6 +291,27 @@ impl QuantumRNG {
         Ok(keypair)
     }
 
+    pub async fn current_source(&self) -> Option<EntropyOrigin> {
+        self.buffer.read().await.source
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<QRNGEvent> {
+        self.events.subscribe()
+    }
+
+    pub async fn add_device(&self, device: Box<dyn QuantumDevice>) {
+        self.backend.write().await.hardware_devices.push(device);
+    }
+
+    pub async fn metrics(&self) -> QRNGMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// `mix_entropy` gated by this generator's `min_entropy_quality`.
+    pub fn mix_entropy(&self, sources: &[EntropySource], output_len: usize) -> Result<MixedEntropy, QRNGError> {
+        mix_entropy(sources, output_len, self.config.min_entropy_quality)
+    }
+
     pub async fn validate_entropy(&mut self) -> Result<bool, QRNGError> {
         let buffer = self.buffer.read().await;
         let ai_analyzer = self.ai_analyzer.read().await;
@@ -148,6 +322,7 @@ impl QuantumRNG {
     async fn refresh_entropy_buffer(&mut self) -> Result<(), QRNGError> {
         let backend = self.backend.read().await;
         let mut new_entropy = Vec::new();
+        let mut origin = EntropyOrigin::Hardware;
 
         if !backend.hardware_devices.is_empty() {
             for device in &backend.hardware_devices {
@@ -163,9 +338,16 @@ impl QuantumRNG {
 
         if new_entropy.is_empty() {
             new_entropy = backend.software_fallback.generate_entropy(self.config.buffer_size)?;
+            origin = EntropyOrigin::Software;
         }
 
         let mut buffer = self.buffer.write().await;
+        if let Some(previous) = buffer.source.filter(|previous| *previous != origin) {
+            warn!("QRNG entropy source changed from {:?} to {:?}", previous, origin);
+            // No subscribers is not an error.
+            let _ = self.events.send(QRNGEvent::Failover { from: previous, to: origin, at: Utc::now() });
+        }
+        buffer.source = Some(origin);
         buffer.buffer = new_entropy;
         buffer.last_refresh = Utc::now();
         buffer.entropy_quality = self.estimate_entropy_quality(&buffer.buffer)?;
@@ -174,22 +356,121 @@ impl QuantumRNG {
     }
 
     fn estimate_entropy_quality(&self, data: &[u8]) -> Result<f64, QRNGError> {
-        let mut counts = [0u32; 256];
-        for &byte in data {
-            counts[byte as usize] += 1;
+        Ok(estimate_entropy_quality(data))
+    }
+}
+
+/// Chi-square test of the byte distribution, mapped onto `(0, 1]`.
+pub fn estimate_entropy_quality(data: &[u8]) -> f64 {
+    let mut counts = [0u32; 256];
+    for &byte in data {
+        counts[byte as usize] += 1;
+    }
+
+    let len = data.len() as f64;
+    let expected = len / 256.0;
+    let chi_square: f64 = counts.iter()
+        .map(|&count| {
+            let diff = count as f64 - expected;
+            diff * diff / expected
+        })
+        .sum();
+
+    (-chi_square / 512.0).exp()
+}
+
+// 🔹 **Output Conditioning**
+/// NIST SP 800-90A CTR_DRBG over AES-256, without a derivation function, so
+/// seeds must be full-entropy `SEED_LEN` bytes.
+pub struct CtrDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+    reseed_counter: u64,
+    reseed_interval: u64,
+    reseeds: u64,
+}
+
+impl std::fmt::Debug for CtrDrbg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CtrDrbg")
+            .field("reseed_counter", &self.reseed_counter)
+            .field("reseed_interval", &self.reseed_interval)
+            .field("reseeds", &self.reseeds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CtrDrbg {
+    pub const SEED_LEN: usize = 48;
+    const MAX_REQUEST_BYTES: usize = 1 << 16;
+
+    pub fn new(seed: &[u8; Self::SEED_LEN], reseed_interval: u64) -> Self {
+        let mut drbg = Self {
+            key: [0u8; 32],
+            v: [0u8; 16],
+            reseed_counter: 1,
+            reseed_interval: reseed_interval.max(1),
+            reseeds: 0,
+        };
+        drbg.update(seed);
+        drbg
+    }
+
+    pub fn reseed(&mut self, seed: &[u8; Self::SEED_LEN]) {
+        self.update(seed);
+        self.reseed_counter = 1;
+        self.reseeds += 1;
+    }
+
+    pub fn needs_reseed(&self) -> bool {
+        self.reseed_counter > self.reseed_interval
+    }
+
+    pub fn reseed_count(&self) -> u64 {
+        self.reseeds
+    }
+
+    pub fn generate(&mut self, length: usize) -> Result<Vec<u8>, QRNGError> {
+        if self.needs_reseed() {
+            return Err(QRNGError::ReseedRequired);
+        }
+
+        let mut output = Vec::with_capacity(length);
+        while output.len() < length {
+            // Rekey after every request-sized chunk for backtracking resistance.
+            let target = output.len() + (length - output.len()).min(Self::MAX_REQUEST_BYTES);
+            let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+            while output.len() < target {
+                let block = self.next_block(&cipher);
+                let take = (target - output.len()).min(16);
+                output.extend_from_slice(&block[..take]);
+            }
+            self.update(&[0u8; Self::SEED_LEN]);
         }
 
-        let len = data.len() as f64;
-        let expected = len / 256.0;
-        let chi_square: f64 = counts.iter()
-            .map(|&count| {
-                let diff = count as f64 - expected;
-                diff * diff / expected
-            })
-            .sum();
+        self.reseed_counter += 1;
+        Ok(output)
+    }
+
+    fn next_block(&mut self, cipher: &Aes256) -> [u8; 16] {
+        let counter = u128::from_be_bytes(self.v).wrapping_add(1);
+        self.v = counter.to_be_bytes();
+        let mut block = GenericArray::clone_from_slice(&self.v);
+        cipher.encrypt_block(&mut block);
+        block.into()
+    }
 
-        let quality = (-chi_square / 512.0).exp();
-        Ok(quality)
+    fn update(&mut self, provided_data: &[u8; Self::SEED_LEN]) {
+        let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+        let mut temp = [0u8; Self::SEED_LEN];
+        for block in temp.chunks_mut(16) {
+            block.copy_from_slice(&self.next_block(&cipher));
+        }
+        for (t, p) in temp.iter_mut().zip(provided_data) {
+            *t ^= p;
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
     }
 }
 
@@ -210,6 +491,7 @@ impl EntropyBuffer {
             buffer: Vec::with_capacity(size),
             last_refresh: Utc::now(),
             entropy_quality: 1.0,
+            source: None,
         }
     }
 
@@ -229,4 +511,117 @@ mod tests {
         let mut qrng = QuantumRNG::new(config).await.unwrap();
         assert!(qrng.validate_entropy().await.unwrap());
     }
+
+    fn quantum_source(len: usize) -> EntropySource {
+        let mut data = vec![0u8; len];
+        rand::thread_rng().fill_bytes(&mut data);
+        EntropySource::new(EntropySourceType::Quantum, data, 0.95)
+    }
+
+    #[test]
+    fn test_constant_source_does_not_weaken_mix() {
+        let constant = EntropySource::new(EntropySourceType::Environmental, vec![0u8; 4096], 0.0);
+        let mixed = mix_entropy(&[constant, quantum_source(4096)], 4096, 0.9).unwrap();
+
+        assert_eq!(mixed.quality, 0.95);
+        assert_eq!(mixed.bytes.len(), 4096);
+        assert!(estimate_entropy_quality(&mixed.bytes) > 0.4);
+    }
+
+    #[test]
+    fn test_mixed_quality_tracks_best_source() {
+        let weak = EntropySource::new(EntropySourceType::Environmental, vec![7u8; 64], 0.2);
+
+        // The best source only covers half the requested output.
+        let mixed = mix_entropy(&[weak, quantum_source(32)], 64, 0.0).unwrap();
+        assert!((mixed.quality - 0.475).abs() < 1e-9);
+
+        let only_constant = EntropySource::new(EntropySourceType::Environmental, vec![0u8; 64], 0.0);
+        let result = mix_entropy(&[only_constant], 32, 0.5);
+        assert!(matches!(result, Err(QRNGError::LowEntropyQuality(q)) if q == 0.0));
+        assert!(matches!(mix_entropy(&[], 32, 0.0), Err(QRNGError::NoEntropySources)));
+    }
+
+    /// Hardware device whose health the test can toggle.
+    #[derive(Debug)]
+    struct ToggleDevice {
+        healthy: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    impl QuantumDevice for ToggleDevice {
+        fn generate_entropy(&self, size: usize) -> Result<Vec<u8>, QRNGError> {
+            if !self.healthy.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(QRNGError::DeviceError("photon detector offline".to_string()));
+            }
+            let mut data = vec![0u8; size];
+            rand::thread_rng().fill_bytes(&mut data);
+            Ok(data)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hardware_failure_falls_over_to_software() {
+        let mut qrng = QuantumRNG::new(QRNGConfig::default()).await.unwrap();
+        let healthy = Arc::new(std::sync::atomic::AtomicBool::new(true));
+        qrng.add_device(Box::new(ToggleDevice { healthy: healthy.clone() })).await;
+        qrng.refresh_entropy_buffer().await.unwrap();
+        assert_eq!(qrng.current_source().await, Some(EntropyOrigin::Hardware));
+
+        let mut events = qrng.subscribe();
+        healthy.store(false, std::sync::atomic::Ordering::SeqCst);
+        qrng.refresh_entropy_buffer().await.unwrap();
+        assert_eq!(qrng.current_source().await, Some(EntropyOrigin::Software));
+
+        let before = qrng.metrics().await.software_bytes;
+        qrng.generate_random_bytes(32).await.unwrap();
+        assert_eq!(qrng.metrics().await.software_bytes, before + 32);
+
+        let event = events.try_recv().unwrap();
+        assert!(matches!(
+            event,
+            QRNGEvent::Failover { from: EntropyOrigin::Hardware, to: EntropyOrigin::Software, .. }
+        ));
+    }
+
+    fn seed(byte: u8) -> [u8; CtrDrbg::SEED_LEN] {
+        [byte; CtrDrbg::SEED_LEN]
+    }
+
+    #[test]
+    fn test_conditioned_output_passes_quality_gate() {
+        // Even a structured seed yields uniform-looking output.
+        let mut drbg = CtrDrbg::new(&seed(0x42), 16);
+        let output = drbg.generate(8192).unwrap();
+        assert!(estimate_entropy_quality(&output) > 0.4);
+    }
+
+    #[test]
+    fn test_drbg_reseeds_on_interval() {
+        let mut drbg = CtrDrbg::new(&seed(1), 3);
+        for _ in 0..3 {
+            drbg.generate(32).unwrap();
+        }
+        assert!(drbg.needs_reseed());
+        assert!(matches!(drbg.generate(32), Err(QRNGError::ReseedRequired)));
+
+        drbg.reseed(&seed(2));
+        assert_eq!(drbg.reseed_count(), 1);
+        assert!(drbg.generate(32).is_ok());
+    }
+
+    #[test]
+    fn test_drbg_output_does_not_repeat() {
+        let mut drbg = CtrDrbg::new(&seed(7), 1024);
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..256 {
+            assert!(seen.insert(drbg.generate(32).unwrap()));
+        }
+
+        // Same seed reproduces, different seed diverges.
+        let first = CtrDrbg::new(&seed(7), 1024).generate(64).unwrap();
+        let again = CtrDrbg::new(&seed(7), 1024).generate(64).unwrap();
+        let other = CtrDrbg::new(&seed(8), 1024).generate(64).unwrap();
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+    }
 }
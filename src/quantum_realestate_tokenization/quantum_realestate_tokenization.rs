@@ -12,6 +12,7 @@ use quantumfuse_sdk::{
     ai::MarketAI,
     contracts::SmartContractEngine,
     consensus::HybridConsensus,
+    ipfs_upload::IpfsClient,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,6 +25,8 @@ pub struct TokenizedProperty {
     digital_signature: Vec<u8>,
     valuation: f64,
     status: PropertyStatus,
+    #[serde(default)]
+    document_cids: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -43,6 +46,7 @@ pub struct QuantumRealEstateContract {
     smart_contract_engine: SmartContractEngine,
     metaverse_integration: MetaverseIntegration,
     market_ai: MarketAI,
+    ipfs: IpfsClient,
 }
 
 impl QuantumRealEstateContract {
@@ -51,6 +55,7 @@ impl QuantumRealEstateContract {
         ledger: QuantumLedger,
         did_registry: DIDRegistry,
         metaverse_integration: MetaverseIntegration,
+        ipfs: IpfsClient,
     ) -> Self {
         QuantumRealEstateContract {
             contract_id: contract_id.to_string(),
@@ -61,6 +66,7 @@ impl QuantumRealEstateContract {
             smart_contract_engine: SmartContractEngine::new(),
             metaverse_integration,
             market_ai: MarketAI::new(),
+            ipfs,
         }
     }
 
@@ -108,6 +114,7 @@ impl QuantumRealEstateContract {
             digital_signature: signature,
             valuation,
             status: PropertyStatus::Available,
+            document_cids: Vec::new(),
         };
 
         self.tokenized_properties.insert(property_id.clone(), tokenized_property.clone());
@@ -151,6 +158,41 @@ impl QuantumRealEstateContract {
         Ok(())
     }
 
+    /// Pins a deed or survey document to IPFS and links its CID to the property.
+    pub async fn attach_document(
+        &mut self,
+        agent_id: &str,
+        property_id: &str,
+        document: &[u8],
+    ) -> Result<String, TokenizationError> {
+        if !self.authorized_agents.contains_key(agent_id) {
+            return Err(TokenizationError::UnauthorizedAgent);
+        }
+        if !self.tokenized_properties.contains_key(property_id) {
+            return Err(TokenizationError::PropertyNotFound);
+        }
+
+        let cid = self.ipfs.store(document).await.map_err(TokenizationError::Ipfs)?;
+
+        let property = self.tokenized_properties.get_mut(property_id).ok_or(TokenizationError::PropertyNotFound)?;
+        if !property.document_cids.contains(&cid) {
+            property.document_cids.push(cid.clone());
+        }
+        self.quantum_ledger.record_event(property_id, &property)?;
+
+        Ok(cid)
+    }
+
+    /// Fetches a linked document, rejecting content that does not match its CID.
+    pub async fn retrieve_document(&self, property_id: &str, cid: &str) -> Result<Vec<u8>, TokenizationError> {
+        let property = self.tokenized_properties.get(property_id).ok_or(TokenizationError::PropertyNotFound)?;
+        if !property.document_cids.iter().any(|c| c == cid) {
+            return Err(TokenizationError::DocumentNotFound);
+        }
+
+        self.ipfs.get(cid).await.map_err(TokenizationError::Ipfs)
+    }
+
     pub fn retrieve_property_details(
         &self,
         agent_id: &str,
@@ -173,16 +215,22 @@ mod tests {
     use super::*;
     use pqcrypto::kem::kyber512::keypair as kyber_keypair;
     use pqcrypto::sign::dilithium2::keypair as dilithium_keypair;
+    use std::sync::Arc;
+    use quantumfuse_sdk::ipfs_upload::InMemoryIpfsBackend;
 
-    #[test]
-    fn test_real_estate_tokenization() {
+    fn test_ipfs() -> IpfsClient {
+        IpfsClient::new(Arc::new(InMemoryIpfsBackend::default()))
+    }
+
+    #[tokio::test]
+    async fn test_real_estate_tokenization() {
         let (kyber_pub, kyber_priv) = kyber_keypair();
         let (dilithium_pub, dilithium_priv) = dilithium_keypair();
         let ledger = QuantumLedger::new();
         let did_registry = DIDRegistry::new();
         let metaverse = MetaverseIntegration::new();
 
-        let mut contract = QuantumRealEstateContract::new("real_estate_001", ledger, did_registry, metaverse);
+        let mut contract = QuantumRealEstateContract::new("real_estate_001", ledger, did_registry, metaverse, test_ipfs());
 
         assert!(contract.authorize_agent("real_estate_agent_1", kyber_pub).is_ok());
 
@@ -201,5 +249,9 @@ mod tests {
 
         let details = contract.retrieve_property_details("real_estate_agent_1", &property_id, &kyber_priv).unwrap();
         assert_eq!(details, "Luxury Apartment, 3BHK, Sea View");
+
+        let cid = contract.attach_document("real_estate_agent_1", &property_id, b"deed of title").await.unwrap();
+        let document = contract.retrieve_document(&property_id, &cid).await.unwrap();
+        assert_eq!(document, b"deed of title");
     }
 }
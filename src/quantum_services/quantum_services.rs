@@ -7,7 +7,8 @@ use quantumfuse_sdk::{
     crypto::{Hash, KeyPair},
     state::StateAccess,
     metrics::ServiceMetrics,
-    qkd::QKDProtocol
+    qkd::QKDProtocol,
+    ipfs_upload::IpfsClient,
 };
 
 // Quantum Teleportation Service
@@ -92,6 +93,7 @@ pub struct NFTMarketplace {
     tokens: Arc<RwLock<HashMap<String, NFToken>>>,
     listings: Arc<RwLock<HashMap<String, Listing>>>,
     transactions: Arc<RwLock<Vec<NFTTransaction>>>,
+    ipfs: IpfsClient,
     metrics: ServiceMetrics,
 }
 
@@ -110,6 +112,8 @@ pub struct TokenMetadata {
     pub name: String,
     pub description: String,
     pub media_url: String,
+    #[serde(default)]
+    pub media_cid: Option<String>,
     pub attributes: HashMap<String, String>,
     pub created_at: DateTime<Utc>,
 }
@@ -241,21 +245,28 @@ impl QKDManager {
 }
 
 impl NFTMarketplace {
-    pub async fn new() -> Result<Self, QuantumServiceError> {
+    pub async fn new(ipfs: IpfsClient) -> Result<Self, QuantumServiceError> {
         Ok(Self {
             tokens: Arc::new(RwLock::new(HashMap::new())),
             listings: Arc::new(RwLock::new(HashMap::new())),
             transactions: Arc::new(RwLock::new(Vec::new())),
+            ipfs,
             metrics: ServiceMetrics::default(),
         })
     }
 
+    /// Pins the media to IPFS and records its CID in the token metadata.
     pub async fn create_fractional_nft(
         &mut self,
         owner: &[u8],
-        metadata: TokenMetadata,
+        mut metadata: TokenMetadata,
+        media: &[u8],
         total_units: u64,
     ) -> Result<NFToken, QuantumServiceError> {
+        let cid = self.ipfs.store(media).await.map_err(QuantumServiceError::Ipfs)?;
+        metadata.media_url = format!("ipfs://{}", cid);
+        metadata.media_cid = Some(cid);
+
         let token = NFToken {
             token_id: generate_token_id()?,
             owner: hex::encode(owner),
@@ -268,6 +279,16 @@ impl NFTMarketplace {
         self.tokens.write().await.insert(token.token_id.clone(), token.clone());
         Ok(token)
     }
+
+    /// Fetches the token's media, rejecting content that does not match its CID.
+    pub async fn fetch_media(&self, token_id: &str) -> Result<Vec<u8>, QuantumServiceError> {
+        let cid = self.tokens.read().await.get(token_id)
+            .ok_or(QuantumServiceError::TokenNotFound)?
+            .metadata.media_cid.clone()
+            .ok_or(QuantumServiceError::MissingContentId)?;
+
+        self.ipfs.get(&cid).await.map_err(QuantumServiceError::Ipfs)
+    }
 }
 
 impl QFCOnramper {
@@ -338,6 +359,7 @@ fn generate_token_id() -> Result<String, QuantumServiceError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quantumfuse_sdk::ipfs_upload::InMemoryIpfsBackend;
 
     #[tokio::test]
     async fn test_quantum_teleportation() {
@@ -368,17 +390,21 @@ mod tests {
 
     #[tokio::test]
     async fn test_nft_creation() {
-        let mut marketplace = NFTMarketplace::new().await.unwrap();
+        let ipfs = IpfsClient::new(Arc::new(InMemoryIpfsBackend::default()));
+        let mut marketplace = NFTMarketplace::new(ipfs).await.unwrap();
         let metadata = TokenMetadata {
             name: "Test NFT".to_string(),
             description: "Test Description".to_string(),
-            media_url: "https://example.com/nft.jpg".to_string(),
+            media_url: String::new(),
+            media_cid: None,
             attributes: HashMap::new(),
             created_at: Utc::now(),
         };
 
-        let result = marketplace.create_fractional_nft(b"owner", metadata, 100).await;
-        assert!(result.is_ok());
+        let token = marketplace.create_fractional_nft(b"owner", metadata, b"nft media", 100).await.unwrap();
+        let cid = token.metadata.media_cid.clone().unwrap();
+        assert_eq!(token.metadata.media_url, format!("ipfs://{}", cid));
+        assert_eq!(marketplace.fetch_media(&token.token_id).await.unwrap(), b"nft media");
     }
 
     #[tokio::test]
@@ -6,9 +6,9 @@ use chrono::Utc;
 use uuid::Uuid;
 use quantumfuse_sdk::{
     error::SupplyChainError,
-    blockchain::QuantumLedger,
+    blockchain::{QuantumLedger, LedgerBatcher, LedgerBatchConfig, BatchedLedger, SealedCommitment},
     did::DIDRegistry,
-    ai::FraudDetectionEngine,
+    fraud_detection::{FraudDecision, FraudRuleEngine, FraudRuleSet, FraudSignal},
     iot::IoTDataVerifier,
 };
 
@@ -16,8 +16,25 @@ use quantumfuse_sdk::{
 pub struct SupplyChainEvent {
     participant: String,
     encrypted_data: Vec<u8>,
+    data_commitment: [u8; 32],
     digital_signature: Vec<u8>,
     timestamp: i64,
+    /// Set when the fraud rules flagged the event for manual review.
+    #[serde(default)]
+    under_review: bool,
+    /// Copies of the data re-encrypted for other authorized participants.
+    #[serde(default)]
+    shared_data: HashMap<String, Vec<u8>>,
+}
+
+impl SupplyChainEvent {
+    fn ciphertext_for(&self, participant: &str) -> Option<&Vec<u8>> {
+        if self.participant == participant {
+            Some(&self.encrypted_data)
+        } else {
+            self.shared_data.get(participant)
+        }
+    }
 }
 
 pub struct QuantumSupplyChainContract {
@@ -26,10 +43,19 @@ pub struct QuantumSupplyChainContract {
     authorized_participants: HashMap<String, PublicKey>,
     supply_chain_events: HashMap<String, SupplyChainEvent>,
     quantum_ledger: QuantumLedger,
-    fraud_detection: FraudDetectionEngine,
+    ledger_batch: LedgerBatcher<SupplyChainEvent>,
+    fraud_detection: FraudRuleEngine,
     did_registry: DIDRegistry,
 }
 
+impl BatchedLedger for QuantumSupplyChainContract {
+    type Event = SupplyChainEvent;
+
+    fn ledger_batch(&self) -> &LedgerBatcher<SupplyChainEvent> {
+        &self.ledger_batch
+    }
+}
+
 impl QuantumSupplyChainContract {
     pub fn new(contract_id: &str, creator: &str, ledger: QuantumLedger, did_registry: DIDRegistry) -> Self {
         QuantumSupplyChainContract {
@@ -37,12 +63,23 @@ impl QuantumSupplyChainContract {
             creator: creator.to_string(),
             authorized_participants: HashMap::new(),
             supply_chain_events: HashMap::new(),
+            ledger_batch: LedgerBatcher::new(ledger.clone(), LedgerBatchConfig::default()),
             quantum_ledger: ledger,
-            fraud_detection: FraudDetectionEngine::new(),
+            fraud_detection: FraudRuleEngine::default(),
             did_registry,
         }
     }
 
+    pub fn with_ledger_batching(mut self, config: LedgerBatchConfig) -> Self {
+        self.ledger_batch = LedgerBatcher::new(self.quantum_ledger.clone(), config);
+        self
+    }
+
+    pub fn with_fraud_rules(mut self, rules: FraudRuleSet) -> Self {
+        self.fraud_detection.set_rules(rules);
+        self
+    }
+
     pub fn authorize_participant(&mut self, participant: &str, public_key: PublicKey) -> Result<(), SupplyChainError> {
         if self.authorized_participants.contains_key(participant) {
             return Err(SupplyChainError::DuplicateParticipant);
@@ -67,30 +104,38 @@ impl QuantumSupplyChainContract {
             return Err(SupplyChainError::UnauthorizedParticipant);
         }
 
-        // Encrypt data using Kyber512
-        let public_key = self.authorized_participants.get(participant).unwrap();
-        let encrypted_data = encrypt(data.as_bytes(), public_key);
-
-        // Generate digital signature using Dilithium2
-        let signature = sign(encrypted_data.clone(), private_key)?;
+        // Rule-based fraud screening; rejected events are never stored
+        let timestamp = Utc::now();
+        let signal = FraudSignal::new(participant, timestamp)
+            .with_identity_verified(self.did_registry.verify_identity(participant)?);
+        let assessment = self.fraud_detection.analyze(&signal);
+        if assessment.decision == FraudDecision::Reject {
+            return Err(SupplyChainError::FraudSuspected(assessment.triggered));
+        }
 
         // Generate event ID
         let event_id = Uuid::new_v4().to_string();
+        let sealed = SealedCommitment::seal(EVENT_DOMAIN, &event_id, data.as_bytes());
+
+        // Encrypt data using Kyber512
+        let public_key = self.authorized_participants.get(participant).unwrap();
+        let encrypted_data = encrypt(&sealed.payload, public_key);
+        let signature = sign(sealed.commitment.to_vec(), private_key)?;
 
         // Store event
         let event = SupplyChainEvent {
             participant: participant.to_string(),
             encrypted_data,
+            data_commitment: sealed.commitment,
             digital_signature: signature,
-            timestamp: Utc::now().timestamp(),
+            timestamp: timestamp.timestamp(),
+            under_review: assessment.decision == FraudDecision::Review,
+            shared_data: HashMap::new(),
         };
         self.supply_chain_events.insert(event_id.clone(), event.clone());
 
-        // Record event on blockchain ledger
-        self.quantum_ledger.record_event(&event_id, &event)?;
-
-        // Run AI-based fraud detection
-        self.fraud_detection.analyze_event(&event);
+        // Queue event for the blockchain ledger
+        self.ledger_batch.push(event_id.clone(), event)?;
 
         Ok(event_id)
     }
@@ -103,21 +148,50 @@ impl QuantumSupplyChainContract {
     ) -> Result<String, SupplyChainError> {
         let event = self.supply_chain_events.get(event_id).ok_or(SupplyChainError::EventNotFound)?;
 
-        if event.participant != participant {
-            return Err(SupplyChainError::UnauthorizedAccess);
-        }
+        let ciphertext = event.ciphertext_for(participant).ok_or(SupplyChainError::UnauthorizedAccess)?;
 
         // Decrypt data using Kyber512
-        let decrypted_data = decrypt(&event.encrypted_data, private_key)?;
-        let data_string = String::from_utf8(decrypted_data).map_err(|_| SupplyChainError::DecryptionFailed)?;
+        let payload = decrypt(ciphertext, private_key)?;
 
         // Verify digital signature using Dilithium2
-        let public_key = self.authorized_participants.get(participant).ok_or(SupplyChainError::InvalidPublicKey)?;
-        if !verify(&event.encrypted_data, &event.digital_signature, public_key)? {
+        let data = self.verify_data(event_id, event, &payload)?;
+
+        String::from_utf8(data.to_vec()).map_err(|_| SupplyChainError::DecryptionFailed)
+    }
+
+    /// Re-encrypts an event's sealed payload for another authorized participant.
+    pub fn share_event(
+        &mut self,
+        participant: &str,
+        event_id: &str,
+        private_key: &SecretKey,
+        new_participant: &str,
+    ) -> Result<(), SupplyChainError> {
+        let participant_key = self.authorized_participants.get(new_participant).ok_or(SupplyChainError::UnauthorizedParticipant)?;
+        let event = self.supply_chain_events.get(event_id).ok_or(SupplyChainError::EventNotFound)?;
+        let ciphertext = event.ciphertext_for(participant).ok_or(SupplyChainError::UnauthorizedAccess)?;
+
+        let payload = decrypt(ciphertext, private_key)?;
+        self.verify_data(event_id, event, &payload)?;
+        let reencrypted = encrypt(&payload, participant_key);
+
+        self.supply_chain_events.get_mut(event_id)
+            .ok_or(SupplyChainError::EventNotFound)?
+            .shared_data.insert(new_participant.to_string(), reencrypted);
+        Ok(())
+    }
+
+    /// Checks a decrypted payload against the event's signed commitment and
+    /// returns the event data inside it.
+    fn verify_data<'a>(&self, event_id: &str, event: &SupplyChainEvent, payload: &'a [u8]) -> Result<&'a [u8], SupplyChainError> {
+        let data = SealedCommitment::open(EVENT_DOMAIN, event_id, payload, &event.data_commitment)
+            .ok_or(SupplyChainError::InvalidSignature)?;
+
+        let public_key = self.authorized_participants.get(&event.participant).ok_or(SupplyChainError::InvalidPublicKey)?;
+        if !verify(&event.data_commitment, &event.digital_signature, public_key)? {
             return Err(SupplyChainError::InvalidSignature);
         }
-
-        Ok(data_string)
+        Ok(data)
     }
 
     pub fn integrate_iot_sensor_data(
@@ -138,11 +212,14 @@ impl QuantumSupplyChainContract {
     }
 }
 
+const EVENT_DOMAIN: &str = "quantumfuse supply chain event data v2";
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pqcrypto::kem::kyber512::keypair as kyber_keypair;
     use pqcrypto::sign::dilithium2::keypair as dilithium_keypair;
+    use quantumfuse_sdk::fraud_detection::{FraudPolicy, FraudRule};
 
     #[test]
     fn test_supply_chain_contract() {
@@ -177,4 +254,57 @@ mod tests {
         
         assert_eq!(decrypted_data, "Temp: 5°C");
     }
+
+    fn contract_with_participants(participants: &[&str]) -> (QuantumSupplyChainContract, HashMap<String, SecretKey>) {
+        let mut contract = QuantumSupplyChainContract::new("contract_789", "creator", QuantumLedger::new(), DIDRegistry::new());
+        let mut keys = HashMap::new();
+        for participant in participants {
+            let (public_key, secret_key) = kyber_keypair();
+            contract.authorized_participants.insert(participant.to_string(), public_key);
+            keys.insert(participant.to_string(), secret_key);
+        }
+        (contract, keys)
+    }
+
+    #[test]
+    fn test_signature_survives_reencryption() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (mut contract, keys) = contract_with_participants(&["supplier_2", "distributor"]);
+
+        let event_id = contract.register_event("supplier_2", "Pallet 42 Dispatched", &dilithium_priv).unwrap();
+        contract.share_event("supplier_2", &event_id, &keys["supplier_2"], "distributor").unwrap();
+
+        let decrypted = contract.retrieve_event("distributor", &event_id, &keys["distributor"]).unwrap();
+        assert_eq!(decrypted, "Pallet 42 Dispatched");
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_fails_verification() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (mut contract, keys) = contract_with_participants(&["supplier_3"]);
+
+        let event_id = contract.register_event("supplier_3", "Customs Cleared", &dilithium_priv).unwrap();
+        let event = contract.supply_chain_events.get_mut(&event_id).unwrap();
+        let last = event.encrypted_data.len() - 1;
+        event.encrypted_data[last] ^= 0x01;
+
+        assert!(contract.retrieve_event("supplier_3", &event_id, &keys["supplier_3"]).is_err());
+    }
+
+    #[test]
+    fn test_fraud_rules_reject_bursts() {
+        let (_, dilithium_priv) = dilithium_keypair();
+        let (contract, _) = contract_with_participants(&["supplier_4"]);
+        let mut contract = contract.with_fraud_rules(FraudRuleSet {
+            rules: vec![FraudRule::Velocity { max_operations: 1, window_secs: 3600, weight: 50 }],
+            policy: FraudPolicy { review_threshold: 50, reject_threshold: 50 },
+        });
+
+        let first = contract.register_event("supplier_4", "Container Loaded", &dilithium_priv).unwrap();
+        assert!(!contract.supply_chain_events[&first].under_review);
+
+        let result = contract.register_event("supplier_4", "Container Loaded", &dilithium_priv);
+        assert!(matches!(result, Err(SupplyChainError::FraudSuspected(ref rules)) if rules == &vec!["velocity".to_string()]));
+        assert_eq!(contract.supply_chain_events.len(), 1);
+    }
 }
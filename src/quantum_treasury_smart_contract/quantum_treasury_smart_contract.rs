@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    entry_point, from_json, to_binary, to_json_vec, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Storage, Uint128,
 };
 use serde::{Deserialize, Serialize};
 use quantumfuse_sdk::{
@@ -11,6 +12,87 @@ use quantumfuse_sdk::{
 pub struct InstantiateMsg {
     pub initial_reserves: Uint128,
     pub ai_optimized_allocation: bool,
+    #[serde(default = "default_max_ai_delta_percent")]
+    pub max_ai_delta_percent: u64,
+}
+
+fn default_max_ai_delta_percent() -> u64 {
+    10
+}
+
+const AI_GUARDRAIL_KEY: &[u8] = b"ai_guardrail";
+/// Clamp events are stored one per key, `AI_CLAMP_LOG_PREFIX` followed by a
+/// big-endian sequence number; `AI_CLAMP_LOG_NEXT_KEY` holds the next one.
+const AI_CLAMP_LOG_PREFIX: &[u8] = b"ai_clamp_log/";
+const AI_CLAMP_LOG_NEXT_KEY: &[u8] = b"ai_clamp_log_next";
+/// Clamp events kept; recording one more drops the oldest.
+const MAX_CLAMP_EVENTS: u64 = 256;
+
+/// Bounds how far one AI optimization may move reserves, as a percentage of current reserves.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AiGuardrail {
+    pub max_delta_percent: u64,
+}
+
+/// Recorded whenever the AI's proposal was outside the guardrail.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClampEvent {
+    pub height: u64,
+    pub previous: Uint128,
+    pub proposed: Uint128,
+    pub applied: Uint128,
+}
+
+impl AiGuardrail {
+    /// Reserves to apply for `proposed`: unchanged when within bounds,
+    /// otherwise moved toward it by exactly the maximum delta.
+    pub fn bound(&self, current: Uint128, proposed: Uint128) -> StdResult<Uint128> {
+        let max_delta = current.multiply_ratio(self.max_delta_percent, 100u128);
+        let applied = if proposed > current {
+            proposed.min(current.checked_add(max_delta)?)
+        } else {
+            proposed.max(current.saturating_sub(max_delta))
+        };
+        Ok(applied)
+    }
+}
+
+fn load_guardrail(storage: &dyn Storage) -> StdResult<AiGuardrail> {
+    match storage.get(AI_GUARDRAIL_KEY) {
+        Some(data) => from_json(&data),
+        None => Ok(AiGuardrail { max_delta_percent: default_max_ai_delta_percent() }),
+    }
+}
+
+fn clamp_log_key(sequence: u64) -> Vec<u8> {
+    [AI_CLAMP_LOG_PREFIX, &sequence.to_be_bytes()].concat()
+}
+
+fn next_clamp_sequence(storage: &dyn Storage) -> StdResult<u64> {
+    storage.get(AI_CLAMP_LOG_NEXT_KEY)
+        .map(|data| from_json(&data))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// The retained clamp events, oldest first.
+pub fn clamp_events(storage: &dyn Storage) -> StdResult<Vec<ClampEvent>> {
+    let next = next_clamp_sequence(storage)?;
+    (next.saturating_sub(MAX_CLAMP_EVENTS)..next)
+        .filter_map(|sequence| storage.get(&clamp_log_key(sequence)))
+        .map(|data| from_json(&data))
+        .collect()
+}
+
+/// Writes `event` under its own key and drops the one that falls out of the window.
+fn record_clamp(storage: &mut dyn Storage, event: ClampEvent) -> StdResult<()> {
+    let sequence = next_clamp_sequence(storage)?;
+    storage.set(&clamp_log_key(sequence), &to_json_vec(&event)?);
+    storage.set(AI_CLAMP_LOG_NEXT_KEY, &to_json_vec(&(sequence + 1))?);
+    if sequence >= MAX_CLAMP_EVENTS {
+        storage.remove(&clamp_log_key(sequence - MAX_CLAMP_EVENTS));
+    }
+    Ok(())
 }
 
 #[entry_point]
@@ -26,6 +108,7 @@ pub fn instantiate(
         ai_enabled: msg.ai_optimized_allocation,
     };
     deps.storage.save("treasury_state", &state)?;
+    deps.storage.set(AI_GUARDRAIL_KEY, &to_json_vec(&AiGuardrail { max_delta_percent: msg.max_ai_delta_percent })?);
     Ok(Response::new().add_attribute("method", "instantiate"))
 }
 
@@ -39,7 +122,7 @@ pub fn execute(
     match msg {
         ExecuteMsg::IssueBond { amount, interest_rate, duration } => issue_bond(deps, info, amount, interest_rate, duration),
         ExecuteMsg::AdjustReserves { adjustment } => adjust_reserves(deps, adjustment),
-        ExecuteMsg::AIOptimizeFunds {} => ai_optimize_funds(deps),
+        ExecuteMsg::AIOptimizeFunds {} => ai_optimize_funds(deps, _env),
     }
 }
 
@@ -73,15 +156,79 @@ pub fn adjust_reserves(deps: DepsMut, adjustment: Uint128) -> StdResult<Response
 }
 
 // 🔹 AI-Optimized Fund Allocation
-pub fn ai_optimize_funds(deps: DepsMut) -> StdResult<Response> {
+/// The model's output is held to the configured guardrail; a clamped move is logged.
+pub fn ai_optimize_funds(deps: DepsMut, env: Env) -> StdResult<Response> {
     let mut state: TreasuryState = deps.storage.load("treasury_state")?;
     if !state.ai_enabled {
         return Err(StdError::generic_err("AI optimization is disabled"));
     }
 
-    let optimized_allocation = PolicyAI::default().optimize_treasury_allocation(state.reserves);
-    state.reserves = optimized_allocation;
+    let proposed = PolicyAI::default().optimize_treasury_allocation(state.reserves);
+    let applied = load_guardrail(deps.storage)?.bound(state.reserves, proposed)?;
+    let clamped = applied != proposed;
+    if clamped {
+        record_clamp(deps.storage, ClampEvent {
+            height: env.block.height,
+            previous: state.reserves,
+            proposed,
+            applied,
+        })?;
+    }
+
+    state.reserves = applied;
     deps.storage.save("treasury_state", &state)?;
 
-    Ok(Response::new().add_attribute("action", "ai_optimize_funds"))
+    Ok(Response::new()
+        .add_attribute("action", "ai_optimize_funds")
+        .add_attribute("clamped", clamped.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    fn guardrail() -> AiGuardrail {
+        AiGuardrail { max_delta_percent: 10 }
+    }
+
+    #[test]
+    fn test_within_bounds_optimization_applies_fully() {
+        let reserves = Uint128::new(1_000_000);
+        assert_eq!(guardrail().bound(reserves, Uint128::new(1_080_000)).unwrap(), Uint128::new(1_080_000));
+        assert_eq!(guardrail().bound(reserves, Uint128::new(900_000)).unwrap(), Uint128::new(900_000));
+    }
+
+    #[test]
+    fn test_extreme_optimization_is_clamped() {
+        let reserves = Uint128::new(1_000_000);
+        assert_eq!(guardrail().bound(reserves, Uint128::zero()).unwrap(), Uint128::new(900_000));
+        assert_eq!(guardrail().bound(reserves, Uint128::new(50_000_000)).unwrap(), Uint128::new(1_100_000));
+    }
+
+    #[test]
+    fn test_clamped_optimization_is_logged() {
+        let mut deps = mock_dependencies();
+        let reserves = Uint128::new(1_000_000);
+        // A zero guardrail clamps any move the model proposes.
+        let msg = InstantiateMsg { initial_reserves: reserves, ai_optimized_allocation: true, max_ai_delta_percent: 0 };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(clamp_events(&deps.storage).unwrap().is_empty());
+
+        let mut env = mock_env();
+        for height in 0..MAX_CLAMP_EVENTS + 4 {
+            env.block.height = height;
+            let response = ai_optimize_funds(deps.as_mut(), env.clone()).unwrap();
+            assert!(response.attributes.iter().any(|attr| attr.key == "clamped" && attr.value == "true"));
+        }
+
+        let events = clamp_events(&deps.storage).unwrap();
+        assert_eq!(events.len() as u64, MAX_CLAMP_EVENTS);
+        assert_eq!(events[0].height, 4);
+        assert_eq!(events.last().unwrap().height, MAX_CLAMP_EVENTS + 3);
+        assert!(events.iter().all(|event| event.previous == reserves && event.applied == reserves && event.proposed != reserves));
+        // The dropped events are gone from storage, not just hidden.
+        assert!(deps.storage.get(&clamp_log_key(3)).is_none());
+        assert!(deps.storage.get(&clamp_log_key(4)).is_some());
+    }
 }
@@ -17,17 +17,20 @@ use quantumfuse_sdk::{
     defi::QuantumLending,
     stablecoin::QUSD,
     metrics::TokenMetrics,
+    amount::Amount,
+    transaction::{FeeSplit, FeeDistribution, GasSettlement},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuantumFuseCoin {
-    total_supply: u64,
-    circulating_supply: u64,
+    total_supply: Amount,
+    circulating_supply: Amount,
     allocation: HashMap<String, AllocationDetails>,
     balances: HashMap<String, Balance>,
     staking: HashMap<String, StakingInfo>,
     liquidity_pools: HashMap<String, LiquidityPool>,
     vesting_schedules: HashMap<String, VestingSchedule>,
+    bonds: HashMap<String, Bond>,
     real_estate_registry: QuantumRealEstate,
     lending_protocol: QuantumLending,
     stablecoin_system: QUSD,
@@ -38,7 +41,7 @@ pub struct QuantumFuseCoin {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LiquidityPool {
-    total_locked: u64,
+    total_locked: Amount,
     dynamic_apr: f64,
     token_pairs: Vec<String>,
 }
@@ -64,8 +67,8 @@ pub struct AllocationDetails {
     allocation_type: AllocationType,
     allocation_percentage: f64,
     vesting_years: u32,
-    allocated_tokens: u64,
-    claimed_tokens: u64,
+    allocated_tokens: Amount,
+    claimed_tokens: Amount,
     last_claim: Option<DateTime<Utc>>,
 }
 
@@ -82,25 +85,25 @@ pub enum AllocationType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Balance {
-    available: u64,
-    locked: u64,
-    staked: u64,
+    available: Amount,
+    locked: Amount,
+    staked: Amount,
     last_transaction: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StakingInfo {
-    amount: u64,
+    amount: Amount,
     start_time: DateTime<Utc>,
     unlock_time: DateTime<Utc>,
-    rewards_earned: u64,
+    rewards_earned: Amount,
     auto_compound: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VestingSchedule {
-    total_amount: u64,
-    released_amount: u64,
+    total_amount: Amount,
+    released_amount: Amount,
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
     cliff_duration: Duration,
@@ -108,13 +111,25 @@ pub struct VestingSchedule {
     next_release: DateTime<Utc>,
 }
 
+/// A governance bond's terms. The principal leaves circulation at issue and is
+/// minted back with simple interest at redemption.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bond {
+    holder: String,
+    principal: Amount,
+    /// Annual simple interest, in basis points.
+    interest_rate_bps: u32,
+    issued_at: DateTime<Utc>,
+    maturity_date: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionRecord {
     hash: Hash,
     transaction_type: TransactionType,
     from: String,
     to: String,
-    amount: u64,
+    amount: Amount,
     timestamp: DateTime<Utc>,
     status: TransactionStatus,
     zk_proof: Option<ZKPTransaction>,
@@ -129,6 +144,7 @@ pub enum TransactionType {
     Unstake,
     ClaimRewards,
     VestingRelease,
+    BondRedemption,
     CrossChainTransfer,
     PrivateTransaction,
     RealEstateTokenization,
@@ -151,20 +167,21 @@ impl QuantumFuseCoin {
                 allocation_type: AllocationType::StakingRewards,
                 allocation_percentage: 0.25,
                 vesting_years: 10,
-                allocated_tokens: 0,
-                claimed_tokens: 0,
+                allocated_tokens: Amount::ZERO,
+                claimed_tokens: Amount::ZERO,
                 last_claim: None,
             },
         );
 
         Self {
-            total_supply: 5_000_000_000,
-            circulating_supply: 0,
+            total_supply: Amount::from_whole(5_000_000_000),
+            circulating_supply: Amount::ZERO,
             allocation,
             balances: HashMap::new(),
             staking: HashMap::new(),
             liquidity_pools: HashMap::new(),
             vesting_schedules: HashMap::new(),
+            bonds: HashMap::new(),
             real_estate_registry: QuantumRealEstate::new(),
             lending_protocol: QuantumLending::new(),
             stablecoin_system: QUSD::new(),
@@ -174,6 +191,191 @@ impl QuantumFuseCoin {
         }
     }
 
+    pub fn mint(&mut self, recipient: &str, amount: Amount) -> Result<TransactionRecord, QFCError> {
+        self.mint_checked(recipient, amount, TransactionType::Mint)
+    }
+
+    /// Starts vesting `total_amount` to `beneficiary`. Nothing is minted until claimed.
+    pub fn create_vesting_schedule(
+        &mut self,
+        beneficiary: &str,
+        total_amount: Amount,
+        start_time: DateTime<Utc>,
+        duration: Duration,
+        cliff_duration: Duration,
+        release_interval: Duration,
+    ) -> Result<(), QFCError> {
+        if self.vesting_schedules.contains_key(beneficiary) {
+            return Err(QFCError::VestingScheduleExists);
+        }
+        self.vesting_schedules.insert(beneficiary.to_string(), VestingSchedule {
+            total_amount,
+            released_amount: Amount::ZERO,
+            start_time,
+            end_time: start_time + duration,
+            cliff_duration,
+            release_interval,
+            next_release: start_time + cliff_duration,
+        });
+        Ok(())
+    }
+
+    /// Mints whatever has vested for `beneficiary` since their last claim.
+    pub fn claim_vested_tokens(&mut self, beneficiary: &str, now: DateTime<Utc>) -> Result<TransactionRecord, QFCError> {
+        let schedule = self.vesting_schedules.get(beneficiary).ok_or(QFCError::AccountNotFound)?;
+        let claimable = schedule.vested_at(now)
+            .checked_sub(schedule.released_amount)
+            .ok_or(QFCError::SupplyUnderflow)?;
+        if claimable.is_zero() {
+            return Err(QFCError::NothingToClaim);
+        }
+
+        let record = self.mint_checked(beneficiary, claimable, TransactionType::VestingRelease)?;
+        if let Some(schedule) = self.vesting_schedules.get_mut(beneficiary) {
+            schedule.released_amount = schedule.released_amount.checked_add(claimable).ok_or(QFCError::SupplyOverflow)?;
+            schedule.next_release = (now + schedule.release_interval).min(schedule.end_time);
+        }
+        Ok(record)
+    }
+
+    /// Mints a staking reward to an account with an active stake.
+    pub fn pay_staking_reward(&mut self, staker: &str, reward: Amount) -> Result<TransactionRecord, QFCError> {
+        let rewards_earned = self.staking.get(staker)
+            .ok_or(QFCError::NotStaking)?
+            .rewards_earned
+            .checked_add(reward)
+            .ok_or(QFCError::SupplyOverflow)?;
+
+        let record = self.mint_checked(staker, reward, TransactionType::ClaimRewards)?;
+        if let Some(info) = self.staking.get_mut(staker) {
+            info.rewards_earned = rewards_earned;
+        }
+        Ok(record)
+    }
+
+    /// Issues a bond to `holder`, taking `principal` from their balance out of circulation.
+    pub fn issue_bond(
+        &mut self,
+        bond_id: &str,
+        holder: &str,
+        principal: Amount,
+        interest_rate_bps: u32,
+        issued_at: DateTime<Utc>,
+        maturity_date: DateTime<Utc>,
+    ) -> Result<TransactionRecord, QFCError> {
+        if self.bonds.contains_key(bond_id) {
+            return Err(QFCError::BondExists);
+        }
+        let circulating_supply = self.circulating_supply
+            .checked_sub(principal)
+            .ok_or(QFCError::SupplyUnderflow)?;
+        let holder_balance = self.balances.get_mut(holder).ok_or(QFCError::AccountNotFound)?;
+        holder_balance.available = holder_balance.available
+            .checked_sub(principal)
+            .ok_or(QFCError::InsufficientBalance)?;
+        holder_balance.last_transaction = Utc::now();
+
+        self.circulating_supply = circulating_supply;
+        self.bonds.insert(bond_id.to_string(), Bond {
+            holder: holder.to_string(),
+            principal,
+            interest_rate_bps,
+            issued_at,
+            maturity_date,
+        });
+        self.update_metrics()?;
+        self.record_transaction(TransactionType::Burn, holder, "", principal)
+    }
+
+    /// Pays out a matured bond to its holder; principal plus the interest its
+    /// terms accrued is newly minted. A bond can be redeemed once.
+    pub fn redeem_bond(&mut self, bond_id: &str, now: DateTime<Utc>) -> Result<TransactionRecord, QFCError> {
+        let bond = self.bonds.get(bond_id).ok_or(QFCError::BondNotFound)?;
+        if now < bond.maturity_date {
+            return Err(QFCError::BondNotMatured);
+        }
+        let payout = bond.payout()?;
+        let holder = bond.holder.clone();
+
+        let record = self.mint_checked(&holder, payout, TransactionType::BondRedemption)?;
+        self.bonds.remove(bond_id);
+        Ok(record)
+    }
+
+    /// The only way new tokens enter circulation. Checks the supply cap before
+    /// touching any balance, so a rejected mint changes nothing.
+    fn mint_checked(&mut self, recipient: &str, amount: Amount, transaction_type: TransactionType) -> Result<TransactionRecord, QFCError> {
+        let circulating_supply = self.circulating_supply
+            .checked_add(amount)
+            .ok_or(QFCError::SupplyOverflow)?;
+        if circulating_supply > self.total_supply {
+            return Err(QFCError::ExceedsMaxSupply);
+        }
+        let available = self.balance_of(recipient)
+            .checked_add(amount)
+            .ok_or(QFCError::SupplyOverflow)?;
+
+        let balance = self.balances.entry(recipient.to_string()).or_insert_with(Balance::new);
+        balance.available = available;
+        balance.last_transaction = Utc::now();
+        self.circulating_supply = circulating_supply;
+
+        self.update_metrics()?;
+        self.record_transaction(transaction_type, "", recipient, amount)
+    }
+
+    pub fn transfer(&mut self, sender: &str, recipient: &str, amount: Amount) -> Result<TransactionRecord, QFCError> {
+        let sender_balance = self.balances.get_mut(sender).ok_or(QFCError::AccountNotFound)?;
+        sender_balance.available = sender_balance.available
+            .checked_sub(amount)
+            .ok_or(QFCError::InsufficientBalance)?;
+        sender_balance.last_transaction = Utc::now();
+
+        let recipient_balance = self.balances.entry(recipient.to_string()).or_insert_with(Balance::new);
+        recipient_balance.available = recipient_balance.available
+            .checked_add(amount)
+            .ok_or(QFCError::SupplyOverflow)?;
+        recipient_balance.last_transaction = Utc::now();
+
+        self.update_metrics()?;
+        self.record_transaction(TransactionType::Transfer, sender, recipient, amount)
+    }
+
+    /// Charges the gas actually used to the payer, tips the block producer and
+    /// burns the rest from circulation. The refunded part is never taken.
+    pub fn settle_fee(
+        &mut self,
+        payer: &str,
+        producer: &str,
+        settlement: &GasSettlement,
+        fee_split: &FeeSplit,
+    ) -> Result<FeeDistribution, QFCError> {
+        let fee = settlement.charged;
+        let distribution = fee_split.apply(fee);
+        let circulating_supply = self.circulating_supply
+            .checked_sub(distribution.burned)
+            .ok_or(QFCError::SupplyUnderflow)?;
+
+        let payer_balance = self.balances.get_mut(payer).ok_or(QFCError::AccountNotFound)?;
+        payer_balance.available = payer_balance.available
+            .checked_sub(fee)
+            .ok_or(QFCError::InsufficientBalance)?;
+        payer_balance.last_transaction = Utc::now();
+
+        let producer_balance = self.balances.entry(producer.to_string()).or_insert_with(Balance::new);
+        producer_balance.available = producer_balance.available
+            .checked_add(distribution.validator_tip)
+            .ok_or(QFCError::SupplyOverflow)?;
+
+        self.circulating_supply = circulating_supply;
+        self.update_metrics()?;
+        Ok(distribution)
+    }
+
+    pub fn balance_of(&self, account: &str) -> Amount {
+        self.balances.get(account).map_or(Amount::ZERO, |b| b.available)
+    }
+
     pub fn tokenize_real_estate(
         &mut self,
         property_id: &str,
@@ -189,7 +391,7 @@ impl QuantumFuseCoin {
         &mut self,
         sender: &str,
         recipient: &str,
-        amount: u64,
+        amount: Amount,
         zkps: &QuantumZK,
     ) -> Result<TransactionRecord, QFCError> {
         let proof = zkps.generate_proof(sender, recipient, amount)?;
@@ -214,6 +416,25 @@ impl QuantumFuseCoin {
         Ok(())
     }
 
+    fn record_transaction(
+        &self,
+        transaction_type: TransactionType,
+        from: &str,
+        to: &str,
+        amount: Amount,
+    ) -> Result<TransactionRecord, QFCError> {
+        Ok(TransactionRecord {
+            hash: self.generate_transaction_hash()?,
+            transaction_type,
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+            timestamp: Utc::now(),
+            status: TransactionStatus::Completed,
+            zk_proof: None,
+        })
+    }
+
     fn generate_transaction_hash(&self) -> Result<Hash, QFCError> {
         let mut hasher = blake3::Hasher::new();
         hasher.update(&Utc::now().timestamp().to_le_bytes());
@@ -225,3 +446,192 @@ impl QuantumFuseCoin {
         Ok(())
     }
 }
+
+impl VestingSchedule {
+    /// Nothing before the cliff, then linear in whole `release_interval` steps until `end_time`.
+    fn vested_at(&self, now: DateTime<Utc>) -> Amount {
+        if now < self.start_time + self.cliff_duration {
+            return Amount::ZERO;
+        }
+        if now >= self.end_time {
+            return self.total_amount;
+        }
+
+        let total_secs = (self.end_time - self.start_time).num_seconds().max(1) as u128;
+        let interval = self.release_interval.num_seconds().max(1);
+        let elapsed = ((now - self.start_time).num_seconds() / interval * interval) as u128;
+        self.total_amount.checked_mul(elapsed)
+            .and_then(|scaled| scaled.checked_div(total_secs))
+            .unwrap_or(self.total_amount)
+    }
+}
+
+const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+impl Bond {
+    /// Principal plus simple interest from issue to maturity, rounded down.
+    fn payout(&self) -> Result<Amount, QFCError> {
+        let term_secs = (self.maturity_date - self.issued_at).num_seconds().max(0) as u128;
+        let interest = self.principal
+            .checked_mul(self.interest_rate_bps as u128 * term_secs)
+            .and_then(|scaled| scaled.checked_div(10_000 * SECONDS_PER_YEAR))
+            .ok_or(QFCError::SupplyOverflow)?;
+        self.principal.checked_add(interest).ok_or(QFCError::SupplyOverflow)
+    }
+}
+
+impl Balance {
+    fn new() -> Self {
+        Self {
+            available: Amount::ZERO,
+            locked: Amount::ZERO,
+            staked: Amount::ZERO,
+            last_transaction: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_many_small_transfers_conserve_supply() {
+        let mut qfc = QuantumFuseCoin::new();
+        let minted = Amount::from_whole(1);
+        qfc.mint("alice", minted).unwrap();
+
+        let step: Amount = "0.000001".parse().unwrap();
+        for i in 0..10_000 {
+            let (from, to) = if i % 3 == 0 { ("alice", "bob") } else { ("alice", "carol") };
+            qfc.transfer(from, to, step).unwrap();
+        }
+        for _ in 0..1_000 {
+            qfc.transfer("carol", "bob", step).unwrap();
+        }
+
+        let total = qfc.balance_of("alice")
+            .checked_add(qfc.balance_of("bob"))
+            .and_then(|sum| sum.checked_add(qfc.balance_of("carol")))
+            .unwrap();
+        assert_eq!(total, minted);
+        assert_eq!(qfc.circulating_supply, minted);
+        assert_eq!(qfc.balance_of("alice"), "0.99".parse().unwrap());
+    }
+
+    #[test]
+    fn test_transfer_rejects_insufficient_balance() {
+        let mut qfc = QuantumFuseCoin::new();
+        qfc.mint("alice", Amount::from_whole(1)).unwrap();
+
+        let result = qfc.transfer("alice", "bob", Amount::from_base_units(Amount::from_whole(1).base_units() + 1));
+        assert!(matches!(result, Err(QFCError::InsufficientBalance)));
+        assert_eq!(qfc.balance_of("alice"), Amount::from_whole(1));
+    }
+
+    /// A coin capped at 100 QFC with 90 already in circulation.
+    fn nearly_capped() -> QuantumFuseCoin {
+        let mut qfc = QuantumFuseCoin::new();
+        qfc.total_supply = Amount::from_whole(100);
+        qfc.mint("treasury", Amount::from_whole(90)).unwrap();
+        qfc
+    }
+
+    #[test]
+    fn test_vesting_claims_respect_supply_cap() {
+        let mut qfc = nearly_capped();
+        let start = Utc::now() - Duration::days(100);
+        qfc.create_vesting_schedule("founder", Amount::from_whole(20), start, Duration::days(100), Duration::zero(), Duration::days(1)).unwrap();
+
+        let result = qfc.claim_vested_tokens("founder", Utc::now());
+        assert!(matches!(result, Err(QFCError::ExceedsMaxSupply)));
+        assert_eq!(qfc.balance_of("founder"), Amount::ZERO);
+        assert_eq!(qfc.vesting_schedules["founder"].released_amount, Amount::ZERO);
+
+        // Half-vested fits under the cap.
+        let mut qfc = nearly_capped();
+        qfc.create_vesting_schedule("founder", Amount::from_whole(20), start, Duration::days(200), Duration::zero(), Duration::days(1)).unwrap();
+        qfc.claim_vested_tokens("founder", Utc::now()).unwrap();
+        assert_eq!(qfc.balance_of("founder"), Amount::from_whole(10));
+        assert_eq!(qfc.circulating_supply, qfc.total_supply);
+        assert!(matches!(qfc.claim_vested_tokens("founder", Utc::now()), Err(QFCError::NothingToClaim)));
+    }
+
+    #[test]
+    fn test_staking_rewards_respect_supply_cap() {
+        let mut qfc = nearly_capped();
+        qfc.staking.insert("validator_1".to_string(), StakingInfo {
+            amount: Amount::from_whole(50),
+            start_time: Utc::now(),
+            unlock_time: Utc::now() + Duration::days(30),
+            rewards_earned: Amount::ZERO,
+            auto_compound: false,
+        });
+
+        qfc.pay_staking_reward("validator_1", Amount::from_whole(4)).unwrap();
+        let result = qfc.pay_staking_reward("validator_1", Amount::from_whole(7));
+        assert!(matches!(result, Err(QFCError::ExceedsMaxSupply)));
+
+        assert_eq!(qfc.staking["validator_1"].rewards_earned, Amount::from_whole(4));
+        assert_eq!(qfc.circulating_supply, Amount::from_whole(94));
+        assert!(matches!(qfc.pay_staking_reward("stranger", Amount::from_whole(1)), Err(QFCError::NotStaking)));
+    }
+
+    #[test]
+    fn test_bond_redemptions_respect_supply_cap() {
+        let mut qfc = nearly_capped();
+        qfc.transfer("treasury", "bondholder", Amount::from_whole(10)).unwrap();
+        let issued_at = Utc::now() - Duration::days(365);
+        qfc.issue_bond("bond-1", "bondholder", Amount::from_whole(10), 1_000, issued_at, issued_at + Duration::days(365)).unwrap();
+        assert_eq!(qfc.circulating_supply, Amount::from_whole(80));
+
+        // Redeeming 11 QFC would take circulation to 101.
+        qfc.mint("treasury", Amount::from_whole(10)).unwrap();
+        let over_cap = qfc.redeem_bond("bond-1", Utc::now());
+        assert!(matches!(over_cap, Err(QFCError::ExceedsMaxSupply)));
+        assert!(matches!(qfc.mint("treasury", Amount::from_whole(11)), Err(QFCError::ExceedsMaxSupply)));
+        assert_eq!(qfc.balance_of("bondholder"), Amount::ZERO);
+        assert!(qfc.bonds.contains_key("bond-1"));
+    }
+
+    #[test]
+    fn test_bond_redemption_pays_the_bonds_terms() {
+        let mut qfc = QuantumFuseCoin::new();
+        qfc.mint("bondholder", Amount::from_whole(1_000)).unwrap();
+        let issued_at = Utc::now() - Duration::days(730);
+        let maturity_date = issued_at + Duration::days(730);
+        qfc.issue_bond("bond-1", "bondholder", Amount::from_whole(1_000), 500, issued_at, maturity_date).unwrap();
+        assert_eq!(qfc.balance_of("bondholder"), Amount::ZERO);
+        assert!(matches!(
+            qfc.issue_bond("bond-1", "bondholder", Amount::ZERO, 500, issued_at, maturity_date),
+            Err(QFCError::BondExists)
+        ));
+
+        assert!(matches!(qfc.redeem_bond("bond-1", maturity_date - Duration::days(1)), Err(QFCError::BondNotMatured)));
+        qfc.redeem_bond("bond-1", maturity_date).unwrap();
+        // Two years at 5%.
+        assert_eq!(qfc.balance_of("bondholder"), Amount::from_whole(1_100));
+        assert_eq!(qfc.circulating_supply, Amount::from_whole(1_100));
+
+        assert!(matches!(qfc.redeem_bond("bond-1", maturity_date), Err(QFCError::BondNotFound)));
+        assert!(matches!(qfc.redeem_bond("bond-2", maturity_date), Err(QFCError::BondNotFound)));
+    }
+
+    #[test]
+    fn test_fee_is_split_between_burn_and_producer_tip() {
+        let mut qfc = QuantumFuseCoin::new();
+        qfc.mint("alice", Amount::from_whole(10)).unwrap();
+        let fee_split = FeeSplit::new(7_000).unwrap();
+        let fee = Amount::from_base_units(1_000_003);
+        let settlement = GasSettlement { gas_used: 21_000, charged: fee, refund: Amount::from_base_units(500) };
+
+        let distribution = qfc.settle_fee("alice", "validator_1", &settlement, &fee_split).unwrap();
+
+        assert_eq!(distribution.burned, Amount::from_base_units(700_002));
+        assert_eq!(distribution.validator_tip, Amount::from_base_units(300_001));
+        assert_eq!(distribution.burned.checked_add(distribution.validator_tip), Some(fee));
+        assert_eq!(qfc.balance_of("validator_1"), distribution.validator_tip);
+        assert_eq!(qfc.balance_of("alice"), Amount::from_whole(10).checked_sub(fee).unwrap());
+        assert_eq!(qfc.circulating_supply, Amount::from_whole(10).checked_sub(distribution.burned).unwrap());
+    }
+}
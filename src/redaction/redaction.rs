@@ -0,0 +1,140 @@
+use std::sync::OnceLock;
+use log::Level;
+use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::transaction::QuantumTransaction;
+
+/// Controls how much of an address, payload or personal record reaches the logs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Characters kept at each end of an address.
+    pub visible_address_chars: usize,
+    /// Log full values at `Debug` and `Trace`. Never enable in production.
+    pub full_detail_at_debug: bool,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            visible_address_chars: 6,
+            full_detail_at_debug: false,
+        }
+    }
+}
+
+/// Formats sensitive values for a log record at a given level.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    config: RedactionConfig,
+}
+
+impl Redactor {
+    pub fn new(config: RedactionConfig) -> Self {
+        Self { config }
+    }
+
+    fn reveals(&self, level: Level) -> bool {
+        self.config.full_detail_at_debug && level >= Level::Debug
+    }
+
+    /// Keeps the first and last few characters, e.g. `qf1abc…89xyz0`.
+    pub fn address(&self, address: &str, level: Level) -> String {
+        let visible = self.config.visible_address_chars;
+        let chars: Vec<char> = address.chars().collect();
+        if self.reveals(level) || chars.len() <= visible * 2 {
+            return address.to_string();
+        }
+        let head: String = chars[..visible].iter().collect();
+        let tail: String = chars[chars.len() - visible..].iter().collect();
+        format!("{}…{}", head, tail)
+    }
+
+    /// Raw bytes such as ciphertexts and contract payloads; only the length is logged.
+    pub fn payload(&self, data: &[u8], level: Level) -> String {
+        if self.reveals(level) {
+            return hex::encode(data);
+        }
+        format!("<redacted {} bytes>", data.len())
+    }
+
+    /// Free-form personal data such as medical or financial details.
+    pub fn sensitive(&self, text: &str, level: Level) -> String {
+        if self.reveals(level) {
+            return text.to_string();
+        }
+        format!("<redacted {} chars>", text.chars().count())
+    }
+
+    pub fn transaction(&self, tx: &QuantumTransaction, level: Level) -> String {
+        format!(
+            "tx {} from {} to {} amount {} {} fee {} payload {}",
+            tx.hash,
+            self.address(&tx.from, level),
+            self.address(&tx.to, level),
+            tx.amount,
+            tx.asset_id,
+            tx.fee,
+            self.payload(&tx.data.payload, level),
+        )
+    }
+}
+
+static REDACTOR: OnceLock<Redactor> = OnceLock::new();
+
+/// Sets the process-wide redactor. Only the first call takes effect.
+pub fn install(config: RedactionConfig) -> bool {
+    REDACTOR.set(Redactor::new(config)).is_ok()
+}
+
+/// Process-wide redactor; fully redacting until `install` is called.
+pub fn redactor() -> &'static Redactor {
+    REDACTOR.get_or_init(Redactor::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quantumfuse_sdk::{amount::Amount, transaction::OperationType};
+
+    fn medical_upload() -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new(
+            "qf1patientwalletaddress0000000000000000".to_string(),
+            "qf1clinicwalletaddress00000000000000000".to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::CallContract,
+            50_000,
+        ).unwrap();
+        tx.data.payload = b"diagnosis: hypertension".to_vec();
+        tx
+    }
+
+    #[test]
+    fn test_transaction_record_redacts_payload() {
+        let tx = medical_upload();
+        let redactor = Redactor::default();
+
+        for level in [Level::Info, Level::Debug, Level::Trace] {
+            let record = redactor.transaction(&tx, level);
+            assert!(record.contains("<redacted 23 bytes>"));
+            assert!(!record.contains(&hex::encode(&tx.data.payload)));
+            assert!(!record.contains(&tx.from));
+            assert!(record.contains("qf1pat…000000"));
+        }
+        assert_eq!(redactor.sensitive("hypertension", Level::Debug), "<redacted 12 chars>");
+    }
+
+    #[test]
+    fn test_full_detail_requires_debug_opt_in() {
+        let tx = medical_upload();
+        let redactor = Redactor::new(RedactionConfig { full_detail_at_debug: true, ..RedactionConfig::default() });
+
+        let info = redactor.transaction(&tx, Level::Info);
+        assert!(info.contains("<redacted 23 bytes>"));
+
+        let debug = redactor.transaction(&tx, Level::Debug);
+        assert!(debug.contains(&hex::encode(&tx.data.payload)));
+        assert!(debug.contains(&tx.from));
+        assert!(debug.contains(&tx.to));
+    }
+}
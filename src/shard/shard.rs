@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
@@ -13,6 +13,7 @@ use quantumfuse_sdk::{
     optimizer::{QuantumAnnealingOptimizer, QuantumRoutingOptimizer},
     metrics::ShardMetrics,
     state::StateAccess,
+    codec::to_canonical_cbor,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,12 +28,23 @@ pub struct QuantumShard {
     pub validators: HashSet<String>,
     pub cross_links: Vec<CrossShardLink>,
     pub quantum_state: QuantumShardState,
+    pub key_range: ShardKeyRange,
+    #[serde(default)]
+    pub cross_shard_inbox: CrossShardInbox,
+}
+
+/// Inclusive range of the address key space (first 8 bytes of `blake3(address)`) owned by a shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardKeyRange {
+    pub start: u64,
+    pub end: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuantumShardState {
     pub capacity: usize,
     pub load_factor: f64,
+    pub overload_threshold: f64,
     pub quantum_security_level: u8,
     pub merkle_root: Hash,
     pub validator_signatures: HashMap<String, Vec<u8>>,
@@ -45,12 +57,41 @@ pub struct CrossShardLink {
     pub transaction_hash: Hash,
     pub timestamp: DateTime<Utc>,
     pub quantum_proof: Vec<u8>,
+    /// Global order of this transaction among all cross-shard transactions.
+    #[serde(default)]
+    pub sequence: u64,
+}
+
+/// A cross-shard transaction as delivered to one of the shards it touches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossShardDelivery {
+    pub shard_id: u64,
+    /// Gap-free position in this shard's cross-shard stream. Positions are handed
+    /// out in global sequence order, so applying by position applies by sequence.
+    pub position: u64,
+    pub link: CrossShardLink,
+    pub transaction: QuantumTransaction,
+}
+
+/// Cross-shard deliveries that arrived ahead of their turn.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrossShardInbox {
+    pub next_position: u64,
+    pub pending: BTreeMap<u64, CrossShardDelivery>,
+}
+
+/// Assigns global sequence numbers and per-shard stream positions.
+#[derive(Debug, Default)]
+struct CrossShardSequencer {
+    next_sequence: u64,
+    next_position: HashMap<u64, u64>,
 }
 
 #[derive(Debug)]
 pub struct ShardAllocator {
     shards: Arc<RwLock<HashMap<u64, QuantumShard>>>,
     metrics: Arc<RwLock<ShardMetrics>>,
+    sequencer: Arc<RwLock<CrossShardSequencer>>,
     config: ShardConfig,
 }
 
@@ -62,6 +103,16 @@ pub struct ShardConfig {
     pub reallocation_threshold: f64,
     pub min_validators_per_shard: usize,
     pub quantum_security_threshold: u8,
+    pub split_load_factor: f64,
+    pub merge_load_factor: f64,
+}
+
+/// Reported after each source shard has been drained during a reshard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReshardProgress {
+    pub source_shard_id: u64,
+    pub migrated_transactions: usize,
+    pub total_transactions: usize,
 }
 
 impl QuantumShard {
@@ -83,19 +134,75 @@ impl QuantumShard {
             quantum_state: QuantumShardState {
                 capacity: 1000000, 
                 load_factor: 0.0,
+                overload_threshold: config.reallocation_threshold,
                 quantum_security_level: 3,
                 merkle_root: Hash::default(),
                 validator_signatures: HashMap::new(),
             },
+            key_range: ShardKeyRange::full(),
+            cross_shard_inbox: CrossShardInbox::default(),
         })
     }
 
     pub fn add_transaction(&mut self, transaction: QuantumTransaction) -> Result<(), ShardError> {
+        if self.is_full() {
+            return Err(ShardError::ShardOverloaded);
+        }
+
         if !transaction.verify()? {
             return Err(ShardError::InvalidTransaction);
         }
 
-        if self.is_overloaded() {
+        self.append_transaction(transaction)
+    }
+
+    /// Buffers the delivery and applies every cross-shard transaction that is now
+    /// next in line, returning their hashes in the order they were applied.
+    /// Intra-shard transactions never wait on this stream.
+    pub fn receive_cross_shard(&mut self, delivery: CrossShardDelivery) -> Result<Vec<Hash>, ShardError> {
+        if delivery.shard_id != self.shard_id {
+            return Err(ShardError::InvalidShardId);
+        }
+        if delivery.position < self.cross_shard_inbox.next_position {
+            // Already applied; redelivery is harmless.
+            return Ok(Vec::new());
+        }
+        self.cross_shard_inbox.pending.insert(delivery.position, delivery);
+
+        let mut applied = Vec::new();
+        while let Some(next) = self.cross_shard_inbox.pending.remove(&self.cross_shard_inbox.next_position) {
+            if self.is_full() {
+                self.cross_shard_inbox.pending.insert(next.position, next);
+                return Err(ShardError::ShardOverloaded);
+            }
+            applied.push(next.transaction.hash);
+            self.cross_links.push(next.link);
+            self.append_transaction(next.transaction)?;
+            self.cross_shard_inbox.next_position += 1;
+        }
+
+        Ok(applied)
+    }
+
+    pub fn is_assigned(&self, validator: &str) -> bool {
+        self.validators.contains(validator)
+    }
+
+    /// Records a validator's signature over this shard's state; only validators
+    /// assigned to the shard may sign for it.
+    pub fn record_validator_signature(&mut self, validator: &str, signature: Vec<u8>) -> Result<(), ShardError> {
+        if !self.is_assigned(validator) {
+            return Err(ShardError::ValidatorNotAssigned {
+                validator: validator.to_string(),
+                shard_id: self.shard_id,
+            });
+        }
+        self.quantum_state.validator_signatures.insert(validator.to_string(), signature);
+        Ok(())
+    }
+
+    fn append_transaction(&mut self, transaction: QuantumTransaction) -> Result<(), ShardError> {
+        if self.is_full() {
             return Err(ShardError::ShardOverloaded);
         }
 
@@ -106,6 +213,15 @@ impl QuantumShard {
         Ok(())
     }
 
+    /// `load_factor` is a ratio of capacity, so it is compared against the configured threshold.
+    pub fn is_overloaded(&self) -> bool {
+        self.quantum_state.load_factor >= self.quantum_state.overload_threshold
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.transactions.len() >= self.quantum_state.capacity
+    }
+
     pub fn optimize_shard_allocation(&mut self) -> Result<(), ShardError> {
         let mut optimizer = QuantumAnnealingOptimizer::new();
         let optimal_allocation = optimizer.optimize_shard_allocation(
@@ -158,7 +274,7 @@ impl QuantumShard {
     fn calculate_merkle_root(&self) -> Result<Hash, ShardError> {
         let mut hasher = blake3::Hasher::new();
         for tx in &self.transactions {
-            hasher.update(&serde_json::to_vec(tx).map_err(|_| ShardError::SerializationError)?);
+            hasher.update(&to_canonical_cbor(tx).map_err(|_| ShardError::SerializationError)?);
         }
 
         Ok(Hash::from(hasher.finalize()))
@@ -197,3 +313,728 @@ impl QuantumShard {
         Ok(())
     }
 }
+
+impl ShardKeyRange {
+    pub fn full() -> Self {
+        Self { start: 0, end: u64::MAX }
+    }
+
+    pub fn contains(&self, key: u64) -> bool {
+        key >= self.start && key <= self.end
+    }
+
+    /// Splits the key space into `count` contiguous ranges of near-equal width,
+    /// so that range `i` holds exactly the keys with `key * count / 2^64 == i`.
+    pub fn partition(count: u64) -> Vec<Self> {
+        let start = |i: u64| -> u128 { ((i as u128) << 64).div_ceil(count as u128) };
+        (0..count)
+            .map(|i| Self {
+                start: start(i) as u64,
+                end: if i + 1 == count { u64::MAX } else { (start(i + 1) - 1) as u64 },
+            })
+            .collect()
+    }
+
+    fn overlaps(&self, other: &ShardKeyRange) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    fn midpoint(&self) -> u64 {
+        self.start + (self.end - self.start) / 2
+    }
+
+    fn is_adjacent(&self, other: &ShardKeyRange) -> bool {
+        self.end.checked_add(1) == Some(other.start) || other.end.checked_add(1) == Some(self.start)
+    }
+}
+
+impl ShardAllocator {
+    pub fn new(config: ShardConfig) -> Result<Self, ShardError> {
+        let mut shards = HashMap::new();
+        shards.insert(0, QuantumShard::new(0, &config)?);
+
+        Ok(Self {
+            shards: Arc::new(RwLock::new(shards)),
+            metrics: Arc::new(RwLock::new(ShardMetrics::default())),
+            sequencer: Arc::new(RwLock::new(CrossShardSequencer::default())),
+            config,
+        })
+    }
+
+    /// Allocates over an existing shard map, such as a chain's `shard_manager`,
+    /// so both see the same layout.
+    pub fn with_shards(config: ShardConfig, shards: Arc<RwLock<HashMap<u64, QuantumShard>>>) -> Self {
+        Self {
+            shards,
+            metrics: Arc::new(RwLock::new(ShardMetrics::default())),
+            sequencer: Arc::new(RwLock::new(CrossShardSequencer::default())),
+            config,
+        }
+    }
+
+    pub fn manages(&self, shards: &Arc<RwLock<HashMap<u64, QuantumShard>>>) -> bool {
+        Arc::ptr_eq(&self.shards, shards)
+    }
+
+    /// Assigns the next global sequence number to a cross-shard transaction and
+    /// returns one delivery for each shard it touches.
+    pub async fn sequence_cross_shard(
+        &self,
+        transaction: QuantumTransaction,
+        source_shard_id: u64,
+        target_shard_id: u64,
+    ) -> Result<Vec<CrossShardDelivery>, ShardError> {
+        if source_shard_id == target_shard_id {
+            return Err(ShardError::InvalidShardId);
+        }
+
+        let mut sequencer = self.sequencer.write().await;
+        let sequence = sequencer.next_sequence;
+        sequencer.next_sequence += 1;
+
+        let link = CrossShardLink {
+            source_shard_id,
+            target_shard_id,
+            transaction_hash: transaction.hash,
+            timestamp: Utc::now(),
+            quantum_proof: Vec::new(),
+            sequence,
+        };
+
+        Ok([source_shard_id, target_shard_id].into_iter().map(|shard_id| {
+            let position = sequencer.next_position.entry(shard_id).or_default();
+            let delivery = CrossShardDelivery {
+                shard_id,
+                position: *position,
+                link: link.clone(),
+                transaction: transaction.clone(),
+            };
+            *position += 1;
+            delivery
+        }).collect())
+    }
+
+    /// Reassigns every shard's validators at an epoch boundary. The order is a
+    /// shuffle of `validators` seeded by the boundary block's `beacon` and the
+    /// epoch, dealt round-robin over the shards in id order, so every node
+    /// computes the same assignment but none can predict it before the beacon.
+    pub async fn assign_validators(&self, epoch: u64, beacon: &Hash, validators: &[String]) -> Result<(), ShardError> {
+        let mut shards = self.shards.write().await;
+        if shards.is_empty() {
+            return Err(ShardError::ShardNotFound);
+        }
+        let required = shards.len() * self.config.min_validators_per_shard;
+        let unique: HashSet<&String> = validators.iter().collect();
+        if unique.len() < required {
+            return Err(ShardError::InsufficientValidators { available: unique.len(), required });
+        }
+
+        let mut shuffled: Vec<&String> = unique.into_iter().collect();
+        shuffled.sort_by_key(|validator| {
+            let mut hasher = blake3::Hasher::new_derive_key("quantumfuse shard assignment v1");
+            hasher.update(beacon.as_bytes());
+            hasher.update(&epoch.to_le_bytes());
+            hasher.update(validator.as_bytes());
+            *hasher.finalize().as_bytes()
+        });
+
+        let mut shard_ids: Vec<u64> = shards.keys().copied().collect();
+        shard_ids.sort_unstable();
+        for shard in shards.values_mut() {
+            shard.validators.clear();
+        }
+        for (i, validator) in shuffled.into_iter().enumerate() {
+            let shard = shards.get_mut(&shard_ids[i % shard_ids.len()]).ok_or(ShardError::ShardNotFound)?;
+            shard.validators.insert(validator.clone());
+        }
+        for shard in shards.values_mut() {
+            shard.update_metrics()?;
+        }
+        Ok(())
+    }
+
+    /// Drops a validator that left the set mid-epoch, returning the shard it served.
+    pub async fn remove_validator(&self, validator: &str) -> Option<u64> {
+        let mut shards = self.shards.write().await;
+        shards.values_mut()
+            .find(|shard| shard.validators.remove(validator))
+            .map(|shard| shard.shard_id)
+    }
+
+    /// Moves validators from shards above `min_validators_per_shard` into shards
+    /// below it. Returns `(validator, from, to)` for each move.
+    pub async fn rebalance_validators(&self) -> Result<Vec<(String, u64, u64)>, ShardError> {
+        let mut shards = self.shards.write().await;
+        let min = self.config.min_validators_per_shard;
+
+        let mut shard_ids: Vec<u64> = shards.keys().copied().collect();
+        shard_ids.sort_unstable();
+
+        let mut moves = Vec::new();
+        for &needy in &shard_ids {
+            while shards[&needy].validators.len() < min {
+                let donor = shard_ids.iter()
+                    .copied()
+                    .filter(|id| shards[id].validators.len() > min)
+                    .max_by_key(|id| (shards[id].validators.len(), std::cmp::Reverse(*id)))
+                    .ok_or_else(|| ShardError::InsufficientValidators {
+                        available: shards.values().map(|s| s.validators.len()).sum(),
+                        required: shards.len() * min,
+                    })?;
+
+                let validator = shards[&donor].validators.iter().min().cloned().ok_or(ShardError::ShardNotFound)?;
+                shards.get_mut(&donor).ok_or(ShardError::ShardNotFound)?.validators.remove(&validator);
+                shards.get_mut(&needy).ok_or(ShardError::ShardNotFound)?.validators.insert(validator.clone());
+                moves.push((validator, donor, needy));
+            }
+        }
+        Ok(moves)
+    }
+
+    /// Errors unless `validator` is assigned to `shard_id`.
+    pub async fn authorize_validator(&self, shard_id: u64, validator: &str) -> Result<(), ShardError> {
+        let shards = self.shards.read().await;
+        let shard = shards.get(&shard_id).ok_or(ShardError::ShardNotFound)?;
+        if !shard.is_assigned(validator) {
+            return Err(ShardError::ValidatorNotAssigned { validator: validator.to_string(), shard_id });
+        }
+        Ok(())
+    }
+
+    /// Splits or merges shards whose load factor crossed the configured thresholds.
+    pub async fn rebalance(&self) -> Result<(), ShardError> {
+        let (overloaded, underutilized) = {
+            let shards = self.shards.read().await;
+            let overloaded: Vec<u64> = shards.values()
+                .filter(|s| s.metrics.load_factor >= self.config.split_load_factor)
+                .map(|s| s.shard_id)
+                .collect();
+            let mut underutilized: Vec<&QuantumShard> = shards.values()
+                .filter(|s| s.metrics.load_factor <= self.config.merge_load_factor)
+                .collect();
+            underutilized.sort_by_key(|s| s.key_range.start);
+            let underutilized: Vec<u64> = underutilized.iter().map(|s| s.shard_id).collect();
+            (overloaded, underutilized)
+        };
+
+        for shard_id in overloaded {
+            self.split_shard(shard_id).await?;
+        }
+
+        for pair in underutilized.chunks(2) {
+            if let [a, b] = pair {
+                match self.merge_shards(*a, *b).await {
+                    Ok(_) | Err(ShardError::ShardsNotAdjacent) | Err(ShardError::MinShardsReached) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Splits a shard's key range in half, moving the upper half's transactions into a new shard.
+    pub async fn split_shard(&self, shard_id: u64) -> Result<u64, ShardError> {
+        let mut shards = self.shards.write().await;
+
+        if shards.len() as u64 >= self.config.max_shards {
+            return Err(ShardError::MaxShardsReached);
+        }
+
+        let new_shard_id = (0..self.config.max_shards)
+            .find(|id| !shards.contains_key(id))
+            .ok_or(ShardError::MaxShardsReached)?;
+
+        let source = shards.get_mut(&shard_id).ok_or(ShardError::ShardNotFound)?;
+        if source.key_range.start == source.key_range.end {
+            return Err(ShardError::ShardNotSplittable);
+        }
+
+        let midpoint = source.key_range.midpoint();
+        let mut child = QuantumShard::new(new_shard_id, &self.config)?;
+        child.parent_shard_id = Some(shard_id);
+        child.key_range = ShardKeyRange { start: midpoint + 1, end: source.key_range.end };
+        child.validators = source.validators.clone();
+        child.quantum_state.capacity = source.quantum_state.capacity;
+        source.key_range.end = midpoint;
+
+        let (kept, moved): (Vec<_>, Vec<_>) = source.transactions
+            .drain(..)
+            .partition(|tx| address_key(&tx.from) <= midpoint);
+        source.transactions = kept;
+        child.transactions = moved;
+
+        let moved_hashes: HashSet<Hash> = child.transactions.iter().map(|tx| tx.hash).collect();
+        let (kept_links, moved_links): (Vec<_>, Vec<_>) = source.cross_links
+            .drain(..)
+            .partition(|link| !moved_hashes.contains(&link.transaction_hash));
+        source.cross_links = kept_links;
+        child.cross_links = moved_links;
+
+        source.update_metrics()?;
+        source.update_quantum_state()?;
+        child.update_metrics()?;
+        child.update_quantum_state()?;
+        shards.insert(new_shard_id, child);
+
+        Self::rewrite_cross_links(&mut shards, shard_id, new_shard_id, Some(&moved_hashes));
+        Ok(new_shard_id)
+    }
+
+    /// Merges shard `b` into shard `a`. The two shards must own adjacent key ranges.
+    pub async fn merge_shards(&self, a: u64, b: u64) -> Result<u64, ShardError> {
+        let mut shards = self.shards.write().await;
+
+        if a == b {
+            return Err(ShardError::InvalidShardId);
+        }
+        if (shards.len() as u64) <= self.config.min_shards {
+            return Err(ShardError::MinShardsReached);
+        }
+
+        let absorbed_range = shards.get(&b).ok_or(ShardError::ShardNotFound)?.key_range;
+        let target_range = shards.get(&a).ok_or(ShardError::ShardNotFound)?.key_range;
+        if !target_range.is_adjacent(&absorbed_range) {
+            return Err(ShardError::ShardsNotAdjacent);
+        }
+
+        let absorbed = shards.remove(&b).ok_or(ShardError::ShardNotFound)?;
+        let target = shards.get_mut(&a).ok_or(ShardError::ShardNotFound)?;
+        target.key_range = ShardKeyRange {
+            start: target_range.start.min(absorbed_range.start),
+            end: target_range.end.max(absorbed_range.end),
+        };
+        target.transactions.extend(absorbed.transactions);
+        target.cross_links.extend(absorbed.cross_links);
+        target.validators.extend(absorbed.validators);
+        target.update_metrics()?;
+        target.update_quantum_state()?;
+
+        Self::rewrite_cross_links(&mut shards, b, a, None);
+        Ok(a)
+    }
+
+    /// Shard that owns `address` under the current layout.
+    pub async fn calculate_shard_id(&self, address: &str) -> Result<u64, ShardError> {
+        let key = address_key(address);
+        let shards = self.shards.read().await;
+        shards.values()
+            .find(|shard| shard.key_range.contains(key))
+            .map(|shard| shard.shard_id)
+            .ok_or(ShardError::ShardNotFound)
+    }
+
+    /// Replaces the current layout with `new_count` shards over equal key ranges
+    /// and moves every transaction to the shard that owns its sender. Holds the
+    /// write lock throughout, so readers see either the old layout or the new one.
+    /// Each new shard keeps the validators of the old shards its range overlaps.
+    pub async fn reshard(
+        &self,
+        new_count: u64,
+        mut on_progress: impl FnMut(ReshardProgress),
+    ) -> Result<(), ShardError> {
+        if new_count == 0 || new_count < self.config.min_shards {
+            return Err(ShardError::MinShardsReached);
+        }
+        if new_count > self.config.max_shards {
+            return Err(ShardError::MaxShardsReached);
+        }
+
+        let mut shards = self.shards.write().await;
+        // Inbox positions are per shard id and don't survive a new layout.
+        if shards.values().any(|shard| !shard.cross_shard_inbox.pending.is_empty()) {
+            return Err(ShardError::PendingCrossShardDeliveries);
+        }
+
+        let ranges = ShardKeyRange::partition(new_count);
+        let owner = |key: u64| shard_for_key(key, new_count);
+        let capacity = shards.values().map(|s| s.quantum_state.capacity).max().unwrap_or_default();
+
+        let mut resharded = BTreeMap::new();
+        for (shard_id, range) in (0..new_count).zip(&ranges) {
+            let mut shard = QuantumShard::new(shard_id, &self.config)?;
+            shard.key_range = *range;
+            shard.quantum_state.capacity = capacity;
+            shard.validators = shards.values()
+                .filter(|old| old.key_range.overlaps(range))
+                .flat_map(|old| old.validators.iter().cloned())
+                .collect();
+            resharded.insert(shard_id, shard);
+        }
+
+        let mut old_ids: Vec<u64> = shards.keys().copied().collect();
+        old_ids.sort_unstable_by_key(|id| shards[id].key_range.start);
+        let total_transactions: usize = shards.values().map(|s| s.transactions.len()).sum();
+        let mut migrated_transactions = 0;
+
+        for old_id in old_ids {
+            let old = shards.remove(&old_id).ok_or(ShardError::ShardNotFound)?;
+            let mut homes = HashMap::new();
+            for tx in old.transactions {
+                let home = owner(address_key(&tx.from));
+                homes.insert(tx.hash, (home, owner(address_key(&tx.to))));
+                resharded.get_mut(&home).ok_or(ShardError::ShardNotFound)?.transactions.push(tx);
+                migrated_transactions += 1;
+            }
+            for mut link in old.cross_links {
+                // A link travels with its transaction; one without falls to whoever owns the old range.
+                let home = match homes.get(&link.transaction_hash) {
+                    Some(&(source, target)) => {
+                        link.source_shard_id = source;
+                        link.target_shard_id = target;
+                        source
+                    }
+                    None => owner(old.key_range.start),
+                };
+                resharded.get_mut(&home).ok_or(ShardError::ShardNotFound)?.cross_links.push(link);
+            }
+            on_progress(ReshardProgress { source_shard_id: old_id, migrated_transactions, total_transactions });
+        }
+
+        for (shard_id, mut shard) in resharded {
+            shard.update_metrics()?;
+            shard.update_quantum_state()?;
+            shards.insert(shard_id, shard);
+        }
+        self.sequencer.write().await.next_position.clear();
+        Ok(())
+    }
+
+    /// Repoints cross-links from `old_id` to `new_id`, optionally only for the given transactions.
+    fn rewrite_cross_links(
+        shards: &mut HashMap<u64, QuantumShard>,
+        old_id: u64,
+        new_id: u64,
+        only: Option<&HashSet<Hash>>,
+    ) {
+        for shard in shards.values_mut() {
+            for link in shard.cross_links.iter_mut() {
+                if only.map_or(false, |hashes| !hashes.contains(&link.transaction_hash)) {
+                    continue;
+                }
+                if link.source_shard_id == old_id {
+                    link.source_shard_id = new_id;
+                }
+                if link.target_shard_id == old_id {
+                    link.target_shard_id = new_id;
+                }
+            }
+        }
+    }
+}
+
+pub fn address_key(address: &str) -> u64 {
+    let hash = blake3::hash(address.as_bytes());
+    u64::from_le_bytes(hash.as_bytes()[0..8].try_into().unwrap())
+}
+
+/// Shard that owns `address` when the key space is split into `shard_count`
+/// equal ranges, as `ShardKeyRange::partition` and `reshard` lay it out.
+pub fn shard_for_address(address: &str, shard_count: u64) -> u64 {
+    shard_for_key(address_key(address), shard_count)
+}
+
+fn shard_for_key(key: u64, shard_count: u64) -> u64 {
+    ((key as u128 * shard_count.max(1) as u128) >> 64) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quantumfuse_sdk::{amount::Amount, transaction::OperationType};
+
+    fn test_config() -> ShardConfig {
+        ShardConfig {
+            min_shards: 1,
+            max_shards: 8,
+            target_load_factor: 0.7,
+            reallocation_threshold: 0.9,
+            min_validators_per_shard: 1,
+            quantum_security_threshold: 3,
+            split_load_factor: 0.75,
+            merge_load_factor: 0.1,
+        }
+    }
+
+    fn test_transaction(from: &str) -> QuantumTransaction {
+        QuantumTransaction::new(
+            from.to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap()
+    }
+
+    async fn deliveries_for(allocator: &ShardAllocator, shard_id: u64, senders: &[&str]) -> Vec<CrossShardDelivery> {
+        let mut deliveries = Vec::new();
+        for sender in senders {
+            let sequenced = allocator.sequence_cross_shard(test_transaction(sender), 0, 1).await.unwrap();
+            deliveries.extend(sequenced.into_iter().filter(|d| d.shard_id == shard_id));
+        }
+        deliveries
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_cross_shard_applied_in_sequence() {
+        let allocator = ShardAllocator::new(test_config()).unwrap();
+        let deliveries = deliveries_for(&allocator, 1, &["a", "b", "c"]).await;
+        let expected: Vec<Hash> = deliveries.iter().map(|d| d.transaction.hash).collect();
+
+        let mut shard = QuantumShard::new(1, &test_config()).unwrap();
+        assert!(shard.receive_cross_shard(deliveries[2].clone()).unwrap().is_empty());
+        assert!(shard.receive_cross_shard(deliveries[1].clone()).unwrap().is_empty());
+        assert!(shard.transactions.is_empty());
+
+        let applied = shard.receive_cross_shard(deliveries[0].clone()).unwrap();
+        assert_eq!(applied, expected);
+        assert_eq!(shard.transactions.iter().map(|tx| tx.hash).collect::<Vec<_>>(), expected);
+        assert_eq!(shard.cross_links.iter().map(|l| l.sequence).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(shard.cross_shard_inbox.pending.is_empty());
+
+        // A redelivered transaction is not applied twice.
+        assert!(shard.receive_cross_shard(deliveries[0].clone()).unwrap().is_empty());
+        assert_eq!(shard.transactions.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_intra_shard_transactions_do_not_wait_on_cross_shard_gap() {
+        let allocator = ShardAllocator::new(test_config()).unwrap();
+        let deliveries = deliveries_for(&allocator, 1, &["a", "b"]).await;
+
+        let mut shard = QuantumShard::new(1, &test_config()).unwrap();
+        shard.receive_cross_shard(deliveries[1].clone()).unwrap();
+        shard.append_transaction(test_transaction("local")).unwrap();
+        assert_eq!(shard.transactions.len(), 1);
+        assert_eq!(shard.transactions[0].from, "local");
+
+        shard.receive_cross_shard(deliveries[0].clone()).unwrap();
+        let order: Vec<&str> = shard.transactions.iter().map(|tx| tx.from.as_str()).collect();
+        assert_eq!(order, vec!["local", "a", "b"]);
+    }
+
+    async fn three_shards_with_min(min_validators_per_shard: usize) -> ShardAllocator {
+        let allocator = ShardAllocator::new(ShardConfig { min_validators_per_shard, ..test_config() }).unwrap();
+        allocator.split_shard(0).await.unwrap();
+        allocator.split_shard(0).await.unwrap();
+        allocator
+    }
+
+    fn validator_set(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("validator-{}", i)).collect()
+    }
+
+    fn beacon(seed: u8) -> Hash {
+        Hash::from([seed; 32])
+    }
+
+    async fn assignment(allocator: &ShardAllocator) -> BTreeMap<u64, Vec<String>> {
+        allocator.shards.read().await.iter()
+            .map(|(id, shard)| {
+                let mut validators: Vec<String> = shard.validators.iter().cloned().collect();
+                validators.sort();
+                (*id, validators)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_validator_assignment_follows_the_beacon() {
+        let allocator = three_shards_with_min(2).await;
+        allocator.assign_validators(1, &beacon(1), &validator_set(12)).await.unwrap();
+        let first = assignment(&allocator).await;
+
+        allocator.assign_validators(1, &beacon(1), &validator_set(12)).await.unwrap();
+        assert_eq!(assignment(&allocator).await, first);
+
+        // Same epoch, different beacon: the shuffle can't be computed ahead of the block.
+        allocator.assign_validators(1, &beacon(2), &validator_set(12)).await.unwrap();
+        assert_ne!(assignment(&allocator).await, first);
+    }
+
+    #[tokio::test]
+    async fn test_every_shard_gets_minimum_validators() {
+        let allocator = three_shards_with_min(2).await;
+        allocator.assign_validators(1, &beacon(1), &validator_set(7)).await.unwrap();
+
+        let shards = allocator.shards.read().await;
+        assert!(shards.values().all(|shard| shard.validators.len() >= 2));
+        assert_eq!(shards.values().map(|shard| shard.validators.len()).sum::<usize>(), 7);
+        drop(shards);
+
+        let result = allocator.assign_validators(2, &beacon(2), &validator_set(5)).await;
+        assert!(matches!(result, Err(ShardError::InsufficientValidators { available: 5, required: 6 })));
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_refills_shard_below_minimum() {
+        let allocator = three_shards_with_min(2).await;
+        allocator.assign_validators(1, &beacon(1), &validator_set(7)).await.unwrap();
+
+        let departing = {
+            let shards = allocator.shards.read().await;
+            let shard = shards.values().find(|shard| shard.validators.len() == 2).unwrap();
+            shard.validators.iter().next().cloned().unwrap()
+        };
+        let depleted = allocator.remove_validator(&departing).await.unwrap();
+
+        let moves = allocator.rebalance_validators().await.unwrap();
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].2, depleted);
+        let shards = allocator.shards.read().await;
+        assert!(shards.values().all(|shard| shard.validators.len() >= 2));
+    }
+
+    #[tokio::test]
+    async fn test_validator_cannot_validate_unassigned_shard() {
+        let allocator = three_shards_with_min(1).await;
+        allocator.assign_validators(1, &beacon(1), &validator_set(3)).await.unwrap();
+
+        let (home, validator) = {
+            let shards = allocator.shards.read().await;
+            let shard = shards.values().next().unwrap();
+            (shard.shard_id, shard.validators.iter().next().cloned().unwrap())
+        };
+        let foreign = {
+            let shards = allocator.shards.read().await;
+            shards.keys().copied().find(|id| *id != home).unwrap()
+        };
+
+        allocator.authorize_validator(home, &validator).await.unwrap();
+        assert!(matches!(
+            allocator.authorize_validator(foreign, &validator).await,
+            Err(ShardError::ValidatorNotAssigned { .. })
+        ));
+
+        let mut shards = allocator.shards.write().await;
+        let shard = shards.get_mut(&foreign).unwrap();
+        assert!(shard.record_validator_signature(&validator, vec![1, 2, 3]).is_err());
+        assert!(shard.quantum_state.validator_signatures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_overloaded_shard_splits() {
+        let allocator = ShardAllocator::new(test_config()).unwrap();
+        {
+            let mut shards = allocator.shards.write().await;
+            let shard = shards.get_mut(&0).unwrap();
+            shard.quantum_state.capacity = 8;
+            for i in 0..8 {
+                shard.transactions.push(test_transaction(&format!("sender_{}", i)));
+            }
+            shard.update_metrics().unwrap();
+        }
+
+        allocator.rebalance().await.unwrap();
+
+        let shards = allocator.shards.read().await;
+        assert_eq!(shards.len(), 2);
+        let total: usize = shards.values().map(|s| s.transactions.len()).sum();
+        assert_eq!(total, 8);
+        for shard in shards.values() {
+            assert!(shard.transactions.iter().all(|tx| shard.key_range.contains(address_key(&tx.from))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idle_shards_merge() {
+        let allocator = ShardAllocator::new(test_config()).unwrap();
+        {
+            let mut shards = allocator.shards.write().await;
+            let shard = shards.get_mut(&0).unwrap();
+            for i in 0..4 {
+                shard.transactions.push(test_transaction(&format!("sender_{}", i)));
+            }
+        }
+        let new_id = allocator.split_shard(0).await.unwrap();
+
+        allocator.rebalance().await.unwrap();
+
+        let shards = allocator.shards.read().await;
+        assert_eq!(shards.len(), 1);
+        assert!(!shards.contains_key(&new_id));
+        assert_eq!(shards[&0].transactions.len(), 4);
+        assert_eq!(shards[&0].key_range, ShardKeyRange::full());
+    }
+
+    #[tokio::test]
+    async fn test_reshard_preserves_transactions() {
+        let allocator = ShardAllocator::new(test_config()).unwrap();
+        allocator.reshard(4, |_| {}).await.unwrap();
+        {
+            let mut shards = allocator.shards.write().await;
+            for i in 0..64 {
+                let tx = test_transaction(&format!("sender_{}", i));
+                let owner = shards.values().find(|s| s.key_range.contains(address_key(&tx.from))).unwrap().shard_id;
+                shards.get_mut(&owner).unwrap().transactions.push(tx);
+            }
+        }
+        let before: HashSet<Hash> = {
+            let shards = allocator.shards.read().await;
+            shards.values().flat_map(|s| s.transactions.iter().map(|tx| tx.hash)).collect()
+        };
+
+        let mut progress = Vec::new();
+        allocator.reshard(8, |p| progress.push(p)).await.unwrap();
+
+        assert_eq!(progress.len(), 4);
+        assert_eq!(progress.last().unwrap().migrated_transactions, 64);
+        assert_eq!(progress.last().unwrap().total_transactions, 64);
+
+        let shards = allocator.shards.read().await;
+        assert_eq!(shards.len(), 8);
+        let after: HashSet<Hash> = shards.values().flat_map(|s| s.transactions.iter().map(|tx| tx.hash)).collect();
+        assert_eq!(after, before);
+        for shard in shards.values() {
+            assert!(shard.transactions.iter().all(|tx| shard.key_range.contains(address_key(&tx.from))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calculate_shard_id_uses_new_count() {
+        let allocator = ShardAllocator::new(test_config()).unwrap();
+        allocator.reshard(4, |_| {}).await.unwrap();
+        let ranges = ShardKeyRange::partition(8);
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges[7].end, u64::MAX);
+        assert!(ranges.windows(2).all(|pair| pair[0].end + 1 == pair[1].start));
+
+        allocator.reshard(8, |_| {}).await.unwrap();
+
+        let mut seen = HashSet::new();
+        for i in 0..64 {
+            let address = format!("account_{}", i);
+            let shard_id = allocator.calculate_shard_id(&address).await.unwrap();
+            let expected = ranges.iter().position(|r| r.contains(address_key(&address))).unwrap() as u64;
+            assert_eq!(shard_id, expected);
+            assert_eq!(shard_for_address(&address, 8), expected);
+            seen.insert(shard_id);
+        }
+        assert!(seen.iter().any(|id| *id >= 4));
+        assert!(matches!(allocator.reshard(9, |_| {}).await, Err(ShardError::MaxShardsReached)));
+    }
+
+    #[test]
+    fn test_full_shard_is_overloaded_and_rejects_transactions() {
+        let mut shard = QuantumShard::new(0, &test_config()).unwrap();
+        shard.quantum_state.capacity = 4;
+        for i in 0..3 {
+            shard.transactions.push(test_transaction(&format!("sender_{}", i)));
+        }
+        shard.update_metrics().unwrap();
+        shard.update_quantum_state().unwrap();
+        assert!(!shard.is_overloaded());
+        assert!(!shard.is_full());
+
+        shard.transactions.push(test_transaction("sender_3"));
+        shard.update_metrics().unwrap();
+        shard.update_quantum_state().unwrap();
+        assert!(shard.is_overloaded());
+        assert!(shard.is_full());
+
+        let result = shard.add_transaction(test_transaction("sender_4"));
+        assert!(matches!(result, Err(ShardError::ShardOverloaded)));
+        assert_eq!(shard.transactions.len(), 4);
+    }
+}
@@ -1,41 +1,467 @@
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, PoisonError, RwLock};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::sync::mpsc::error::TrySendError;
+use log::{warn, Level};
 use quantumfuse_sdk::{
     wallet::QuantumWallet,
-    transaction::Transaction,
+    transaction::{Transaction, QuantumTransaction, AssetId, AccountView, FeeSplit, FeeDistribution, GasSettlement, StateOverrides},
+    error::TransactionError,
+    amount::Amount,
     error::StateError,
     pqc::dilithium::{DilithiumKeyPair, Signature},
     pqc::kyber1024::{KyberCiphertext, KyberKeyPair},
     zkps::QuantumZK,
     consensus::{Block, BlockHeader},
-    blockchain::StateProof,
     ai::NetworkPredictor,
-    mempool::MempoolTransaction
+    crypto::Hash,
+    codec::to_canonical_cbor,
+    address::Address,
+    redaction::redactor,
 };
 
 // 🔹 **State Change Events**
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum StateEvent {
-    BalanceUpdate { wallet_id: String, new_balance: f64 },
+    BalanceUpdate { wallet_id: String, new_balance: Amount },
     StakeUpdate { wallet_id: String, staked_amount: f64 },
     NewBlock { header: BlockHeader },
     TpsUpdate { current: u64, predicted: u64 },
     MempoolChange { size: usize, avg_fee: f64 },
+    /// The canonical chain switched branches; state derived from blocks after
+    /// `common_ancestor` must be refetched.
+    Reorg { common_ancestor: u64, old_tip: u64, new_tip: u64 },
+}
+
+// 🔹 **Transaction Receipts**
+/// Buffered receipts per subscriber; a subscriber that falls this far behind misses receipts.
+const RECEIPT_BUFFER: usize = 256;
+/// Receipts remembered for waiters that arrive just after their transaction confirmed.
+const RECENT_RECEIPTS: usize = 4096;
+/// Blocks whose changes are kept for undoing in a reorg.
+const MAX_UNDO_BLOCKS: usize = 1_000;
+/// Open `wait_for_transaction` waiters across all hashes.
+const MAX_TRANSACTION_WAITERS: usize = 10_000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    pub transaction_hash: Hash,
+    pub block_height: u64,
+    /// Position of the transaction within its block.
+    pub index: usize,
+    pub from: String,
+    pub to: String,
+}
+
+/// Per-address receipt subscriptions. Filtering happens here, so a
+/// subscriber only ever receives receipts naming its own address.
+#[derive(Debug, Default)]
+pub struct ReceiptHub {
+    subscribers: RwLock<HashMap<String, Vec<mpsc::Sender<TransactionReceipt>>>>,
+    transactions: RwLock<TransactionWaiters>,
+}
+
+/// One-shot waiters keyed by transaction hash, plus the most recent receipts.
+#[derive(Debug, Default)]
+struct TransactionWaiters {
+    waiting: HashMap<Hash, Vec<oneshot::Sender<TransactionReceipt>>>,
+    /// Senders held in `waiting`, open or not.
+    pending: usize,
+    recent: HashMap<Hash, TransactionReceipt>,
+    order: VecDeque<Hash>,
+}
+
+impl TransactionWaiters {
+    /// Drops senders whose receiver has gone, for `hash` or for every hash.
+    fn prune_closed(&mut self, hash: Option<&Hash>) {
+        let mut pruned = 0;
+        self.waiting.retain(|waiting_on, senders| {
+            if hash.map_or(true, |hash| hash == waiting_on) {
+                let before = senders.len();
+                senders.retain(|sender| !sender.is_closed());
+                pruned += before - senders.len();
+            }
+            !senders.is_empty()
+        });
+        self.pending -= pruned;
+    }
+}
+
+impl ReceiptHub {
+    pub fn subscribe(&self, address: &str) -> Result<mpsc::Receiver<TransactionReceipt>, StateError> {
+        let (sender, receiver) = mpsc::channel(RECEIPT_BUFFER);
+        self.subscribers.write().map_err(|_| StateError::LockError)?
+            .entry(address.to_string())
+            .or_default()
+            .push(sender);
+        Ok(receiver)
+    }
+
+    /// Resolves with the transaction's receipt once it is published, or at once
+    /// if it was published recently. Fails with `TooManyWaiters` once
+    /// `MAX_TRANSACTION_WAITERS` are open.
+    pub fn wait_for_transaction(&self, hash: Hash) -> Result<oneshot::Receiver<TransactionReceipt>, StateError> {
+        let (sender, receiver) = oneshot::channel();
+        let mut transactions = self.transactions.write().map_err(|_| StateError::LockError)?;
+        if let Some(receipt) = transactions.recent.get(&hash) {
+            let _ = sender.send(receipt.clone());
+            return Ok(receiver);
+        }
+
+        transactions.prune_closed(Some(&hash));
+        if transactions.pending >= MAX_TRANSACTION_WAITERS {
+            transactions.prune_closed(None);
+            if transactions.pending >= MAX_TRANSACTION_WAITERS {
+                return Err(StateError::TooManyWaiters);
+            }
+        }
+        transactions.waiting.entry(hash).or_default().push(sender);
+        transactions.pending += 1;
+        Ok(receiver)
+    }
+
+    /// Forgets waiters on `hash` whose receivers were dropped, e.g. after a timeout.
+    pub fn release_waiters(&self, hash: &Hash) -> Result<(), StateError> {
+        self.transactions.write().map_err(|_| StateError::LockError)?.prune_closed(Some(hash));
+        Ok(())
+    }
+
+    /// Delivers to subscribers of the sender and recipient and to waiters on
+    /// the transaction, dropping closed subscriptions.
+    pub fn publish(&self, receipt: &TransactionReceipt) -> Result<(), StateError> {
+        {
+            let mut transactions = self.transactions.write().map_err(|_| StateError::LockError)?;
+            let waiters = transactions.waiting.remove(&receipt.transaction_hash).unwrap_or_default();
+            transactions.pending -= waiters.len();
+            for waiter in waiters {
+                let _ = waiter.send(receipt.clone());
+            }
+            if transactions.recent.insert(receipt.transaction_hash, receipt.clone()).is_none() {
+                transactions.order.push_back(receipt.transaction_hash);
+            }
+            while transactions.order.len() > RECENT_RECEIPTS {
+                if let Some(oldest) = transactions.order.pop_front() {
+                    transactions.recent.remove(&oldest);
+                }
+            }
+        }
+
+        let mut subscribers = self.subscribers.write().map_err(|_| StateError::LockError)?;
+
+        let mut addresses = vec![&receipt.from];
+        if receipt.to != receipt.from {
+            addresses.push(&receipt.to);
+        }
+
+        for address in addresses {
+            let Some(senders) = subscribers.get_mut(address) else { continue };
+            senders.retain(|sender| match sender.try_send(receipt.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    warn!(
+                        "Receipt subscriber for {} is lagging; dropped receipt at height {}",
+                        redactor().address(address, Level::Warn),
+                        receipt.block_height
+                    );
+                    true
+                }
+                Err(TrySendError::Closed(_)) => false,
+            });
+            if senders.is_empty() {
+                subscribers.remove(address);
+            }
+        }
+        Ok(())
+    }
 }
 
 // 🔹 **Quantum State Manager**
 #[derive(Debug)]
 pub struct QuantumStateManager {
     wallets: Arc<RwLock<HashMap<String, QuantumWallet>>>,
-    mempool: Arc<RwLock<Vec<MempoolTransaction>>>,
+    mempool: Arc<RwLock<Vec<QuantumTransaction>>>,
+    /// Next nonce each account's transactions must carry.
+    nonces: Arc<RwLock<HashMap<String, u64>>>,
     blocks: Arc<RwLock<Vec<Block>>>,
     tx_sender: broadcast::Sender<StateEvent>,
     metrics: Arc<RwLock<NetworkMetrics>>,
-    state_root: Arc<RwLock<StateProof>>,
+    /// Last `calculate_state_root`, refreshed after every state change.
+    state_root: Arc<RwLock<Hash>>,
     ai_predictor: Arc<RwLock<NetworkPredictor>>,
+    history: Arc<RwLock<BalanceHistory>>,
+    receipts: Arc<ReceiptHub>,
+    contracts: Arc<RwLock<HashMap<String, DeployedContract>>>,
+    undo: Arc<RwLock<UndoLog>>,
+}
+
+// 🔹 **Deployed Contracts**
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployedContract {
+    pub address: String,
+    pub deployer: String,
+    pub code: Vec<u8>,
+    pub code_hash: Hash,
+}
+
+// 🔹 **Historical Balances**
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateHistoryConfig {
+    /// A full balance checkpoint is kept every `checkpoint_interval` blocks.
+    pub checkpoint_interval: u64,
+    /// Keep at least the most recent `n` blocks of history; older blocks are
+    /// dropped a checkpoint at a time. `None` keeps everything.
+    #[serde(default)]
+    pub retained_blocks: Option<u64>,
+}
+
+impl Default for StateHistoryConfig {
+    fn default() -> Self {
+        Self { checkpoint_interval: 1_000, retained_blocks: None }
+    }
+}
+
+/// Native balances by height: periodic full checkpoints plus, for every block,
+/// the post-block balances of the accounts it touched.
+#[derive(Debug, Clone, Default)]
+pub struct BalanceHistory {
+    config: StateHistoryConfig,
+    checkpoints: BTreeMap<u64, HashMap<String, Amount>>,
+    block_writes: BTreeMap<u64, HashMap<String, Amount>>,
+    latest_height: Option<u64>,
+    /// Lowest height still answerable once older blocks are pruned.
+    earliest_height: u64,
+}
+
+impl BalanceHistory {
+    pub fn new(config: StateHistoryConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Records a block's balance writes. `full_state` is only read on checkpoint heights.
+    pub fn record_block(
+        &mut self,
+        height: u64,
+        writes: HashMap<String, Amount>,
+        full_state: impl FnOnce() -> HashMap<String, Amount>,
+    ) -> Result<(), StateError> {
+        if self.latest_height.is_some_and(|latest| height <= latest) {
+            return Err(StateError::NonSequentialHeight(height));
+        }
+
+        if height % self.config.checkpoint_interval.max(1) == 0 {
+            self.checkpoints.insert(height, full_state());
+        }
+        self.block_writes.insert(height, writes);
+        self.latest_height = Some(height);
+        self.prune(height);
+        Ok(())
+    }
+
+    /// Drops everything before the newest checkpoint that still leaves
+    /// `retained_blocks` answerable, so lookups from there on start at a checkpoint.
+    fn prune(&mut self, tip: u64) {
+        let Some(retained) = self.config.retained_blocks else {
+            return;
+        };
+        let keep_from = (tip + 1).saturating_sub(retained);
+        let Some(&base) = self.checkpoints.range(..=keep_from).next_back().map(|(height, _)| height) else {
+            return;
+        };
+        if base <= self.earliest_height {
+            return;
+        }
+
+        self.checkpoints = self.checkpoints.split_off(&base);
+        self.block_writes = self.block_writes.split_off(&(base + 1));
+        self.earliest_height = base;
+    }
+
+    /// Balance as of the end of block `height`.
+    pub fn balance_at(&self, address: &str, height: u64) -> Result<Amount, StateError> {
+        if self.latest_height.map_or(true, |latest| height > latest) || height < self.earliest_height {
+            return Err(StateError::HeightNotFound(height));
+        }
+
+        let (start, mut balance) = match self.checkpoints.range(..=height).next_back() {
+            Some((&checkpoint, balances)) => {
+                (checkpoint + 1, balances.get(address).copied().unwrap_or(Amount::ZERO))
+            }
+            None => (0, Amount::ZERO),
+        };
+
+        for writes in self.block_writes.range(start..=height).map(|(_, writes)| writes) {
+            if let Some(&written) = writes.get(address) {
+                balance = written;
+            }
+        }
+        Ok(balance)
+    }
+
+    pub fn latest_height(&self) -> Option<u64> {
+        self.latest_height
+    }
+
+    /// Forgets every block after `height` and returns, for each address those
+    /// blocks wrote, its balance as of `height`.
+    pub fn revert_to(&mut self, height: u64) -> Result<HashMap<String, Amount>, StateError> {
+        let Some(latest) = self.latest_height else {
+            return Ok(HashMap::new());
+        };
+        if height > latest || height < self.earliest_height {
+            return Err(StateError::HeightNotFound(height));
+        }
+
+        let addresses: HashSet<&String> = self.block_writes.range(height + 1..)
+            .flat_map(|(_, writes)| writes.keys())
+            .collect();
+        let restored = addresses.into_iter()
+            .map(|address| Ok((address.clone(), self.balance_at(address, height)?)))
+            .collect::<Result<HashMap<_, _>, StateError>>()?;
+
+        self.block_writes.split_off(&(height + 1));
+        self.checkpoints.split_off(&(height + 1));
+        self.latest_height = Some(height);
+        Ok(restored)
+    }
+}
+
+// 🔹 **Block Undo Log**
+/// The parts of a wallet that blocks change.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct WalletBalances {
+    balances: HashMap<AssetId, Amount>,
+    staked: HashMap<AssetId, Amount>,
+}
+
+impl WalletBalances {
+    fn of(wallet: &QuantumWallet) -> Self {
+        Self { balances: wallet.balances.clone(), staked: wallet.staked_balances.clone() }
+    }
+
+    fn apply_to(self, wallet: &mut QuantumWallet) {
+        wallet.balances = self.balances;
+        wallet.staked_balances = self.staked;
+    }
+}
+
+/// What one block changed: the balances of every wallet it touched as they
+/// were before the block (`None` if the block created the wallet) and the
+/// contracts it deployed. Returned by `begin_block` and handed back to `end_block`.
+#[derive(Debug, Clone, Default)]
+pub struct BlockUndo {
+    wallets: HashMap<String, Option<WalletBalances>>,
+    contracts_before: HashSet<String>,
+    deployed: Vec<String>,
+}
+
+/// Undo records for the last `MAX_UNDO_BLOCKS` blocks, by height.
+#[derive(Debug, Clone, Default)]
+struct UndoLog {
+    blocks: BTreeMap<u64, BlockUndo>,
+    /// Highest height whose record was dropped; no rollback may go below it.
+    pruned_through: Option<u64>,
+}
+
+impl UndoLog {
+    fn record(&mut self, height: u64, undo: BlockUndo) {
+        self.blocks.insert(height, undo);
+        while self.blocks.len() > MAX_UNDO_BLOCKS {
+            if let Some((pruned, _)) = self.blocks.pop_first() {
+                self.pruned_through = Some(pruned);
+            }
+        }
+    }
+
+    fn check_reachable(&self, height: u64) -> Result<(), StateError> {
+        if self.pruned_through.is_some_and(|pruned| height < pruned) {
+            return Err(StateError::HeightNotFound(height));
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the records above `height`, newest first.
+    fn take_after(&mut self, height: u64) -> Vec<BlockUndo> {
+        self.blocks.split_off(&(height + 1)).into_values().rev().collect()
+    }
+}
+
+/// A reorg in progress; see `QuantumStateManager::begin_reorg`.
+#[derive(Debug)]
+pub struct ReorgCheckpoint {
+    common_ancestor: u64,
+    old_tip: u64,
+    saved: RollbackSnapshot,
+}
+
+/// State taken before a reorg rollback, restored if the new branch fails.
+#[derive(Debug)]
+struct RollbackSnapshot {
+    balances: HashMap<String, WalletBalances>,
+    /// Wallets the rolled-back blocks created, moved out rather than dropped.
+    removed_wallets: HashMap<String, QuantumWallet>,
+    contracts: HashMap<String, DeployedContract>,
+    nonces: HashMap<String, u64>,
+    history: BalanceHistory,
+    blocks: Vec<Block>,
+    undo: UndoLog,
+}
+
+// 🔹 **Account View**
+/// Committed balances and nonces. `AccountView` can't report a poisoned
+/// lock, so reads go through one.
+impl AccountView for QuantumStateManager {
+    fn balance(&self, address: &str, asset_id: &AssetId) -> Amount {
+        self.wallets.read().unwrap_or_else(PoisonError::into_inner)
+            .get(address)
+            .and_then(|wallet| wallet.balances.get(asset_id).copied())
+            .unwrap_or(Amount::ZERO)
+    }
+
+    fn nonce(&self, address: &str) -> u64 {
+        self.nonces.read().unwrap_or_else(PoisonError::into_inner).get(address).copied().unwrap_or(0)
+    }
+}
+
+/// Committed state as `sender` will see it once its pending transactions run.
+struct PendingView<'a> {
+    state: &'a QuantumStateManager,
+    sender: &'a str,
+    pending: u64,
+    spent: HashMap<AssetId, Amount>,
+}
+
+impl<'a> PendingView<'a> {
+    fn new(state: &'a QuantumStateManager, sender: &'a str, mempool: &[QuantumTransaction]) -> Self {
+        let mut view = Self { state, sender, pending: 0, spent: HashMap::new() };
+        for tx in mempool.iter().filter(|tx| tx.from == sender) {
+            view.pending += 1;
+            view.spend(AssetId::native(), tx.fee);
+            view.spend(tx.asset_id.clone(), tx.amount);
+        }
+        view
+    }
+
+    fn spend(&mut self, asset_id: AssetId, amount: Amount) {
+        let spent = self.spent.entry(asset_id).or_insert(Amount::ZERO);
+        *spent = spent.saturating_add(amount);
+    }
+}
+
+impl AccountView for PendingView<'_> {
+    fn balance(&self, address: &str, asset_id: &AssetId) -> Amount {
+        let committed = self.state.balance(address, asset_id);
+        match self.spent.get(asset_id) {
+            Some(spent) if address == self.sender => committed.saturating_sub(*spent),
+            _ => committed,
+        }
+    }
+
+    fn nonce(&self, address: &str) -> u64 {
+        let committed = self.state.nonce(address);
+        if address == self.sender { committed + self.pending } else { committed }
+    }
 }
 
 // 🔹 **Network Metrics**
@@ -54,31 +480,40 @@ pub struct NetworkMetrics {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateSnapshot {
     pub block_height: u64,
-    pub state_root: StateProof,
+    pub state_root: Hash,
     pub timestamp: DateTime<Utc>,
     pub metrics: NetworkMetrics,
 }
 
 impl QuantumStateManager {
     pub fn new() -> Self {
+        Self::with_history_config(StateHistoryConfig::default())
+    }
+
+    pub fn with_history_config(history_config: StateHistoryConfig) -> Self {
         let (tx_sender, _) = broadcast::channel(1000);
-        
+
         Self {
             wallets: Arc::new(RwLock::new(HashMap::new())),
             mempool: Arc::new(RwLock::new(Vec::new())),
+            nonces: Arc::new(RwLock::new(HashMap::new())),
             blocks: Arc::new(RwLock::new(Vec::new())),
             tx_sender,
             metrics: Arc::new(RwLock::new(NetworkMetrics::default())),
-            state_root: Arc::new(RwLock::new(StateProof::default())),
+            state_root: Arc::new(RwLock::new(Hash::default())),
             ai_predictor: Arc::new(RwLock::new(NetworkPredictor::new())),
+            history: Arc::new(RwLock::new(BalanceHistory::new(history_config))),
+            receipts: Arc::new(ReceiptHub::default()),
+            contracts: Arc::new(RwLock::new(HashMap::new())),
+            undo: Arc::new(RwLock::new(UndoLog::default())),
         }
     }
 
-    pub async fn update_balance(&self, wallet_id: &str, amount: f64) -> Result<(), StateError> {
+    pub async fn update_balance(&self, wallet_id: &str, amount: Amount) -> Result<(), StateError> {
         let mut wallets = self.wallets.write().map_err(|_| StateError::LockError)?;
         
         if let Some(wallet) = wallets.get_mut(wallet_id) {
-            wallet.balance = amount;
+            wallet.balances.insert(AssetId::native(), amount);
             
             // Notify subscribers
             let _ = self.tx_sender.send(StateEvent::BalanceUpdate {
@@ -94,12 +529,188 @@ impl QuantumStateManager {
         }
     }
 
+    /// Opens a watch-only account for `address` unless the state already has one.
+    pub fn open_account(&self, address: &str) -> Result<(), StateError> {
+        let mut wallets = self.wallets.write().map_err(|_| StateError::LockError)?;
+        if !wallets.contains_key(address) {
+            let wallet = QuantumWallet::watch_only(address).map_err(|e| StateError::InvalidTransaction(e.to_string()))?;
+            wallets.insert(address.to_string(), wallet);
+        }
+        Ok(())
+    }
+
+    /// Moves `amount` of the native coin from `from` to `to`, opening `to` if needed.
+    pub async fn transfer(&self, from: &str, to: &str, amount: Amount) -> Result<(), StateError> {
+        self.open_account(to)?;
+        let balances = {
+            let mut wallets = self.wallets.write().map_err(|_| StateError::LockError)?;
+            let sender = wallets.get_mut(from).ok_or(StateError::WalletNotFound)?;
+            let available = sender.balances.get(&AssetId::native()).copied().unwrap_or(Amount::ZERO);
+            let remaining = available.checked_sub(amount)
+                .ok_or(StateError::InsufficientBalance { required: amount, available })?;
+            sender.balances.insert(AssetId::native(), remaining);
+
+            let recipient = wallets.get_mut(to).ok_or(StateError::WalletNotFound)?;
+            let balance = recipient.balances.entry(AssetId::native()).or_insert(Amount::ZERO);
+            *balance = balance.saturating_add(amount);
+            [(from, remaining), (to, *balance)]
+        };
+
+        for (wallet_id, new_balance) in balances {
+            let _ = self.tx_sender.send(StateEvent::BalanceUpdate { wallet_id: wallet_id.to_string(), new_balance });
+        }
+        self.update_state_root().await
+    }
+
+    pub fn account_nonce(&self, address: &str) -> Result<u64, StateError> {
+        Ok(self.nonces.read().map_err(|_| StateError::LockError)?.get(address).copied().unwrap_or(0))
+    }
+
+    /// Admits a transaction that passes `validate_mempool_transaction`.
+    pub async fn add_mempool_transaction(&self, transaction: QuantumTransaction) -> Result<(), StateError> {
+        let mut mempool = self.mempool.write().map_err(|_| StateError::LockError)?;
+        self.validate_mempool_transaction(&transaction, &mempool)?;
+        mempool.push(transaction);
+        Ok(())
+    }
+
+    /// Rejects transactions that could never execute: a bad signature, or one
+    /// that fails `simulate` against committed state less the sender's
+    /// pending transactions.
+    fn validate_mempool_transaction(&self, transaction: &QuantumTransaction, mempool: &[QuantumTransaction]) -> Result<(), StateError> {
+        if !transaction.verify().map_err(|e| StateError::InvalidTransaction(e.to_string()))? {
+            return Err(StateError::InvalidSignature);
+        }
+
+        let view = PendingView::new(self, &transaction.from, mempool);
+        match transaction.simulate(&view, &StateOverrides::new(), &FeeSplit::NO_BURN) {
+            Ok(_) => Ok(()),
+            Err(TransactionError::InvalidNonce) => Err(StateError::InvalidNonce {
+                expected: view.nonce(&transaction.from),
+                actual: transaction.nonce,
+            }),
+            Err(TransactionError::InsufficientBalance) => {
+                let asset_available = view.balance(&transaction.from, &transaction.asset_id);
+                let (required, available) = if transaction.asset_id.is_native() {
+                    (transaction.amount.saturating_add(transaction.fee), asset_available)
+                } else if asset_available < transaction.amount {
+                    (transaction.amount, asset_available)
+                } else {
+                    (transaction.fee, view.balance(&transaction.from, &AssetId::native()))
+                };
+                Err(StateError::InsufficientBalance { required, available })
+            }
+            Err(e) => Err(StateError::InvalidTransaction(e.to_string())),
+        }
+    }
+
+    /// Call before applying a block's transactions; captures what `end_block`
+    /// needs to make the block undoable.
+    pub fn begin_block(&self, transactions: &[QuantumTransaction]) -> Result<BlockUndo, StateError> {
+        let wallets = self.wallets.read().map_err(|_| StateError::LockError)?;
+        let mut undo = BlockUndo::default();
+        for tx in transactions {
+            for address in [&tx.from, &tx.to] {
+                undo.wallets.entry(address.clone()).or_insert_with(|| wallets.get(address).map(WalletBalances::of));
+            }
+        }
+        undo.contracts_before = self.contracts.read().map_err(|_| StateError::LockError)?.keys().cloned().collect();
+        Ok(undo)
+    }
+
+    /// Charges a transaction's settled gas to `payer` and credits the producer's
+    /// tip under `fee_split`; the rest is burned. With no producer, or no
+    /// producer wallet, the tip is burned as well. Both wallets are added to `undo`.
+    pub fn settle_fee(
+        &self,
+        undo: &mut BlockUndo,
+        payer: &str,
+        producer: Option<&str>,
+        settlement: &GasSettlement,
+        fee_split: &FeeSplit,
+    ) -> Result<FeeDistribution, StateError> {
+        let mut wallets = self.wallets.write().map_err(|_| StateError::LockError)?;
+        for address in std::iter::once(payer).chain(producer) {
+            undo.wallets.entry(address.to_string()).or_insert_with(|| wallets.get(address).map(WalletBalances::of));
+        }
+
+        let distribution = fee_split.apply(settlement.charged);
+        let payer_wallet = wallets.get_mut(payer).ok_or(StateError::WalletNotFound)?;
+        let available = payer_wallet.balances.get(&AssetId::native()).copied().unwrap_or(Amount::ZERO);
+        let remaining = available.checked_sub(settlement.charged)
+            .ok_or(StateError::InsufficientBalance { required: settlement.charged, available })?;
+        payer_wallet.balances.insert(AssetId::native(), remaining);
+
+        if let Some(producer_wallet) = producer.and_then(|producer| wallets.get_mut(producer)) {
+            let balance = producer_wallet.balances.entry(AssetId::native()).or_insert(Amount::ZERO);
+            *balance = balance.saturating_add(distribution.validator_tip);
+        }
+        Ok(distribution)
+    }
+
+    /// Puts back everything a block changed since `begin_block`, for a block
+    /// whose transactions failed part way through.
+    pub fn abort_block(&self, undo: BlockUndo) -> Result<(), StateError> {
+        let mut wallets = self.wallets.write().map_err(|_| StateError::LockError)?;
+        for (address, prior) in undo.wallets {
+            match prior {
+                Some(prior) => {
+                    if let Some(wallet) = wallets.get_mut(&address) {
+                        prior.apply_to(wallet);
+                    }
+                }
+                None => {
+                    wallets.remove(&address);
+                }
+            }
+        }
+        drop(wallets);
+        self.contracts.write().map_err(|_| StateError::LockError)?
+            .retain(|address, _| undo.contracts_before.contains(address));
+        Ok(())
+    }
+
+    /// Call once a block's transactions are applied: records the touched
+    /// wallets' balance history, advances sender nonces, drops the
+    /// transactions from the mempool and records the block's undo.
+    pub fn end_block(&self, height: u64, transactions: &[QuantumTransaction], mut undo: BlockUndo) -> Result<(), StateError> {
+        self.record_balance_history(height, undo.wallets.keys().cloned().collect())?;
+        {
+            let mut nonces = self.nonces.write().map_err(|_| StateError::LockError)?;
+            for tx in transactions.iter().filter(|tx| !tx.is_system) {
+                *nonces.entry(tx.from.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let included: HashSet<&Hash> = transactions.iter().map(|tx| &tx.hash).collect();
+        self.mempool.write().map_err(|_| StateError::LockError)?.retain(|tx| !included.contains(&tx.hash));
+
+        let contracts_before = std::mem::take(&mut undo.contracts_before);
+        undo.deployed = self.contracts.read().map_err(|_| StateError::LockError)?
+            .keys()
+            .filter(|address| !contracts_before.contains(*address))
+            .cloned()
+            .collect();
+        self.undo.write().map_err(|_| StateError::LockError)?.record(height, undo);
+        Ok(())
+    }
+
     pub async fn process_block(&self, block: Block) -> Result<(), StateError> {
         self.validate_block(&block)?;
 
-        for tx in block.transactions {
-            self.apply_transaction(&tx).await?;
+        let undo = self.begin_block(&block.transactions)?;
+        let mut receipts = Vec::with_capacity(block.transactions.len());
+        for (index, tx) in block.transactions.iter().enumerate() {
+            self.apply_transaction(tx).await?;
+            receipts.push(TransactionReceipt {
+                transaction_hash: tx.hash.clone(),
+                block_height: block.header.height,
+                index,
+                from: tx.from.clone(),
+                to: tx.to.clone(),
+            });
         }
+        self.end_block(block.header.height, &block.transactions, undo)?;
 
         let mut blocks = self.blocks.write().map_err(|_| StateError::LockError)?;
         blocks.push(block.clone());
@@ -113,17 +724,195 @@ impl QuantumStateManager {
             header: block.header,
         });
 
+        for receipt in &receipts {
+            self.receipts.publish(receipt)?;
+        }
+
         self.update_state_root().await?;
         
         Ok(())
     }
 
-    async fn update_state_root(&self) -> Result<(), StateError> {
+    /// Switches to `branch`, which forks off after `common_ancestor`. State is
+    /// rolled back to the ancestor and the branch is replayed; if any branch
+    /// block fails, the pre-reorg state is restored.
+    pub async fn reorg(&self, common_ancestor: u64, branch: Vec<Block>) -> Result<(), StateError> {
+        let checkpoint = self.begin_reorg(common_ancestor)?;
+
+        let mut new_tip = common_ancestor;
+        for block in branch {
+            new_tip = block.header.height;
+            if let Err(e) = self.process_block(block).await {
+                warn!("Reorg past height {} failed at {}; restoring previous chain", common_ancestor, new_tip);
+                self.abort_reorg(checkpoint)?;
+                return Err(e);
+            }
+        }
+        self.finish_reorg(checkpoint, new_tip).await
+    }
+
+    /// First step of `reorg` for callers that replay the branch themselves:
+    /// rolls state back to `common_ancestor`. Pass the checkpoint to
+    /// `finish_reorg` once the branch is applied, or to `abort_reorg` if it fails.
+    pub fn begin_reorg(&self, common_ancestor: u64) -> Result<ReorgCheckpoint, StateError> {
+        let saved = self.rollback_to(common_ancestor)?;
+        let old_tip = saved.history.latest_height().unwrap_or(common_ancestor);
+        Ok(ReorgCheckpoint { common_ancestor, old_tip, saved })
+    }
+
+    /// Puts back the state `begin_reorg` rolled away.
+    pub fn abort_reorg(&self, checkpoint: ReorgCheckpoint) -> Result<(), StateError> {
+        self.restore(checkpoint.saved)
+    }
+
+    pub async fn finish_reorg(&self, checkpoint: ReorgCheckpoint, new_tip: u64) -> Result<(), StateError> {
+        self.update_state_root().await?;
+
+        let ReorgCheckpoint { common_ancestor, old_tip, .. } = checkpoint;
+        let _ = self.tx_sender.send(StateEvent::Reorg { common_ancestor, old_tip, new_tip });
+        Ok(())
+    }
+
+    /// Undoes every block above `height`: wallets, assets, nonces and
+    /// contracts they changed, balance history and blocks. Returns what
+    /// `restore` needs to undo the rollback.
+    fn rollback_to(&self, height: u64) -> Result<RollbackSnapshot, StateError> {
+        let mut wallets = self.wallets.write().map_err(|_| StateError::LockError)?;
+        let mut contracts = self.contracts.write().map_err(|_| StateError::LockError)?;
+        let mut nonces = self.nonces.write().map_err(|_| StateError::LockError)?;
+        let mut history = self.history.write().map_err(|_| StateError::LockError)?;
+        let mut blocks = self.blocks.write().map_err(|_| StateError::LockError)?;
+        let mut undo = self.undo.write().map_err(|_| StateError::LockError)?;
+        undo.check_reachable(height)?;
+
+        let mut saved = RollbackSnapshot {
+            balances: wallets.iter().map(|(address, wallet)| (address.clone(), WalletBalances::of(wallet))).collect(),
+            removed_wallets: HashMap::new(),
+            contracts: contracts.clone(),
+            nonces: nonces.clone(),
+            history: history.clone(),
+            blocks: blocks.clone(),
+            undo: undo.clone(),
+        };
+        history.revert_to(height)?;
+
+        // Every abandoned transaction advanced its sender's nonce by one.
+        for tx in blocks.iter().filter(|block| block.header.height > height).flat_map(|block| &block.transactions) {
+            if let Some(nonce) = nonces.get_mut(&tx.from).filter(|_| !tx.is_system) {
+                *nonce = nonce.saturating_sub(1);
+            }
+        }
+        for block_undo in undo.take_after(height) {
+            for (address, prior) in block_undo.wallets {
+                match prior {
+                    Some(prior) => {
+                        if let Some(wallet) = wallets.get_mut(&address) {
+                            prior.apply_to(wallet);
+                        }
+                    }
+                    None => {
+                        if let Some(wallet) = wallets.remove(&address) {
+                            saved.removed_wallets.insert(address, wallet);
+                        }
+                    }
+                }
+            }
+            for address in block_undo.deployed {
+                contracts.remove(&address);
+            }
+        }
+        blocks.retain(|block| block.header.height <= height);
+        Ok(saved)
+    }
+
+    fn restore(&self, saved: RollbackSnapshot) -> Result<(), StateError> {
+        let mut wallets = self.wallets.write().map_err(|_| StateError::LockError)?;
+        wallets.extend(saved.removed_wallets);
+        for (address, balances) in saved.balances {
+            if let Some(wallet) = wallets.get_mut(&address) {
+                balances.apply_to(wallet);
+            }
+        }
+        drop(wallets);
+        *self.contracts.write().map_err(|_| StateError::LockError)? = saved.contracts;
+        *self.nonces.write().map_err(|_| StateError::LockError)? = saved.nonces;
+        *self.history.write().map_err(|_| StateError::LockError)? = saved.history;
+        *self.blocks.write().map_err(|_| StateError::LockError)? = saved.blocks;
+        *self.undo.write().map_err(|_| StateError::LockError)? = saved.undo;
+        Ok(())
+    }
+
+    /// The hub receipts are published to; share it with anything that waits on them.
+    pub fn receipt_hub(&self) -> Arc<ReceiptHub> {
+        self.receipts.clone()
+    }
+
+    /// Receipts for every processed transaction sent from or to `address`.
+    pub fn subscribe_address(&self, address: &str) -> Result<mpsc::Receiver<TransactionReceipt>, StateError> {
+        self.receipts.subscribe(address)
+    }
+
+    /// Deploys `code` at the address derived from `sender` and `nonce`.
+    pub fn deploy_contract(&self, sender: &Address, nonce: u64, code: Vec<u8>) -> Result<String, StateError> {
+        self.install_contract(Address::for_contract(sender, nonce), sender, code)
+    }
+
+    /// Deploys `code` at `hash(sender, salt, code)`, so the address is known before deployment.
+    pub fn deploy_with_salt(&self, sender: &Address, salt: [u8; 32], code: Vec<u8>) -> Result<String, StateError> {
+        self.install_contract(Address::for_salted_contract(sender, &salt, &code), sender, code)
+    }
+
+    pub fn get_contract(&self, address: &str) -> Result<Option<DeployedContract>, StateError> {
+        Ok(self.contracts.read().map_err(|_| StateError::LockError)?.get(address).cloned())
+    }
+
+    /// Refuses addresses already holding a contract or an account, so a
+    /// repeated nonce or salt can never overwrite existing code.
+    fn install_contract(&self, address: Address, sender: &Address, code: Vec<u8>) -> Result<String, StateError> {
+        let address = address.to_string();
         let wallets = self.wallets.read().map_err(|_| StateError::LockError)?;
-        let mempool = self.mempool.read().map_err(|_| StateError::LockError)?;
-        let metrics = self.metrics.read().map_err(|_| StateError::LockError)?;
+        let mut contracts = self.contracts.write().map_err(|_| StateError::LockError)?;
+        if contracts.contains_key(&address) || wallets.contains_key(&address) {
+            return Err(StateError::ContractExists(address));
+        }
+
+        contracts.insert(address.clone(), DeployedContract {
+            address: address.clone(),
+            deployer: sender.to_string(),
+            code_hash: Hash::from(blake3::hash(&code)),
+            code,
+        });
+        Ok(address)
+    }
+
+    /// Native balance of `address` at the end of block `height`.
+    pub fn get_balance_at(&self, address: &str, height: u64) -> Result<Amount, StateError> {
+        self.history.read().map_err(|_| StateError::LockError)?.balance_at(address, height)
+    }
+
+    fn record_balance_history(&self, height: u64, touched: Vec<String>) -> Result<(), StateError> {
+        let wallets = self.wallets.read().map_err(|_| StateError::LockError)?;
+        let native = AssetId::native();
+        let native_balance = |wallet: &QuantumWallet| wallet.balances.get(&native).copied().unwrap_or(Amount::ZERO);
+
+        let writes = touched.into_iter()
+            .filter_map(|address| wallets.get(&address).map(|wallet| (address, native_balance(wallet))))
+            .collect();
+
+        self.history.write().map_err(|_| StateError::LockError)?.record_block(height, writes, || {
+            wallets.iter().map(|(address, wallet)| (address.clone(), native_balance(wallet))).collect()
+        })
+    }
 
-        let new_root = StateProof::calculate(wallets, mempool, metrics)?;
+    /// Hashes the canonical encoding of all wallets so every node derives the same root.
+    pub fn calculate_state_root(&self) -> Result<Hash, StateError> {
+        let wallets = self.wallets.read().map_err(|_| StateError::LockError)?;
+        let encoded = to_canonical_cbor(&*wallets).map_err(|_| StateError::SerializationError)?;
+        Ok(Hash::from(blake3::hash(&encoded)))
+    }
+
+    async fn update_state_root(&self) -> Result<(), StateError> {
+        let new_root = self.calculate_state_root()?;
         *self.state_root.write().map_err(|_| StateError::LockError)? = new_root;
 
         Ok(())
@@ -138,6 +927,7 @@ impl QuantumStateManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quantumfuse_sdk::{crypto::KeyPair, transaction::OperationType, wallet::KdfParams};
 
     #[tokio::test]
     async fn test_state_snapshot() {
@@ -149,21 +939,376 @@ mod tests {
         assert!(snapshot.timestamp <= Utc::now());
     }
 
+    fn deployer() -> Address {
+        Address::from_public_keys(b"deployer-dilithium", b"deployer-kyber")
+    }
+
+    #[test]
+    fn test_deploy_contract() {
+        let state_manager = QuantumStateManager::new();
+        let address = state_manager.deploy_contract(&deployer(), 0, b"contract code".to_vec()).unwrap();
+
+        assert_eq!(address, Address::for_contract(&deployer(), 0).to_string());
+        let contract = state_manager.get_contract(&address).unwrap().unwrap();
+        assert_eq!(contract.code, b"contract code");
+        assert_eq!(contract.deployer, deployer().to_string());
+        assert!(state_manager.get_contract(&Address::for_contract(&deployer(), 1).to_string()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_colliding_deploy_is_rejected() {
+        let state_manager = QuantumStateManager::new();
+        let address = state_manager.deploy_contract(&deployer(), 7, b"original".to_vec()).unwrap();
+
+        let result = state_manager.deploy_contract(&deployer(), 7, b"replacement".to_vec());
+        assert!(matches!(result, Err(StateError::ContractExists(ref existing)) if *existing == address));
+        assert_eq!(state_manager.get_contract(&address).unwrap().unwrap().code, b"original");
+
+        let salted = state_manager.deploy_with_salt(&deployer(), [1; 32], b"original".to_vec()).unwrap();
+        assert!(matches!(
+            state_manager.deploy_with_salt(&deployer(), [1; 32], b"original".to_vec()),
+            Err(StateError::ContractExists(ref existing)) if *existing == salted
+        ));
+    }
+
+    #[test]
+    fn test_salted_deploys_are_deterministic_and_distinct() {
+        let state_manager = QuantumStateManager::new();
+        let code = b"factory child".to_vec();
+
+        let expected = Address::for_salted_contract(&deployer(), &[1; 32], &code).to_string();
+        let first = state_manager.deploy_with_salt(&deployer(), [1; 32], code.clone()).unwrap();
+        assert_eq!(first, expected);
+
+        let other_salt = state_manager.deploy_with_salt(&deployer(), [2; 32], code.clone()).unwrap();
+        let other_code = state_manager.deploy_with_salt(&deployer(), [1; 32], b"other child".to_vec()).unwrap();
+        let other_sender = Address::from_public_keys(b"other-dilithium", b"other-kyber");
+        let other_deployer = state_manager.deploy_with_salt(&other_sender, [1; 32], code).unwrap();
+
+        let addresses: HashSet<String> = [first, other_salt, other_code, other_deployer].into_iter().collect();
+        assert_eq!(addresses.len(), 4);
+    }
+
+    /// A state manager where a fresh key holds `balance` of the native coin.
+    fn funded_sender(balance: Amount) -> (QuantumStateManager, KeyPair) {
+        let state_manager = QuantumStateManager::new();
+        let keypair = KeyPair::generate();
+        let mut wallet = QuantumWallet::new_with_kdf("password", KdfParams { memory_kib: 256, iterations: 1, parallelism: 1 }).unwrap();
+        wallet.balances.insert(AssetId::native(), balance);
+        state_manager.wallets.write().unwrap().insert(keypair.address(), wallet);
+        (state_manager, keypair)
+    }
+
+    fn signed_transfer(keypair: &KeyPair, amount: Amount, nonce: u64) -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new(
+            keypair.address(),
+            Address::from_public_keys(b"receiver-dilithium", b"receiver-kyber").to_string(),
+            amount,
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+        tx.nonce = nonce;
+        tx.sign(keypair).unwrap();
+        tx
+    }
+
     #[tokio::test]
     async fn test_mempool_transaction() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(100));
+
+        assert!(state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(10), 0)).await.is_ok());
+        // The next transaction from the same sender takes the following nonce.
+        assert!(state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(10), 1)).await.is_ok());
+
+        let mempool = state_manager.mempool.read().unwrap();
+        assert_eq!(mempool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_underfunded_transaction_rejected_at_admission() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(10));
+
+        // Exactly the balance leaves nothing for the fee.
+        let result = state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(10), 0)).await;
+        assert!(matches!(result, Err(StateError::InsufficientBalance { available, .. }) if available == Amount::from_whole(10)));
+        assert!(state_manager.mempool.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bad_nonce_rejected_at_admission() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(100));
+        state_manager.nonces.write().unwrap().insert(keypair.address(), 5);
+
+        let result = state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(1), 4)).await;
+        assert!(matches!(result, Err(StateError::InvalidNonce { expected: 5, actual: 4 })));
+        let result = state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(1), 7)).await;
+        assert!(matches!(result, Err(StateError::InvalidNonce { expected: 5, actual: 7 })));
+        assert!(state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(1), 5)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pending_spends_count_against_admission() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(15));
+        state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(10), 0)).await.unwrap();
+
+        // 15 covers either transfer alone, not both.
+        let result = state_manager.add_mempool_transaction(signed_transfer(&keypair, Amount::from_whole(10), 1)).await;
+        assert!(matches!(result, Err(StateError::InsufficientBalance { .. })));
+        assert_eq!(state_manager.mempool.read().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_signature_rejected_at_admission() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(100));
+
+        let mut tampered = signed_transfer(&keypair, Amount::from_whole(1), 0);
+        tampered.amount = Amount::from_whole(2);
+        let result = state_manager.add_mempool_transaction(tampered).await;
+        assert!(matches!(result, Err(StateError::InvalidSignature)));
+
+        // Signed by a key other than the sender's.
+        let mut forged = signed_transfer(&keypair, Amount::from_whole(1), 0);
+        forged.sign(&KeyPair::generate()).unwrap();
+        assert!(matches!(state_manager.add_mempool_transaction(forged).await, Err(StateError::InvalidSignature)));
+        assert!(state_manager.mempool.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_balance_at_historical_heights() {
+        let mut history = BalanceHistory::new(StateHistoryConfig { checkpoint_interval: 3, retained_blocks: None });
+        let mut current: HashMap<String, Amount> = HashMap::new();
+        let mut expected_alice = Vec::new();
+
+        // Alice's balance changes on some blocks; Bob's on others.
+        for height in 0..10u64 {
+            let mut writes = HashMap::new();
+            if height % 2 == 0 {
+                writes.insert("alice".to_string(), Amount::from_whole(100 + height));
+            }
+            if height % 3 == 1 {
+                writes.insert("bob".to_string(), Amount::from_whole(height));
+            }
+            current.extend(writes.clone());
+            let snapshot = current.clone();
+            history.record_block(height, writes, || snapshot).unwrap();
+            expected_alice.push(current.get("alice").copied().unwrap_or(Amount::ZERO));
+        }
+
+        for (height, expected) in expected_alice.iter().enumerate() {
+            assert_eq!(history.balance_at("alice", height as u64).unwrap(), *expected);
+        }
+        assert_eq!(history.balance_at("bob", 0).unwrap(), Amount::ZERO);
+        assert_eq!(history.balance_at("bob", 5).unwrap(), Amount::from_whole(4));
+        assert_eq!(history.balance_at("bob", 9).unwrap(), Amount::from_whole(7));
+    }
+
+    #[test]
+    fn test_balance_at_future_height_is_rejected() {
+        let mut history = BalanceHistory::new(StateHistoryConfig::default());
+        history.record_block(0, HashMap::new(), HashMap::new).unwrap();
+
+        assert!(matches!(history.balance_at("alice", 1), Err(StateError::HeightNotFound(1))));
+        assert!(matches!(
+            history.record_block(0, HashMap::new(), HashMap::new),
+            Err(StateError::NonSequentialHeight(0))
+        ));
+    }
+
+    /// Alice's balance is `100 + height` after every block up to `tip`.
+    fn alice_history(tip: u64) -> BalanceHistory {
+        let mut history = BalanceHistory::new(StateHistoryConfig { checkpoint_interval: 2, retained_blocks: None });
+        for height in 0..=tip {
+            let writes = HashMap::from([("alice".to_string(), Amount::from_whole(100 + height))]);
+            let snapshot = writes.clone();
+            history.record_block(height, writes, || snapshot).unwrap();
+        }
+        history
+    }
+
+    #[test]
+    fn test_reorg_balances_follow_new_canonical_chain() {
+        let mut history = alice_history(5);
+
+        let restored = history.revert_to(2).unwrap();
+        assert_eq!(restored, HashMap::from([("alice".to_string(), Amount::from_whole(102))]));
+        assert_eq!(history.latest_height(), Some(2));
+        assert!(matches!(history.balance_at("alice", 3), Err(StateError::HeightNotFound(3))));
+
+        // Replaying the new branch builds on the ancestor's balances.
+        let writes = HashMap::from([("alice".to_string(), Amount::from_whole(7))]);
+        history.record_block(3, writes, HashMap::new).unwrap();
+        assert_eq!(history.balance_at("alice", 2).unwrap(), Amount::from_whole(102));
+        assert_eq!(history.balance_at("alice", 3).unwrap(), Amount::from_whole(7));
+    }
+
+    #[tokio::test]
+    async fn test_reorg_emits_event_with_common_ancestor() {
         let state_manager = QuantumStateManager::new();
-        let tx = MempoolTransaction {
-            hash: Hash::default(),
-            from: "sender".to_string(),
-            to: "receiver".to_string(),
-            amount: 10.0,
-            fee: 0.1,
-            timestamp: Utc::now(),
+        *state_manager.history.write().unwrap() = alice_history(4);
+        let mut events = state_manager.tx_sender.subscribe();
+
+        state_manager.reorg(1, Vec::new()).await.unwrap();
+
+        match events.recv().await.unwrap() {
+            StateEvent::Reorg { common_ancestor, old_tip, new_tip } => {
+                assert_eq!((common_ancestor, old_tip, new_tip), (1, 4, 1));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(state_manager.get_balance_at("alice", 1).unwrap(), Amount::from_whole(101));
+        assert!(state_manager.get_balance_at("alice", 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_undoes_everything_the_block_changed() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(100));
+        let sender = keypair.address();
+        let tx = signed_transfer(&keypair, Amount::from_whole(10), 0);
+        let recipient = tx.to.clone();
+        let balances_before = state_manager.wallets.read().unwrap()[&sender].balances.clone();
+
+        // Block 1 as `apply_transaction` might leave it: a new account, a
+        // non-native asset and a contract deployment.
+        let undo = state_manager.begin_block(std::slice::from_ref(&tx)).unwrap();
+        {
+            let recipient_wallet = QuantumWallet::new_with_kdf("password", KdfParams { memory_kib: 256, iterations: 1, parallelism: 1 }).unwrap();
+            let mut wallets = state_manager.wallets.write().unwrap();
+            wallets.insert(recipient.clone(), recipient_wallet);
+            wallets.get_mut(&sender).unwrap().balances.insert(AssetId::new("GOLD"), Amount::from_whole(5));
+        }
+        let contract = state_manager.deploy_contract(&deployer(), 0, b"branch code".to_vec()).unwrap();
+        state_manager.end_block(1, std::slice::from_ref(&tx), undo).unwrap();
+        assert_eq!(state_manager.account_nonce(&sender).unwrap(), 1);
+
+        let checkpoint = state_manager.begin_reorg(0).unwrap();
+        {
+            let wallets = state_manager.wallets.read().unwrap();
+            assert!(!wallets.contains_key(&recipient));
+            assert_eq!(wallets[&sender].balances, balances_before);
+        }
+        assert!(state_manager.get_contract(&contract).unwrap().is_none());
+        assert_eq!(state_manager.account_nonce(&sender).unwrap(), 0);
+
+        // Aborting brings the abandoned branch's state back.
+        state_manager.abort_reorg(checkpoint).unwrap();
+        assert!(state_manager.wallets.read().unwrap().contains_key(&recipient));
+        assert!(state_manager.get_contract(&contract).unwrap().is_some());
+        assert_eq!(state_manager.account_nonce(&sender).unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_settled_fee_is_split_and_rolled_back() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(100));
+        let sender = keypair.address();
+        let producer = "producer".to_string();
+        let producer_wallet = QuantumWallet::new_with_kdf("password", KdfParams { memory_kib: 256, iterations: 1, parallelism: 1 }).unwrap();
+        state_manager.wallets.write().unwrap().insert(producer.clone(), producer_wallet);
+        let settlement = GasSettlement { gas_used: 21_000, charged: Amount::from_whole(2), refund: Amount::from_whole(1) };
+        let fee_split = FeeSplit::new(5_000).unwrap();
+        let native_of = |address: &str| {
+            state_manager.wallets.read().unwrap()[address].balances.get(&AssetId::native()).copied().unwrap_or(Amount::ZERO)
         };
-        
-        assert!(state_manager.add_mempool_transaction(tx).await.is_ok());
-        
-        let mempool = state_manager.mempool.read().unwrap();
-        assert_eq!(mempool.len(), 1);
+
+        let mut undo = state_manager.begin_block(&[]).unwrap();
+        let distribution = state_manager.settle_fee(&mut undo, &sender, Some(&producer), &settlement, &fee_split).unwrap();
+        state_manager.end_block(1, &[], undo).unwrap();
+        assert_eq!(distribution, fee_split.apply(settlement.charged));
+        assert_eq!(native_of(&sender), Amount::from_whole(98));
+        assert_eq!(native_of(&producer), distribution.validator_tip);
+
+        state_manager.begin_reorg(0).unwrap();
+        assert_eq!(native_of(&sender), Amount::from_whole(100));
+        assert_eq!(native_of(&producer), Amount::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_aborted_block_leaves_no_partial_state() {
+        let (state_manager, keypair) = funded_sender(Amount::from_whole(100));
+        let sender = keypair.address();
+        let settlement = GasSettlement { gas_used: 21_000, charged: Amount::from_whole(60), refund: Amount::ZERO };
+        let fee_split = FeeSplit::new(5_000).unwrap();
+
+        let mut undo = state_manager.begin_block(&[]).unwrap();
+        state_manager.settle_fee(&mut undo, &sender, None, &settlement, &fee_split).unwrap();
+        assert!(state_manager.settle_fee(&mut undo, &sender, None, &settlement, &fee_split).is_err());
+        state_manager.abort_block(undo).unwrap();
+
+        assert_eq!(state_manager.balance(&sender, &AssetId::native()), Amount::from_whole(100));
+    }
+
+    fn receipt(from: &str, to: &str, block_height: u64, index: usize) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: Hash::default(),
+            block_height,
+            index,
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_address_subscription_receives_only_its_receipts() {
+        let hub = ReceiptHub::default();
+        let mut alice = hub.subscribe("alice").unwrap();
+        let mut dave = hub.subscribe("dave").unwrap();
+
+        hub.publish(&receipt("alice", "bob", 1, 0)).unwrap();
+        hub.publish(&receipt("carol", "erin", 1, 1)).unwrap();
+        hub.publish(&receipt("bob", "alice", 2, 0)).unwrap();
+
+        assert_eq!(alice.recv().await.unwrap(), receipt("alice", "bob", 1, 0));
+        assert_eq!(alice.recv().await.unwrap(), receipt("bob", "alice", 2, 0));
+        assert!(alice.try_recv().is_err());
+        assert!(dave.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_self_transfer_is_delivered_once() {
+        let hub = ReceiptHub::default();
+        let mut alice = hub.subscribe("alice").unwrap();
+
+        hub.publish(&receipt("alice", "alice", 3, 0)).unwrap();
+
+        assert_eq!(alice.recv().await.unwrap().block_height, 3);
+        assert!(alice.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_dropped_subscriptions_are_pruned() {
+        let hub = ReceiptHub::default();
+        drop(hub.subscribe("alice").unwrap());
+
+        hub.publish(&receipt("alice", "bob", 1, 0)).unwrap();
+        assert!(hub.subscribers.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_abandoned_waiters_are_released() {
+        let hub = ReceiptHub::default();
+        let hash = Hash::default();
+        drop(hub.wait_for_transaction(hash).unwrap());
+        let live = hub.wait_for_transaction(hash).unwrap();
+        assert_eq!(hub.transactions.read().unwrap().pending, 1);
+
+        drop(live);
+        hub.release_waiters(&hash).unwrap();
+        let transactions = hub.transactions.read().unwrap();
+        assert!(transactions.waiting.is_empty());
+        assert_eq!(transactions.pending, 0);
+    }
+
+    #[test]
+    fn test_waiters_are_capped() {
+        let hub = ReceiptHub::default();
+        let hash = Hash::default();
+        let waiters: Vec<_> = (0..MAX_TRANSACTION_WAITERS).map(|_| hub.wait_for_transaction(hash).unwrap()).collect();
+
+        assert!(matches!(hub.wait_for_transaction(hash), Err(StateError::TooManyWaiters)));
+
+        // Closed waiters make room again.
+        drop(waiters);
+        assert!(hub.wait_for_transaction(hash).is_ok());
+        assert_eq!(hub.transactions.read().unwrap().pending, 1);
     }
 }
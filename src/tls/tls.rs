@@ -0,0 +1,191 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use axum::{extract::Request, http::header, response::Redirect, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::error::TlsError;
+
+// 🔹 **TLS Configuration**
+/// PEM certificate chain and private key for HTTPS; servers fall back to plain HTTP without one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// Plain-HTTP port that redirects to HTTPS. No HTTP listener when unset.
+    #[serde(default)]
+    pub http_redirect_port: Option<u16>,
+}
+
+impl TlsConfig {
+    /// Reads `<PREFIX>_TLS_CERT`, `<PREFIX>_TLS_KEY` and `<PREFIX>_HTTP_REDIRECT_PORT`.
+    pub fn from_env(prefix: &str) -> Option<Self> {
+        let cert_path = std::env::var(format!("{}_TLS_CERT", prefix)).ok()?;
+        let key_path = std::env::var(format!("{}_TLS_KEY", prefix)).ok()?;
+        let http_redirect_port = std::env::var(format!("{}_HTTP_REDIRECT_PORT", prefix))
+            .ok()
+            .and_then(|port| port.parse().ok());
+
+        Some(Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            http_redirect_port,
+        })
+    }
+
+    /// Loads and checks the certificate and key; every failure names the offending file.
+    pub fn load_server_config(&self) -> Result<ServerConfig, TlsError> {
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_key(&self.key_path)?;
+
+        ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| TlsError::InvalidCertificate(format!("{}: {}", self.cert_path.display(), e)))
+    }
+}
+
+fn open_pem(path: &Path) -> Result<BufReader<File>, TlsError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|_| TlsError::MissingFile(path.to_path_buf()))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certs = rustls_pemfile::certs(&mut open_pem(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| TlsError::InvalidCertificate(format!("{}: {}", path.display(), e)))?;
+
+    if certs.is_empty() {
+        return Err(TlsError::InvalidCertificate(format!("{}: no certificates found", path.display())));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, TlsError> {
+    rustls_pemfile::private_key(&mut open_pem(path)?)
+        .map_err(|e| TlsError::InvalidKey(format!("{}: {}", path.display(), e)))?
+        .ok_or_else(|| TlsError::InvalidKey(format!("{}: no private key found", path.display())))
+}
+
+/// `Location` for an HTTP request redirected to the HTTPS listener on `https_port`.
+pub fn https_redirect_location(host: Option<&str>, path_and_query: &str, https_port: u16) -> String {
+    let host = host.unwrap_or("localhost");
+    // Drop any port from the Host header, keeping bracketed IPv6 literals intact.
+    let hostname = match host.rfind(':') {
+        Some(index) if !host[index..].contains(']') => &host[..index],
+        _ => host,
+    };
+
+    if https_port == 443 {
+        format!("https://{}{}", hostname, path_and_query)
+    } else {
+        format!("https://{}:{}{}", hostname, https_port, path_and_query)
+    }
+}
+
+// 🔹 **Axum Serving**
+/// Serves `router` on `listener`, over HTTPS when `tls` is set. TLS material is
+/// loaded before anything is served, so a bad path fails startup with an error.
+pub async fn serve_router(router: Router, listener: TcpListener, tls: Option<&TlsConfig>) -> Result<(), TlsError> {
+    let Some(tls) = tls else {
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+        axum::serve(listener, router.into_make_service()).await?;
+        return Ok(());
+    };
+
+    let server_config = tls.load_server_config()?;
+    let https_port = listener.local_addr()?.port();
+    if let Some(http_port) = tls.http_redirect_port {
+        spawn_https_redirect(SocketAddr::from(([0, 0, 0, 0], http_port)), https_port).await?;
+    }
+
+    axum_server::from_tcp_rustls(listener, RustlsConfig::from_config(Arc::new(server_config)))
+        .serve(router.into_make_service())
+        .await?;
+    Ok(())
+}
+
+async fn spawn_https_redirect(addr: SocketAddr, https_port: u16) -> Result<(), TlsError> {
+    let redirect = Router::new().fallback(move |request: Request| async move {
+        let host = request.headers().get(header::HOST).and_then(|h| h.to_str().ok());
+        let path = request.uri().path_and_query().map_or("/", |p| p.as_str());
+        Redirect::permanent(&https_redirect_location(host, path, https_port))
+    });
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, redirect.into_make_service()).await {
+            eprintln!("❌ HTTPS redirect listener stopped: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+
+    fn write_self_signed(dir: &Path) -> (TlsConfig, String) {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_pem = certified.cert.pem();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, &cert_pem).unwrap();
+        std::fs::write(&key_path, certified.key_pair.serialize_pem()).unwrap();
+
+        (TlsConfig { cert_path, key_path, http_redirect_port: None }, cert_pem)
+    }
+
+    #[tokio::test]
+    async fn test_tls_server_completes_handshake() {
+        let dir = std::env::temp_dir().join(format!("tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (tls, cert_pem) = write_self_signed(&dir);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let router = Router::new().route("/ping", get(|| async { "pong" }));
+        tokio::spawn(async move { serve_router(router, listener, Some(&tls)).await });
+
+        let client = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(cert_pem.as_bytes()).unwrap())
+            .build()
+            .unwrap();
+        let body = client.get(format!("https://localhost:{}/ping", port))
+            .send().await.unwrap()
+            .text().await.unwrap();
+        assert_eq!(body, "pong");
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_missing_cert_is_startup_error() {
+        let tls = TlsConfig {
+            cert_path: PathBuf::from("/nonexistent/cert.pem"),
+            key_path: PathBuf::from("/nonexistent/key.pem"),
+            http_redirect_port: None,
+        };
+
+        let result = tls.load_server_config();
+        assert!(matches!(result, Err(TlsError::MissingFile(ref path)) if path == Path::new("/nonexistent/cert.pem")));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let result = serve_router(Router::new(), listener, Some(&tls)).await;
+        assert!(matches!(result, Err(TlsError::MissingFile(_))));
+    }
+
+    #[test]
+    fn test_redirect_location_targets_https_port() {
+        assert_eq!(https_redirect_location(Some("node.example:8080"), "/api/v1/metrics?x=1", 8443), "https://node.example:8443/api/v1/metrics?x=1");
+        assert_eq!(https_redirect_location(Some("[::1]:80"), "/", 443), "https://[::1]/");
+        assert_eq!(https_redirect_location(None, "/ready", 443), "https://localhost/ready");
+    }
+}
@@ -1,15 +1,19 @@
 use quantumfuse_sdk::blockchain::{Blockchain, Transaction, Shard};
-use quantumfuse_sdk::network::{AIEngine, TPSAnalyzer};
+use quantumfuse_sdk::shard::shard_for_address;
+use quantumfuse_sdk::network::TPSAnalyzer;
 use quantumfuse_sdk::consensus::QuantumBridge;
 use pqcrypto::sign::dilithium2::{generate_keypair, sign, verify};
 use pqcrypto::kem::kyber512::{encapsulate, decapsulate, generate_keypair as kyber_generate};
-use tokio::sync::mpsc;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
 use tokio::task;
 use serde_json::json;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use log::{info, error};
 
 /// Number of threads for parallel processing
@@ -17,96 +21,489 @@ const NUM_THREADS: usize = 8;
 /// Benchmark different transaction loads
 const TRANSACTION_LOADS: [usize; 3] = [1000, 10_000, 100_000];
 
-#[tokio::main]
-async fn main() {
-    let blockchain = Arc::new(Blockchain::new());
-    let ai_engine = AIEngine::new();
-    let mut results = Vec::new();
-
-    for &num_transactions in TRANSACTION_LOADS.iter() {
-        let (tx, mut rx) = mpsc::channel(num_transactions);
-        let mut transactions = Vec::new();
-
-        // Generate transactions with quantum-safe signatures
-        for _ in 0..num_transactions {
-            let (public_key, secret_key) = generate_keypair();
-            let mut transaction = Transaction::new(
-                "sender_wallet".to_string(),
-                "recipient_wallet".to_string(),
-                1.0, // Amount
-                0.001, // Fee
-                public_key.to_bytes(), // Quantum-safe signature
-            );
-            let signature = sign(transaction.hash().as_bytes(), &secret_key);
-            transaction.attach_signature(signature.to_bytes());
-
-            transactions.push(transaction);
+/// Loads to run and where, if anywhere, to write the reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchmarkConfig {
+    pub transaction_loads: Vec<usize>,
+    /// Worker threads per shard.
+    pub threads: usize,
+    pub shard_count: u64,
+    pub csv_output: Option<PathBuf>,
+    pub json_output: Option<PathBuf>,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            transaction_loads: TRANSACTION_LOADS.to_vec(),
+            threads: NUM_THREADS,
+            shard_count: 1,
+            csv_output: None,
+            json_output: None,
         }
+    }
+}
 
-        // Measure TPS
-        let start_time = Instant::now();
-        let mut handles = Vec::new();
-
-        for _ in 0..NUM_THREADS {
-            let blockchain = blockchain.clone();
-            let mut rx = rx.clone();
-            handles.push(task::spawn(async move {
-                while let Some(tx) = rx.recv().await {
-                    blockchain.process_transaction(tx).await;
-                }
-            }));
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadResult {
+    pub transactions: usize,
+    /// Transactions the workers actually took off the queue and processed.
+    pub processed: usize,
+    pub time_seconds: f64,
+    /// Sum of the per-shard throughputs.
+    pub tps: f64,
+    pub shards: Vec<ShardResult>,
+    pub metrics: PerfMetrics,
+    pub bottleneck: Option<Bottleneck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardResult {
+    pub shard_id: u64,
+    pub processed: usize,
+    pub time_seconds: f64,
+    pub tps: f64,
+}
+
+// 🔹 **Bottleneck Detection**
+/// Time spent in each processing stage, summed over the transactions measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerfMetrics {
+    /// Waiting for the next transaction from the queue.
+    pub mempool_wait: Duration,
+    pub signature_verify: Duration,
+    pub state_apply: Duration,
+    pub consensus: Duration,
+}
+
+impl PerfMetrics {
+    pub fn total(&self) -> Duration {
+        self.mempool_wait + self.signature_verify + self.state_apply + self.consensus
+    }
+
+    pub fn accumulate(&mut self, other: &PerfMetrics) {
+        self.mempool_wait += other.mempool_wait;
+        self.signature_verify += other.signature_verify;
+        self.state_apply += other.state_apply;
+        self.consensus += other.consensus;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stage {
+    Mempool,
+    SignatureVerify,
+    StateApply,
+    Consensus,
+}
+
+/// How much of the total time the slowest stage takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    /// Under 40%.
+    Low,
+    /// 40% to under 60%.
+    Moderate,
+    /// 60% to under 80%.
+    High,
+    /// 80% or more.
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bottleneck {
+    pub stage: Stage,
+    pub severity: Severity,
+}
+
+/// Picks the stage that took the most time. With nothing measured there is no bottleneck.
+pub fn detect_bottlenecks(metrics: &PerfMetrics) -> Option<Bottleneck> {
+    let total = metrics.total();
+    if total.is_zero() {
+        return None;
+    }
+
+    let (stage, time) = [
+        (Stage::Mempool, metrics.mempool_wait),
+        (Stage::SignatureVerify, metrics.signature_verify),
+        (Stage::StateApply, metrics.state_apply),
+        (Stage::Consensus, metrics.consensus),
+    ]
+    .into_iter()
+    .max_by_key(|(_, time)| *time)?;
+
+    let share = time.as_secs_f64() / total.as_secs_f64();
+    let severity = match share {
+        s if s >= 0.8 => Severity::Critical,
+        s if s >= 0.6 => Severity::High,
+        s if s >= 0.4 => Severity::Moderate,
+        _ => Severity::Low,
+    };
+    Some(Bottleneck { stage, severity })
+}
+
+/// One entry per configured load, in the order the loads were given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub results: Vec<LoadResult>,
+}
+
+/// Whatever the benchmark pushes transactions through. Returns the time
+/// spent in each stage; the benchmark fills in `mempool_wait` itself.
+#[async_trait]
+pub trait TransactionProcessor: Send + Sync + 'static {
+    async fn process(&self, transaction: Transaction) -> PerfMetrics;
+}
+
+#[async_trait]
+impl TransactionProcessor for Blockchain {
+    async fn process(&self, transaction: Transaction) -> PerfMetrics {
+        let verify_start = Instant::now();
+        let signature_ok = transaction.verify_signature();
+        let signature_verify = verify_start.elapsed();
+        if !signature_ok {
+            error!("Benchmark transaction failed signature verification");
         }
 
-        // Send transactions for processing
-        for transaction in transactions {
-            if let Err(e) = tx.send(transaction).await {
-                error!("Transaction send error: {:?}", e);
-            }
+        let apply_start = Instant::now();
+        self.process_transaction(transaction).await;
+        PerfMetrics {
+            signature_verify,
+            state_apply: apply_start.elapsed(),
+            ..PerfMetrics::default()
         }
-        drop(tx); // Close channel
+    }
+}
 
-        // Wait for all transactions to be processed
+#[tokio::main]
+async fn main() {
+    let config = BenchmarkConfig {
+        csv_output: Some(PathBuf::from("tps_benchmark_results.csv")),
+        json_output: Some(PathBuf::from("tps_benchmark_results.json")),
+        ..BenchmarkConfig::default()
+    };
+
+    if let Err(e) = run_benchmark(config).await {
+        error!("Benchmark failed: {:?}", e);
+    }
+}
+
+/// Benchmarks a fresh `Blockchain` against every configured load.
+pub async fn run_benchmark(config: BenchmarkConfig) -> io::Result<BenchmarkReport> {
+    run_benchmark_with(Arc::new(Blockchain::new()), config).await
+}
+
+pub async fn run_benchmark_with<P: TransactionProcessor>(
+    processor: Arc<P>,
+    config: BenchmarkConfig,
+) -> io::Result<BenchmarkReport> {
+    let mut report = BenchmarkReport::default();
+    let shard_count = config.shard_count.max(1);
+
+    for &num_transactions in &config.transaction_loads {
+        let shards = generate_transactions(num_transactions, shard_count);
+
+        // Each shard drains its own queue in parallel with the others
+        let start_time = Instant::now();
+        let handles: Vec<_> = shards.into_iter()
+            .enumerate()
+            .map(|(shard_id, transactions)| {
+                let processor = processor.clone();
+                let threads = config.threads.max(1);
+                task::spawn(run_shard(processor, shard_id as u64, transactions, threads))
+            })
+            .collect();
+
+        let mut shard_results = Vec::new();
+        let mut metrics = PerfMetrics::default();
         for handle in handles {
-            let _ = handle.await;
+            match handle.await {
+                Ok((shard_result, shard_metrics)) => {
+                    metrics.accumulate(&shard_metrics);
+                    shard_results.push(shard_result);
+                }
+                Err(e) => error!("Benchmark shard failed: {:?}", e),
+            }
         }
 
         let elapsed_time = start_time.elapsed();
-        let tps = num_transactions as f64 / elapsed_time.as_secs_f64();
+        let processed = shard_results.iter().map(|s| s.processed).sum();
+        let tps = shard_results.iter().map(|s| s.tps).sum();
 
-        // AI-Powered Performance Analysis
-        let bottleneck = ai_engine.detect_bottlenecks(tps);
+        // Per-stage performance analysis
+        let bottleneck = detect_bottlenecks(&metrics);
         info!(
-            "Benchmark Completed: Processed {} transactions in {:.2?} seconds. TPS = {:.2}. Bottleneck: {:?}",
-            num_transactions, elapsed_time, tps, bottleneck
+            "Benchmark Completed: Processed {} transactions across {} shards in {:.2?} seconds. TPS = {:.2}. Bottleneck: {:?}",
+            num_transactions, shard_count, elapsed_time, tps, bottleneck
         );
 
-        results.push((num_transactions, elapsed_time.as_secs_f64(), tps, bottleneck));
+        report.results.push(LoadResult {
+            transactions: num_transactions,
+            processed,
+            time_seconds: elapsed_time.as_secs_f64(),
+            tps,
+            shards: shard_results,
+            metrics,
+            bottleneck,
+        });
+    }
+
+    if let Some(path) = &config.csv_output {
+        write_csv_report(path, &report.results)?;
+    }
+    if let Some(path) = &config.json_output {
+        write_json_report(path, &report.results)?;
+    }
+    Ok(report)
+}
+
+/// Processes one shard's transactions with `threads` workers sharing its queue.
+async fn run_shard<P: TransactionProcessor>(
+    processor: Arc<P>,
+    shard_id: u64,
+    transactions: Vec<Transaction>,
+    threads: usize,
+) -> (ShardResult, PerfMetrics) {
+    // Queue every transaction up front, so the timer only covers processing
+    let (tx, rx) = mpsc::channel(transactions.len().max(1));
+    for transaction in transactions {
+        if let Err(e) = tx.send(transaction).await {
+            error!("Transaction send error: {:?}", e);
+        }
+    }
+    drop(tx); // Close channel so workers stop once it drains
+
+    // Workers share one receiver; each takes the next transaction in turn
+    let rx = Arc::new(Mutex::new(rx));
+    let start_time = Instant::now();
+    let mut handles = Vec::new();
+
+    for _ in 0..threads {
+        let processor = processor.clone();
+        let rx = rx.clone();
+        handles.push(task::spawn(async move {
+            let mut processed = 0;
+            let mut metrics = PerfMetrics::default();
+            loop {
+                let wait_start = Instant::now();
+                let next = rx.lock().await.recv().await;
+                metrics.mempool_wait += wait_start.elapsed();
+                match next {
+                    Some(transaction) => {
+                        metrics.accumulate(&processor.process(transaction).await);
+                        processed += 1;
+                    }
+                    None => break (processed, metrics),
+                }
+            }
+        }));
+    }
+
+    // Wait for all transactions to be processed
+    let mut processed = 0;
+    let mut metrics = PerfMetrics::default();
+    for handle in handles {
+        match handle.await {
+            Ok((count, worker_metrics)) => {
+                processed += count;
+                metrics.accumulate(&worker_metrics);
+            }
+            Err(e) => error!("Benchmark worker failed: {:?}", e),
+        }
     }
 
-    generate_csv_report(&results);
-    generate_json_report(&results);
+    let elapsed_time = start_time.elapsed();
+    let result = ShardResult {
+        shard_id,
+        processed,
+        time_seconds: elapsed_time.as_secs_f64(),
+        tps: processed as f64 / elapsed_time.as_secs_f64().max(f64::EPSILON),
+    };
+    (result, metrics)
+}
+
+/// Generate transactions with quantum-safe signatures from distinct senders,
+/// grouped by the shard each sender's address maps to.
+fn generate_transactions(count: usize, shard_count: u64) -> Vec<Vec<Transaction>> {
+    let mut shards: Vec<Vec<Transaction>> = (0..shard_count).map(|_| Vec::new()).collect();
+    for i in 0..count {
+        let sender = format!("sender_wallet_{}", i);
+        let shard_id = shard_for_address(&sender, shard_count);
+
+        let (public_key, secret_key) = generate_keypair();
+        let mut transaction = Transaction::new(
+            sender,
+            "recipient_wallet".to_string(),
+            1.0, // Amount
+            0.001, // Fee
+            public_key.to_bytes(), // Quantum-safe signature
+        );
+        let signature = sign(transaction.hash().as_bytes(), &secret_key);
+        transaction.attach_signature(signature.to_bytes());
+
+        shards[shard_id as usize].push(transaction);
+    }
+    shards
 }
 
 /// Generates a CSV report of the TPS benchmarking results
-fn generate_csv_report(results: &Vec<(usize, f64, f64, String)>) {
-    let mut file = File::create("tps_benchmark_results.csv").expect("Unable to create CSV file");
-    writeln!(file, "Transactions,Time (s),TPS,Bottleneck").expect("Unable to write to CSV file");
+pub fn write_csv_report(path: &Path, results: &[LoadResult]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "Transactions,Processed,Time (s),TPS,Bottleneck,Severity")?;
 
-    for (num_tx, time, tps, bottleneck) in results {
-        writeln!(file, "{},{},{},{}", num_tx, time, tps, bottleneck).expect("Unable to write to CSV file");
+    for result in results {
+        let (stage, severity) = match result.bottleneck {
+            Some(b) => (format!("{:?}", b.stage), format!("{:?}", b.severity)),
+            None => (String::new(), String::new()),
+        };
+        writeln!(
+            file, "{},{},{},{},{},{}",
+            result.transactions, result.processed, result.time_seconds, result.tps, stage, severity
+        )?;
     }
-    info!("CSV report generated: tps_benchmark_results.csv");
+    info!("CSV report generated: {}", path.display());
+    Ok(())
 }
 
 /// Generates a JSON report of the TPS benchmarking results
-fn generate_json_report(results: &Vec<(usize, f64, f64, String)>) {
-    let json_data = json!({
-        "benchmark_results": results.iter().map(|(num_tx, time, tps, bottleneck)| {
-            json!({"transactions": num_tx, "time_seconds": time, "tps": tps, "bottleneck": bottleneck})
-        }).collect::<Vec<_>>()
-    });
-
-    let mut file = File::create("tps_benchmark_results.json").expect("Unable to create JSON file");
-    writeln!(file, "{}", json_data.to_string()).expect("Unable to write to JSON file");
-    info!("JSON report generated: tps_benchmark_results.json");
+pub fn write_json_report(path: &Path, results: &[LoadResult]) -> io::Result<()> {
+    let json_data = json!({ "benchmark_results": results });
+
+    let mut file = File::create(path)?;
+    writeln!(file, "{}", json_data)?;
+    info!("JSON report generated: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingProcessor {
+        processed: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TransactionProcessor for CountingProcessor {
+        async fn process(&self, _transaction: Transaction) -> PerfMetrics {
+            self.processed.fetch_add(1, Ordering::SeqCst);
+            PerfMetrics::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_small_benchmark_reports_each_load() {
+        let processor = Arc::new(CountingProcessor::default());
+        let config = BenchmarkConfig {
+            transaction_loads: vec![10, 25],
+            threads: 2,
+            ..BenchmarkConfig::default()
+        };
+
+        let report = run_benchmark_with(processor.clone(), config).await.unwrap();
+
+        let loads: Vec<_> = report.results.iter().map(|r| r.transactions).collect();
+        assert_eq!(loads, vec![10, 25]);
+        assert!(report.results.iter().all(|r| r.tps > 0.0));
+        assert_eq!(processor.processed.load(Ordering::SeqCst), 35);
+    }
+
+    #[tokio::test]
+    async fn test_reports_are_written_when_requested() {
+        let dir = std::env::temp_dir().join(format!("tps-bench-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = BenchmarkConfig {
+            transaction_loads: vec![5],
+            threads: 1,
+            shard_count: 1,
+            csv_output: Some(dir.join("results.csv")),
+            json_output: Some(dir.join("results.json")),
+        };
+
+        run_benchmark_with(Arc::new(CountingProcessor::default()), config).await.unwrap();
+
+        let csv = std::fs::read_to_string(dir.join("results.csv")).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join("results.json")).unwrap()).unwrap();
+        assert_eq!(json["benchmark_results"][0]["transactions"], 5);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_every_queued_transaction_is_processed_once() {
+        let processor = Arc::new(CountingProcessor::default());
+        let config = BenchmarkConfig {
+            transaction_loads: vec![200],
+            threads: 4,
+            ..BenchmarkConfig::default()
+        };
+
+        let report = run_benchmark_with(processor.clone(), config).await.unwrap();
+
+        assert_eq!(report.results[0].processed, 200);
+        assert_eq!(processor.processed.load(Ordering::SeqCst), 200);
+    }
+
+    fn metrics_ms(mempool_wait: u64, signature_verify: u64, state_apply: u64, consensus: u64) -> PerfMetrics {
+        PerfMetrics {
+            mempool_wait: Duration::from_millis(mempool_wait),
+            signature_verify: Duration::from_millis(signature_verify),
+            state_apply: Duration::from_millis(state_apply),
+            consensus: Duration::from_millis(consensus),
+        }
+    }
+
+    #[test]
+    fn test_detects_dominant_stage() {
+        let cases = [
+            (metrics_ms(900, 40, 40, 20), Stage::Mempool),
+            (metrics_ms(10, 700, 200, 90), Stage::SignatureVerify),
+            (metrics_ms(100, 100, 500, 300), Stage::StateApply),
+            (metrics_ms(50, 150, 200, 600), Stage::Consensus),
+        ];
+
+        for (metrics, expected) in cases {
+            assert_eq!(detect_bottlenecks(&metrics).unwrap().stage, expected);
+        }
+    }
+
+    #[test]
+    fn test_severity_reflects_stage_share() {
+        assert_eq!(detect_bottlenecks(&metrics_ms(0, 850, 100, 50)).unwrap().severity, Severity::Critical);
+        assert_eq!(detect_bottlenecks(&metrics_ms(0, 650, 200, 150)).unwrap().severity, Severity::High);
+        assert_eq!(detect_bottlenecks(&metrics_ms(0, 450, 300, 250)).unwrap().severity, Severity::Moderate);
+        assert_eq!(detect_bottlenecks(&metrics_ms(250, 260, 250, 240)).unwrap().severity, Severity::Low);
+    }
+
+    #[test]
+    fn test_no_measurements_means_no_bottleneck() {
+        assert_eq!(detect_bottlenecks(&PerfMetrics::default()), None);
+    }
+
+    #[tokio::test]
+    async fn test_transactions_spread_across_shards() {
+        let processor = Arc::new(CountingProcessor::default());
+        let config = BenchmarkConfig {
+            transaction_loads: vec![2000],
+            threads: 2,
+            shard_count: 4,
+            ..BenchmarkConfig::default()
+        };
+
+        let report = run_benchmark_with(processor.clone(), config).await.unwrap();
+        let result = &report.results[0];
+
+        assert_eq!(result.shards.len(), 4);
+        for shard in &result.shards {
+            assert!((400..=600).contains(&shard.processed), "shard {} got {}", shard.shard_id, shard.processed);
+        }
+        assert_eq!(result.processed, 2000);
+        assert_eq!(processor.processed.load(Ordering::SeqCst), 2000);
+
+        let per_shard_tps: f64 = result.shards.iter().map(|s| s.tps).sum();
+        assert!((result.tps - per_shard_tps).abs() < 1e-6);
+    }
 }
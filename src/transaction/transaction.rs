@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use blake3::Hash;
@@ -6,11 +6,27 @@ use quantumfuse_sdk::{
     error::TransactionError,
     crypto::{Hash, KeyPair, AESGCM},
     pqc::dilithium::{PublicKey, SecretKey, Signature},
-    pqc::kyber512::{KyberCiphertext, KyberKeyPair},
+    pqc::kyber512::KyberKeyPair,
     consensus::QuantumBridge,
     ai::FraudDetectionEngine,
-    state::StateAccess
+    state::StateAccess,
+    codec::to_canonical_cbor,
+    amount::{Amount, RoundingPolicy},
+    address::Address,
 };
+use pqcrypto::prelude::*;
+use pqcrypto::kem::kyber512::{self, encapsulate, decapsulate};
+
+/// Only transaction format currently accepted.
+pub const SUPPORTED_VERSION: u32 = 1;
+/// Gas bounds for user transactions; system transactions carry no gas.
+pub const MIN_GAS_LIMIT: u64 = 21_000;
+pub const MAX_GAS_LIMIT: u64 = 30_000_000;
+/// How far ahead of the local clock a timestamp may be.
+pub const MAX_FUTURE_DRIFT_SECS: i64 = 120;
+/// Headroom added to a measured contract call so small state changes between
+/// estimate and inclusion don't run it out of gas.
+pub const GAS_ESTIMATE_MARGIN_PERCENT: u64 = 20;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuantumTransaction {
@@ -19,14 +35,62 @@ pub struct QuantumTransaction {
     pub nonce: u64,
     pub from: String,
     pub to: String,
-    pub amount: f64,
-    pub fee: f64,
+    pub amount: Amount,
+    pub fee: Amount,
     pub gas_limit: u64,
     pub gas_used: u64,
     pub data: TransactionData,
     pub timestamp: DateTime<Utc>,
     pub signature: Option<Signature>,
     pub quantum_proof: Option<QuantumProof>,
+    #[serde(default)]
+    pub is_system: bool, // Genesis/protocol-issued, exempt from the minimum fee
+    #[serde(default)]
+    pub asset_id: AssetId,
+    /// Chain the transaction was signed for; covered by the signature so it cannot be replayed elsewhere.
+    #[serde(default)]
+    pub chain_id: u64,
+    /// Accounts the transaction may touch. Empty means undeclared, which forces
+    /// the transaction to run on its own.
+    #[serde(default)]
+    pub access_list: Vec<AccessEntry>,
+}
+
+/// Identifier of a transferable asset; the native QFC coin is the default.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssetId(String);
+
+impl AssetId {
+    pub const NATIVE: &'static str = "QFC";
+
+    pub fn new(symbol: &str) -> Self {
+        Self(symbol.to_string())
+    }
+
+    pub fn native() -> Self {
+        Self::new(Self::NATIVE)
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.0 == Self::NATIVE
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for AssetId {
+    fn default() -> Self {
+        Self::native()
+    }
+}
+
+impl std::fmt::Display for AssetId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,7 +100,7 @@ pub struct TransactionData {
     pub payload: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OperationType {
     Transfer,
     Stake,
@@ -48,11 +112,44 @@ pub enum OperationType {
     CreateValidator,
     RemoveValidator,
     UpdateConsensus,
+    FreezeAccount,
+    UnfreezeAccount,
+    UnjailValidator,
+}
+
+// 🔹 **Compliance: Frozen Accounts**
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FrozenAccounts {
+    frozen: HashSet<String>,
+    history: Vec<FreezeRecord>,
+    /// Passed compliance proposals not yet acted on, by proposal id.
+    #[serde(default)]
+    approved: HashMap<String, ComplianceApproval>,
+}
+
+/// What a passed compliance proposal authorises: one freeze or unfreeze of one address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceApproval {
+    pub address: String,
+    pub freeze: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreezeRecord {
+    pub address: String,
+    pub frozen: bool,
+    pub proposal_id: String,
+    pub transaction_hash: Hash,
+    pub timestamp: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuantumProof {
-    pub kyber_ciphertext: KyberCiphertext,
+    /// Kyber512 encapsulation to the verifier's key.
+    pub kyber_ciphertext: Vec<u8>,
+    /// Binds the encapsulated secret to the transaction's signing message.
+    pub commitment: [u8; 32],
+    /// Sender's signature over the ciphertext, commitment and timestamp.
     pub dilithium_signature: Signature,
     pub timestamp: DateTime<Utc>,
 }
@@ -67,6 +164,65 @@ pub struct TransactionReceipt {
     pub logs: Vec<Log>,
     pub events: Vec<Event>,
     pub quantum_security_level: u8,
+    pub fee_distribution: FeeDistribution,
+    /// Fee returned to the sender for unused gas.
+    #[serde(default)]
+    pub gas_refund: Amount,
+}
+
+/// Size bounds, in bytes. Contract deployments carry code in the payload and
+/// get the larger `max_contract_code_size` bound instead of `max_payload_size`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionLimits {
+    pub max_transaction_size: usize,
+    pub max_payload_size: usize,
+    pub max_contract_code_size: usize,
+}
+
+impl Default for TransactionLimits {
+    fn default() -> Self {
+        Self {
+            max_transaction_size: 128 * 1024,
+            max_payload_size: 64 * 1024,
+            max_contract_code_size: 512 * 1024,
+        }
+    }
+}
+
+/// Share of each fee that is burned, in basis points; the remainder tips the block producer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeSplit {
+    burn_basis_points: u16,
+}
+
+/// Unused gas is refunded at the transaction's gas price, but never more than
+/// `max_refund_percent` of the gas actually used, so padding the limit to
+/// reserve block space doesn't come for free.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GasRefundPolicy {
+    pub max_refund_percent: u64,
+}
+
+impl Default for GasRefundPolicy {
+    fn default() -> Self {
+        Self { max_refund_percent: 100 }
+    }
+}
+
+/// What a transaction pays once its gas use is known; `charged` and
+/// `refund` always sum to its `fee`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasSettlement {
+    pub gas_used: u64,
+    pub charged: Amount,
+    pub refund: Amount,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeDistribution {
+    pub burned: Amount,
+    pub validator_tip: Amount,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,19 +246,270 @@ pub enum TransactionStatus {
     Failed(String),
 }
 
+impl FeeSplit {
+    pub const BASIS_POINTS: u16 = 10_000;
+    /// Every fee goes to the producer; for callers that only need a receipt's shape.
+    pub const NO_BURN: FeeSplit = FeeSplit { burn_basis_points: 0 };
+
+    pub fn new(burn_basis_points: u16) -> Result<Self, TransactionError> {
+        if burn_basis_points > Self::BASIS_POINTS {
+            return Err(TransactionError::InvalidFeeSplit);
+        }
+        Ok(Self { burn_basis_points })
+    }
+
+    /// Splits like a reward pool under `RoundingPolicy::FloorToSink`, with the
+    /// tip as the sink: the burned share is rounded down and the parts always
+    /// sum to `fee`.
+    pub fn apply(&self, fee: Amount) -> FeeDistribution {
+        let bps = self.burn_basis_points as u128;
+        let weights = [bps, Self::BASIS_POINTS as u128 - bps];
+        let split = fee.split_weighted(&weights, RoundingPolicy::FloorToSink)
+            .expect("basis-point weights cannot overflow");
+        let burned = split.shares[0];
+
+        FeeDistribution {
+            burned,
+            validator_tip: Amount::from_base_units(fee.base_units() - burned.base_units()),
+        }
+    }
+}
+
+// 🔹 **Simulation**
+/// Read-only account state a simulation runs against.
+pub trait AccountView {
+    fn balance(&self, address: &str, asset_id: &AssetId) -> Amount;
+    /// The nonce the account's next transaction must carry.
+    fn nonce(&self, address: &str) -> u64;
+}
+
+/// Hypothetical values for one account, in the spirit of `eth_call` overrides.
+/// `balance` replaces the native balance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountOverride {
+    pub balance: Option<Amount>,
+    pub nonce: Option<u64>,
+}
+
+/// Overrides keyed by address.
+pub type StateOverrides = HashMap<String, AccountOverride>;
+
+/// `overrides` layered over `base`; reads only, so nothing reaches real state.
+pub struct OverlayState<'a> {
+    base: &'a dyn AccountView,
+    overrides: &'a StateOverrides,
+}
+
+impl<'a> OverlayState<'a> {
+    pub fn new(base: &'a dyn AccountView, overrides: &'a StateOverrides) -> Self {
+        Self { base, overrides }
+    }
+}
+
+impl AccountView for OverlayState<'_> {
+    fn balance(&self, address: &str, asset_id: &AssetId) -> Amount {
+        match self.overrides.get(address).and_then(|o| o.balance) {
+            Some(balance) if asset_id.is_native() => balance,
+            _ => self.base.balance(address, asset_id),
+        }
+    }
+
+    fn nonce(&self, address: &str) -> u64 {
+        self.overrides.get(address)
+            .and_then(|o| o.nonce)
+            .unwrap_or_else(|| self.base.nonce(address))
+    }
+}
+
+// 🔹 **Gas Estimation**
+/// Result of a metered contract execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome {
+    Success { gas_used: u64 },
+    Reverted { gas_used: u64, reason: String },
+    OutOfGas,
+}
+
+/// Runs contract code against current state without committing, metering gas.
+pub trait ContractExecutor: Send + Sync + std::fmt::Debug {
+    fn dry_run(&self, transaction: &QuantumTransaction, gas_limit: u64) -> CallOutcome;
+
+    /// Runs the transaction during block execution, passing each state access
+    /// to `on_access` before making it; an error from the hook aborts the call.
+    /// Executors that don't track storage accesses meter a dry run instead.
+    fn execute(
+        &self,
+        transaction: &QuantumTransaction,
+        gas_limit: u64,
+        _on_access: &dyn Fn(&AccessEntry) -> Result<(), TransactionError>,
+    ) -> Result<CallOutcome, TransactionError> {
+        Ok(self.dry_run(transaction, gas_limit))
+    }
+}
+
+/// Governance-tuned factors applied to base gas per operation type; operations
+/// without an entry use 1.0.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GasMultipliers(HashMap<OperationType, f64>);
+
+impl GasMultipliers {
+    pub const DEFAULT: f64 = 1.0;
+
+    pub fn get(&self, operation_type: &OperationType) -> f64 {
+        self.0.get(operation_type).copied().unwrap_or(Self::DEFAULT)
+    }
+
+    pub fn set(&mut self, operation_type: OperationType, multiplier: f64) -> Result<(), TransactionError> {
+        if !multiplier.is_finite() || multiplier <= 0.0 {
+            return Err(TransactionError::InvalidGasMultiplier);
+        }
+        self.0.insert(operation_type, multiplier);
+        Ok(())
+    }
+
+    /// Scaled gas, rounded up and kept within the gas limit bounds.
+    pub fn apply(&self, operation_type: &OperationType, base_gas: u64) -> u64 {
+        let scaled = (base_gas as f64 * self.get(operation_type)).ceil();
+        (scaled.min(MAX_GAS_LIMIT as f64) as u64).max(MIN_GAS_LIMIT)
+    }
+}
+
+// 🔹 **Access Lists**
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessMode {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessEntry {
+    pub address: String,
+    pub mode: AccessMode,
+}
+
+impl AccessEntry {
+    pub fn read(address: &str) -> Self {
+        Self { address: address.to_string(), mode: AccessMode::Read }
+    }
+
+    pub fn write(address: &str) -> Self {
+        Self { address: address.to_string(), mode: AccessMode::Write }
+    }
+
+    /// Two accesses conflict when they share an address and either writes.
+    fn conflicts_with(&self, other: &AccessEntry) -> bool {
+        self.address == other.address && (self.mode == AccessMode::Write || other.mode == AccessMode::Write)
+    }
+}
+
+/// Groups `transactions` into waves that may each run in parallel, keeping
+/// every conflicting pair in its original order. Returns indexes into
+/// `transactions`. A transaction without an access list gets a wave to itself
+/// and nothing after it moves ahead of it.
+pub fn schedule_parallel(transactions: &[QuantumTransaction]) -> Vec<Vec<usize>> {
+    let mut waves: Vec<Vec<usize>> = Vec::new();
+    let mut placed: Vec<usize> = Vec::with_capacity(transactions.len());
+    // First wave a later transaction may join.
+    let mut barrier = 0;
+
+    for (index, tx) in transactions.iter().enumerate() {
+        let wave = if tx.access_list.is_empty() {
+            let wave = waves.len();
+            barrier = wave + 1;
+            wave
+        } else {
+            transactions[..index].iter()
+                .zip(&placed)
+                .filter(|(earlier, _)| earlier.conflicts_with(tx))
+                .map(|(_, wave)| wave + 1)
+                .fold(barrier, usize::max)
+        };
+
+        if wave == waves.len() {
+            waves.push(Vec::new());
+        }
+        waves[wave].push(index);
+        placed.push(wave);
+    }
+
+    waves
+}
+
+impl FrozenAccounts {
+    pub fn is_frozen(&self, address: &str) -> bool {
+        self.frozen.contains(address)
+    }
+
+    pub fn history(&self) -> &[FreezeRecord] {
+        &self.history
+    }
+
+    /// Rejects transfers where either party is frozen. Compliance actions themselves always pass.
+    pub fn check(&self, transaction: &QuantumTransaction) -> Result<(), TransactionError> {
+        if matches!(transaction.data.operation_type, OperationType::FreezeAccount | OperationType::UnfreezeAccount) {
+            return Ok(());
+        }
+        if self.is_frozen(&transaction.from) || self.is_frozen(&transaction.to) {
+            return Err(TransactionError::AccountFrozen);
+        }
+        Ok(())
+    }
+
+    /// Records a passed compliance proposal so a later system transaction can carry it out.
+    pub fn approve_proposal(&mut self, proposal_id: &str, approval: ComplianceApproval) {
+        self.approved.insert(proposal_id.to_string(), approval);
+    }
+
+    /// Applies a freeze/unfreeze issued by governance. Only system transactions
+    /// referencing an approved proposal for exactly this action are accepted,
+    /// and each approval is used once.
+    pub fn apply_governance_action(&mut self, transaction: &QuantumTransaction) -> Result<(), TransactionError> {
+        let freeze = match transaction.data.operation_type {
+            OperationType::FreezeAccount => true,
+            OperationType::UnfreezeAccount => false,
+            _ => return Err(TransactionError::InvalidOperation),
+        };
+
+        if !transaction.is_system {
+            return Err(TransactionError::UnauthorizedComplianceAction);
+        }
+        let proposal_id = transaction.data.parameters
+            .get("proposal_id")
+            .ok_or(TransactionError::UnauthorizedComplianceAction)?;
+        let authorised = ComplianceApproval { address: transaction.to.clone(), freeze };
+        if self.approved.get(proposal_id) != Some(&authorised) {
+            return Err(TransactionError::UnauthorizedComplianceAction);
+        }
+        self.approved.remove(proposal_id);
+
+        if freeze {
+            self.frozen.insert(transaction.to.clone());
+        } else {
+            self.frozen.remove(&transaction.to);
+        }
+
+        self.history.push(FreezeRecord {
+            address: transaction.to.clone(),
+            frozen: freeze,
+            proposal_id: proposal_id.clone(),
+            transaction_hash: transaction.hash,
+            timestamp: Utc::now(),
+        });
+
+        Ok(())
+    }
+}
+
 impl QuantumTransaction {
     pub fn new(
         from: String,
         to: String,
-        amount: f64,
-        fee: f64,
+        amount: Amount,
+        fee: Amount,
         operation_type: OperationType,
         gas_limit: u64,
     ) -> Result<Self, TransactionError> {
-        if amount < 0.0 || fee < 0.0 {
-            return Err(TransactionError::InvalidAmount);
-        }
-
         let data = TransactionData {
             operation_type,
             parameters: HashMap::new(),
@@ -123,6 +530,10 @@ impl QuantumTransaction {
             timestamp: Utc::now(),
             signature: None,
             quantum_proof: None,
+            is_system: false,
+            asset_id: AssetId::native(),
+            chain_id: 0,
+            access_list: Vec::new(),
         };
 
         tx.hash = tx.calculate_hash()?;
@@ -130,6 +541,58 @@ impl QuantumTransaction {
         Ok(tx)
     }
 
+    /// Transfer of a non-native asset; the fee is still paid in the native coin.
+    pub fn new_asset_transfer(
+        from: String,
+        to: String,
+        asset_id: AssetId,
+        amount: Amount,
+        fee: Amount,
+        gas_limit: u64,
+    ) -> Result<Self, TransactionError> {
+        let mut tx = Self::new(from, to, amount, fee, OperationType::Transfer, gas_limit)?;
+        tx.asset_id = asset_id;
+        tx.hash = tx.calculate_hash()?;
+        Ok(tx)
+    }
+
+    pub fn new_system(to: String, amount: Amount, operation_type: OperationType) -> Result<Self, TransactionError> {
+        let mut tx = Self::new("SYSTEM".to_string(), to, amount, Amount::ZERO, operation_type, 0)?;
+        tx.is_system = true;
+        tx.hash = tx.calculate_hash()?;
+        Ok(tx)
+    }
+
+    /// Binds the transaction to `chain_id`; call before signing.
+    pub fn with_chain_id(mut self, chain_id: u64) -> Result<Self, TransactionError> {
+        self.chain_id = chain_id;
+        self.hash = self.calculate_hash()?;
+        Ok(self)
+    }
+
+    /// Declares the accounts the transaction touches; call before signing.
+    pub fn with_access_list(mut self, access_list: Vec<AccessEntry>) -> Result<Self, TransactionError> {
+        self.access_list = access_list;
+        self.hash = self.calculate_hash()?;
+        Ok(self)
+    }
+
+    /// Rejects transactions signed for another chain. System transactions are
+    /// produced locally and carry no chain id.
+    pub fn validate_chain(&self, chain_id: u64) -> Result<(), TransactionError> {
+        if !self.is_system && self.chain_id != chain_id {
+            return Err(TransactionError::WrongChain { expected: chain_id, actual: self.chain_id });
+        }
+        Ok(())
+    }
+
+    pub fn validate_fee(&self, min_fee: Amount) -> Result<(), TransactionError> {
+        if !self.is_system && self.fee < min_fee {
+            return Err(TransactionError::FeeTooLow);
+        }
+        Ok(())
+    }
+
     pub fn sign(&mut self, keypair: &KeyPair) -> Result<(), TransactionError> {
         let message = self.get_signing_message()?;
         let signature = keypair.sign(&message)?;
@@ -138,8 +601,26 @@ impl QuantumTransaction {
         Ok(())
     }
 
+    /// Like `sign`, for signers that keep their key elsewhere, such as a secure enclave.
+    pub fn sign_with<F, E>(&mut self, signer: F) -> Result<(), E>
+    where
+        F: FnOnce(&[u8]) -> Result<Signature, E>,
+        E: From<TransactionError>,
+    {
+        let message = self.get_signing_message()?;
+        self.signature = Some(signer(&message)?);
+        self.hash = self.calculate_hash()?;
+        Ok(())
+    }
+
     pub fn verify(&self) -> Result<bool, TransactionError> {
-        self.validate_basics()?;
+        self.verify_with_skew(MAX_FUTURE_DRIFT_SECS)
+    }
+
+    /// `verify`, accepting timestamps up to `max_clock_skew_secs` ahead of the local clock.
+    pub fn verify_with_skew(&self, max_clock_skew_secs: i64) -> Result<bool, TransactionError> {
+        self.validate_addresses()?;
+        self.validate_basics_with_skew(Utc::now(), max_clock_skew_secs)?;
 
         if let Some(signature) = &self.signature {
             let message = self.get_signing_message()?;
@@ -158,7 +639,152 @@ impl QuantumTransaction {
         Ok(true)
     }
 
-    pub fn execute_parallel(&self, state: &dyn StateAccess) -> Result<TransactionReceipt, TransactionError> {
+    /// Verifies a system transaction, which carries no sender key of its own,
+    /// against the key of `issuer`, the producer of the block holding it.
+    /// Timestamps may be up to `max_clock_skew_secs` ahead of the local clock.
+    pub fn verify_issued_by(&self, issuer: &str, max_clock_skew_secs: i64) -> Result<bool, TransactionError> {
+        if !self.is_system {
+            return Err(TransactionError::InvalidOperation);
+        }
+        self.validate_addresses()?;
+        self.validate_basics_with_skew(Utc::now(), max_clock_skew_secs)?;
+
+        let Some(signature) = &self.signature else {
+            return Ok(false);
+        };
+        let message = self.get_signing_message()?;
+        Ok(PublicKey::from_address(issuer)?.verify(&message, signature)?)
+    }
+
+    /// Encapsulates a secret to `verifier_key`, commits it to this transaction and
+    /// signs the result. Call after `sign`; the transaction hash covers the proof.
+    pub fn attach_quantum_proof(&mut self, keypair: &KeyPair, verifier_key: &kyber512::PublicKey) -> Result<(), TransactionError> {
+        let message = self.get_signing_message()?;
+        let (shared_secret, ciphertext) = encapsulate(verifier_key);
+        let kyber_ciphertext = ciphertext.as_bytes().to_vec();
+        let commitment = proof_commitment(shared_secret.as_bytes(), &message);
+        let timestamp = Utc::now();
+
+        let dilithium_signature = keypair.sign(&proof_message(&message, &kyber_ciphertext, &commitment, timestamp))?;
+        self.quantum_proof = Some(QuantumProof { kyber_ciphertext, commitment, dilithium_signature, timestamp });
+        self.hash = self.calculate_hash()?;
+        Ok(())
+    }
+
+    /// Checks that the ciphertext is a well-formed Kyber512 ciphertext and that
+    /// the sender signed it for this transaction.
+    pub fn verify_quantum_proof(&self, proof: &QuantumProof) -> Result<(), TransactionError> {
+        kyber512::Ciphertext::from_bytes(&proof.kyber_ciphertext)
+            .map_err(|_| TransactionError::InvalidQuantumProof)?;
+
+        let message = proof_message(&self.get_signing_message()?, &proof.kyber_ciphertext, &proof.commitment, proof.timestamp);
+        let public_key = PublicKey::from_address(&self.from)?;
+        if !public_key.verify(&message, &proof.dilithium_signature)? {
+            return Err(TransactionError::InvalidQuantumProof);
+        }
+        Ok(())
+    }
+
+    /// Full check for the holder of the verifier key: the decapsulated secret
+    /// must reproduce the commitment.
+    pub fn verify_quantum_proof_with_key(&self, secret_key: &kyber512::SecretKey) -> Result<(), TransactionError> {
+        let proof = self.quantum_proof.as_ref().ok_or(TransactionError::InvalidQuantumProof)?;
+        self.verify_quantum_proof(proof)?;
+
+        let ciphertext = kyber512::Ciphertext::from_bytes(&proof.kyber_ciphertext)
+            .map_err(|_| TransactionError::InvalidQuantumProof)?;
+        let shared_secret = decapsulate(&ciphertext, secret_key);
+        let expected = proof_commitment(shared_secret.as_bytes(), &self.get_signing_message()?);
+        // blake3::Hash compares in constant time.
+        if blake3::Hash::from(expected) != blake3::Hash::from(proof.commitment) {
+            return Err(TransactionError::InvalidQuantumProof);
+        }
+        Ok(())
+    }
+
+    pub fn validate_basics(&self) -> Result<(), TransactionError> {
+        self.validate_basics_at(Utc::now())
+    }
+
+    /// Range checks that need no state or signature. Amounts are unsigned, so the only
+    /// invalid amount is zero on an operation that moves value.
+    pub fn validate_basics_at(&self, now: DateTime<Utc>) -> Result<(), TransactionError> {
+        self.validate_basics_with_skew(now, MAX_FUTURE_DRIFT_SECS)
+    }
+
+    /// Timestamps up to and including `now + max_clock_skew_secs` are accepted.
+    pub fn validate_basics_with_skew(&self, now: DateTime<Utc>, max_clock_skew_secs: i64) -> Result<(), TransactionError> {
+        if self.version != SUPPORTED_VERSION {
+            return Err(TransactionError::UnsupportedVersion(self.version));
+        }
+        // u64::MAX is reserved so the next nonce can never overflow.
+        if self.nonce == u64::MAX {
+            return Err(TransactionError::InvalidNonce);
+        }
+        if self.timestamp > now + chrono::Duration::seconds(max_clock_skew_secs) {
+            return Err(TransactionError::FutureTimestamp);
+        }
+        if self.is_system {
+            return Ok(());
+        }
+
+        if !(MIN_GAS_LIMIT..=MAX_GAS_LIMIT).contains(&self.gas_limit) {
+            return Err(TransactionError::InvalidGasLimit(self.gas_limit));
+        }
+        let moves_value = matches!(
+            self.data.operation_type,
+            OperationType::Transfer | OperationType::Stake | OperationType::Unstake | OperationType::BridgeAsset
+        );
+        if moves_value && self.amount.is_zero() {
+            return Err(TransactionError::InvalidAmount);
+        }
+        Ok(())
+    }
+
+    /// Rejects transactions whose payload or encoded size exceeds `limits`.
+    pub fn validate_size(&self, limits: &TransactionLimits) -> Result<(), TransactionError> {
+        let (payload_limit, size_limit) = match self.data.operation_type {
+            OperationType::DeployContract => (
+                limits.max_contract_code_size,
+                limits.max_transaction_size.saturating_add(limits.max_contract_code_size),
+            ),
+            _ => (limits.max_payload_size, limits.max_transaction_size),
+        };
+
+        let payload_size = self.data.payload.len();
+        if payload_size > payload_limit {
+            return Err(TransactionError::TransactionTooLarge { size: payload_size, limit: payload_limit });
+        }
+
+        let size = to_canonical_cbor(self).map_err(|_| TransactionError::SerializationError)?.len();
+        if size > size_limit {
+            return Err(TransactionError::TransactionTooLarge { size, limit: size_limit });
+        }
+        Ok(())
+    }
+
+    /// Rejects malformed or mistyped addresses before any signature work.
+    /// System transactions have no sender address.
+    pub fn validate_addresses(&self) -> Result<(), TransactionError> {
+        if !self.is_system {
+            Address::from_address(&self.from).map_err(|_| TransactionError::InvalidAddress(self.from.clone()))?;
+        }
+        Address::from_address(&self.to).map_err(|_| TransactionError::InvalidAddress(self.to.clone()))?;
+        Ok(())
+    }
+
+    /// Executes the transaction and settles its gas: the sender pays for the gas
+    /// the execution reports, priced by `multipliers`, less the refund
+    /// `refund_policy` allows.
+    pub fn execute_parallel(
+        &self,
+        state: &dyn StateAccess,
+        fee_split: &FeeSplit,
+        multipliers: &GasMultipliers,
+        refund_policy: &GasRefundPolicy,
+    ) -> Result<TransactionReceipt, TransactionError> {
+        self.check_access(&self.intrinsic_accesses())?;
+
         // Use AI for fraud detection
         let fraud_detector = FraudDetectionEngine::new();
         if fraud_detector.detect_anomalies(self)? {
@@ -166,10 +792,181 @@ impl QuantumTransaction {
         }
 
         // Process transaction in parallel execution pool
-        state.process_transaction_parallel(self)?;
+        let gas_used = state.process_transaction_parallel(self)?;
+
+        let settlement = self.settle_measured_gas(gas_used, multipliers, refund_policy)?;
+        Ok(self.settled_receipt(&settlement, fee_split))
+    }
+
+    /// Executes the transaction through `executor` and settles the fee on the
+    /// gas it used; see `execute_gas`.
+    pub fn settle_gas(
+        &self,
+        executor: &dyn ContractExecutor,
+        multipliers: &GasMultipliers,
+        policy: &GasRefundPolicy,
+    ) -> Result<GasSettlement, TransactionError> {
+        self.settle_measured_gas(self.execute_gas(executor)?, multipliers, policy)
+    }
+
+    /// Splits `fee` (the price of the full `gas_limit`) into the part charged
+    /// for `base_gas` scaled by the operation's multiplier and the refund for
+    /// unused gas under `policy`. The refund rounds down, so any dust is charged.
+    /// `base_gas` must come from executing the transaction.
+    fn settle_measured_gas(
+        &self,
+        base_gas: u64,
+        multipliers: &GasMultipliers,
+        policy: &GasRefundPolicy,
+    ) -> Result<GasSettlement, TransactionError> {
+        let gas_used = multipliers.apply(&self.data.operation_type, base_gas);
+        // A zero gas limit opts out of metering: the whole fee is charged.
+        if self.gas_limit == 0 {
+            return Ok(GasSettlement { gas_used, charged: self.fee, refund: Amount::ZERO });
+        }
+        if gas_used > self.gas_limit {
+            return Err(TransactionError::OutOfGas);
+        }
+
+        let cap = gas_used.saturating_mul(policy.max_refund_percent) / 100;
+        let refunded_gas = (self.gas_limit - gas_used).min(cap);
+        let refund = self.fee.checked_mul(refunded_gas as u128)
+            .and_then(|scaled| scaled.checked_div(self.gas_limit as u128))
+            .ok_or(TransactionError::InvalidAmount)?;
+        let charged = self.fee.checked_sub(refund).ok_or(TransactionError::InvalidAmount)?;
+        Ok(GasSettlement { gas_used, charged, refund })
+    }
+
+    /// Receipt for an executed transaction: only the charged part of the fee
+    /// is burned or tipped.
+    pub fn settled_receipt(&self, settlement: &GasSettlement, fee_split: &FeeSplit) -> TransactionReceipt {
+        TransactionReceipt {
+            gas_used: settlement.gas_used,
+            fee_distribution: fee_split.apply(settlement.charged),
+            gas_refund: settlement.refund,
+            ..self.receipt(fee_split)
+        }
+    }
+
+    /// Dry-runs the transaction against `state` with `overrides` layered on
+    /// top, returning the receipt it would produce. Nothing is written.
+    pub fn simulate(&self, state: &dyn AccountView, overrides: &StateOverrides, fee_split: &FeeSplit) -> Result<TransactionReceipt, TransactionError> {
+        self.validate_basics()?;
+        let view = OverlayState::new(state, overrides);
+
+        if !self.is_system {
+            if self.nonce != view.nonce(&self.from) {
+                return Err(TransactionError::InvalidNonce);
+            }
+
+            let native = AssetId::native();
+            let native_needed = if self.asset_id.is_native() {
+                self.amount.checked_add(self.fee).ok_or(TransactionError::InvalidAmount)?
+            } else {
+                if view.balance(&self.from, &self.asset_id) < self.amount {
+                    return Err(TransactionError::InsufficientBalance);
+                }
+                self.fee
+            };
+            if view.balance(&self.from, &native) < native_needed {
+                return Err(TransactionError::InsufficientBalance);
+            }
+        }
+
+        Ok(self.receipt(fee_split))
+    }
+
+    /// Gas limit to set on this transaction. Contract calls and deploys are
+    /// dry-run at the maximum limit and the measured gas plus
+    /// `GAS_ESTIMATE_MARGIN_PERCENT` is returned; a revert is reported as such.
+    pub fn estimate_gas(&self, executor: &dyn ContractExecutor) -> Result<u64, TransactionError> {
+        if !matches!(self.data.operation_type, OperationType::CallContract | OperationType::DeployContract) {
+            return Ok(MIN_GAS_LIMIT);
+        }
+
+        match executor.dry_run(self, MAX_GAS_LIMIT) {
+            CallOutcome::Success { gas_used } => {
+                let with_margin = gas_used.saturating_add(gas_used.saturating_mul(GAS_ESTIMATE_MARGIN_PERCENT) / 100);
+                Ok(with_margin.clamp(MIN_GAS_LIMIT, MAX_GAS_LIMIT))
+            }
+            CallOutcome::Reverted { reason, .. } => Err(TransactionError::ExecutionReverted(reason)),
+            CallOutcome::OutOfGas => Err(TransactionError::OutOfGas),
+        }
+    }
+
+    /// Base gas the transaction consumes when `executor` runs it at its own
+    /// gas limit: a revert still pays for what it used and running out pays the
+    /// whole limit. Non-contract operations cost `MIN_GAS_LIMIT`. The `gas_used`
+    /// field isn't signed, so settlement never reads it.
+    pub fn measure_gas(&self, executor: &dyn ContractExecutor) -> u64 {
+        if !matches!(self.data.operation_type, OperationType::CallContract | OperationType::DeployContract) {
+            return MIN_GAS_LIMIT;
+        }
+
+        self.gas_of(executor.dry_run(self, self.gas_limit))
+    }
+
+    /// `measure_gas` for block execution: the intrinsic accesses and every
+    /// access the executor reports must be covered by the access list.
+    pub fn execute_gas(&self, executor: &dyn ContractExecutor) -> Result<u64, TransactionError> {
+        self.check_access(&self.intrinsic_accesses())?;
+        if !matches!(self.data.operation_type, OperationType::CallContract | OperationType::DeployContract) {
+            return Ok(MIN_GAS_LIMIT);
+        }
+
+        let outcome = executor.execute(self, self.gas_limit, &|access| self.check_access(std::slice::from_ref(access)))?;
+        Ok(self.gas_of(outcome))
+    }
+
+    fn gas_of(&self, outcome: CallOutcome) -> u64 {
+        match outcome {
+            CallOutcome::Success { gas_used } | CallOutcome::Reverted { gas_used, .. } => gas_used,
+            CallOutcome::OutOfGas => self.gas_limit,
+        }
+    }
+
+    /// `estimate_gas` scaled by the multiplier for this transaction's operation type.
+    pub fn estimate_gas_usage(&self, executor: &dyn ContractExecutor, multipliers: &GasMultipliers) -> Result<u64, TransactionError> {
+        let base_gas = self.estimate_gas(executor)?;
+        Ok(multipliers.apply(&self.data.operation_type, base_gas))
+    }
+
+    /// Accounts every transaction touches regardless of what it executes.
+    pub fn intrinsic_accesses(&self) -> Vec<AccessEntry> {
+        let mut accesses = vec![AccessEntry::write(&self.to)];
+        if !self.is_system {
+            accesses.push(AccessEntry::write(&self.from));
+        }
+        accesses
+    }
+
+    /// Errors on the first access the access list doesn't cover. Executors
+    /// report contract storage accesses here as they happen. Undeclared
+    /// transactions run alone, so anything goes.
+    pub fn check_access(&self, accessed: &[AccessEntry]) -> Result<(), TransactionError> {
+        if self.access_list.is_empty() {
+            return Ok(());
+        }
+
+        for access in accessed {
+            let covered = self.access_list.iter().any(|declared| {
+                declared.address == access.address
+                    && (declared.mode == AccessMode::Write || access.mode == AccessMode::Read)
+            });
+            if !covered {
+                return Err(TransactionError::UndeclaredAccess(access.address.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn conflicts_with(&self, other: &QuantumTransaction) -> bool {
+        self.access_list.iter().any(|a| other.access_list.iter().any(|b| a.conflicts_with(b)))
+    }
 
-        // Generate execution receipt
-        Ok(TransactionReceipt {
+    /// Receipt charging the full gas limit, for dry runs where gas use isn't known yet.
+    fn receipt(&self, fee_split: &FeeSplit) -> TransactionReceipt {
+        TransactionReceipt {
             transaction_hash: self.hash,
             block_height: 0,
             block_hash: Hash::default(),
@@ -178,11 +975,39 @@ impl QuantumTransaction {
             logs: Vec::new(),
             events: Vec::new(),
             quantum_security_level: self.get_security_level(),
-        })
+            fee_distribution: fee_split.apply(self.fee),
+            gas_refund: Amount::ZERO,
+        }
+    }
+
+    /// Everything the signature covers, including the chain id.
+    fn get_signing_message(&self) -> Result<Vec<u8>, TransactionError> {
+        let mut hasher = blake3::Hasher::new();
+        self.hash_unsigned_fields(&mut hasher)?;
+        Ok(hasher.finalize().as_bytes().to_vec())
     }
 
-    fn calculate_hash(&self) -> Result<Hash, TransactionError> {
+    /// Hash over the signed fields and signature; peers recompute it rather
+    /// than trust the `hash` a transaction carries.
+    pub fn calculate_hash(&self) -> Result<Hash, TransactionError> {
         let mut hasher = blake3::Hasher::new();
+        self.hash_unsigned_fields(&mut hasher)?;
+
+        if let Some(signature) = &self.signature {
+            hasher.update(&signature.to_bytes());
+        }
+
+        if let Some(proof) = &self.quantum_proof {
+            hasher.update(&proof.kyber_ciphertext);
+            hasher.update(&proof.commitment);
+            hasher.update(&proof.dilithium_signature.to_bytes());
+            hasher.update(&proof.timestamp.timestamp().to_le_bytes());
+        }
+
+        Ok(Hash::from(hasher.finalize()))
+    }
+
+    fn hash_unsigned_fields(&self, hasher: &mut blake3::Hasher) -> Result<(), TransactionError> {
         hasher.update(&self.version.to_le_bytes());
         hasher.update(&self.nonce.to_le_bytes());
         hasher.update(self.from.as_bytes());
@@ -190,19 +1015,783 @@ impl QuantumTransaction {
         hasher.update(&self.amount.to_le_bytes());
         hasher.update(&self.fee.to_le_bytes());
         hasher.update(&self.gas_limit.to_le_bytes());
+        hasher.update(&[self.is_system as u8]);
+        // Native transfers hash exactly as before multi-asset support.
+        if !self.asset_id.is_native() {
+            hasher.update(self.asset_id.as_str().as_bytes());
+        }
         hasher.update(&self.timestamp.timestamp().to_le_bytes());
-        hasher.update(&serde_json::to_vec(&self.data).map_err(|_| TransactionError::SerializationError)?);
+        hasher.update(&to_canonical_cbor(&self.data).map_err(|_| TransactionError::SerializationError)?);
+        // Transactions not bound to a chain hash exactly as before chain ids.
+        if self.chain_id != 0 {
+            hasher.update(&self.chain_id.to_le_bytes());
+        }
+        if !self.access_list.is_empty() {
+            hasher.update(&to_canonical_cbor(&self.access_list).map_err(|_| TransactionError::SerializationError)?);
+        }
+        Ok(())
+    }
+}
 
-        if let Some(signature) = &self.signature {
-            hasher.update(&signature.to_bytes());
+fn proof_commitment(shared_secret: &[u8], signing_message: &[u8]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new_derive_key("quantumfuse tx quantum proof v1");
+    hasher.update(shared_secret);
+    hasher.update(signing_message);
+    *hasher.finalize().as_bytes()
+}
+
+fn proof_message(signing_message: &[u8], ciphertext: &[u8], commitment: &[u8; 32], timestamp: DateTime<Utc>) -> Vec<u8> {
+    let mut message = Vec::with_capacity(signing_message.len() + ciphertext.len() + 40);
+    message.extend_from_slice(signing_message);
+    message.extend_from_slice(ciphertext);
+    message.extend_from_slice(commitment);
+    message.extend_from_slice(&timestamp.timestamp().to_le_bytes());
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_independent_of_parameter_insertion_order() {
+        let mut first = QuantumTransaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(10),
+            Amount::from_base_units(100_000),
+            OperationType::CallContract,
+            50_000,
+        ).unwrap();
+        let mut second = first.clone();
+
+        first.data.parameters = HashMap::new();
+        second.data.parameters = HashMap::new();
+        for i in 0..16 {
+            first.data.parameters.insert(format!("arg_{}", i), i.to_string());
+        }
+        for i in (0..16).rev() {
+            second.data.parameters.insert(format!("arg_{}", i), i.to_string());
         }
 
-        if let Some(proof) = &self.quantum_proof {
-            hasher.update(&proof.kyber_ciphertext.to_bytes());
-            hasher.update(&proof.dilithium_signature.to_bytes());
-            hasher.update(&proof.timestamp.timestamp().to_le_bytes());
+        assert_eq!(first.calculate_hash().unwrap(), second.calculate_hash().unwrap());
+    }
+
+    #[test]
+    fn test_minimum_fee_enforcement() {
+        let min_fee = Amount::from_base_units(1_000);
+        let transfer = |fee| QuantumTransaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(1),
+            fee,
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+
+        let below = transfer(Amount::from_base_units(999));
+        assert!(matches!(below.validate_fee(min_fee), Err(TransactionError::FeeTooLow)));
+
+        let at_minimum = transfer(min_fee);
+        assert!(at_minimum.validate_fee(min_fee).is_ok());
+
+        let system = QuantumTransaction::new_system("validator_1".to_string(), Amount::from_whole(5), OperationType::CreateValidator).unwrap();
+        assert!(system.validate_fee(min_fee).is_ok());
+    }
+
+    fn compliance_action(operation_type: OperationType, address: &str) -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new_system(address.to_string(), Amount::ZERO, operation_type).unwrap();
+        tx.data.parameters.insert("proposal_id".to_string(), "proposal-42".to_string());
+        tx
+    }
+
+    #[test]
+    fn test_frozen_accounts_block_transfers() {
+        let mut frozen = FrozenAccounts::default();
+        let transfer = |from: &str, to: &str| QuantumTransaction::new(
+            from.to_string(),
+            to.to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+
+        frozen.approve_proposal("proposal-42", ComplianceApproval { address: "sanctioned".to_string(), freeze: true });
+        frozen.apply_governance_action(&compliance_action(OperationType::FreezeAccount, "sanctioned")).unwrap();
+        assert!(matches!(frozen.check(&transfer("sanctioned", "alice")), Err(TransactionError::AccountFrozen)));
+        assert!(matches!(frozen.check(&transfer("alice", "sanctioned")), Err(TransactionError::AccountFrozen)));
+        assert!(frozen.check(&transfer("alice", "bob")).is_ok());
+
+        frozen.approve_proposal("proposal-42", ComplianceApproval { address: "sanctioned".to_string(), freeze: false });
+        frozen.apply_governance_action(&compliance_action(OperationType::UnfreezeAccount, "sanctioned")).unwrap();
+        assert!(frozen.check(&transfer("sanctioned", "alice")).is_ok());
+        assert_eq!(frozen.history().len(), 2);
+        assert_eq!(frozen.history()[0].proposal_id, "proposal-42");
+    }
+
+    #[test]
+    fn test_freeze_requires_governed_system_transaction() {
+        let mut frozen = FrozenAccounts::default();
+        let mut user_tx = compliance_action(OperationType::FreezeAccount, "alice");
+        user_tx.is_system = false;
+
+        let result = frozen.apply_governance_action(&user_tx);
+        assert!(matches!(result, Err(TransactionError::UnauthorizedComplianceAction)));
+        assert!(!frozen.is_frozen("alice"));
+    }
+
+    #[test]
+    fn test_freeze_requires_matching_approved_proposal() {
+        let mut frozen = FrozenAccounts::default();
+        let action = compliance_action(OperationType::FreezeAccount, "alice");
+
+        // Unknown proposal.
+        assert!(matches!(frozen.apply_governance_action(&action), Err(TransactionError::UnauthorizedComplianceAction)));
+
+        // Approved for a different address.
+        frozen.approve_proposal("proposal-42", ComplianceApproval { address: "bob".to_string(), freeze: true });
+        assert!(matches!(frozen.apply_governance_action(&action), Err(TransactionError::UnauthorizedComplianceAction)));
+
+        // An approval is spent by the action it authorises.
+        frozen.approve_proposal("proposal-42", ComplianceApproval { address: "alice".to_string(), freeze: true });
+        frozen.apply_governance_action(&action).unwrap();
+        assert!(frozen.is_frozen("alice"));
+        assert!(matches!(frozen.apply_governance_action(&action), Err(TransactionError::UnauthorizedComplianceAction)));
+    }
+
+    #[test]
+    fn test_malformed_address_rejected_before_signature_check() {
+        let valid = Address::from_public_keys(b"dilithium", b"kyber").to_string();
+        let tx = QuantumTransaction::new(
+            valid.clone(),
+            format!("{}x", valid),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+
+        assert!(matches!(tx.verify(), Err(TransactionError::InvalidAddress(_))));
+    }
+
+    fn signed_transfer(chain_id: u64) -> QuantumTransaction {
+        let keypair = KeyPair::generate();
+        let mut tx = QuantumTransaction::new(
+            keypair.address(),
+            Address::from_public_keys(b"dilithium", b"kyber").to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap().with_chain_id(chain_id).unwrap();
+        tx.sign(&keypair).unwrap();
+        tx
+    }
+
+    #[test]
+    fn test_same_chain_transaction_accepted() {
+        let tx = signed_transfer(1);
+        assert!(tx.verify().unwrap());
+        assert!(tx.validate_chain(1).is_ok());
+    }
+
+    #[test]
+    fn test_cross_chain_replay_rejected() {
+        let tx = signed_transfer(1);
+        assert!(matches!(
+            tx.validate_chain(2),
+            Err(TransactionError::WrongChain { expected: 2, actual: 1 })
+        ));
+
+        // Rewriting the chain id to match the target invalidates the signature.
+        let mut replayed = tx.clone();
+        replayed.chain_id = 2;
+        assert!(replayed.validate_chain(2).is_ok());
+        assert!(!replayed.verify().unwrap());
+    }
+
+    fn proven_transfer() -> (QuantumTransaction, kyber512::SecretKey) {
+        let keypair = KeyPair::generate();
+        let (verifier_public, verifier_secret) = kyber512::keypair();
+        let mut tx = QuantumTransaction::new(
+            keypair.address(),
+            Address::from_public_keys(b"dilithium", b"kyber").to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+        tx.sign(&keypair).unwrap();
+        tx.attach_quantum_proof(&keypair, &verifier_public).unwrap();
+        (tx, verifier_secret)
+    }
+
+    #[test]
+    fn test_valid_quantum_proof_verifies() {
+        let (tx, verifier_secret) = proven_transfer();
+        assert!(tx.verify().unwrap());
+        assert!(tx.verify_quantum_proof_with_key(&verifier_secret).is_ok());
+    }
+
+    #[test]
+    fn test_forged_quantum_proof_fails() {
+        let (tx, verifier_secret) = proven_transfer();
+
+        let mut truncated = tx.clone();
+        truncated.quantum_proof.as_mut().unwrap().kyber_ciphertext.pop();
+        assert!(matches!(truncated.verify(), Err(TransactionError::InvalidQuantumProof)));
+
+        let mut forged = tx.clone();
+        forged.quantum_proof.as_mut().unwrap().kyber_ciphertext[0] ^= 0xff;
+        assert!(matches!(forged.verify(), Err(TransactionError::InvalidQuantumProof)));
+
+        // Swapping in the commitment from another proof is caught as well.
+        let (other, _) = proven_transfer();
+        let mut mismatched = tx.clone();
+        mismatched.quantum_proof.as_mut().unwrap().commitment = other.quantum_proof.unwrap().commitment;
+        assert!(matches!(
+            mismatched.verify_quantum_proof_with_key(&verifier_secret),
+            Err(TransactionError::InvalidQuantumProof)
+        ));
+    }
+
+    fn with_payload(operation_type: OperationType, payload_size: usize) -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Amount::ZERO,
+            Amount::from_base_units(1_000),
+            operation_type,
+            1_000_000,
+        ).unwrap();
+        tx.data.payload = vec![0xab; payload_size];
+        tx
+    }
+
+    #[test]
+    fn test_payload_size_limits() {
+        let limits = TransactionLimits::default();
+
+        let at_limit = with_payload(OperationType::CallContract, limits.max_payload_size);
+        assert!(at_limit.validate_size(&limits).is_ok());
+
+        let oversized = with_payload(OperationType::CallContract, limits.max_payload_size + 1);
+        assert!(matches!(
+            oversized.validate_size(&limits),
+            Err(TransactionError::TransactionTooLarge { limit, .. }) if limit == limits.max_payload_size
+        ));
+    }
+
+    #[test]
+    fn test_timestamp_skew_boundary() {
+        let now = Utc::now();
+        let mut tx = with_payload(OperationType::CallContract, 0);
+
+        tx.timestamp = now + chrono::Duration::seconds(10);
+        assert!(tx.validate_basics_with_skew(now, 30).is_ok());
+        tx.timestamp = now + chrono::Duration::seconds(30);
+        assert!(tx.validate_basics_with_skew(now, 30).is_ok());
+        tx.timestamp = now + chrono::Duration::seconds(31);
+        assert!(matches!(tx.validate_basics_with_skew(now, 30), Err(TransactionError::FutureTimestamp)));
+    }
+
+    /// Balances and nonces keyed by address, standing in for chain state.
+    #[derive(Default)]
+    struct TestAccounts {
+        balances: HashMap<(String, AssetId), Amount>,
+        nonces: HashMap<String, u64>,
+    }
+
+    impl AccountView for TestAccounts {
+        fn balance(&self, address: &str, asset_id: &AssetId) -> Amount {
+            self.balances.get(&(address.to_string(), asset_id.clone())).copied().unwrap_or(Amount::ZERO)
         }
 
-        Ok(Hash::from(hasher.finalize()))
+        fn nonce(&self, address: &str) -> u64 {
+            self.nonces.get(address).copied().unwrap_or(0)
+        }
+    }
+
+    fn simulated_transfer() -> QuantumTransaction {
+        QuantumTransaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Amount::from_whole(50),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_balance_override_lets_simulation_succeed() {
+        let mut state = TestAccounts::default();
+        state.balances.insert(("sender".to_string(), AssetId::native()), Amount::from_whole(10));
+        let fee_split = FeeSplit::new(5_000).unwrap();
+        let tx = simulated_transfer();
+
+        assert!(matches!(
+            tx.simulate(&state, &StateOverrides::new(), &fee_split),
+            Err(TransactionError::InsufficientBalance)
+        ));
+
+        let mut overrides = StateOverrides::new();
+        overrides.insert("sender".to_string(), AccountOverride { balance: Some(Amount::from_whole(100)), nonce: None });
+        let receipt = tx.simulate(&state, &overrides, &fee_split).unwrap();
+        assert_eq!(receipt.transaction_hash, tx.hash);
+        assert!(matches!(receipt.status, TransactionStatus::Confirmed));
+        assert_eq!(receipt.fee_distribution, fee_split.apply(tx.fee));
+
+        // A nonce override is honoured the same way.
+        overrides.get_mut("sender").unwrap().nonce = Some(3);
+        assert!(matches!(tx.simulate(&state, &overrides, &fee_split), Err(TransactionError::InvalidNonce)));
+    }
+
+    #[test]
+    fn test_overrides_do_not_leak_into_state() {
+        let mut state = TestAccounts::default();
+        state.balances.insert(("sender".to_string(), AssetId::native()), Amount::from_whole(10));
+        state.nonces.insert("sender".to_string(), 0);
+        let fee_split = FeeSplit::new(0).unwrap();
+
+        let mut overrides = StateOverrides::new();
+        overrides.insert("sender".to_string(), AccountOverride { balance: Some(Amount::from_whole(100)), nonce: Some(0) });
+        simulated_transfer().simulate(&state, &overrides, &fee_split).unwrap();
+
+        assert_eq!(state.balance("sender", &AssetId::native()), Amount::from_whole(10));
+        assert_eq!(state.nonce("sender"), 0);
+        assert_eq!(overrides["sender"].balance, Some(Amount::from_whole(100)));
+        assert!(simulated_transfer().simulate(&state, &StateOverrides::new(), &fee_split).is_err());
+    }
+
+    /// Interprets the payload as `[op, iterations (u32 le)]`: op 0 returns at
+    /// once, 1 loops `iterations` times, 2 reverts.
+    #[derive(Debug)]
+    struct LoopingExecutor;
+
+    impl ContractExecutor for LoopingExecutor {
+        fn dry_run(&self, transaction: &QuantumTransaction, gas_limit: u64) -> CallOutcome {
+            let payload = &transaction.data.payload;
+            let iterations = payload.get(1..5).map_or(0, |b| u32::from_le_bytes(b.try_into().unwrap())) as u64;
+            let gas_used = match payload.first() {
+                Some(1) => 30_000 + iterations * 2_000,
+                Some(2) => return CallOutcome::Reverted { gas_used: 24_000, reason: "caller not allowed".to_string() },
+                _ => 30_000,
+            };
+            if gas_used > gas_limit {
+                return CallOutcome::OutOfGas;
+            }
+            CallOutcome::Success { gas_used }
+        }
+    }
+
+    fn contract_call(op: u8, iterations: u32) -> QuantumTransaction {
+        let mut tx = with_payload(OperationType::CallContract, 0);
+        tx.data.payload = [vec![op], iterations.to_le_bytes().to_vec()].concat();
+        tx
+    }
+
+    #[test]
+    fn test_cheap_call_estimates_low() {
+        assert_eq!(contract_call(0, 0).estimate_gas(&LoopingExecutor).unwrap(), 36_000);
+        assert_eq!(with_payload(OperationType::Transfer, 0).estimate_gas(&LoopingExecutor).unwrap(), MIN_GAS_LIMIT);
+    }
+
+    #[test]
+    fn test_loop_heavy_call_estimates_high() {
+        let estimate = contract_call(1, 5_000).estimate_gas(&LoopingExecutor).unwrap();
+        assert_eq!(estimate, (30_000 + 5_000 * 2_000) * 120 / 100);
+        assert!(estimate > contract_call(1, 10).estimate_gas(&LoopingExecutor).unwrap());
+
+        // Beyond the block gas limit the call can never succeed.
+        assert!(matches!(contract_call(1, 20_000).estimate_gas(&LoopingExecutor), Err(TransactionError::OutOfGas)));
+    }
+
+    #[test]
+    fn test_reverting_call_surfaces_revert() {
+        let result = contract_call(2, 0).estimate_gas(&LoopingExecutor);
+        assert!(matches!(result, Err(TransactionError::ExecutionReverted(ref reason)) if reason == "caller not allowed"));
+    }
+
+    fn gas_priced_transfer(gas_limit: u64) -> QuantumTransaction {
+        let mut tx = with_payload(OperationType::Transfer, 0);
+        tx.gas_limit = gas_limit;
+        // 10 base units per unit of gas.
+        tx.fee = Amount::from_base_units(gas_limit as u128 * 10);
+        tx
+    }
+
+    #[test]
+    fn test_unused_gas_is_refunded() {
+        let tx = gas_priced_transfer(50_000);
+
+        let settlement = tx.settle_measured_gas(30_000, &GasMultipliers::default(), &GasRefundPolicy::default()).unwrap();
+        assert_eq!(settlement.charged, Amount::from_base_units(300_000));
+        assert_eq!(settlement.refund, Amount::from_base_units(200_000));
+
+        let receipt = tx.settled_receipt(&settlement, &FeeSplit::new(5_000).unwrap());
+        assert_eq!(receipt.gas_used, 30_000);
+        assert_eq!(receipt.gas_refund, settlement.refund);
+        assert_eq!(receipt.fee_distribution, FeeSplit::new(5_000).unwrap().apply(settlement.charged));
+    }
+
+    #[test]
+    fn test_refund_cap_limits_inflated_gas_limit() {
+        let tx = gas_priced_transfer(1_000_000);
+        let policy = GasRefundPolicy { max_refund_percent: 50 };
+
+        // Only half of the 21_000 gas used comes back, not the 979_000 left unused.
+        let settlement = tx.settle_measured_gas(21_000, &GasMultipliers::default(), &policy).unwrap();
+        assert_eq!(settlement.refund, Amount::from_base_units(10_500 * 10));
+        assert_eq!(settlement.charged.checked_add(settlement.refund), Some(tx.fee));
+        assert!(matches!(tx.settle_measured_gas(1_000_001, &GasMultipliers::default(), &policy), Err(TransactionError::OutOfGas)));
+    }
+
+    #[test]
+    fn test_gas_multiplier_prices_settled_gas() {
+        let tx = gas_priced_transfer(100_000);
+        let mut multipliers = GasMultipliers::default();
+        multipliers.set(OperationType::Transfer, 2.0).unwrap();
+
+        let settlement = tx.settle_measured_gas(30_000, &multipliers, &GasRefundPolicy::default()).unwrap();
+        assert_eq!(settlement.gas_used, 60_000);
+        assert_eq!(settlement.charged, Amount::from_base_units(600_000));
+
+        multipliers.set(OperationType::Transfer, 4.0).unwrap();
+        let over = tx.settle_measured_gas(30_000, &multipliers, &GasRefundPolicy::default());
+        assert!(matches!(over, Err(TransactionError::OutOfGas)));
+    }
+
+    #[test]
+    fn test_claimed_gas_used_is_ignored() {
+        let mut tx = gas_priced_transfer(50_000);
+        tx.gas_used = 1;
+
+        let settlement = tx.settle_gas(&LoopingExecutor, &GasMultipliers::default(), &GasRefundPolicy::default()).unwrap();
+        assert_eq!(settlement.gas_used, MIN_GAS_LIMIT);
+        assert_eq!(settlement.charged, Amount::from_base_units(MIN_GAS_LIMIT as u128 * 10));
+    }
+
+    #[test]
+    fn test_gas_used_at_limit_refunds_nothing() {
+        let tx = gas_priced_transfer(21_000);
+
+        let settlement = tx.settle_measured_gas(21_000, &GasMultipliers::default(), &GasRefundPolicy::default()).unwrap();
+        assert_eq!(settlement.refund, Amount::ZERO);
+        assert_eq!(settlement.charged, tx.fee);
+    }
+
+    #[test]
+    fn test_zero_gas_limit_settles_full_fee() {
+        let mut tx = gas_priced_transfer(0);
+        tx.fee = Amount::from_base_units(1_000);
+
+        let settlement = tx.settle_measured_gas(21_000, &GasMultipliers::default(), &GasRefundPolicy::default()).unwrap();
+        assert_eq!(settlement.charged, tx.fee);
+        assert_eq!(settlement.refund, Amount::ZERO);
+    }
+
+    #[test]
+    fn test_default_gas_multiplier_is_one() {
+        let multipliers = GasMultipliers::default();
+        assert_eq!(multipliers.get(&OperationType::DeployContract), 1.0);
+
+        let deploy = with_payload(OperationType::DeployContract, 0);
+        assert_eq!(deploy.estimate_gas_usage(&LoopingExecutor, &multipliers).unwrap(), deploy.estimate_gas(&LoopingExecutor).unwrap());
+    }
+
+    #[test]
+    fn test_raising_deploy_multiplier_increases_deploy_gas() {
+        let deploy = with_payload(OperationType::DeployContract, 0);
+        let mut multipliers = GasMultipliers::default();
+        multipliers.set(OperationType::DeployContract, 2.5).unwrap();
+
+        assert_eq!(deploy.estimate_gas_usage(&LoopingExecutor, &multipliers).unwrap(), 90_000);
+        assert!(matches!(multipliers.set(OperationType::DeployContract, 0.0), Err(TransactionError::InvalidGasMultiplier)));
+    }
+
+    #[test]
+    fn test_deploy_multiplier_leaves_transfer_gas_unchanged() {
+        let transfer = with_payload(OperationType::Transfer, 0);
+        let mut multipliers = GasMultipliers::default();
+        multipliers.set(OperationType::DeployContract, 4.0).unwrap();
+
+        assert_eq!(multipliers.get(&OperationType::Transfer), 1.0);
+        assert_eq!(transfer.estimate_gas_usage(&LoopingExecutor, &multipliers).unwrap(), MIN_GAS_LIMIT);
+    }
+
+    fn declared_transfer(from: &str, to: &str) -> QuantumTransaction {
+        QuantumTransaction::new(
+            from.to_string(),
+            to.to_string(),
+            Amount::from_whole(1),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap()
+            .with_access_list(vec![AccessEntry::write(from), AccessEntry::write(to)])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_access_lists_enable_parallelism() {
+        let transactions = vec![
+            declared_transfer("alice", "bob"),
+            declared_transfer("carol", "dave"),
+            declared_transfer("bob", "erin"),
+        ];
+
+        // The third shares "bob" with the first, so it waits one wave.
+        assert_eq!(schedule_parallel(&transactions), vec![vec![0, 1], vec![2]]);
+        for tx in &transactions {
+            tx.check_access(&tx.intrinsic_accesses()).unwrap();
+        }
+
+        // Readers of the same account don't conflict with each other.
+        let readers: Vec<QuantumTransaction> = ["x", "y"].iter()
+            .map(|from| declared_transfer(from, from).with_access_list(vec![
+                AccessEntry::write(from),
+                AccessEntry::read("oracle"),
+            ]).unwrap())
+            .collect();
+        assert_eq!(schedule_parallel(&readers), vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn test_incomplete_access_list_fails_validation() {
+        let tx = declared_transfer("alice", "bob")
+            .with_access_list(vec![AccessEntry::write("alice")])
+            .unwrap();
+        assert!(matches!(
+            tx.check_access(&tx.intrinsic_accesses()),
+            Err(TransactionError::UndeclaredAccess(address)) if address == "bob"
+        ));
+
+        // A read-only declaration doesn't cover a write.
+        let tx = tx.with_access_list(vec![AccessEntry::write("alice"), AccessEntry::read("bob")]).unwrap();
+        assert!(tx.check_access(&[AccessEntry::write("bob")]).is_err());
+        assert!(tx.check_access(&[AccessEntry::read("bob")]).is_ok());
+    }
+
+    #[test]
+    fn test_missing_access_list_serializes_execution() {
+        let undeclared = simulated_transfer();
+        assert!(undeclared.access_list.is_empty());
+        assert!(undeclared.check_access(&[AccessEntry::write("anyone")]).is_ok());
+
+        let transactions = vec![
+            declared_transfer("alice", "bob"),
+            undeclared,
+            declared_transfer("carol", "dave"),
+        ];
+        assert_eq!(schedule_parallel(&transactions), vec![vec![0], vec![1], vec![2]]);
+    }
+
+    /// Writes the storage slot named by the payload before returning.
+    #[derive(Debug)]
+    struct StorageExecutor;
+
+    impl ContractExecutor for StorageExecutor {
+        fn dry_run(&self, _transaction: &QuantumTransaction, _gas_limit: u64) -> CallOutcome {
+            CallOutcome::Success { gas_used: 30_000 }
+        }
+
+        fn execute(
+            &self,
+            transaction: &QuantumTransaction,
+            gas_limit: u64,
+            on_access: &dyn Fn(&AccessEntry) -> Result<(), TransactionError>,
+        ) -> Result<CallOutcome, TransactionError> {
+            on_access(&AccessEntry::write(&String::from_utf8_lossy(&transaction.data.payload)))?;
+            Ok(self.dry_run(transaction, gas_limit))
+        }
+    }
+
+    #[test]
+    fn test_executor_accesses_are_checked_against_the_access_list() {
+        let mut tx = with_payload(OperationType::CallContract, 0);
+        tx.data.payload = b"vault".to_vec();
+        let declaring = |extra: AccessEntry| {
+            let mut access_list = tx.intrinsic_accesses();
+            access_list.push(extra);
+            tx.clone().with_access_list(access_list).unwrap()
+        };
+
+        assert!(matches!(
+            declaring(AccessEntry::read("vault")).execute_gas(&StorageExecutor),
+            Err(TransactionError::UndeclaredAccess(address)) if address == "vault"
+        ));
+        assert_eq!(declaring(AccessEntry::write("vault")).execute_gas(&StorageExecutor).unwrap(), 30_000);
+        // Undeclared transactions run alone, so any access goes.
+        assert_eq!(tx.execute_gas(&StorageExecutor).unwrap(), 30_000);
+    }
+
+    #[test]
+    fn test_contract_deploy_uses_code_size_bound() {
+        let limits = TransactionLimits::default();
+        let code_size = limits.max_payload_size * 4;
+
+        assert!(with_payload(OperationType::DeployContract, code_size).validate_size(&limits).is_ok());
+        assert!(with_payload(OperationType::CallContract, code_size).validate_size(&limits).is_err());
+        assert!(with_payload(OperationType::DeployContract, limits.max_contract_code_size + 1)
+            .validate_size(&limits)
+            .is_err());
+    }
+}
+
+/// Property tests over generated transactions.
+///
+/// Runs are deterministic: every property uses `FUZZ_SEED` unless `QF_FUZZ_SEED`
+/// is set. To replay a failure, rerun with the seed printed alongside it, e.g.
+/// `QF_FUZZ_SEED=12648430 cargo test transaction::proptests`.
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use chrono::TimeZone;
+    use proptest::prelude::*;
+    use proptest::test_runner::{Config, RngSeed};
+
+    const FUZZ_SEED: u64 = 0x00C0_FFEE;
+
+    fn fuzz_config() -> Config {
+        let seed = std::env::var("QF_FUZZ_SEED").ok().and_then(|s| s.parse().ok()).unwrap_or(FUZZ_SEED);
+        Config {
+            cases: 256,
+            rng_seed: RngSeed::Fixed(seed),
+            failure_persistence: None,
+            ..Config::default()
+        }
+    }
+
+    fn reference_now() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_750_000_000, 0).unwrap()
+    }
+
+    fn arb_address() -> impl Strategy<Value = String> {
+        any::<[u8; 32]>().prop_map(|key| Address::from_public_keys(&key, b"kyber").to_string())
+    }
+
+    fn arb_operation() -> impl Strategy<Value = OperationType> {
+        prop_oneof![
+            Just(OperationType::Transfer),
+            Just(OperationType::Stake),
+            Just(OperationType::Unstake),
+            Just(OperationType::BridgeAsset),
+            Just(OperationType::CallContract),
+            Just(OperationType::DeployContract),
+        ]
+    }
+
+    fn arb_data() -> impl Strategy<Value = TransactionData> {
+        (
+            arb_operation(),
+            proptest::collection::hash_map("[a-z_]{1,12}", "[ -~]{0,24}", 0..6),
+            proptest::collection::vec(any::<u8>(), 0..128),
+        ).prop_map(|(operation_type, parameters, payload)| TransactionData { operation_type, parameters, payload })
+    }
+
+    fn build(
+        from: String,
+        to: String,
+        amount: u128,
+        fee: u128,
+        data: TransactionData,
+        gas_limit: u64,
+        nonce: u64,
+        offset_secs: i64,
+    ) -> QuantumTransaction {
+        let mut tx = QuantumTransaction::new(
+            from,
+            to,
+            Amount::from_base_units(amount),
+            Amount::from_base_units(fee),
+            data.operation_type.clone(),
+            gas_limit,
+        ).unwrap();
+        tx.data = data;
+        tx.nonce = nonce;
+        tx.timestamp = reference_now() + chrono::Duration::seconds(offset_secs);
+        tx.hash = tx.calculate_hash().unwrap();
+        tx
+    }
+
+    /// Transactions inside every range `validate_basics` enforces.
+    fn arb_valid_transaction() -> impl Strategy<Value = QuantumTransaction> {
+        (
+            arb_address(),
+            arb_address(),
+            1..=u64::MAX as u128,
+            0..=1_000_000_000u128,
+            arb_data(),
+            MIN_GAS_LIMIT..=MAX_GAS_LIMIT,
+            0..u64::MAX,
+            -86_400 * 365..=MAX_FUTURE_DRIFT_SECS,
+        ).prop_map(|(from, to, amount, fee, data, gas, nonce, offset)| build(from, to, amount, fee, data, gas, nonce, offset))
+    }
+
+    /// Transactions drawn across and beyond each boundary.
+    fn arb_any_transaction() -> impl Strategy<Value = QuantumTransaction> {
+        (
+            arb_address(),
+            arb_address(),
+            prop_oneof![Just(0u128), 1..=u64::MAX as u128],
+            arb_data(),
+            prop_oneof![0..MIN_GAS_LIMIT, MIN_GAS_LIMIT..=MAX_GAS_LIMIT, MAX_GAS_LIMIT + 1..=u64::MAX],
+            prop_oneof![0..u64::MAX, Just(u64::MAX)],
+            -86_400..=MAX_FUTURE_DRIFT_SECS * 4,
+        ).prop_map(|(from, to, amount, data, gas, nonce, offset)| build(from, to, amount, 1_000, data, gas, nonce, offset))
+    }
+
+    /// Independent restatement of the rules `validate_basics_at` is meant to enforce.
+    fn expected_valid(tx: &QuantumTransaction, now: DateTime<Utc>) -> bool {
+        let moves_value = matches!(
+            tx.data.operation_type,
+            OperationType::Transfer | OperationType::Stake | OperationType::Unstake | OperationType::BridgeAsset
+        );
+        tx.nonce != u64::MAX
+            && (tx.timestamp - now).num_seconds() <= MAX_FUTURE_DRIFT_SECS
+            && tx.gas_limit >= MIN_GAS_LIMIT
+            && tx.gas_limit <= MAX_GAS_LIMIT
+            && !(moves_value && tx.amount.is_zero())
+    }
+
+    proptest! {
+        #![proptest_config(fuzz_config())]
+
+        #[test]
+        fn prop_signed_transaction_verifies(mut tx in arb_valid_transaction()) {
+            let keypair = KeyPair::generate();
+            tx.from = keypair.address();
+            tx.timestamp = Utc::now();
+            tx.sign(&keypair).unwrap();
+
+            prop_assert!(tx.verify().unwrap());
+
+            // Any change to signed content breaks the signature.
+            tx.amount = Amount::from_base_units(tx.amount.base_units() ^ 1);
+            prop_assert!(!tx.verify().unwrap());
+        }
+
+        #[test]
+        fn prop_hash_stable_across_serialization(tx in arb_valid_transaction()) {
+            let json: QuantumTransaction = serde_json::from_str(&serde_json::to_string(&tx).unwrap()).unwrap();
+            prop_assert_eq!(json.calculate_hash().unwrap(), tx.hash);
+
+            let cbor = to_canonical_cbor(&tx).unwrap();
+            let decoded: QuantumTransaction = ciborium::de::from_reader(cbor.as_slice()).unwrap();
+            prop_assert_eq!(decoded.calculate_hash().unwrap(), tx.hash);
+        }
+
+        #[test]
+        fn prop_validate_basics_accepts_in_range(tx in arb_valid_transaction()) {
+            prop_assert!(tx.validate_basics_at(reference_now()).is_ok());
+        }
+
+        #[test]
+        fn prop_validate_basics_rejects_exactly_out_of_range(tx in arb_any_transaction()) {
+            let now = reference_now();
+            prop_assert_eq!(tx.validate_basics_at(now).is_ok(), expected_valid(&tx, now));
+        }
     }
 }
@@ -1,45 +1,291 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use quantumfuse_sdk::{
     wallet::Wallet,
-    transaction::Transaction,
-    crypto::{Hash, KeyPair, AESGCM, QuantumRandom},
+    transaction::{Transaction, QuantumTransaction, AssetId, OperationType, MIN_GAS_LIMIT},
+    amount::Amount,
+    crypto::{Hash, KeyPair, QuantumRandom},
     staking::StakingInfo,
     consensus::QuantumBridge,
     ai::GasEstimator,
     error::WalletError,
     hardware::{FIDO2Authenticator, SecureEnclave},
+    address::Address,
+    pqc::dilithium::Signature,
 };
-use pqcrypto::sign::dilithium2::{generate_keypair, sign, verify};
+use pqcrypto::prelude::*;
+use pqcrypto::sign::dilithium2::{self, generate_keypair, sign, verify};
 use pqcrypto::kem::kyber512::{encapsulate, decapsulate, generate_keypair as kyber_generate};
+use argon2::{Algorithm, Argon2, Params, Version};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+const KDF_SALT_LEN: usize = 16;
+
+// 🔹 **Key Storage**
+/// Holds the wallet's Dilithium signing key and signs on its behalf.
+/// Implementations never hand back the private key.
+pub trait KeyStorageBackend: Send + Sync + fmt::Debug {
+    fn public_key(&self) -> Vec<u8>;
+    /// Detached Dilithium2 signature over `message`.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, WalletError>;
+}
+
+/// Keeps the signing key in process memory.
+pub struct InMemoryKeyStorage {
+    public_key: dilithium2::PublicKey,
+    secret_key: dilithium2::SecretKey,
+}
+
+impl InMemoryKeyStorage {
+    pub fn generate() -> Self {
+        let (public_key, secret_key) = dilithium2::keypair();
+        Self { public_key, secret_key }
+    }
+
+    /// Rebuilds the storage from a previously generated key pair.
+    pub fn from_secret(public_key: &[u8], secret_key: &[u8]) -> Result<Self, WalletError> {
+        let public_key = dilithium2::PublicKey::from_bytes(public_key)
+            .map_err(|_| WalletError::KeyDerivation("malformed Dilithium public key".to_string()))?;
+        let secret_key = dilithium2::SecretKey::from_bytes(secret_key)
+            .map_err(|_| WalletError::KeyDerivation("malformed Dilithium secret key".to_string()))?;
+        Ok(Self { public_key, secret_key })
+    }
+}
+
+impl fmt::Debug for InMemoryKeyStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryKeyStorage").finish_non_exhaustive()
+    }
+}
+
+impl KeyStorageBackend for InMemoryKeyStorage {
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.as_bytes().to_vec()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, WalletError> {
+        Ok(dilithium2::detached_sign(message, &self.secret_key).as_bytes().to_vec())
+    }
+}
+
+/// What the wallet needs from a hardware secure enclave. Keys are created
+/// on the device and referred to by handle.
+pub trait EnclaveDevice: Send + Sync {
+    /// Creates a Dilithium2 key, returning its handle and public key.
+    fn generate_signing_key(&self, label: &str) -> Result<(String, Vec<u8>), WalletError>;
+    fn sign(&self, key_handle: &str, message: &[u8]) -> Result<Vec<u8>, WalletError>;
+}
+
+impl EnclaveDevice for SecureEnclave {
+    fn generate_signing_key(&self, label: &str) -> Result<(String, Vec<u8>), WalletError> {
+        SecureEnclave::generate_key(self, label)
+    }
+
+    fn sign(&self, key_handle: &str, message: &[u8]) -> Result<Vec<u8>, WalletError> {
+        SecureEnclave::sign(self, key_handle, message)
+    }
+}
+
+/// Keeps the signing key inside a secure enclave; signing happens on the device.
+pub struct EnclaveKeyStorage {
+    device: Arc<dyn EnclaveDevice>,
+    key_handle: String,
+    public_key: Vec<u8>,
+}
+
+impl EnclaveKeyStorage {
+    pub fn provision(device: Arc<dyn EnclaveDevice>, label: &str) -> Result<Self, WalletError> {
+        let (key_handle, public_key) = device.generate_signing_key(label)?;
+        Ok(Self { device, key_handle, public_key })
+    }
+}
+
+impl fmt::Debug for EnclaveKeyStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnclaveKeyStorage")
+            .field("key_handle", &self.key_handle)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyStorageBackend for EnclaveKeyStorage {
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, WalletError> {
+        self.device.sign(&self.key_handle, message)
+    }
+}
+
+/// Where a new wallet keeps its signing key.
+pub enum KeyBackend {
+    InMemory,
+    Enclave(Arc<dyn EnclaveDevice>),
+}
+
+/// Checks a detached Dilithium2 signature; malformed keys or signatures fail.
+pub fn verify_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) = (
+        dilithium2::PublicKey::from_bytes(public_key),
+        dilithium2::DetachedSignature::from_bytes(signature),
+    ) else {
+        return false;
+    };
+    dilithium2::verify_detached_signature(&signature, message, &public_key).is_ok()
+}
+
+/// Argon2id cost parameters for deriving a key-encryption key from the wallet password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 19_456,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// A private key sealed with AES-256-GCM under a password-derived key. The
+/// salt and KDF parameters travel with the ciphertext so it can be reopened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedKey {
+    pub kdf: KdfParams,
+    pub salt: [u8; KDF_SALT_LEN],
+    pub nonce: [u8; NONCE_LEN],
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedKey {
+    pub fn seal(plaintext: &[u8], password: &str, kdf: KdfParams) -> Result<Self, WalletError> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; KDF_SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt).map_err(|_| WalletError::KeyDerivation("random salt unavailable".to_string()))?;
+        rng.fill(&mut nonce).map_err(|_| WalletError::KeyDerivation("random nonce unavailable".to_string()))?;
+
+        let key = cipher_key(password, &salt, &kdf)?;
+        let mut ciphertext = plaintext.to_vec();
+        key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut ciphertext)
+            .map_err(|_| WalletError::KeyDerivation("encryption failed".to_string()))?;
+
+        Ok(Self { kdf, salt, nonce, ciphertext })
+    }
+
+    /// Fails with `InvalidPassword` when the password is wrong or the ciphertext was altered.
+    pub fn open(&self, password: &str) -> Result<Vec<u8>, WalletError> {
+        let key = cipher_key(password, &self.salt, &self.kdf)?;
+        let mut buffer = self.ciphertext.clone();
+        let plaintext = key.open_in_place(Nonce::assume_unique_for_key(self.nonce), Aad::empty(), &mut buffer)
+            .map_err(|_| WalletError::InvalidPassword)?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+fn cipher_key(password: &str, salt: &[u8], kdf: &KdfParams) -> Result<LessSafeKey, WalletError> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| WalletError::KeyDerivation(e.to_string()))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| WalletError::KeyDerivation(e.to_string()))?;
+
+    let key = UnboundKey::new(&AES_256_GCM, &key)
+        .map_err(|_| WalletError::KeyDerivation("invalid key length".to_string()))?;
+    Ok(LessSafeKey::new(key))
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QuantumWallet {
     pub address: String,
     pub did: String,
-    pub balance: f64,
+    pub balances: HashMap<AssetId, Amount>,
+    #[serde(default)]
+    pub staked_balances: HashMap<AssetId, Amount>,
     pub staking_info: StakingInfo,
     pub transaction_history: Vec<TransactionRecord>,
     pub multisig_owners: HashMap<String, Vec<u8>>, // Multi-Sig Public Keys
     last_sync: DateTime<Utc>,
-    kyber_keypair: KeyPair,
-    dilithium_keypair: KeyPair,
+    /// Decrypted Kyber key; never serialized. Rebuilt from
+    /// `encrypted_private_keys` by `unlock`, and always `None` for watch-only wallets.
     #[serde(skip)]
-    encrypted_private_keys: HashMap<String, Vec<u8>>,
+    kyber_keypair: Option<KeyPair>,
+    #[serde(default)]
+    kyber_public_key: Vec<u8>,
+    #[serde(default)]
+    dilithium_public_key: Vec<u8>,
+    /// Not serialized; `unlock` restores an in-memory key, other backends must be
+    /// reattached with `attach_key_storage` after loading a wallet.
+    #[serde(skip)]
+    key_storage: Option<Arc<dyn KeyStorageBackend>>,
+    #[serde(default)]
+    encrypted_private_keys: HashMap<String, EncryptedKey>,
+    #[serde(default)]
+    pub rebroadcast_policy: RebroadcastPolicy,
+    /// Signed transactions not yet confirmed, keyed by their current hash.
+    #[serde(default)]
+    pending_transactions: HashMap<Hash, PendingTransaction>,
+    /// Tracks an address without holding any of its keys; signing always fails.
+    #[serde(default)]
+    watch_only: bool,
+    /// Chain every transaction this wallet signs is bound to.
+    #[serde(default)]
+    pub chain_id: u64,
+}
+
+/// When a pending transaction counts as stuck, and how its replacement is priced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RebroadcastPolicy {
+    pub timeout_secs: i64,
+    /// Raises the fee by this percentage on each rebroadcast; `None` resends unchanged.
+    pub fee_bump_percent: Option<u32>,
+}
+
+impl Default for RebroadcastPolicy {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 300,
+            fee_bump_percent: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    pub transaction: QuantumTransaction,
+    pub last_broadcast: DateTime<Utc>,
+    pub attempts: u32,
+    /// Hash and fee of each transaction this one replaced, oldest first. Any
+    /// of them may still confirm in its place.
+    #[serde(default)]
+    pub replaced: Vec<(Hash, Amount)>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionRecord {
     pub hash: Hash,
     pub timestamp: DateTime<Utc>,
-    pub amount: f64,
+    pub amount: Amount,
+    #[serde(default)]
+    pub asset_id: AssetId,
     pub transaction_type: TransactionType,
     pub status: TransactionStatus,
     pub gas_used: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionType {
     Send,
     Receive,
@@ -50,49 +296,156 @@ pub enum TransactionType {
     MultisigApproval,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionStatus {
     Pending,
     Confirmed,
     Failed,
 }
 
+/// Unset fields match every record. `from` is inclusive, `until` exclusive.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub transaction_type: Option<TransactionType>,
+    pub status: Option<TransactionStatus>,
+    pub from: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, record: &TransactionRecord) -> bool {
+        self.transaction_type.map_or(true, |t| record.transaction_type == t)
+            && self.status.map_or(true, |s| record.status == s)
+            && self.from.map_or(true, |from| record.timestamp >= from)
+            && self.until.map_or(true, |until| record.timestamp < until)
+    }
+}
+
+#[derive(Debug)]
+pub struct HistoryPage<'a> {
+    pub records: Vec<&'a TransactionRecord>,
+    /// Matching records across all pages.
+    pub total: usize,
+}
+
 impl QuantumWallet {
-    pub fn new() -> Result<Self, WalletError> {
-        let (dilithium_private, dilithium_public) = generate_keypair();
-        let (kyber_private, kyber_public) = kyber_generate();
+    pub fn new(password: &str) -> Result<Self, WalletError> {
+        Self::new_with_kdf(password, KdfParams::default())
+    }
 
-        let address = Self::derive_address(&dilithium_public, &kyber_public)?;
-        let did = Self::generate_did(&address)?;
+    /// Creates a wallet whose private keys are sealed under `password` with the given KDF cost.
+    pub fn new_with_kdf(password: &str, kdf: KdfParams) -> Result<Self, WalletError> {
+        Self::new_with_backend(password, kdf, KeyBackend::InMemory)
+    }
+
+    /// Creates a wallet whose signing key lives in `backend`. An enclave-held
+    /// key is never exported, so only the Kyber key is sealed under `password`.
+    pub fn new_with_backend(password: &str, kdf: KdfParams, backend: KeyBackend) -> Result<Self, WalletError> {
+        let (kyber_private, kyber_public) = kyber_generate();
 
         let mut encrypted_private_keys = HashMap::new();
-        encrypted_private_keys.insert(
-            "dilithium".to_string(),
-            Self::encrypt_private_key(&dilithium_private)?
-        );
         encrypted_private_keys.insert(
             "kyber".to_string(),
-            Self::encrypt_private_key(&kyber_private)?
+            EncryptedKey::seal(&kyber_private, password, kdf)?
         );
 
+        let key_storage: Arc<dyn KeyStorageBackend> = match backend {
+            KeyBackend::InMemory => {
+                let storage = InMemoryKeyStorage::generate();
+                encrypted_private_keys.insert(
+                    "dilithium".to_string(),
+                    EncryptedKey::seal(storage.secret_key.as_bytes(), password, kdf)?
+                );
+                Arc::new(storage)
+            }
+            KeyBackend::Enclave(device) => Arc::new(EnclaveKeyStorage::provision(device, "quantumfuse-wallet")?),
+        };
+
+        let address = Self::derive_address(&key_storage.public_key(), &kyber_public)?;
+        let did = Self::generate_did(&address)?;
+
         Ok(Self {
             address,
             did,
-            balance: 0.0,
+            balances: HashMap::new(),
+            staked_balances: HashMap::new(),
             staking_info: StakingInfo::default(),
             transaction_history: Vec::new(),
             multisig_owners: HashMap::new(),
             last_sync: Utc::now(),
-            kyber_keypair: KeyPair::new(kyber_public, kyber_private),
-            dilithium_keypair: KeyPair::new(dilithium_public, dilithium_private),
+            kyber_public_key: kyber_public.to_vec(),
+            kyber_keypair: Some(KeyPair::new(kyber_public, kyber_private)),
+            dilithium_public_key: key_storage.public_key(),
+            key_storage: Some(key_storage),
             encrypted_private_keys,
+            rebroadcast_policy: RebroadcastPolicy::default(),
+            pending_transactions: HashMap::new(),
+            watch_only: false,
+            chain_id: 0,
+        })
+    }
+
+    /// Monitors `address` for balances and history without any key material.
+    pub fn watch_only(address: &str) -> Result<Self, WalletError> {
+        Address::from_address(address).map_err(|_| WalletError::InvalidAddress(address.to_string()))?;
+
+        Ok(Self {
+            address: address.to_string(),
+            did: Self::generate_did(address)?,
+            balances: HashMap::new(),
+            staked_balances: HashMap::new(),
+            staking_info: StakingInfo::default(),
+            transaction_history: Vec::new(),
+            multisig_owners: HashMap::new(),
+            last_sync: Utc::now(),
+            kyber_keypair: None,
+            kyber_public_key: Vec::new(),
+            dilithium_public_key: Vec::new(),
+            key_storage: None,
+            encrypted_private_keys: HashMap::new(),
+            rebroadcast_policy: RebroadcastPolicy::default(),
+            pending_transactions: HashMap::new(),
+            watch_only: true,
+            chain_id: 0,
+        })
+    }
+
+    pub fn is_watch_only(&self) -> bool {
+        self.watch_only
+    }
+
+    /// Binds the transactions this wallet signs to `chain_id`.
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    pub fn attach_key_storage(&mut self, key_storage: Arc<dyn KeyStorageBackend>) {
+        self.key_storage = Some(key_storage);
+    }
+
+    fn signer(&self) -> Result<&dyn KeyStorageBackend, WalletError> {
+        if self.watch_only {
+            return Err(WalletError::WatchOnly);
+        }
+        self.key_storage.as_deref().ok_or(WalletError::KeyNotFound)
+    }
+
+    pub fn signing_public_key(&self) -> Result<Vec<u8>, WalletError> {
+        Ok(self.signer()?.public_key())
+    }
+
+    fn sign_quantum_transaction(&self, transaction: &mut QuantumTransaction) -> Result<(), WalletError> {
+        let signer = self.signer()?;
+        transaction.sign_with(|message| {
+            let bytes = signer.sign(message)?;
+            Signature::from_bytes(&bytes).map_err(|e| WalletError::SigningError(e.to_string()))
         })
     }
 
     pub fn sign_transaction(&self, transaction: &mut Transaction) -> Result<(), WalletError> {
         let msg = transaction.calculate_hash().as_bytes();
-        let signature = sign(msg, &self.dilithium_keypair.private_key)
-            .map_err(|e| WalletError::SigningError(e.to_string()))?;
+        let signature = self.signer()?.sign(msg)?;
         transaction.signature = Some(signature);
         Ok(())
     }
@@ -101,38 +454,235 @@ impl QuantumWallet {
         match &transaction.signature {
             Some(signature) => {
                 let msg = transaction.calculate_hash().as_bytes();
-                Ok(verify(msg, signature, &self.dilithium_keypair.public_key)
-                    .map_err(|e| WalletError::VerificationError(e.to_string()))?)
+                Ok(verify_signature(&self.signer()?.public_key(), msg, signature))
             }
             None => Ok(false)
         }
     }
 
-    pub fn stake(&mut self, amount: f64) -> Result<Transaction, WalletError> {
-        if amount > self.balance {
-            return Err(WalletError::InsufficientFunds);
+    pub fn balance(&self, asset_id: &AssetId) -> Amount {
+        self.balances.get(asset_id).copied().unwrap_or(Amount::ZERO)
+    }
+
+    pub fn credit(&mut self, asset_id: &AssetId, amount: Amount) -> Result<(), WalletError> {
+        let balance = self.balance(asset_id).checked_add(amount).ok_or(WalletError::BalanceOverflow)?;
+        self.balances.insert(asset_id.clone(), balance);
+        Ok(())
+    }
+
+    /// Debits `amount` of `asset_id` plus a native `fee`, checking both before changing either.
+    fn debit(&mut self, asset_id: &AssetId, amount: Amount, fee: Amount) -> Result<(), WalletError> {
+        let native = AssetId::native();
+        if asset_id.is_native() {
+            let total = amount.checked_add(fee).ok_or(WalletError::BalanceOverflow)?;
+            let remaining = self.balance(&native).checked_sub(total).ok_or(WalletError::InsufficientFunds)?;
+            self.balances.insert(native, remaining);
+        } else {
+            let remaining_asset = self.balance(asset_id).checked_sub(amount).ok_or(WalletError::InsufficientFunds)?;
+            let remaining_native = self.balance(&native).checked_sub(fee).ok_or(WalletError::InsufficientFunds)?;
+            self.balances.insert(asset_id.clone(), remaining_asset);
+            self.balances.insert(native, remaining_native);
         }
+        Ok(())
+    }
 
-        let mut transaction = Transaction::new(
+    pub fn transfer(
+        &mut self,
+        recipient: &str,
+        asset_id: &AssetId,
+        amount: Amount,
+        fee: Amount,
+    ) -> Result<QuantumTransaction, WalletError> {
+        let mut transaction = QuantumTransaction::new_asset_transfer(
+            self.address.clone(),
+            recipient.to_string(),
+            asset_id.clone(),
+            amount,
+            fee,
+            MIN_GAS_LIMIT,
+        )?.with_chain_id(self.chain_id)?;
+        // Sign first so a signing failure leaves balances untouched.
+        self.sign_quantum_transaction(&mut transaction)?;
+        self.debit(asset_id, amount, fee)?;
+
+        self.record(&transaction, TransactionType::Send);
+        self.track_pending(&transaction);
+        Ok(transaction)
+    }
+
+    pub fn stake(&mut self, asset_id: &AssetId, amount: Amount, fee: Amount) -> Result<QuantumTransaction, WalletError> {
+        let mut transaction = QuantumTransaction::new(
             self.address.clone(),
             "STAKING_CONTRACT".to_string(),
             amount,
-            TransactionType::Stake,
-        );
+            fee,
+            OperationType::Stake,
+            MIN_GAS_LIMIT,
+        )?;
+        transaction.asset_id = asset_id.clone();
+        let mut transaction = transaction.with_chain_id(self.chain_id)?;
+        self.sign_quantum_transaction(&mut transaction)?;
+        self.debit(asset_id, amount, fee)?;
 
-        self.sign_transaction(&mut transaction)?;
-        self.balance -= amount;
-        self.staking_info.staked_amount += amount;
+        let staked = self.staked_balances.get(asset_id).copied().unwrap_or(Amount::ZERO)
+            .checked_add(amount)
+            .ok_or(WalletError::BalanceOverflow)?;
+        self.staked_balances.insert(asset_id.clone(), staked);
+
+        self.record(&transaction, TransactionType::Stake);
+        self.track_pending(&transaction);
+        Ok(transaction)
+    }
+
+    /// Matching records, newest first, skipping `offset` and returning at most `limit`.
+    pub fn transaction_history_page(&self, filter: &HistoryFilter, offset: usize, limit: usize) -> HistoryPage<'_> {
+        let mut matching: Vec<&TransactionRecord> = self.transaction_history.iter()
+            .filter(|record| filter.matches(record))
+            .collect();
+        matching.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+        let total = matching.len();
+        let records = matching.into_iter().skip(offset).take(limit).collect();
+        HistoryPage { records, total }
+    }
+
+    fn record(&mut self, transaction: &QuantumTransaction, transaction_type: TransactionType) {
         self.transaction_history.push(TransactionRecord {
             hash: transaction.hash.clone(),
             timestamp: Utc::now(),
-            amount,
-            transaction_type: TransactionType::Stake,
+            amount: transaction.amount,
+            asset_id: transaction.asset_id.clone(),
+            transaction_type,
             status: TransactionStatus::Pending,
-            gas_used: transaction.gas_used,
+            gas_used: transaction.gas_used as f64,
         });
+    }
 
-        Ok(transaction)
+    fn track_pending(&mut self, transaction: &QuantumTransaction) {
+        self.pending_transactions.insert(transaction.hash.clone(), PendingTransaction {
+            transaction: transaction.clone(),
+            last_broadcast: Utc::now(),
+            attempts: 1,
+            replaced: Vec::new(),
+        });
+    }
+
+    pub fn pending_transactions(&self) -> impl Iterator<Item = &PendingTransaction> {
+        self.pending_transactions.values()
+    }
+
+    /// Records the network's verdict on a transaction. Anything no longer
+    /// `Pending` stops being tracked for rebroadcast. `hash` may also be an
+    /// earlier fee-bumped version of a pending transaction.
+    pub fn update_status(&mut self, hash: &Hash, status: TransactionStatus) -> Result<(), WalletError> {
+        let replaced_by = self.pending_transactions.iter()
+            .find(|(_, pending)| pending.replaced.iter().any(|(old, _)| old == hash))
+            .map(|(current, _)| current.clone());
+        if let Some(current) = replaced_by {
+            return self.update_replaced_status(&current, hash, status);
+        }
+
+        let record = self.transaction_history.iter_mut()
+            .find(|record| &record.hash == hash)
+            .ok_or(WalletError::TransactionNotFound)?;
+        record.status = status;
+
+        if status != TransactionStatus::Pending {
+            self.pending_transactions.remove(hash);
+        }
+        Ok(())
+    }
+
+    /// A verdict on `hash`, which `current` replaced. Its confirmation settles
+    /// the nonce at the older fee, so the bumps are credited back; a failure
+    /// only stops watching `hash`.
+    fn update_replaced_status(&mut self, current: &Hash, hash: &Hash, status: TransactionStatus) -> Result<(), WalletError> {
+        let pending = self.pending_transactions.get_mut(current).ok_or(WalletError::TransactionNotFound)?;
+        match status {
+            TransactionStatus::Pending => Ok(()),
+            TransactionStatus::Failed => {
+                pending.replaced.retain(|(old, _)| old != hash);
+                Ok(())
+            }
+            TransactionStatus::Confirmed => {
+                let confirmed_fee = pending.replaced.iter()
+                    .find(|(old, _)| old == hash)
+                    .map(|(_, fee)| *fee)
+                    .ok_or(WalletError::TransactionNotFound)?;
+                let overpaid = pending.transaction.fee.checked_sub(confirmed_fee).ok_or(WalletError::BalanceOverflow)?;
+                self.credit(&AssetId::native(), overpaid)?;
+                self.pending_transactions.remove(current);
+
+                if let Some(record) = self.transaction_history.iter_mut().find(|record| &record.hash == current) {
+                    record.hash = hash.clone();
+                    record.status = status;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the pending transactions whose last broadcast is older than the
+    /// policy timeout, ready to be re-submitted. With a fee bump, each is
+    /// replaced by a re-signed copy with the same nonce and a higher fee; the
+    /// extra fee is debited and the history record follows the new hash.
+    pub fn rebroadcast_stuck(&mut self, now: DateTime<Utc>) -> Result<Vec<QuantumTransaction>, WalletError> {
+        let timeout = chrono::Duration::seconds(self.rebroadcast_policy.timeout_secs);
+        let stuck_hashes: Vec<Hash> = self.pending_transactions.iter()
+            .filter(|(_, pending)| now - pending.last_broadcast >= timeout)
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        let mut stuck: Vec<PendingTransaction> = stuck_hashes.iter()
+            .filter_map(|hash| self.pending_transactions.remove(hash))
+            .collect();
+        stuck.sort_by_key(|pending| pending.transaction.nonce);
+
+        let mut rebroadcast = Vec::with_capacity(stuck.len());
+        let mut stuck = stuck.into_iter();
+        while let Some(mut pending) = stuck.next() {
+            if let Some(percent) = self.rebroadcast_policy.fee_bump_percent {
+                let hash = pending.transaction.hash.clone();
+                match self.bump_fee(&pending.transaction, percent) {
+                    Ok(replacement) => {
+                        pending.replaced.push((hash.clone(), pending.transaction.fee));
+                        pending.transaction = replacement;
+                    }
+                    Err(e) => {
+                        for untouched in std::iter::once(pending).chain(stuck) {
+                            self.pending_transactions.insert(untouched.transaction.hash.clone(), untouched);
+                        }
+                        return Err(e);
+                    }
+                }
+                if let Some(record) = self.transaction_history.iter_mut().find(|record| record.hash == hash) {
+                    record.hash = pending.transaction.hash.clone();
+                }
+            }
+
+            pending.last_broadcast = now;
+            pending.attempts += 1;
+            rebroadcast.push(pending.transaction.clone());
+            self.pending_transactions.insert(pending.transaction.hash.clone(), pending);
+        }
+        Ok(rebroadcast)
+    }
+
+    /// Re-signed copy of `transaction` with its fee raised by `percent` (at
+    /// least one base unit), debiting the difference.
+    fn bump_fee(&mut self, transaction: &QuantumTransaction, percent: u32) -> Result<QuantumTransaction, WalletError> {
+        let increase = transaction.fee.checked_mul(percent as u128)
+            .and_then(|scaled| scaled.checked_div(100))
+            .ok_or(WalletError::BalanceOverflow)?
+            .max(Amount::from_base_units(1));
+
+        let mut replacement = transaction.clone();
+        replacement.fee = transaction.fee.checked_add(increase).ok_or(WalletError::BalanceOverflow)?;
+        self.debit(&AssetId::native(), Amount::ZERO, increase)?;
+        if let Err(e) = self.sign_quantum_transaction(&mut replacement) {
+            self.credit(&AssetId::native(), increase)?;
+            return Err(e);
+        }
+        Ok(replacement)
     }
 
     pub fn execute_smart_contract(&mut self, contract_address: &str, gas_estimator: &GasEstimator) -> Result<Transaction, WalletError> {
@@ -161,10 +711,10 @@ impl QuantumWallet {
         let owner_pubkey = self.multisig_owners.get(owner_id).ok_or(WalletError::Unauthorized)?;
         
         let msg = transaction.calculate_hash().as_bytes();
-        let signature = sign(msg, &self.dilithium_keypair.private_key)?;
-        transaction.signature = Some(signature);
+        let signature = self.signer()?.sign(msg)?;
+        transaction.signature = Some(signature.clone());
 
-        if verify(msg, &signature, owner_pubkey)? {
+        if verify_signature(owner_pubkey, msg, &signature) {
             transaction.status = TransactionStatus::Confirmed;
             Ok(())
         } else {
@@ -183,15 +733,464 @@ impl QuantumWallet {
     }
 
     fn derive_address(dilithium_pub: &[u8], kyber_pub: &[u8]) -> Result<String, WalletError> {
-        let combined = [dilithium_pub, kyber_pub].concat();
-        Ok(format!("qf{}", hex::encode(&combined[..20])))
+        Ok(Address::from_public_keys(dilithium_pub, kyber_pub).to_string())
     }
 
     fn generate_did(address: &str) -> Result<String, WalletError> {
         Ok(format!("did:qf:{}", address))
     }
 
-    fn encrypt_private_key(private_key: &[u8]) -> Result<Vec<u8>, WalletError> {
-        AESGCM::encrypt(private_key, "secure_password")
+    /// Decrypts the Kyber key into memory, e.g. after loading a serialized
+    /// wallet, and reattaches an in-memory signing key if none is attached.
+    pub fn unlock(&mut self, password: &str) -> Result<(), WalletError> {
+        if self.watch_only {
+            return Err(WalletError::WatchOnly);
+        }
+        let kyber_private = self.decrypt_private_key("kyber", password)?;
+        if self.key_storage.is_none() && self.encrypted_private_keys.contains_key("dilithium") {
+            let dilithium_private = self.decrypt_private_key("dilithium", password)?;
+            let storage = InMemoryKeyStorage::from_secret(&self.dilithium_public_key, &dilithium_private)?;
+            if Self::derive_address(&storage.public_key(), &self.kyber_public_key)? != self.address {
+                return Err(WalletError::KeyNotFound);
+            }
+            self.key_storage = Some(Arc::new(storage));
+        }
+        self.kyber_keypair = Some(KeyPair::new(self.kyber_public_key.clone(), kyber_private));
+        Ok(())
+    }
+
+    /// Drops the decrypted Kyber key; `unlock` brings it back.
+    pub fn lock(&mut self) {
+        self.kyber_keypair = None;
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.kyber_keypair.is_some()
+    }
+
+    /// Decrypts a stored private key ("dilithium" or "kyber").
+    pub fn decrypt_private_key(&self, key_name: &str, password: &str) -> Result<Vec<u8>, WalletError> {
+        self.encrypted_private_keys.get(key_name)
+            .ok_or(WalletError::KeyNotFound)?
+            .open(password)
+    }
+
+    /// Re-encrypts every stored key under `new_password` with fresh salts.
+    /// Nothing changes unless `old_password` opens all of them.
+    pub fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<(), WalletError> {
+        let mut resealed = HashMap::with_capacity(self.encrypted_private_keys.len());
+        for (name, sealed) in &self.encrypted_private_keys {
+            let plaintext = sealed.open(old_password)?;
+            resealed.insert(name.clone(), EncryptedKey::seal(&plaintext, new_password, sealed.kdf)?);
+        }
+        self.encrypted_private_keys = resealed;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "correct horse battery staple";
+
+    /// Cheap KDF settings so tests don't spend time on key stretching.
+    fn test_kdf() -> KdfParams {
+        KdfParams { memory_kib: 256, iterations: 1, parallelism: 1 }
+    }
+
+    fn funded_wallet() -> QuantumWallet {
+        let mut wallet = QuantumWallet::new_with_kdf(PASSWORD, test_kdf()).unwrap();
+        wallet.credit(&AssetId::native(), Amount::from_whole(10)).unwrap();
+        wallet.credit(&AssetId::new("USDC"), Amount::from_whole(100)).unwrap();
+        wallet
+    }
+
+    /// Six records, one per day going back from now, alternating send/stake
+    /// and cycling through the statuses.
+    fn wallet_with_history() -> (QuantumWallet, DateTime<Utc>) {
+        let mut wallet = QuantumWallet::new_with_kdf(PASSWORD, test_kdf()).unwrap();
+        let now = Utc::now();
+        let statuses = [TransactionStatus::Pending, TransactionStatus::Confirmed, TransactionStatus::Failed];
+        for day in 0..6 {
+            wallet.transaction_history.push(TransactionRecord {
+                hash: Hash::default(),
+                timestamp: now - chrono::Duration::days(day),
+                amount: Amount::from_whole(day as u64 + 1),
+                asset_id: AssetId::native(),
+                transaction_type: if day % 2 == 0 { TransactionType::Send } else { TransactionType::Stake },
+                status: statuses[day as usize % 3],
+                gas_used: 0.0,
+            });
+        }
+        (wallet, now)
+    }
+
+    #[test]
+    fn test_history_filters_by_type() {
+        let (wallet, _) = wallet_with_history();
+        let filter = HistoryFilter { transaction_type: Some(TransactionType::Stake), ..Default::default() };
+
+        let page = wallet.transaction_history_page(&filter, 0, 10);
+        assert_eq!(page.total, 3);
+        assert!(page.records.iter().all(|r| r.transaction_type == TransactionType::Stake));
+    }
+
+    #[test]
+    fn test_history_filters_by_status() {
+        let (wallet, _) = wallet_with_history();
+        let filter = HistoryFilter { status: Some(TransactionStatus::Failed), ..Default::default() };
+
+        let page = wallet.transaction_history_page(&filter, 0, 10);
+        assert_eq!(page.total, 2);
+        assert!(page.records.iter().all(|r| r.status == TransactionStatus::Failed));
+    }
+
+    #[test]
+    fn test_history_filters_by_date_range() {
+        let (wallet, now) = wallet_with_history();
+        let filter = HistoryFilter {
+            from: Some(now - chrono::Duration::days(3)),
+            until: Some(now - chrono::Duration::days(1)),
+            ..Default::default()
+        };
+
+        // Days 3 and 2 ago; day 1 ago falls on the exclusive upper bound.
+        let page = wallet.transaction_history_page(&filter, 0, 10);
+        let amounts: Vec<Amount> = page.records.iter().map(|r| r.amount).collect();
+        assert_eq!(amounts, vec![Amount::from_whole(3), Amount::from_whole(4)]);
+    }
+
+    #[test]
+    fn test_history_pagination_boundaries() {
+        let (wallet, _) = wallet_with_history();
+        let all = HistoryFilter::default();
+
+        let first = wallet.transaction_history_page(&all, 0, 4);
+        assert_eq!((first.records.len(), first.total), (4, 6));
+        assert_eq!(first.records[0].amount, Amount::from_whole(1));
+
+        let last = wallet.transaction_history_page(&all, 4, 4);
+        assert_eq!(last.records.len(), 2);
+        assert_eq!(last.records[1].amount, Amount::from_whole(6));
+
+        let past_end = wallet.transaction_history_page(&all, 6, 4);
+        assert!(past_end.records.is_empty());
+        assert_eq!(past_end.total, 6);
+        assert!(wallet.transaction_history_page(&all, 0, 0).records.is_empty());
+    }
+
+    fn past_timeout(wallet: &QuantumWallet) -> DateTime<Utc> {
+        Utc::now() + chrono::Duration::seconds(wallet.rebroadcast_policy.timeout_secs + 1)
+    }
+
+    #[test]
+    fn test_stuck_transaction_is_rebroadcast() {
+        let mut wallet = funded_wallet();
+        let tx = wallet.transfer("receiver", &AssetId::native(), Amount::from_whole(1), Amount::from_base_units(1_000)).unwrap();
+
+        assert!(wallet.rebroadcast_stuck(Utc::now()).unwrap().is_empty());
+
+        let resent = wallet.rebroadcast_stuck(past_timeout(&wallet)).unwrap();
+        assert_eq!(resent.len(), 1);
+        assert_eq!(resent[0].hash, tx.hash);
+        assert_eq!(wallet.pending_transactions().next().unwrap().attempts, 2);
+    }
+
+    #[test]
+    fn test_confirmed_transaction_is_not_rebroadcast() {
+        let mut wallet = funded_wallet();
+        let tx = wallet.transfer("receiver", &AssetId::native(), Amount::from_whole(1), Amount::from_base_units(1_000)).unwrap();
+
+        wallet.update_status(&tx.hash, TransactionStatus::Confirmed).unwrap();
+        assert_eq!(wallet.pending_transactions().count(), 0);
+        assert!(wallet.rebroadcast_stuck(past_timeout(&wallet)).unwrap().is_empty());
+        assert!(matches!(
+            wallet.update_status(&Hash::default(), TransactionStatus::Confirmed),
+            Err(WalletError::TransactionNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_fee_bump_produces_signed_replacement() {
+        let mut wallet = funded_wallet();
+        wallet.rebroadcast_policy.fee_bump_percent = Some(50);
+        let fee = Amount::from_base_units(1_000);
+        let tx = wallet.transfer("receiver", &AssetId::native(), Amount::from_whole(1), fee).unwrap();
+        let balance_before = wallet.balance(&AssetId::native());
+
+        let replacement = wallet.rebroadcast_stuck(past_timeout(&wallet)).unwrap().remove(0);
+        assert_eq!(replacement.nonce, tx.nonce);
+        assert_eq!(replacement.amount, tx.amount);
+        assert_eq!(replacement.fee, Amount::from_base_units(1_500));
+        assert_ne!(replacement.hash, tx.hash);
+        assert!(replacement.signature.is_some());
+        replacement.validate_basics().unwrap();
+
+        assert_eq!(wallet.balance(&AssetId::native()), balance_before.checked_sub(Amount::from_base_units(500)).unwrap());
+        // The history record and pending entry both follow the replacement.
+        assert_eq!(wallet.transaction_history[0].hash, replacement.hash);
+        assert_eq!(wallet.pending_transactions().next().unwrap().transaction.hash, replacement.hash);
+
+        // Confirming the replacement ends tracking.
+        wallet.update_status(&replacement.hash, TransactionStatus::Confirmed).unwrap();
+        assert_eq!(wallet.pending_transactions().count(), 0);
+    }
+
+    #[test]
+    fn test_original_confirming_after_bumps_refunds_them() {
+        let mut wallet = funded_wallet();
+        wallet.rebroadcast_policy.fee_bump_percent = Some(50);
+        let tx = wallet.transfer("receiver", &AssetId::native(), Amount::from_whole(1), Amount::from_base_units(1_000)).unwrap();
+        let balance_after_send = wallet.balance(&AssetId::native());
+
+        wallet.rebroadcast_stuck(past_timeout(&wallet)).unwrap();
+        let second = wallet.rebroadcast_stuck(past_timeout(&wallet) + chrono::Duration::seconds(wallet.rebroadcast_policy.timeout_secs)).unwrap();
+        assert_eq!(second[0].fee, Amount::from_base_units(2_250));
+        assert_eq!(wallet.pending_transactions().next().unwrap().replaced.len(), 2);
+
+        // The original wins the nonce: both bumps come back and nothing is re-bumped.
+        wallet.update_status(&tx.hash, TransactionStatus::Confirmed).unwrap();
+        assert_eq!(wallet.balance(&AssetId::native()), balance_after_send);
+        assert_eq!(wallet.pending_transactions().count(), 0);
+        assert_eq!(wallet.transaction_history[0].hash, tx.hash);
+        assert_eq!(wallet.transaction_history[0].status, TransactionStatus::Confirmed);
+    }
+
+    #[test]
+    fn test_transfer_non_native_asset() {
+        let mut wallet = funded_wallet();
+        let usdc = AssetId::new("USDC");
+        let fee = Amount::from_base_units(1_000);
+
+        let tx = wallet.transfer("recipient", &usdc, Amount::from_whole(40), fee).unwrap();
+
+        assert_eq!(tx.asset_id, usdc);
+        assert_eq!(wallet.balance(&usdc), Amount::from_whole(60));
+        assert_eq!(wallet.balance(&AssetId::native()), Amount::from_whole(10).checked_sub(fee).unwrap());
+    }
+
+    #[test]
+    fn test_insufficient_balance_is_per_asset() {
+        let mut wallet = funded_wallet();
+        let weth = AssetId::new("WETH");
+
+        let result = wallet.transfer("recipient", &weth, Amount::from_whole(1), Amount::ZERO);
+        assert!(matches!(result, Err(WalletError::InsufficientFunds)));
+
+        let result = wallet.stake(&AssetId::new("USDC"), Amount::from_whole(101), Amount::ZERO);
+        assert!(matches!(result, Err(WalletError::InsufficientFunds)));
+        assert_eq!(wallet.balance(&AssetId::new("USDC")), Amount::from_whole(100));
+        assert_eq!(wallet.balance(&AssetId::native()), Amount::from_whole(10));
+    }
+
+    #[test]
+    fn test_stake_pays_fee_and_passes_admission_checks() {
+        let mut wallet = funded_wallet();
+        let fee = Amount::from_base_units(1_000);
+
+        let tx = wallet.stake(&AssetId::native(), Amount::from_whole(2), fee).unwrap();
+
+        tx.validate_basics().unwrap();
+        assert_eq!(tx.gas_limit, MIN_GAS_LIMIT);
+        assert_eq!(wallet.balance(&AssetId::native()), Amount::from_whole(8).checked_sub(fee).unwrap());
+        assert_eq!(wallet.staked_balances[&AssetId::native()], Amount::from_whole(2));
+    }
+
+    #[test]
+    fn test_wallet_transactions_carry_its_chain_id() {
+        let mut wallet = funded_wallet().with_chain_id(7);
+        let fee = Amount::from_base_units(1_000);
+
+        let transfer = wallet.transfer("recipient", &AssetId::native(), Amount::from_whole(1), fee).unwrap();
+        let stake = wallet.stake(&AssetId::native(), Amount::from_whole(1), fee).unwrap();
+
+        for tx in [transfer, stake] {
+            assert_eq!(tx.chain_id, 7);
+            tx.validate_chain(7).unwrap();
+            assert!(tx.signature.is_some());
+        }
+    }
+
+    #[test]
+    fn test_native_transfer_unchanged() {
+        let mut wallet = funded_wallet();
+        let fee = Amount::from_base_units(1_000);
+
+        let tx = wallet.transfer("recipient", &AssetId::native(), Amount::from_whole(3), fee).unwrap();
+
+        assert!(tx.asset_id.is_native());
+        let expected = Amount::from_whole(7).checked_sub(fee).unwrap();
+        assert_eq!(wallet.balance(&AssetId::native()), expected);
+        assert_eq!(wallet.balance(&AssetId::new("USDC")), Amount::from_whole(100));
+    }
+
+    #[test]
+    fn test_private_key_round_trip() {
+        let secret = b"dilithium secret key bytes";
+        let sealed = EncryptedKey::seal(secret, PASSWORD, test_kdf()).unwrap();
+
+        assert_ne!(sealed.ciphertext.as_slice(), secret.as_slice());
+        assert!(!sealed.ciphertext.windows(secret.len()).any(|w| w == secret));
+        assert_eq!(sealed.open(PASSWORD).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_wrong_password_fails() {
+        let wallet = funded_wallet();
+        let result = wallet.decrypt_private_key("dilithium", "hunter2");
+        assert!(matches!(result, Err(WalletError::InvalidPassword)));
+    }
+
+    #[test]
+    fn test_each_seal_uses_fresh_salt() {
+        let first = EncryptedKey::seal(b"same key", PASSWORD, test_kdf()).unwrap();
+        let second = EncryptedKey::seal(b"same key", PASSWORD, test_kdf()).unwrap();
+        assert_ne!(first.salt, second.salt);
+        assert_ne!(first.ciphertext, second.ciphertext);
+    }
+
+    #[test]
+    fn test_change_password_reencrypts_keys() {
+        let mut wallet = funded_wallet();
+        let original = wallet.decrypt_private_key("kyber", PASSWORD).unwrap();
+
+        assert!(matches!(wallet.change_password("wrong", "new password"), Err(WalletError::InvalidPassword)));
+        assert_eq!(wallet.decrypt_private_key("kyber", PASSWORD).unwrap(), original);
+
+        wallet.change_password(PASSWORD, "new password").unwrap();
+        assert!(matches!(wallet.decrypt_private_key("kyber", PASSWORD), Err(WalletError::InvalidPassword)));
+        assert_eq!(wallet.decrypt_private_key("kyber", "new password").unwrap(), original);
+    }
+
+    #[test]
+    fn test_serialized_wallet_holds_no_plaintext_kyber_key() {
+        let wallet = funded_wallet();
+        let kyber_private = wallet.decrypt_private_key("kyber", PASSWORD).unwrap();
+        let json = serde_json::to_value(&wallet).unwrap();
+        assert!(json.get("kyber_keypair").is_none());
+
+        let mut loaded: QuantumWallet = serde_json::from_value(json).unwrap();
+        assert!(!loaded.is_unlocked());
+        assert!(matches!(loaded.unlock("hunter2"), Err(WalletError::InvalidPassword)));
+        assert!(!loaded.is_unlocked());
+
+        loaded.unlock(PASSWORD).unwrap();
+        assert!(loaded.is_unlocked());
+        assert_eq!(loaded.decrypt_private_key("kyber", PASSWORD).unwrap(), kyber_private);
+        loaded.lock();
+        assert!(!loaded.is_unlocked());
+    }
+
+    #[test]
+    fn test_unlock_restores_in_memory_signing_key() {
+        let wallet = funded_wallet();
+        let public_key = wallet.signing_public_key().unwrap();
+
+        let mut loaded: QuantumWallet = serde_json::from_value(serde_json::to_value(&wallet).unwrap()).unwrap();
+        assert!(matches!(loaded.signing_public_key(), Err(WalletError::KeyNotFound)));
+
+        loaded.unlock(PASSWORD).unwrap();
+        assert_eq!(loaded.signing_public_key().unwrap(), public_key);
+        let tx = loaded.transfer("receiver", &AssetId::native(), Amount::from_whole(1), Amount::from_base_units(1_000)).unwrap();
+        assert!(tx.signature.is_some());
+    }
+
+    /// Stands in for an enclave: keys stay in its own map and only signatures come out.
+    #[derive(Default)]
+    struct MockEnclave {
+        keys: std::sync::Mutex<HashMap<String, dilithium2::SecretKey>>,
+    }
+
+    impl EnclaveDevice for MockEnclave {
+        fn generate_signing_key(&self, label: &str) -> Result<(String, Vec<u8>), WalletError> {
+            let (public_key, secret_key) = dilithium2::keypair();
+            let handle = format!("{}-{}", label, uuid::Uuid::new_v4());
+            self.keys.lock().unwrap().insert(handle.clone(), secret_key);
+            Ok((handle, public_key.as_bytes().to_vec()))
+        }
+
+        fn sign(&self, key_handle: &str, message: &[u8]) -> Result<Vec<u8>, WalletError> {
+            let keys = self.keys.lock().unwrap();
+            let secret_key = keys.get(key_handle).ok_or(WalletError::KeyNotFound)?;
+            Ok(dilithium2::detached_sign(message, secret_key).as_bytes().to_vec())
+        }
+    }
+
+    #[test]
+    fn test_enclave_signs_without_exporting_key() {
+        let enclave = Arc::new(MockEnclave::default());
+        let wallet = QuantumWallet::new_with_backend(PASSWORD, test_kdf(), KeyBackend::Enclave(enclave.clone())).unwrap();
+
+        assert!(!wallet.encrypted_private_keys.contains_key("dilithium"));
+        assert!(matches!(wallet.decrypt_private_key("dilithium", PASSWORD), Err(WalletError::KeyNotFound)));
+        assert_eq!(enclave.keys.lock().unwrap().len(), 1);
+
+        let message = b"transfer 5 QFC";
+        let signature = wallet.signer().unwrap().sign(message).unwrap();
+        let public_key = wallet.signing_public_key().unwrap();
+        assert!(verify_signature(&public_key, message, &signature));
+        assert!(!verify_signature(&public_key, b"transfer 500 QFC", &signature));
+    }
+
+    fn watched_wallet() -> QuantumWallet {
+        let address = funded_wallet().address;
+        QuantumWallet::watch_only(&address).unwrap()
+    }
+
+    #[test]
+    fn test_watch_only_wallet_queries_balance_and_history() {
+        let mut wallet = watched_wallet();
+        wallet.credit(&AssetId::native(), Amount::from_whole(7)).unwrap();
+        wallet.transaction_history.push(TransactionRecord {
+            hash: Hash::default(),
+            timestamp: Utc::now(),
+            amount: Amount::from_whole(7),
+            asset_id: AssetId::native(),
+            transaction_type: TransactionType::Send,
+            status: TransactionStatus::Confirmed,
+            gas_used: 0.0,
+        });
+
+        assert!(wallet.is_watch_only());
+        assert_eq!(wallet.balance(&AssetId::native()), Amount::from_whole(7));
+        assert_eq!(wallet.transaction_history_page(&HistoryFilter::default(), 0, 10).total, 1);
+    }
+
+    #[test]
+    fn test_watch_only_wallet_cannot_sign() {
+        let mut wallet = watched_wallet();
+        wallet.credit(&AssetId::native(), Amount::from_whole(10)).unwrap();
+
+        let result = wallet.transfer("receiver", &AssetId::native(), Amount::from_whole(1), Amount::from_base_units(1_000));
+        assert!(matches!(result, Err(WalletError::WatchOnly)));
+        assert!(matches!(wallet.stake(&AssetId::native(), Amount::from_whole(1), Amount::from_base_units(1_000)), Err(WalletError::WatchOnly)));
+        assert!(matches!(wallet.signing_public_key(), Err(WalletError::WatchOnly)));
+        assert_eq!(wallet.balance(&AssetId::native()), Amount::from_whole(10));
+
+        // Attaching a key store does not turn it into a signing wallet.
+        wallet.attach_key_storage(Arc::new(InMemoryKeyStorage::generate()));
+        assert!(matches!(wallet.signing_public_key(), Err(WalletError::WatchOnly)));
+    }
+
+    #[test]
+    fn test_watch_only_wallet_holds_no_private_keys() {
+        let wallet = watched_wallet();
+
+        assert!(wallet.kyber_keypair.is_none());
+        assert!(wallet.key_storage.is_none());
+        assert!(wallet.encrypted_private_keys.is_empty());
+        assert!(matches!(wallet.decrypt_private_key("dilithium", PASSWORD), Err(WalletError::KeyNotFound)));
+        assert!(matches!(QuantumWallet::watch_only("not-an-address"), Err(WalletError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_in_memory_backend_signatures_verify() {
+        let wallet = funded_wallet();
+        let message = b"stake 1 QFC";
+
+        let signature = wallet.signer().unwrap().sign(message).unwrap();
+        assert!(verify_signature(&wallet.signing_public_key().unwrap(), message, &signature));
+        assert!(wallet.decrypt_private_key("dilithium", PASSWORD).is_ok());
     }
 }
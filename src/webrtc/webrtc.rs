@@ -1,22 +1,446 @@
-use datachannel::{Connection, ConnectionConfig, DataChannelHandler};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex, Notify};
+use webrtc::api::interceptor_registry::register_default_interceptors;
+use webrtc::api::media_engine::MediaEngine;
+use webrtc::api::APIBuilder;
+use webrtc::data_channel::data_channel_message::DataChannelMessage;
+use webrtc::data_channel::RTCDataChannel;
+use webrtc::ice_transport::ice_server::RTCIceServer;
+use webrtc::interceptor::registry::Registry;
+use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+use webrtc::peer_connection::RTCPeerConnection;
+use quantumfuse_sdk::{
+    error::TransportError,
+    block::QuantumBlock,
+    codec::{CodecConfig, WireFormat},
+    transaction::QuantumTransaction,
+    p2p::PeerTransport,
+};
 
-fn main() {
-    let config = ConnectionConfig::default();
-    let conn = Connection::new(&config).unwrap();
+const DATA_CHANNEL_LABEL: &str = "quantumfuse";
+const FRAME_HEADER_LEN: usize = 5;
+const INBOUND_BUFFER: usize = 256;
 
-    conn.on_data_channel(|channel| {
-        let handler = Arc::new(Mutex::new(AudioChannelHandler {}));
-        channel.set_handler(handler.clone());
-    });
+// 🔹 **WebRTC Configuration**
+#[derive(Debug, Clone)]
+pub struct WebRtcConfig {
+    pub ice_servers: Vec<String>,
+    pub negotiation_timeout: Duration,
+    /// Payload encoding; nodes set this from `NodeConfig.codec.network`.
+    pub codec: WireFormat,
+}
+
+impl Default for WebRtcConfig {
+    fn default() -> Self {
+        Self {
+            ice_servers: vec!["stun:stun.l.google.com:19302".to_string()],
+            negotiation_timeout: Duration::from_secs(10),
+            codec: CodecConfig::default().network,
+        }
+    }
+}
+
+// 🔹 **Wire Messages**
+/// Messages streamed over the data channel, framed as `[kind: u8][len: u32 BE][payload]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WireMessage {
+    Transaction(QuantumTransaction),
+    Block(QuantumBlock),
+}
+
+impl WireMessage {
+    const KIND_TRANSACTION: u8 = 0x01;
+    const KIND_BLOCK: u8 = 0x02;
+
+    pub fn encode(&self, codec: WireFormat) -> Result<Vec<u8>, TransportError> {
+        let (kind, payload) = match self {
+            WireMessage::Transaction(tx) => (Self::KIND_TRANSACTION, codec.encode(tx)),
+            WireMessage::Block(block) => (Self::KIND_BLOCK, codec.encode(block)),
+        };
+        let payload = payload.map_err(|e| TransportError::InvalidFrame(e.to_string()))?;
+        let len = u32::try_from(payload.len())
+            .map_err(|_| TransportError::InvalidFrame("payload too large".to_string()))?;
+
+        let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+        frame.push(kind);
+        frame.extend_from_slice(&len.to_be_bytes());
+        frame.extend_from_slice(&payload);
+        Ok(frame)
+    }
+
+    pub fn decode(frame: &[u8], codec: WireFormat) -> Result<Self, TransportError> {
+        if frame.len() < FRAME_HEADER_LEN {
+            return Err(TransportError::InvalidFrame("truncated header".to_string()));
+        }
+        let len = u32::from_be_bytes([frame[1], frame[2], frame[3], frame[4]]) as usize;
+        let payload = &frame[FRAME_HEADER_LEN..];
+        if payload.len() != len {
+            return Err(TransportError::InvalidFrame("length mismatch".to_string()));
+        }
+
+        let message = match frame[0] {
+            Self::KIND_TRANSACTION => codec.decode(payload).map(WireMessage::Transaction),
+            Self::KIND_BLOCK => codec.decode(payload).map(WireMessage::Block),
+            kind => return Err(TransportError::InvalidFrame(format!("unknown message kind {kind}"))),
+        };
+        message.map_err(|e| TransportError::InvalidFrame(e.to_string()))
+    }
+}
+
+// 🔹 **Signaling**
+/// Exchanges an SDP offer for the remote peer's answer.
+#[async_trait]
+pub trait Signaling: Send + Sync {
+    async fn exchange(&self, offer: RTCSessionDescription) -> Result<RTCSessionDescription, TransportError>;
+}
+
+/// Posts the offer as JSON to a signaling endpoint and reads back the answer.
+pub struct HttpSignaling {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl HttpSignaling {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Signaling for HttpSignaling {
+    async fn exchange(&self, offer: RTCSessionDescription) -> Result<RTCSessionDescription, TransportError> {
+        let response = self.client
+            .post(&self.endpoint)
+            .json(&offer)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| TransportError::Signaling(e.to_string()))?;
+
+        response.json::<RTCSessionDescription>()
+            .await
+            .map_err(|e| TransportError::Signaling(e.to_string()))
+    }
+}
+
+// 🔹 **WebRTC Transport**
+pub struct WebRtcTransport {
+    peer_connection: Arc<RTCPeerConnection>,
+    data_channel: Arc<RTCDataChannel>,
+    inbound: Mutex<mpsc::Receiver<WireMessage>>,
+    codec: WireFormat,
+}
+
+impl WebRtcTransport {
+    /// Opens a connection as the offering side. Any failure closes the peer
+    /// connection and surfaces as `NegotiationFailed`, so the caller can fall
+    /// back to another transport.
+    pub async fn connect(config: &WebRtcConfig, signaling: &dyn Signaling) -> Result<Self, TransportError> {
+        let peer_connection = new_peer_connection(config).await?;
+
+        let offer = Self::offer(&peer_connection, signaling, config.codec);
+        match tokio::time::timeout(config.negotiation_timeout, offer).await {
+            Ok(Ok((data_channel, inbound))) => Ok(Self {
+                peer_connection,
+                data_channel,
+                inbound: Mutex::new(inbound),
+                codec: config.codec,
+            }),
+            Ok(Err(e)) => {
+                let _ = peer_connection.close().await;
+                Err(TransportError::NegotiationFailed(e.to_string()))
+            }
+            Err(_) => {
+                let _ = peer_connection.close().await;
+                Err(TransportError::NegotiationFailed("negotiation timed out".to_string()))
+            }
+        }
+    }
+
+    async fn offer(
+        peer_connection: &Arc<RTCPeerConnection>,
+        signaling: &dyn Signaling,
+        codec: WireFormat,
+    ) -> Result<(Arc<RTCDataChannel>, mpsc::Receiver<WireMessage>), TransportError> {
+        let data_channel = peer_connection.create_data_channel(DATA_CHANNEL_LABEL, None).await
+            .map_err(|e| TransportError::NegotiationFailed(e.to_string()))?;
+        let (tx, rx) = mpsc::channel(INBOUND_BUFFER);
+        let opened = Arc::new(Notify::new());
+        attach_handlers(&data_channel, tx, opened.clone(), codec);
+
+        let offer = peer_connection.create_offer(None).await
+            .map_err(|e| TransportError::NegotiationFailed(e.to_string()))?;
+        let local = set_local_description(peer_connection, offer).await?;
+
+        let answer = signaling.exchange(local).await?;
+        peer_connection.set_remote_description(answer).await
+            .map_err(|e| TransportError::NegotiationFailed(e.to_string()))?;
+
+        opened.notified().await;
+        Ok((data_channel, rx))
+    }
+
+    /// Answers a remote offer. The returned `PendingTransport` resolves once
+    /// the offerer's data channel opens.
+    pub async fn accept(
+        config: &WebRtcConfig,
+        offer: RTCSessionDescription,
+    ) -> Result<(RTCSessionDescription, PendingTransport), TransportError> {
+        let peer_connection = new_peer_connection(config).await?;
+        let (tx, rx) = mpsc::channel(INBOUND_BUFFER);
+        let opened = Arc::new(Notify::new());
+        let channel_slot: Arc<Mutex<Option<Arc<RTCDataChannel>>>> = Arc::new(Mutex::new(None));
+
+        let slot = channel_slot.clone();
+        let notify = opened.clone();
+        let codec = config.codec;
+        peer_connection.on_data_channel(Box::new(move |data_channel: Arc<RTCDataChannel>| {
+            let slot = slot.clone();
+            let tx = tx.clone();
+            let notify = notify.clone();
+            Box::pin(async move {
+                attach_handlers(&data_channel, tx, notify, codec);
+                *slot.lock().await = Some(data_channel);
+            })
+        }));
+
+        let answer = async {
+            peer_connection.set_remote_description(offer).await
+                .map_err(|e| TransportError::NegotiationFailed(e.to_string()))?;
+            let answer = peer_connection.create_answer(None).await
+                .map_err(|e| TransportError::NegotiationFailed(e.to_string()))?;
+            set_local_description(&peer_connection, answer).await
+        }.await;
+
+        match answer {
+            Ok(answer) => Ok((answer, PendingTransport {
+                peer_connection,
+                channel_slot,
+                opened,
+                inbound: rx,
+                timeout: config.negotiation_timeout,
+                codec,
+            })),
+            Err(e) => {
+                let _ = peer_connection.close().await;
+                Err(e)
+            }
+        }
+    }
+}
+
+pub struct PendingTransport {
+    peer_connection: Arc<RTCPeerConnection>,
+    channel_slot: Arc<Mutex<Option<Arc<RTCDataChannel>>>>,
+    opened: Arc<Notify>,
+    inbound: mpsc::Receiver<WireMessage>,
+    timeout: Duration,
+    codec: WireFormat,
+}
+
+impl PendingTransport {
+    pub async fn established(self) -> Result<WebRtcTransport, TransportError> {
+        if tokio::time::timeout(self.timeout, self.opened.notified()).await.is_err() {
+            let _ = self.peer_connection.close().await;
+            return Err(TransportError::NegotiationFailed("data channel never opened".to_string()));
+        }
+
+        let data_channel = self.channel_slot.lock().await.take()
+            .ok_or_else(|| TransportError::NegotiationFailed("missing data channel".to_string()))?;
+
+        Ok(WebRtcTransport {
+            peer_connection: self.peer_connection,
+            data_channel,
+            inbound: Mutex::new(self.inbound),
+            codec: self.codec,
+        })
+    }
+}
+
+#[async_trait]
+impl PeerTransport for WebRtcTransport {
+    async fn send(&self, message: WireMessage) -> Result<(), TransportError> {
+        let frame = message.encode(self.codec)?;
+        self.data_channel.send(&Bytes::from(frame)).await
+            .map_err(|_| TransportError::ChannelClosed)?;
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<WireMessage, TransportError> {
+        self.inbound.lock().await.recv().await.ok_or(TransportError::ChannelClosed)
+    }
+
+    async fn close(&self) -> Result<(), TransportError> {
+        self.peer_connection.close().await
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))
+    }
+}
+
+// 🔹 **Helpers**
+async fn new_peer_connection(config: &WebRtcConfig) -> Result<Arc<RTCPeerConnection>, TransportError> {
+    let mut media_engine = MediaEngine::default();
+    let registry = register_default_interceptors(Registry::new(), &mut media_engine)
+        .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+
+    let api = APIBuilder::new()
+        .with_media_engine(media_engine)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let rtc_config = RTCConfiguration {
+        ice_servers: config.ice_servers.iter()
+            .map(|url| RTCIceServer { urls: vec![url.clone()], ..Default::default() })
+            .collect(),
+        ..Default::default()
+    };
+
+    api.new_peer_connection(rtc_config).await
+        .map(Arc::new)
+        .map_err(|e| TransportError::ConnectionFailed(e.to_string()))
+}
+
+/// Sets the local description and waits for ICE gathering, so the returned
+/// SDP carries every candidate and signaling needs a single round trip.
+async fn set_local_description(
+    peer_connection: &Arc<RTCPeerConnection>,
+    description: RTCSessionDescription,
+) -> Result<RTCSessionDescription, TransportError> {
+    let mut gathering_complete = peer_connection.gathering_complete_promise().await;
+    peer_connection.set_local_description(description).await
+        .map_err(|e| TransportError::NegotiationFailed(e.to_string()))?;
+    let _ = gathering_complete.recv().await;
+
+    peer_connection.local_description().await
+        .ok_or_else(|| TransportError::NegotiationFailed("missing local description".to_string()))
+}
 
-    println!("🎧 WebRTC Audio Streaming Server Running!");
+fn attach_handlers(
+    data_channel: &Arc<RTCDataChannel>,
+    inbound: mpsc::Sender<WireMessage>,
+    opened: Arc<Notify>,
+    codec: WireFormat,
+) {
+    data_channel.on_open(Box::new(move || {
+        opened.notify_one();
+        Box::pin(async {})
+    }));
+
+    data_channel.on_message(Box::new(move |msg: DataChannelMessage| {
+        let inbound = inbound.clone();
+        Box::pin(async move {
+            match WireMessage::decode(&msg.data, codec) {
+                Ok(message) => {
+                    let _ = inbound.send(message).await;
+                }
+                Err(e) => println!("⚠️ Dropping malformed WebRTC frame: {}", e),
+            }
+        })
+    }));
 }
 
-struct AudioChannelHandler;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quantumfuse_sdk::{amount::Amount, transaction::OperationType};
+
+    struct LoopbackSignaling {
+        config: WebRtcConfig,
+        pending: Mutex<Option<PendingTransport>>,
+    }
+
+    #[async_trait]
+    impl Signaling for LoopbackSignaling {
+        async fn exchange(&self, offer: RTCSessionDescription) -> Result<RTCSessionDescription, TransportError> {
+            let (answer, pending) = WebRtcTransport::accept(&self.config, offer).await?;
+            *self.pending.lock().await = Some(pending);
+            Ok(answer)
+        }
+    }
+
+    struct UnreachableSignaling;
+
+    #[async_trait]
+    impl Signaling for UnreachableSignaling {
+        async fn exchange(&self, _offer: RTCSessionDescription) -> Result<RTCSessionDescription, TransportError> {
+            Err(TransportError::Signaling("endpoint unreachable".to_string()))
+        }
+    }
+
+    fn loopback_config() -> WebRtcConfig {
+        WebRtcConfig {
+            ice_servers: vec![],
+            negotiation_timeout: Duration::from_secs(10),
+            codec: WireFormat::Bincode,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loopback_exchanges_transaction() {
+        let signaling = LoopbackSignaling {
+            config: loopback_config(),
+            pending: Mutex::new(None),
+        };
+
+        let client = WebRtcTransport::connect(&loopback_config(), &signaling).await.unwrap();
+        let pending = signaling.pending.lock().await.take().unwrap();
+        let server = pending.established().await.unwrap();
+
+        let tx = QuantumTransaction::new(
+            "alice".to_string(),
+            "bob".to_string(),
+            Amount::from_whole(3),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+        client.send(WireMessage::Transaction(tx.clone())).await.unwrap();
+
+        match server.recv().await.unwrap() {
+            WireMessage::Transaction(received) => assert_eq!(received.hash, tx.hash),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        client.close().await.unwrap();
+        server.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_signaling_reports_negotiation_failure() {
+        let result = WebRtcTransport::connect(&loopback_config(), &UnreachableSignaling).await;
+        assert!(matches!(result, Err(TransportError::NegotiationFailed(_))));
+    }
+
+    #[test]
+    fn test_frame_rejects_length_mismatch() {
+        let mut frame = vec![WireMessage::KIND_TRANSACTION];
+        frame.extend_from_slice(&10u32.to_be_bytes());
+        frame.extend_from_slice(b"{}");
+        assert!(matches!(WireMessage::decode(&frame, WireFormat::Json), Err(TransportError::InvalidFrame(_))));
+    }
+
+    #[test]
+    fn test_frame_payload_uses_the_configured_codec() {
+        let tx = QuantumTransaction::new(
+            "alice".to_string(),
+            "bob".to_string(),
+            Amount::from_whole(3),
+            Amount::from_base_units(1_000),
+            OperationType::Transfer,
+            21_000,
+        ).unwrap();
+        let message = WireMessage::Transaction(tx.clone());
 
-impl DataChannelHandler for AudioChannelHandler {
-    fn on_message(&self, data: &[u8]) {
-        println!("🎵 Streaming Audio: {} bytes received", data.len());
+        let frame = message.encode(WireFormat::Bincode).unwrap();
+        assert_eq!(&frame[FRAME_HEADER_LEN..], WireFormat::Bincode.encode(&tx).unwrap().as_slice());
+        assert!(matches!(WireMessage::decode(&frame, WireFormat::Bincode), Ok(WireMessage::Transaction(_))));
+        assert!(WireMessage::decode(&frame, WireFormat::Json).is_err());
     }
 }
@@ -1,59 +1,487 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
 use ark_bls12_381::{Bls12_381, Fr};
-use ark_groth16::{create_random_proof, prepare_verifying_key, verify_proof, ProvingKey, VerifyingKey}; // ✅ Fixed VerifierKey import
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey};
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use rand::thread_rng;
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+use ark_std::rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
+use quantumfuse_sdk::error::VotingError;
 
-/// 🔹 Zero-Knowledge Voting System
+/// Depth of the eligibility Merkle tree (up to 256 registered voters).
+pub const TREE_DEPTH: usize = 8;
+const MIMC_ROUNDS: usize = 110;
+
+/// 🔹 Anonymous ballot: proves membership in the eligibility tree and binds a
+/// per-proposal nullifier, without revealing which voter cast it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ZKVote {
-    pub voter_id: String,
+pub struct AnonymousBallot {
     pub proposal_id: String,
+    pub choice: bool,
+    pub eligibility_root: Vec<u8>,
+    pub nullifier: Vec<u8>,
     pub proof: Vec<u8>,
 }
 
-/// 🔹 ZKP System for Secure Voting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Tally {
+    pub yes: u64,
+    pub no: u64,
+}
+
+/// 🔹 Voter secret, kept by the voter. Only its commitment is registered.
+#[derive(Clone)]
+pub struct VoterSecret(Fr);
+
+impl VoterSecret {
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self(Fr::rand(rng))
+    }
+
+    pub fn commitment(&self) -> Fr {
+        mimc_hash(self.0, Fr::zero())
+    }
+
+    pub fn nullifier(&self, proposal_id: &str) -> Fr {
+        mimc_hash(self.0, proposal_field(proposal_id))
+    }
+}
+
+/// 🔹 ZK membership credential: the voter's secret plus its leaf position.
+#[derive(Clone)]
+pub struct MembershipCredential {
+    secret: VoterSecret,
+    leaf_index: usize,
+}
+
+/// 🔹 Registry of eligible voter commitments, arranged as a MiMC Merkle tree.
+#[derive(Debug, Clone, Default)]
+pub struct VoterRegistry {
+    leaves: Vec<Fr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MembershipPath {
+    pub siblings: Vec<Fr>,
+    pub path_bits: Vec<bool>,
+}
+
+impl VoterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn issue_credential(&mut self, secret: VoterSecret) -> Result<MembershipCredential, VotingError> {
+        let commitment = secret.commitment();
+        if self.leaves.len() >= 1 << TREE_DEPTH {
+            return Err(VotingError::RegistryFull);
+        }
+        if self.leaves.contains(&commitment) {
+            return Err(VotingError::DuplicateCredential);
+        }
+
+        self.leaves.push(commitment);
+        Ok(MembershipCredential { secret, leaf_index: self.leaves.len() - 1 })
+    }
+
+    pub fn root(&self) -> Fr {
+        self.levels()[TREE_DEPTH][0]
+    }
+
+    pub fn membership_path(&self, leaf_index: usize) -> Result<MembershipPath, VotingError> {
+        if leaf_index >= self.leaves.len() {
+            return Err(VotingError::UnknownCredential);
+        }
+
+        let levels = self.levels();
+        let mut siblings = Vec::with_capacity(TREE_DEPTH);
+        let mut path_bits = Vec::with_capacity(TREE_DEPTH);
+        let mut index = leaf_index;
+        for level in levels.iter().take(TREE_DEPTH) {
+            siblings.push(level[index ^ 1]);
+            path_bits.push(index & 1 == 1);
+            index >>= 1;
+        }
+        Ok(MembershipPath { siblings, path_bits })
+    }
+
+    fn levels(&self) -> Vec<Vec<Fr>> {
+        let mut level = self.leaves.clone();
+        level.resize(1 << TREE_DEPTH, Fr::zero());
+
+        let mut levels = vec![level];
+        for _ in 0..TREE_DEPTH {
+            let next = levels.last().unwrap()
+                .chunks(2)
+                .map(|pair| mimc_hash(pair[0], pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+}
+
+/// 🔹 ZKP System for Anonymous Voting
 pub struct ZKPSystem {
     pub proving_key: ProvingKey<Bls12_381>,
-    pub verifier_key: VerifyingKey<Bls12_381>,  // ✅ Fixed name
+    pub verifier_key: PreparedVerifyingKey<Bls12_381>,
+}
+
+type CircuitKeys = (ProvingKey<Bls12_381>, PreparedVerifyingKey<Bls12_381>);
+
+/// The membership circuit's trusted setup, run once per process and shared by
+/// every `ZKPSystem` so their ballots verify against each other.
+static CIRCUIT_KEYS: OnceLock<Result<CircuitKeys, String>> = OnceLock::new();
+
+fn circuit_setup() -> Result<CircuitKeys, String> {
+    let (proving_key, verifying_key) =
+        Groth16::<Bls12_381>::setup(MembershipCircuit::blank(), &mut ark_std::rand::thread_rng())
+            .map_err(|e| e.to_string())?;
+    let verifier_key = Groth16::<Bls12_381>::process_vk(&verifying_key).map_err(|e| e.to_string())?;
+    Ok((proving_key, verifier_key))
 }
 
 impl ZKPSystem {
-    pub fn new() -> Self {
-        let rng = &mut thread_rng();
-        let circuit = DummyCircuit::<Fr> {};  // ✅ Added DummyCircuit
-        let (proving_key, verifier_key) = ark_groth16::generate_random_parameters::<Bls12_381, _, _>(circuit, rng).unwrap();
-        Self { proving_key, verifier_key }
+    pub fn new() -> Result<Self, VotingError> {
+        let (proving_key, verifier_key) = CIRCUIT_KEYS.get_or_init(circuit_setup).as_ref()
+            .map_err(|e| VotingError::ProofGeneration(e.clone()))?;
+        Ok(Self { proving_key: proving_key.clone(), verifier_key: verifier_key.clone() })
     }
 
-    pub fn generate_proof(&self, voter_id: &str, proposal_id: &str) -> Vec<u8> {
-        let circuit = DummyCircuit::<Fr> {};
-        let proof = create_random_proof(circuit, &self.proving_key, &mut thread_rng()).unwrap();
-        let mut proof_bytes = vec![];
-        proof.serialize(&mut proof_bytes).unwrap();
-        proof_bytes
+    pub fn cast_ballot<R: RngCore + CryptoRng>(
+        &self,
+        credential: &MembershipCredential,
+        registry: &VoterRegistry,
+        proposal_id: &str,
+        choice: bool,
+        rng: &mut R,
+    ) -> Result<AnonymousBallot, VotingError> {
+        let path = registry.membership_path(credential.leaf_index)?;
+        let root = registry.root();
+        let nullifier = credential.secret.nullifier(proposal_id);
+
+        let circuit = MembershipCircuit {
+            root: Some(root),
+            nullifier: Some(nullifier),
+            external_nullifier: Some(proposal_field(proposal_id)),
+            choice: Some(choice_field(choice)),
+            secret: Some(credential.secret.0),
+            siblings: path.siblings.into_iter().map(Some).collect(),
+            path_bits: path.path_bits.into_iter().map(Some).collect(),
+        };
+        let proof = Groth16::<Bls12_381>::prove(&self.proving_key, circuit, rng)
+            .map_err(|e| VotingError::ProofGeneration(e.to_string()))?;
+
+        Ok(AnonymousBallot {
+            proposal_id: proposal_id.to_string(),
+            choice,
+            eligibility_root: to_bytes(&root)?,
+            nullifier: to_bytes(&nullifier)?,
+            proof: to_bytes(&proof)?,
+        })
     }
 
-    pub fn verify_vote(&self, zk_vote: &ZKVote) -> bool {
-        let proof = Proof::deserialize(&zk_vote.proof[..]).unwrap();
-        let prepared_vk = prepare_verifying_key(&self.verifier_key);
-        verify_proof(&prepared_vk, &proof, &[]).unwrap()
+    pub fn verify_ballot(&self, ballot: &AnonymousBallot) -> Result<bool, VotingError> {
+        let root = Fr::deserialize_compressed(&ballot.eligibility_root[..])
+            .map_err(|e| VotingError::Serialization(e.to_string()))?;
+        let nullifier = Fr::deserialize_compressed(&ballot.nullifier[..])
+            .map_err(|e| VotingError::Serialization(e.to_string()))?;
+        let proof = Proof::<Bls12_381>::deserialize_compressed(&ballot.proof[..])
+            .map_err(|e| VotingError::Serialization(e.to_string()))?;
+
+        let public_inputs = [root, nullifier, proposal_field(&ballot.proposal_id), choice_field(ballot.choice)];
+        Groth16::<Bls12_381>::verify_with_processed_vk(&self.verifier_key, &public_inputs, &proof)
+            .map_err(|e| VotingError::Serialization(e.to_string()))
     }
 }
 
-/// 🔹 Dummy Circuit for ZKP Simulation
-use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
-use ark_ff::UniformRand;
-use ark_std::rand::Rng;
+/// 🔹 Ballot box for one proposal. Rejects reused nullifiers and keeps every
+/// accepted ballot so anyone can re-verify the tally.
+pub struct AnonymousBallotBox {
+    pub proposal_id: String,
+    eligibility_root: Vec<u8>,
+    nullifiers: HashSet<Vec<u8>>,
+    ballots: Vec<AnonymousBallot>,
+}
 
+impl AnonymousBallotBox {
+    /// Opens voting against a snapshot of the registry's current root.
+    pub fn open(proposal_id: &str, registry: &VoterRegistry) -> Result<Self, VotingError> {
+        Ok(Self {
+            proposal_id: proposal_id.to_string(),
+            eligibility_root: to_bytes(&registry.root())?,
+            nullifiers: HashSet::new(),
+            ballots: Vec::new(),
+        })
+    }
+
+    pub fn submit(&mut self, system: &ZKPSystem, ballot: AnonymousBallot) -> Result<(), VotingError> {
+        if ballot.proposal_id != self.proposal_id {
+            return Err(VotingError::WrongProposal);
+        }
+        if ballot.eligibility_root != self.eligibility_root {
+            return Err(VotingError::StaleEligibilityRoot);
+        }
+        if self.nullifiers.contains(&ballot.nullifier) {
+            return Err(VotingError::DoubleVote);
+        }
+        if !system.verify_ballot(&ballot)? {
+            return Err(VotingError::InvalidProof);
+        }
+
+        self.nullifiers.insert(ballot.nullifier.clone());
+        self.ballots.push(ballot);
+        Ok(())
+    }
+
+    pub fn ballots(&self) -> &[AnonymousBallot] {
+        &self.ballots
+    }
+
+    pub fn tally(&self) -> Tally {
+        self.ballots.iter().fold(Tally::default(), |mut tally, ballot| {
+            if ballot.choice {
+                tally.yes += 1;
+            } else {
+                tally.no += 1;
+            }
+            tally
+        })
+    }
+
+    /// Re-verifies every recorded ballot and nullifier before returning the tally.
+    pub fn verify_tally(&self, system: &ZKPSystem) -> Result<Tally, VotingError> {
+        let mut seen = HashSet::new();
+        for ballot in &self.ballots {
+            if ballot.eligibility_root != self.eligibility_root || !seen.insert(&ballot.nullifier) {
+                return Err(VotingError::InvalidTally);
+            }
+            if !system.verify_ballot(ballot)? {
+                return Err(VotingError::InvalidTally);
+            }
+        }
+        Ok(self.tally())
+    }
+}
+
+/// 🔹 Membership Circuit
+/// Public inputs: eligibility root, nullifier, proposal field, choice.
 #[derive(Clone)]
-pub struct DummyCircuit<F: ark_ff::PrimeField> {
-    pub input: F,
+pub struct MembershipCircuit {
+    root: Option<Fr>,
+    nullifier: Option<Fr>,
+    external_nullifier: Option<Fr>,
+    choice: Option<Fr>,
+    secret: Option<Fr>,
+    siblings: Vec<Option<Fr>>,
+    path_bits: Vec<Option<bool>>,
 }
 
-impl<F: ark_ff::PrimeField> ConstraintSynthesizer<F> for DummyCircuit<F> {
-    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
-        let _input_var = cs.new_input_variable(|| Ok(self.input))?;
-        Ok(())
+impl MembershipCircuit {
+    fn blank() -> Self {
+        Self {
+            root: None,
+            nullifier: None,
+            external_nullifier: None,
+            choice: None,
+            secret: None,
+            siblings: vec![None; TREE_DEPTH],
+            path_bits: vec![None; TREE_DEPTH],
+        }
     }
+}
+
+impl ConstraintSynthesizer<Fr> for MembershipCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let constants = mimc_constants();
+
+        let root = AllocatedFr::input(&cs, self.root)?;
+        let nullifier = AllocatedFr::input(&cs, self.nullifier)?;
+        let external_nullifier = AllocatedFr::input(&cs, self.external_nullifier)?;
+        let choice = AllocatedFr::input(&cs, self.choice)?;
+        let secret = AllocatedFr::witness(&cs, self.secret)?;
+
+        let mut current = mimc_gadget(&cs, &secret, &AllocatedFr::constant(Fr::zero()), &constants)?;
+        for (sibling, bit) in self.siblings.into_iter().zip(self.path_bits) {
+            let sibling = AllocatedFr::witness(&cs, sibling)?;
+            let bit = AllocatedFr::witness(&cs, bit.map(|b| if b { Fr::one() } else { Fr::zero() }))?;
+            bit.enforce_boolean(&cs)?;
+
+            // bit = 1 means the current node is the right child.
+            let delta = bit.mul(&cs, &sibling.sub(&current))?;
+            let left = current.add(&delta);
+            let right = sibling.sub(&delta);
+            current = mimc_gadget(&cs, &left, &right, &constants)?;
+        }
+        current.enforce_equal(&cs, &root)?;
+
+        let computed_nullifier = mimc_gadget(&cs, &secret, &external_nullifier, &constants)?;
+        computed_nullifier.enforce_equal(&cs, &nullifier)?;
+
+        choice.enforce_boolean(&cs)
+    }
+}
+
+#[derive(Clone)]
+struct AllocatedFr {
+    lc: LinearCombination<Fr>,
+    value: Option<Fr>,
+}
+
+impl AllocatedFr {
+    fn input(cs: &ConstraintSystemRef<Fr>, value: Option<Fr>) -> Result<Self, SynthesisError> {
+        let var = cs.new_input_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+        Ok(Self { lc: lc!() + var, value })
+    }
+
+    fn witness(cs: &ConstraintSystemRef<Fr>, value: Option<Fr>) -> Result<Self, SynthesisError> {
+        let var = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+        Ok(Self { lc: lc!() + var, value })
+    }
+
+    fn constant(value: Fr) -> Self {
+        Self { lc: lc!() + (value, Variable::One), value: Some(value) }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self {
+            lc: self.lc.clone() + &other.lc,
+            value: self.value.zip(other.value).map(|(a, b)| a + b),
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self {
+            lc: self.lc.clone() - &other.lc,
+            value: self.value.zip(other.value).map(|(a, b)| a - b),
+        }
+    }
+
+    fn mul(&self, cs: &ConstraintSystemRef<Fr>, other: &Self) -> Result<Self, SynthesisError> {
+        let product = Self::witness(cs, self.value.zip(other.value).map(|(a, b)| a * b))?;
+        cs.enforce_constraint(self.lc.clone(), other.lc.clone(), product.lc.clone())?;
+        Ok(product)
+    }
+
+    fn enforce_equal(&self, cs: &ConstraintSystemRef<Fr>, other: &Self) -> Result<(), SynthesisError> {
+        cs.enforce_constraint(self.lc.clone() - &other.lc, lc!() + Variable::One, lc!())
+    }
+
+    fn enforce_boolean(&self, cs: &ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        cs.enforce_constraint(self.lc.clone(), lc!() + Variable::One - &self.lc, lc!())
+    }
+}
+
+// 🔹 MiMC (x^5) compression, used both natively and inside the circuit.
+fn mimc_constants() -> Vec<Fr> {
+    (0..MIMC_ROUNDS)
+        .map(|i| {
+            let seed = blake3::hash(format!("quantumfuse-zkp-voting-mimc-{}", i).as_bytes());
+            Fr::from_le_bytes_mod_order(seed.as_bytes())
+        })
+        .collect()
+}
+
+pub fn mimc_hash(left: Fr, right: Fr) -> Fr {
+    let mut x = right;
+    for c in mimc_constants() {
+        let t = x + left + c;
+        let t2 = t * t;
+        x = t2 * t2 * t;
+    }
+    x + left + right
+}
+
+fn mimc_gadget(
+    cs: &ConstraintSystemRef<Fr>,
+    left: &AllocatedFr,
+    right: &AllocatedFr,
+    constants: &[Fr],
+) -> Result<AllocatedFr, SynthesisError> {
+    let mut x = right.clone();
+    for c in constants {
+        let t = x.add(left).add(&AllocatedFr::constant(*c));
+        let t2 = t.mul(cs, &t)?;
+        let t4 = t2.mul(cs, &t2)?;
+        x = t4.mul(cs, &t)?;
     }
+    Ok(x.add(left).add(right))
+}
+
+fn proposal_field(proposal_id: &str) -> Fr {
+    Fr::from_le_bytes_mod_order(blake3::hash(proposal_id.as_bytes()).as_bytes())
+}
+
+fn choice_field(choice: bool) -> Fr {
+    if choice { Fr::one() } else { Fr::zero() }
+}
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>, VotingError> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes)
+        .map_err(|e| VotingError::Serialization(e.to_string()))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::rand::thread_rng;
+
+    fn registry_with_voters(count: usize) -> (VoterRegistry, Vec<MembershipCredential>) {
+        let mut registry = VoterRegistry::new();
+        let credentials = (0..count)
+            .map(|_| registry.issue_credential(VoterSecret::random(&mut thread_rng())).unwrap())
+            .collect();
+        (registry, credentials)
+    }
+
+    #[test]
+    fn test_eligible_anonymous_vote_is_counted() {
+        let system = ZKPSystem::new().unwrap();
+        let (registry, credentials) = registry_with_voters(3);
+        let mut ballot_box = AnonymousBallotBox::open("proposal-1", &registry).unwrap();
+
+        for (credential, choice) in credentials.iter().zip([true, true, false]) {
+            let ballot = system.cast_ballot(credential, &registry, "proposal-1", choice, &mut thread_rng()).unwrap();
+            ballot_box.submit(&system, ballot).unwrap();
+        }
+
+        assert_eq!(ballot_box.verify_tally(&system).unwrap(), Tally { yes: 2, no: 1 });
+    }
+
+    #[test]
+    fn test_double_vote_is_rejected() {
+        let system = ZKPSystem::new().unwrap();
+        let (registry, credentials) = registry_with_voters(2);
+        let mut ballot_box = AnonymousBallotBox::open("proposal-1", &registry).unwrap();
+
+        let first = system.cast_ballot(&credentials[0], &registry, "proposal-1", true, &mut thread_rng()).unwrap();
+        let second = system.cast_ballot(&credentials[0], &registry, "proposal-1", false, &mut thread_rng()).unwrap();
+        assert_eq!(first.nullifier, second.nullifier);
+
+        ballot_box.submit(&system, first).unwrap();
+        assert!(matches!(ballot_box.submit(&system, second), Err(VotingError::DoubleVote)));
+        assert_eq!(ballot_box.tally(), Tally { yes: 1, no: 0 });
+    }
+
+    #[test]
+    fn test_ineligible_voter_proof_fails() {
+        let system = ZKPSystem::new().unwrap();
+        let (registry, _) = registry_with_voters(2);
+        let mut ballot_box = AnonymousBallotBox::open("proposal-1", &registry).unwrap();
+
+        // The outsider can only prove membership in a tree of their own; the
+        // proof doesn't carry over once the ballot claims the real root.
+        let (outsider_registry, outsider) = registry_with_voters(1);
+        let mut ballot = system.cast_ballot(&outsider[0], &outsider_registry, "proposal-1", true, &mut thread_rng()).unwrap();
+        assert!(matches!(ballot_box.submit(&system, ballot.clone()), Err(VotingError::StaleEligibilityRoot)));
+
+        ballot.eligibility_root = to_bytes(&registry.root()).unwrap();
+        assert!(matches!(ballot_box.submit(&system, ballot), Err(VotingError::InvalidProof)));
+        assert_eq!(ballot_box.tally(), Tally::default());
+    }
+}